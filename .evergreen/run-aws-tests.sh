@@ -44,4 +44,10 @@ set -o errexit
 
 . ~/.cargo/env
 
-RUST_BACKTRACE=1 cargo test --features aws-auth auth_aws::auth_aws
+if [ "$ASYNC_RUNTIME" = "async-std" ]; then
+    FEATURE_FLAGS="--no-default-features --features aws-auth,async-std-runtime"
+else
+    FEATURE_FLAGS="--features aws-auth"
+fi
+
+RUST_BACKTRACE=1 cargo test $FEATURE_FLAGS auth_aws::auth_aws
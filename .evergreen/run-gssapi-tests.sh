@@ -0,0 +1,11 @@
+#!/bin/bash
+
+set -o errexit
+set -o xtrace
+
+. ~/.cargo/env
+
+# A Kerberos ticket for MONGO_GSSAPI_PRINCIPAL must be obtained before running these tests, e.g.
+# via `kinit -k -t "$KEYTAB" "$MONGO_GSSAPI_PRINCIPAL"`. The server at MONGO_GSSAPI_HOST is expected
+# to contain the document { kerberos: true, authenticated: "yeah" } in the kerberos.test collection.
+RUST_BACKTRACE=1 cargo test --features gssapi-auth gssapi
@@ -1,7 +1,15 @@
-use chrono::{offset::Utc, DateTime};
+#[cfg(test)]
+mod test;
+
+use std::{future::Future, time::Duration};
+
+use chrono::{offset::Utc, DateTime, TimeZone};
 use hmac::Hmac;
-use serde::Deserialize;
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Deserializer};
 use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
 
 use crate::{
     bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
@@ -19,10 +27,21 @@ use crate::{
     runtime::HttpClient,
 };
 
-const AWS_ECS_IP: &str = "169.254.170.2";
-const AWS_EC2_IP: &str = "169.254.169.254";
+const AWS_ECS_URL: &str = "http://169.254.170.2";
+const AWS_EC2_URL: &str = "http://169.254.169.254";
+const AWS_STS_URL: &str = "https://sts.amazonaws.com";
 const AWS_LONG_DATE_FMT: &str = "%Y%m%dT%H%M%SZ";
 
+/// Cached temporary credentials are refreshed once they are within this amount of time of
+/// expiring, so that they cannot expire in the middle of an authentication attempt.
+const EXPIRATION_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+lazy_static! {
+    /// The temporary credentials obtained from the STS, ECS or EC2 endpoints, which are shared by
+    /// all connections that authenticate without explicit credentials.
+    static ref CACHED_CREDENTIAL: CredentialCache = CredentialCache::default();
+}
+
 /// Performs MONGODB-AWS authentication for a given stream.
 pub(super) async fn authenticate_stream(
     conn: &mut Connection,
@@ -65,8 +84,28 @@ pub(super) async fn authenticate_stream(
         ServerFirst::parse(server_first_response.auth_response_body("MONGODB-AWS")?)?;
     server_first.validate(&nonce)?;
 
-    let aws_credential = AwsCredential::get(credential, http_client).await?;
+    let endpoints = AwsEndpoints::from_env();
+    let aws_credential =
+        AwsCredential::get(credential, http_client, &CACHED_CREDENTIAL, &endpoints).await?;
+
+    let result = send_client_second(conn, source, &server_first, &aws_credential, server_api).await;
+
+    // The cached credentials may have been revoked, so they are discarded if the server rejects
+    // them.
+    if result.is_err() && aws_credential.expiration.is_some() {
+        CACHED_CREDENTIAL.clear().await;
+    }
+
+    result
+}
 
+async fn send_client_second(
+    conn: &mut Connection,
+    source: &str,
+    server_first: &ServerFirst,
+    aws_credential: &AwsCredential,
+    server_api: Option<&ServerApi>,
+) -> Result<()> {
     let date = Utc::now();
 
     let authorization_header = aws_credential.compute_authorization_header(
@@ -80,7 +119,7 @@ pub(super) async fn authenticate_stream(
         "d": date.format(AWS_LONG_DATE_FMT).to_string(),
     };
 
-    if let Some(security_token) = aws_credential.session_token {
+    if let Some(ref security_token) = aws_credential.session_token {
         client_second_payload.insert("t", security_token);
     }
 
@@ -113,8 +152,83 @@ pub(super) async fn authenticate_stream(
     Ok(())
 }
 
+/// The endpoints that temporary credentials are obtained from.
+#[derive(Clone, Debug)]
+struct AwsEndpoints {
+    /// The base URL of the ECS credentials endpoint, to which
+    /// `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI` is appended.
+    ecs: String,
+
+    /// The base URL of the EC2 instance metadata service.
+    ec2: String,
+
+    /// The URL of the AWS Security Token Service.
+    sts: String,
+}
+
+impl Default for AwsEndpoints {
+    fn default() -> Self {
+        Self {
+            ecs: AWS_ECS_URL.to_string(),
+            ec2: AWS_EC2_URL.to_string(),
+            sts: AWS_STS_URL.to_string(),
+        }
+    }
+}
+
+impl AwsEndpoints {
+    /// Returns the default endpoints, with any overrides from the environment applied.
+    fn from_env() -> Self {
+        let mut endpoints = Self::default();
+        if let Ok(ec2) = std::env::var("AWS_EC2_METADATA_SERVICE_ENDPOINT") {
+            endpoints.ec2 = ec2;
+        }
+        if let Ok(sts) = std::env::var("AWS_ENDPOINT_URL_STS") {
+            endpoints.sts = sts;
+        }
+        endpoints
+    }
+}
+
+/// A cache for temporary credentials. Credentials are reused until they are about to expire; the
+/// lock is held while new credentials are obtained so that concurrent connections do not all
+/// request them at once.
+#[derive(Debug, Default)]
+struct CredentialCache {
+    credential: Mutex<Option<AwsCredential>>,
+}
+
+impl CredentialCache {
+    /// Returns the cached credentials if they are not about to expire, or otherwise obtains new
+    /// ones from `fetch`. Credentials without an expiration are never cached.
+    async fn get_or_fetch(
+        &self,
+        fetch: impl Future<Output = Result<AwsCredential>>,
+    ) -> Result<AwsCredential> {
+        let mut cached = self.credential.lock().await;
+        if let Some(ref credential) = *cached {
+            if !credential.expires_soon(Utc::now()) {
+                return Ok(credential.clone());
+            }
+        }
+
+        let credential = fetch.await?;
+        *cached = if credential.expiration.is_some() {
+            Some(credential.clone())
+        } else {
+            None
+        };
+
+        Ok(credential)
+    }
+
+    async fn clear(&self) {
+        *self.credential.lock().await = None;
+    }
+}
+
 /// Contains the credentials for MONGODB-AWS authentication.
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 struct AwsCredential {
     #[serde(rename = "AccessKeyId")]
     access_key: String,
@@ -122,14 +236,75 @@ struct AwsCredential {
     #[serde(rename = "SecretAccessKey")]
     secret_key: String,
 
-    #[serde(rename = "Token")]
+    #[serde(rename = "Token", alias = "SessionToken")]
     session_token: Option<String>,
+
+    #[serde(
+        rename = "Expiration",
+        default,
+        deserialize_with = "deserialize_expiration"
+    )]
+    expiration: Option<DateTime<Utc>>,
+}
+
+/// The response to an AssumeRoleWithWebIdentity request.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AssumeRoleWithWebIdentityResponse {
+    assume_role_with_web_identity_response: AssumeRoleWithWebIdentityResponseBody,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AssumeRoleWithWebIdentityResponseBody {
+    assume_role_with_web_identity_result: AssumeRoleWithWebIdentityResult,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AssumeRoleWithWebIdentityResult {
+    credentials: AwsCredential,
+}
+
+/// Deserializes the expiration of temporary credentials, which the ECS and EC2 endpoints return as
+/// an RFC 3339 date and STS returns as a number of seconds since the Unix epoch.
+fn deserialize_expiration<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Expiration {
+        Date(String),
+        Timestamp(f64),
+    }
+
+    let expiration = match Option::<Expiration>::deserialize(deserializer)? {
+        Some(Expiration::Date(date)) => DateTime::parse_from_rfc3339(&date)
+            .map_err(serde::de::Error::custom)?
+            .with_timezone(&Utc),
+        Some(Expiration::Timestamp(seconds)) => Utc
+            .timestamp_opt(seconds as i64, 0)
+            .single()
+            .ok_or_else(|| serde::de::Error::custom("expiration out of range"))?,
+        None => return Ok(None),
+    };
+
+    Ok(Some(expiration))
 }
 
 impl AwsCredential {
     /// Derives the credentials for an authentication attempt given the set of credentials the user
-    /// passed in.
-    async fn get(credential: &Credential, http_client: &HttpClient) -> Result<Self> {
+    /// passed in. Temporary credentials obtained from STS or the metadata endpoints are shared
+    /// through `cache`.
+    async fn get(
+        credential: &Credential,
+        http_client: &HttpClient,
+        cache: &CredentialCache,
+        endpoints: &AwsEndpoints,
+    ) -> Result<Self> {
         let access_key = credential
             .username
             .clone()
@@ -155,6 +330,7 @@ impl AwsCredential {
                 access_key,
                 secret_key,
                 session_token,
+                expiration: None,
             });
         }
 
@@ -173,38 +349,109 @@ impl AwsCredential {
             ));
         }
 
-        if let Ok(relative_uri) = std::env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
-            Self::get_from_ecs(relative_uri, http_client).await
+        cache
+            .get_or_fetch(Self::get_temporary(http_client, endpoints))
+            .await
+    }
+
+    /// Obtains temporary credentials from the first configured source.
+    async fn get_temporary(http_client: &HttpClient, endpoints: &AwsEndpoints) -> Result<Self> {
+        if let Ok(token_file) = std::env::var("AWS_WEB_IDENTITY_TOKEN_FILE") {
+            let role_arn = std::env::var("AWS_ROLE_ARN").map_err(|_| {
+                Error::authentication_error(
+                    "MONGODB-AWS",
+                    "AWS_ROLE_ARN must be set when AWS_WEB_IDENTITY_TOKEN_FILE is set",
+                )
+            })?;
+            let session_name = std::env::var("AWS_ROLE_SESSION_NAME").ok();
+            let token = std::fs::read_to_string(&token_file).map_err(|e| {
+                Error::authentication_error(
+                    "MONGODB-AWS",
+                    &format!(
+                        "failed to read the web identity token from {}: {}",
+                        token_file, e
+                    ),
+                )
+            })?;
+
+            Self::get_from_web_identity(
+                token.trim(),
+                &role_arn,
+                session_name.as_deref(),
+                http_client,
+                &endpoints.sts,
+            )
+            .await
+        } else if let Ok(relative_uri) = std::env::var("AWS_CONTAINER_CREDENTIALS_RELATIVE_URI") {
+            let uri = format!(
+                "{}/{}",
+                endpoints.ecs.trim_end_matches('/'),
+                relative_uri.trim_start_matches('/')
+            );
+            Self::get_from_ecs(&uri, http_client).await
+        } else if let Ok(uri) = std::env::var("AWS_CONTAINER_CREDENTIALS_FULL_URI") {
+            Self::get_from_ecs(&uri, http_client).await
         } else {
-            Self::get_from_ec2(http_client).await
+            Self::get_from_ec2(http_client, &endpoints.ec2).await
         }
     }
 
-    /// Obtains credentials from the ECS endpoint.
-    async fn get_from_ecs(relative_uri: String, http_client: &HttpClient) -> Result<Self> {
-        // Use the local IP address that AWS uses for ECS agents.
-        let uri = format!("http://{}/{}", AWS_ECS_IP, relative_uri);
+    /// Obtains temporary credentials for the role `role_arn` from an AssumeRoleWithWebIdentity
+    /// request to the STS endpoint at `sts_url`. A random session name is used if none is given.
+    async fn get_from_web_identity(
+        token: &str,
+        role_arn: &str,
+        session_name: Option<&str>,
+        http_client: &HttpClient,
+        sts_url: &str,
+    ) -> Result<Self> {
+        let session_name = match session_name {
+            Some(name) => name.to_string(),
+            None => hex::encode(&auth::generate_nonce_bytes()[..16]),
+        };
+        let uri = format!(
+            "{}/?Action=AssumeRoleWithWebIdentity&RoleSessionName={}&RoleArn={}&\
+             WebIdentityToken={}&Version=2011-06-15",
+            sts_url.trim_end_matches('/'),
+            utf8_percent_encode(&session_name, NON_ALPHANUMERIC),
+            utf8_percent_encode(role_arn, NON_ALPHANUMERIC),
+            utf8_percent_encode(token, NON_ALPHANUMERIC),
+        );
+
+        let response: AssumeRoleWithWebIdentityResponse = http_client
+            .get_and_deserialize_json(&uri, &[("Accept", "application/json")])
+            .await
+            .map_err(|e| credential_source_error("AssumeRoleWithWebIdentity", e))?;
+
+        Ok(response
+            .assume_role_with_web_identity_response
+            .assume_role_with_web_identity_result
+            .credentials)
+    }
 
+    /// Obtains credentials from the ECS endpoint at `uri`.
+    async fn get_from_ecs(uri: &str, http_client: &HttpClient) -> Result<Self> {
         http_client
-            .get_and_deserialize_json(&uri, None)
+            .get_and_deserialize_json(uri, None)
             .await
-            .map_err(|_| Error::unknown_authentication_error("MONGODB-AWS"))
+            .map_err(|e| credential_source_error("the ECS endpoint", e))
     }
 
-    /// Obtains temporary credentials for an EC2 instance to use for authentication.
-    async fn get_from_ec2(http_client: &HttpClient) -> Result<Self> {
+    /// Obtains temporary credentials for an EC2 instance to use for authentication from the
+    /// instance metadata service at `ec2_url`.
+    async fn get_from_ec2(http_client: &HttpClient, ec2_url: &str) -> Result<Self> {
+        let ec2_url = ec2_url.trim_end_matches('/');
+        let map_err = |e| credential_source_error("the EC2 instance metadata service", e);
+
         let temporary_token = http_client
             .put_and_read_string(
-                &format!("http://{}/latest/api/token", AWS_EC2_IP),
+                &format!("{}/latest/api/token", ec2_url),
                 &[("X-aws-ec2-metadata-token-ttl-seconds", "30")],
             )
             .await
-            .map_err(|_| Error::unknown_authentication_error("MONGODB-AWS"))?;
+            .map_err(map_err)?;
 
-        let role_name_uri = format!(
-            "http://{}/latest/meta-data/iam/security-credentials/",
-            AWS_EC2_IP
-        );
+        let role_name_uri = format!("{}/latest/meta-data/iam/security-credentials/", ec2_url);
 
         let role_name = http_client
             .get_and_read_string(
@@ -212,9 +459,9 @@ impl AwsCredential {
                 &[("X-aws-ec2-metadata-token", &temporary_token[..])],
             )
             .await
-            .map_err(|_| Error::unknown_authentication_error("MONGODB-AWS"))?;
+            .map_err(map_err)?;
 
-        let credential_uri = format!("{}/{}", role_name_uri, role_name);
+        let credential_uri = format!("{}{}", role_name_uri, role_name.trim());
 
         http_client
             .get_and_deserialize_json(
@@ -222,7 +469,18 @@ impl AwsCredential {
                 &[("X-aws-ec2-metadata-token", &temporary_token[..])],
             )
             .await
-            .map_err(|_| Error::unknown_authentication_error("MONGODB-AWS"))
+            .map_err(map_err)
+    }
+
+    /// Whether the credentials expire within `EXPIRATION_WINDOW` of `now`. Credentials without an
+    /// expiration never expire.
+    fn expires_soon(&self, now: DateTime<Utc>) -> bool {
+        match self.expiration {
+            Some(expiration) => {
+                expiration <= now + chrono::Duration::from_std(EXPIRATION_WINDOW).unwrap()
+            }
+            None => false,
+        }
     }
 
     /// Computes the signed authorization header for the credentials to send to the server in a sasl
@@ -428,3 +686,10 @@ impl ServerFirst {
         }
     }
 }
+
+fn credential_source_error(source: &str, error: Error) -> Error {
+    Error::authentication_error(
+        "MONGODB-AWS",
+        &format!("failed to obtain credentials from {}: {}", source, error),
+    )
+}
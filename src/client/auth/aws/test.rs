@@ -0,0 +1,285 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+};
+
+use chrono::{offset::Utc, TimeZone};
+
+use super::{AwsCredential, CredentialCache};
+use crate::{error::Result, runtime::HttpClient};
+
+/// Starts a stand-in HTTP server on a local port that answers requests for the paths in `routes`
+/// with the corresponding status and body, and any other request with a 404. Returns the server's
+/// base URL and a receiver for the HTTP requests it receives.
+fn start_server(routes: &[(&str, u16, &str)]) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let routes: HashMap<String, (u16, String)> = routes
+        .iter()
+        .map(|(path, status, body)| (path.to_string(), (*status, body.to_string())))
+        .collect();
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => return,
+            };
+
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buffer[..n]),
+                }
+            }
+            let request = String::from_utf8_lossy(&request).to_string();
+
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            let path = path.split('?').next().unwrap_or_default();
+            let (status, body) = routes
+                .get(path)
+                .cloned()
+                .unwrap_or_else(|| (404, "not found".to_string()));
+
+            // Send the body with the chunked transfer encoding when it is long enough to split.
+            let response = if body.len() > 16 {
+                let (first, second) = body.split_at(body.len() / 2);
+                format!(
+                    "HTTP/1.1 {} Status\r\nTransfer-Encoding: \
+                     chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                    status,
+                    first.len(),
+                    first,
+                    second.len(),
+                    second
+                )
+            } else {
+                format!(
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+            };
+            let _ = stream.write_all(response.as_bytes());
+            let _ = sender.send(request);
+        }
+    });
+
+    (url, receiver)
+}
+
+const CREDENTIALS_JSON: &str = r#"{
+    "Code": "Success",
+    "AccessKeyId": "ASIAEXAMPLE",
+    "SecretAccessKey": "secret",
+    "Token": "session-token",
+    "Expiration": "2030-01-01T00:00:00Z"
+}"#;
+
+fn expected_credential() -> AwsCredential {
+    AwsCredential {
+        access_key: "ASIAEXAMPLE".to_string(),
+        secret_key: "secret".to_string(),
+        session_token: Some("session-token".to_string()),
+        expiration: Some(Utc.timestamp_opt(1893456000, 0).unwrap()),
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn ec2_credentials() {
+    let (url, requests) = start_server(&[
+        ("/latest/api/token", 200, "imds-token"),
+        (
+            "/latest/meta-data/iam/security-credentials/",
+            200,
+            "test-role\n",
+        ),
+        (
+            "/latest/meta-data/iam/security-credentials/test-role",
+            200,
+            CREDENTIALS_JSON,
+        ),
+    ]);
+
+    let credential = AwsCredential::get_from_ec2(&HttpClient::default(), &url)
+        .await
+        .unwrap();
+    assert_eq!(credential, expected_credential());
+
+    let token_request = requests.recv().unwrap();
+    assert!(
+        token_request.starts_with("PUT /latest/api/token HTTP/1.1\r\n"),
+        "{}",
+        token_request
+    );
+    assert!(token_request.contains("X-aws-ec2-metadata-token-ttl-seconds: 30\r\n"));
+    for _ in 0..2 {
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("GET "), "{}", request);
+        assert!(request.contains("X-aws-ec2-metadata-token: imds-token\r\n"));
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn ecs_credentials() {
+    let (url, requests) = start_server(&[("/v2/credentials/abc", 200, CREDENTIALS_JSON)]);
+
+    let credential = AwsCredential::get_from_ecs(
+        &format!("{}/v2/credentials/abc", url),
+        &HttpClient::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(credential, expected_credential());
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /v2/credentials/abc HTTP/1.1\r\n"));
+
+    let (url, _) = start_server(&[("/v2/credentials/abc", 500, "internal error")]);
+    let error = AwsCredential::get_from_ecs(
+        &format!("{}/v2/credentials/abc", url),
+        &HttpClient::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("ECS endpoint"), "{}", error);
+    assert!(error.to_string().contains("500"), "{}", error);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn web_identity_credentials() {
+    let response = r#"{
+        "AssumeRoleWithWebIdentityResponse": {
+            "AssumeRoleWithWebIdentityResult": {
+                "Credentials": {
+                    "AccessKeyId": "ASIAEXAMPLE",
+                    "SecretAccessKey": "secret",
+                    "SessionToken": "session-token",
+                    "Expiration": 1893456000.0
+                }
+            }
+        }
+    }"#;
+    let (url, requests) = start_server(&[("/", 200, response)]);
+
+    let credential = AwsCredential::get_from_web_identity(
+        "header.payload.signature",
+        "arn:aws:iam::123456789012:role/test",
+        Some("session"),
+        &HttpClient::default(),
+        &url,
+    )
+    .await
+    .unwrap();
+    assert_eq!(credential, expected_credential());
+
+    let request = requests.recv().unwrap();
+    let request_line = request.lines().next().unwrap();
+    assert!(
+        request_line.starts_with("GET /?Action=AssumeRoleWithWebIdentity&"),
+        "{}",
+        request_line
+    );
+    assert!(request_line.contains("&RoleSessionName=session&"));
+    assert!(request_line.contains("&RoleArn=arn%3Aaws%3Aiam%3A%3A123456789012%3Arole%2Ftest&"));
+    assert!(request_line.contains("&WebIdentityToken=header%2Epayload%2Esignature&"));
+    assert!(request.contains("Accept: application/json\r\n"));
+}
+
+async fn fetch_counted(fetches: &AtomicUsize, credential: AwsCredential) -> Result<AwsCredential> {
+    fetches.fetch_add(1, Ordering::SeqCst);
+    Ok(credential)
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn temporary_credentials_are_cached() {
+    let cache = CredentialCache::default();
+    let fetches = AtomicUsize::new(0);
+
+    let credential = expected_credential();
+    for _ in 0..3 {
+        let cached = cache
+            .get_or_fetch(fetch_counted(&fetches, credential.clone()))
+            .await
+            .unwrap();
+        assert_eq!(cached, credential);
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+    // Credentials are fetched again once the cache is cleared.
+    cache.clear().await;
+    cache
+        .get_or_fetch(fetch_counted(&fetches, credential.clone()))
+        .await
+        .unwrap();
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+    // Credentials without an expiration are not cached.
+    let cache = CredentialCache::default();
+    let fetches = AtomicUsize::new(0);
+    let permanent = AwsCredential {
+        expiration: None,
+        ..expected_credential()
+    };
+    for _ in 0..2 {
+        cache
+            .get_or_fetch(fetch_counted(&fetches, permanent.clone()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn expiring_credentials_are_refreshed() {
+    let cache = CredentialCache::default();
+    let fetches = AtomicUsize::new(0);
+
+    // Credentials that expire within the expiration window are refreshed before they are reused.
+    let expiring = AwsCredential {
+        expiration: Some(Utc::now() + chrono::Duration::minutes(2)),
+        ..expected_credential()
+    };
+    for _ in 0..2 {
+        cache
+            .get_or_fetch(fetch_counted(&fetches, expiring.clone()))
+            .await
+            .unwrap();
+    }
+    assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+    let fresh = expected_credential();
+    cache
+        .get_or_fetch(fetch_counted(&fetches, fresh.clone()))
+        .await
+        .unwrap();
+    let cached = cache
+        .get_or_fetch(fetch_counted(&fetches, expiring))
+        .await
+        .unwrap();
+    assert_eq!(cached, fresh);
+    assert_eq!(fetches.load(Ordering::SeqCst), 3);
+
+    let now = Utc::now();
+    assert!(!fresh.expires_soon(now));
+    assert!(AwsCredential {
+        expiration: Some(now - chrono::Duration::seconds(1)),
+        ..fresh
+    }
+    .expires_soon(now));
+}
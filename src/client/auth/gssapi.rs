@@ -0,0 +1,312 @@
+#[cfg(feature = "gssapi-auth")]
+use cross_krb5::{ClientCtx, InitiateFlags, K5Ctx, Step};
+
+use crate::{
+    bson::Bson,
+    client::auth::Credential,
+    error::{Error, Result},
+};
+#[cfg(feature = "gssapi-auth")]
+use crate::{
+    client::{
+        auth::{
+            sasl::{SaslContinue, SaslResponse, SaslStart},
+            AuthMechanism,
+            GSSAPI_STR,
+        },
+        options::{DnsResolver, ResolverConfig, ServerApi},
+    },
+    cmap::Connection,
+    runtime::AsyncResolver,
+};
+
+const SERVICE_NAME: &str = "SERVICE_NAME";
+const CANONICALIZE_HOST_NAME: &str = "CANONICALIZE_HOST_NAME";
+const SERVICE_REALM: &str = "SERVICE_REALM";
+const SERVICE_HOST: &str = "SERVICE_HOST";
+
+/// The service name used when the `SERVICE_NAME` mechanism property is not specified.
+const DEFAULT_SERVICE_NAME: &str = "mongodb";
+
+/// Requests that no security layer be applied to messages after authentication, as described in
+/// [RFC 4752](https://tools.ietf.org/html/rfc4752#section-3.1).
+#[cfg(feature = "gssapi-auth")]
+const NO_SECURITY_LAYER: u8 = 0x01;
+
+/// How the hostname of the server is canonicalized before being used to build the service
+/// principal name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum CanonicalizeHostName {
+    /// The hostname is used as is.
+    None,
+
+    /// The hostname is replaced by its canonical name from a forward DNS lookup.
+    Forward,
+
+    /// The hostname is resolved to an address, and the name from a reverse DNS lookup on that
+    /// address is used.
+    ForwardAndReverse,
+}
+
+/// The GSSAPI mechanism properties of a credential.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(not(feature = "gssapi-auth"), allow(dead_code))]
+pub(super) struct GssapiProperties {
+    pub(super) service_name: String,
+    pub(super) canonicalize_host_name: CanonicalizeHostName,
+    pub(super) service_realm: Option<String>,
+    pub(super) service_host: Option<String>,
+}
+
+impl GssapiProperties {
+    /// Parses the mechanism properties of `credential`, returning an error if any are unknown or
+    /// have invalid values.
+    pub(super) fn from_credential(credential: &Credential) -> Result<Self> {
+        let mut properties = GssapiProperties {
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            canonicalize_host_name: CanonicalizeHostName::None,
+            service_realm: None,
+            service_host: None,
+        };
+
+        let document = match credential.mechanism_properties {
+            Some(ref document) => document,
+            None => return Ok(properties),
+        };
+
+        for (key, value) in document {
+            match (key.as_str(), value) {
+                (SERVICE_NAME, Bson::String(name)) => properties.service_name = name.clone(),
+                (CANONICALIZE_HOST_NAME, Bson::Boolean(canonicalize)) => {
+                    properties.canonicalize_host_name = if *canonicalize {
+                        CanonicalizeHostName::ForwardAndReverse
+                    } else {
+                        CanonicalizeHostName::None
+                    };
+                }
+                (CANONICALIZE_HOST_NAME, Bson::String(mode)) => {
+                    properties.canonicalize_host_name = match mode.as_str() {
+                        "none" => CanonicalizeHostName::None,
+                        "forward" => CanonicalizeHostName::Forward,
+                        "forwardAndReverse" => CanonicalizeHostName::ForwardAndReverse,
+                        _ => {
+                            return Err(Error::invalid_argument(format!(
+                                "invalid {} value for GSSAPI authentication: {}; expected one of \
+                                 \"none\", \"forward\", \"forwardAndReverse\", \"true\" or \
+                                 \"false\"",
+                                CANONICALIZE_HOST_NAME, mode
+                            )))
+                        }
+                    };
+                }
+                (SERVICE_REALM, Bson::String(realm)) => {
+                    properties.service_realm = Some(realm.clone())
+                }
+                (SERVICE_HOST, Bson::String(host)) => properties.service_host = Some(host.clone()),
+                (SERVICE_NAME, _)
+                | (CANONICALIZE_HOST_NAME, _)
+                | (SERVICE_REALM, _)
+                | (SERVICE_HOST, _) => {
+                    return Err(Error::invalid_argument(format!(
+                        "invalid type for the {} mechanism property: {}",
+                        key, value
+                    )))
+                }
+                _ => {
+                    return Err(Error::invalid_argument(format!(
+                        "unknown mechanism property for GSSAPI authentication: {}",
+                        key
+                    )))
+                }
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// Returns the name of the service principal for the server running on `host`, e.g.
+    /// "mongodb/db.example.com@EXAMPLE.COM".
+    #[cfg_attr(not(feature = "gssapi-auth"), allow(dead_code))]
+    pub(super) fn service_principal(&self, host: &str) -> String {
+        match self.service_realm {
+            Some(ref realm) => format!("{}/{}@{}", self.service_name, host, realm),
+            None => format!("{}/{}", self.service_name, host),
+        }
+    }
+}
+
+/// Performs GSSAPI (Kerberos) authentication for a given stream. The credentials of the user are
+/// taken from the Kerberos credential cache, so a ticket must already have been obtained (e.g. via
+/// `kinit` or a keytab).
+#[cfg(feature = "gssapi-auth")]
+pub(super) async fn authenticate_stream(
+    conn: &mut Connection,
+    credential: &Credential,
+    server_api: Option<&ServerApi>,
+    resolver_config: Option<&ResolverConfig>,
+    dns_resolver: Option<&dyn DnsResolver>,
+) -> Result<()> {
+    let properties = GssapiProperties::from_credential(credential)?;
+    let source = credential.source.as_deref().unwrap_or("$external");
+    let user_principal = credential
+        .username
+        .as_deref()
+        .ok_or_else(|| Error::authentication_error(GSSAPI_STR, "no username supplied"))?;
+
+    let host = match properties.service_host {
+        Some(ref host) => host.clone(),
+        None => conn.address.host().into_owned(),
+    };
+    let host = canonicalize_host_name(
+        host,
+        properties.canonicalize_host_name,
+        resolver_config,
+        dns_resolver,
+    )
+    .await?;
+    let service_principal = properties.service_principal(&host);
+
+    let (mut pending, token) = ClientCtx::new(
+        InitiateFlags::empty(),
+        Some(user_principal),
+        &service_principal,
+        None,
+    )
+    .map_err(|e| gssapi_error("failed to initialize the security context", e))?;
+
+    let command = SaslStart::new(
+        source.into(),
+        AuthMechanism::Gssapi,
+        token.to_vec(),
+        server_api.cloned(),
+    )
+    .into_command();
+    let response = conn.send_command(command, None).await?;
+    let mut response = SaslResponse::parse(GSSAPI_STR, response.auth_response_body(GSSAPI_STR)?)?;
+    let conversation_id = response.conversation_id.clone();
+
+    // Exchange tokens with the server until the security context is established. The last token
+    // is sent even if it is empty so that the server can respond with the security layer
+    // challenge.
+    let mut context = loop {
+        if response.done {
+            return Err(Error::invalid_authentication_response(GSSAPI_STR));
+        }
+
+        match pending
+            .step(&response.payload)
+            .map_err(|e| gssapi_error("failed to establish the security context", e))?
+        {
+            Step::Continue((next, token)) => {
+                pending = next;
+                response =
+                    sasl_continue(conn, source, &conversation_id, token.to_vec(), server_api)
+                        .await?;
+            }
+            Step::Finished((context, token)) => {
+                let token = token.map(|token| token.to_vec()).unwrap_or_default();
+                response = sasl_continue(conn, source, &conversation_id, token, server_api).await?;
+                break context;
+            }
+        }
+    };
+
+    // The server sends the security layers it supports and its maximum message size, to which the
+    // client responds without requesting a security layer.
+    let challenge = context
+        .unwrap(&response.payload)
+        .map_err(|e| gssapi_error("failed to unwrap the security layer challenge", e))?;
+    if challenge.len() != 4 {
+        return Err(Error::invalid_authentication_response(GSSAPI_STR));
+    }
+
+    let mut message = vec![NO_SECURITY_LAYER, 0, 0, 0];
+    message.extend_from_slice(user_principal.as_bytes());
+    let message = context
+        .wrap(false, &message)
+        .map_err(|e| gssapi_error("failed to wrap the security layer response", e))?;
+
+    let response =
+        sasl_continue(conn, source, &conversation_id, message.to_vec(), server_api).await?;
+    if !response.done {
+        return Err(Error::invalid_authentication_response(GSSAPI_STR));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "gssapi-auth")]
+async fn sasl_continue(
+    conn: &mut Connection,
+    source: &str,
+    conversation_id: &Bson,
+    payload: Vec<u8>,
+    server_api: Option<&ServerApi>,
+) -> Result<SaslResponse> {
+    let command = SaslContinue::new(
+        source.into(),
+        conversation_id.clone(),
+        payload,
+        server_api.cloned(),
+    )
+    .into_command();
+    let response = conn.send_command(command, None).await?;
+    SaslResponse::parse(GSSAPI_STR, response.auth_response_body(GSSAPI_STR)?)
+}
+
+/// Canonicalizes `host` according to the `CANONICALIZE_HOST_NAME` mechanism property. The
+/// original or forward-resolved name is used if a lookup does not return any records.
+///
+/// The CNAME and PTR lookups are done with trust-dns using `resolver_config`, if any. The address
+/// of the host is looked up with `dns_resolver` if it provides one, in the same way as when
+/// connecting to the server.
+#[cfg(feature = "gssapi-auth")]
+async fn canonicalize_host_name(
+    host: String,
+    mode: CanonicalizeHostName,
+    resolver_config: Option<&ResolverConfig>,
+    dns_resolver: Option<&dyn DnsResolver>,
+) -> Result<String> {
+    if mode == CanonicalizeHostName::None {
+        return Ok(host);
+    }
+
+    let resolver = AsyncResolver::new(resolver_config.map(|config| config.inner.clone())).await?;
+    let canonical_name = resolver.canonical_name(&host).await?.unwrap_or(host);
+    if mode == CanonicalizeHostName::Forward {
+        return Ok(normalize_host_name(canonical_name));
+    }
+
+    let addresses = match dns_resolver {
+        Some(dns_resolver) => dns_resolver.ip_lookup(&canonical_name).await?,
+        None => None,
+    };
+    let addresses = match addresses {
+        Some(addresses) => addresses,
+        None => resolver.lookup_ip(&canonical_name).await?,
+    };
+    let address = addresses.into_iter().next().ok_or_else(|| {
+        Error::authentication_error(
+            GSSAPI_STR,
+            &format!("no addresses found for {}", canonical_name),
+        )
+    })?;
+    let name = resolver
+        .reverse_lookup(address)
+        .await?
+        .unwrap_or(canonical_name);
+
+    Ok(normalize_host_name(name))
+}
+
+/// Lowercases a name returned by a DNS lookup and removes the trailing dot of a fully qualified
+/// domain name.
+#[cfg(feature = "gssapi-auth")]
+fn normalize_host_name(name: String) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+#[cfg(feature = "gssapi-auth")]
+fn gssapi_error(message: &str, error: impl std::fmt::Display) -> Error {
+    Error::authentication_error(GSSAPI_STR, &format!("{}: {}", message, error))
+}
@@ -3,24 +3,33 @@
 
 #[cfg(feature = "aws-auth")]
 mod aws;
+mod gssapi;
+pub(crate) mod oidc;
 mod plain;
+mod provider;
 mod sasl;
 mod scram;
 #[cfg(test)]
 mod test;
 mod x509;
 
-use std::{borrow::Cow, fmt::Debug, str::FromStr};
+use std::{borrow::Cow, fmt::Debug, str::FromStr, sync::Arc};
 
+use derivative::Derivative;
 use hmac::{digest::KeyInit, Mac};
 use rand::Rng;
 use serde::Deserialize;
 use typed_builder::TypedBuilder;
 
-use self::scram::ScramVersion;
+pub(crate) use self::provider::{provide_credential, ClientCredentialProvider};
+use self::{gssapi::GssapiProperties, scram::ScramVersion};
+pub use self::{
+    oidc::{IdpInfo, OidcCallback, OidcCallbackContext, OidcToken},
+    provider::CredentialProvider,
+};
 use crate::{
     bson::Document,
-    client::options::ServerApi,
+    client::options::{DnsResolver, ResolverConfig, ServerApi},
     cmap::{Command, Connection, StreamDescription},
     error::{Error, ErrorKind, Result},
     runtime::HttpClient,
@@ -33,6 +42,7 @@ const GSSAPI_STR: &str = "GSSAPI";
 const MONGODB_AWS_STR: &str = "MONGODB-AWS";
 const MONGODB_X509_STR: &str = "MONGODB-X509";
 const PLAIN_STR: &str = "PLAIN";
+const MONGODB_OIDC_STR: &str = "MONGODB-OIDC";
 
 /// The authentication mechanisms supported by MongoDB.
 ///
@@ -64,7 +74,21 @@ pub enum AuthMechanism {
     ///
     /// See the [MongoDB documentation](https://docs.mongodb.com/manual/core/kerberos/) for more information.
     ///
-    /// Note: This mechanism is not currently supported by this driver but will be in the future.
+    /// The following mechanism properties are supported:
+    ///   * `SERVICE_NAME`: the service name of the server, which defaults to "mongodb"
+    ///   * `CANONICALIZE_HOST_NAME`: how the hostname of the server is canonicalized; one of
+    ///     "none" (the default), "forward" or "forwardAndReverse". `true` and `false` are also
+    ///     accepted and are equivalent to "forwardAndReverse" and "none" respectively
+    ///   * `SERVICE_REALM`: the realm of the service principal, if it differs from the realm of
+    ///     the user
+    ///   * `SERVICE_HOST`: the hostname to use in the service principal instead of the hostname of
+    ///     the server
+    ///
+    /// The user's Kerberos credentials are taken from the credential cache, so a ticket must
+    /// already have been obtained (e.g. via `kinit`) before authenticating. Any password in the
+    /// credential is ignored.
+    ///
+    /// Note: This mechanism is only supported with the `gssapi-auth` feature flag.
     Gssapi,
 
     /// The SASL PLAIN mechanism, as defined in [RFC 4616](), is used in MongoDB to perform LDAP
@@ -77,14 +101,35 @@ pub enum AuthMechanism {
 
     /// MONGODB-AWS authenticates using AWS IAM credentials (an access key ID and a secret access
     /// key), temporary AWS IAM credentials obtained from an AWS Security Token Service (STS)
-    /// Assume Role request, or temporary AWS IAM credentials assigned to an EC2 instance or ECS
-    /// task.
+    /// Assume Role or AssumeRoleWithWebIdentity request, or temporary AWS IAM credentials assigned
+    /// to an EC2 instance or ECS task.
+    ///
+    /// If no credentials are specified in the `Credential` or the `AWS_ACCESS_KEY_ID` and
+    /// `AWS_SECRET_ACCESS_KEY` environment variables, temporary credentials are obtained from the
+    /// first of the following sources that is configured:
+    ///   - an AssumeRoleWithWebIdentity request using the token in `AWS_WEB_IDENTITY_TOKEN_FILE`
+    ///     and the role in `AWS_ROLE_ARN` (with an optional `AWS_ROLE_SESSION_NAME`)
+    ///   - the ECS credentials endpoint given by `AWS_CONTAINER_CREDENTIALS_RELATIVE_URI` or
+    ///     `AWS_CONTAINER_CREDENTIALS_FULL_URI`
+    ///   - the EC2 instance metadata service
+    ///
+    /// Temporary credentials are cached and shared by all connections until shortly before they
+    /// expire. The endpoints of the EC2 instance metadata service and STS can be overridden with
+    /// the `AWS_EC2_METADATA_SERVICE_ENDPOINT` and `AWS_ENDPOINT_URL_STS` environment variables.
     ///
-    /// Note: Only server versions 4.4+ support AWS authentication. Additionally, the driver only
-    /// supports AWS authentication with the tokio runtime.
+    /// Note: Only server versions 4.4+ support AWS authentication.
     #[cfg(any(feature = "aws-auth", docsrs))]
     #[cfg_attr(docsrs, doc(cfg(feature = "aws-auth")))]
     MongoDbAws,
+
+    /// MONGODB-OIDC authenticates using access tokens issued by an OpenID Connect identity
+    /// provider. The tokens are obtained from the callback set in
+    /// [`Credential::oidc_callback`] and cached by the `Client`. When the server reports that a
+    /// connection must reauthenticate, e.g. because its token has expired, the connection
+    /// reauthenticates with a new token and the operation is run again.
+    ///
+    /// Note: Only server versions 7.0+ support OIDC authentication.
+    MongoDbOidc,
 }
 
 impl AuthMechanism {
@@ -165,6 +210,45 @@ impl AuthMechanism {
 
                 Ok(())
             }
+            AuthMechanism::Gssapi => {
+                if credential.username.is_none() {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "No username provided for GSSAPI authentication".to_string(),
+                    }
+                    .into());
+                }
+
+                if credential.source.as_deref().unwrap_or("$external") != "$external" {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "only $external may be specified as an auth source for GSSAPI"
+                            .to_string(),
+                    }
+                    .into());
+                }
+
+                GssapiProperties::from_credential(credential)?;
+
+                Ok(())
+            }
+            AuthMechanism::MongoDbOidc => {
+                if credential.password.is_some() {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "A password cannot be specified with MONGODB-OIDC".to_string(),
+                    }
+                    .into());
+                }
+
+                if credential.source.as_deref().unwrap_or("$external") != "$external" {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "only $external may be specified as an auth source for \
+                                  MONGODB-OIDC"
+                            .to_string(),
+                    }
+                    .into());
+                }
+
+                Ok(())
+            }
             #[cfg(feature = "aws-auth")]
             AuthMechanism::MongoDbAws => {
                 if credential.username.is_some() && credential.password.is_none() {
@@ -191,6 +275,7 @@ impl AuthMechanism {
             AuthMechanism::MongoDbX509 => MONGODB_X509_STR,
             AuthMechanism::Gssapi => GSSAPI_STR,
             AuthMechanism::Plain => PLAIN_STR,
+            AuthMechanism::MongoDbOidc => MONGODB_OIDC_STR,
             #[cfg(feature = "aws-auth")]
             AuthMechanism::MongoDbAws => MONGODB_AWS_STR,
         }
@@ -199,16 +284,16 @@ impl AuthMechanism {
     /// Get the default authSource for a given mechanism depending on the database provided in the
     /// connection string.
     pub(crate) fn default_source<'a>(&'a self, uri_db: Option<&'a str>) -> &'a str {
-        // TODO: fill in others as they're implemented
         match self {
             AuthMechanism::ScramSha1 | AuthMechanism::ScramSha256 | AuthMechanism::MongoDbCr => {
                 uri_db.unwrap_or("admin")
             }
-            AuthMechanism::MongoDbX509 => "$external",
+            AuthMechanism::MongoDbX509 | AuthMechanism::Gssapi | AuthMechanism::MongoDbOidc => {
+                "$external"
+            }
             AuthMechanism::Plain => uri_db.unwrap_or("$external"),
             #[cfg(feature = "aws-auth")]
             AuthMechanism::MongoDbAws => "$external",
-            _ => "",
         }
     }
 
@@ -233,7 +318,9 @@ impl AuthMechanism {
             Self::MongoDbX509 => Ok(Some(ClientFirst::X509(Box::new(
                 x509::build_speculative_client_first(credential),
             )))),
-            Self::Plain => Ok(None),
+            Self::Plain | Self::Gssapi => Ok(None),
+            Self::MongoDbOidc => Ok(oidc::build_speculative_client_first(credential)
+                .map(|client_first| ClientFirst::Oidc(Box::new(client_first)))),
             #[cfg(feature = "aws-auth")]
             AuthMechanism::MongoDbAws => Ok(None),
             AuthMechanism::MongoDbCr => Err(ErrorKind::Authentication {
@@ -242,10 +329,6 @@ impl AuthMechanism {
                     .into(),
             }
             .into()),
-            _ => Err(ErrorKind::Authentication {
-                message: format!("Authentication mechanism {:?} not yet implemented.", self),
-            }
-            .into()),
         }
     }
 
@@ -255,6 +338,12 @@ impl AuthMechanism {
         credential: &Credential,
         server_api: Option<&ServerApi>,
         #[cfg_attr(not(feature = "aws-auth"), allow(unused))] http_client: &HttpClient,
+        #[cfg_attr(not(feature = "gssapi-auth"), allow(unused))] resolver_config: Option<
+            &ResolverConfig,
+        >,
+        #[cfg_attr(not(feature = "gssapi-auth"), allow(unused))] dns_resolver: Option<
+            &dyn DnsResolver,
+        >,
     ) -> Result<()> {
         self.validate_credential(credential)?;
 
@@ -275,6 +364,27 @@ impl AuthMechanism {
             AuthMechanism::Plain => {
                 plain::authenticate_stream(stream, credential, server_api).await
             }
+            #[cfg(feature = "gssapi-auth")]
+            AuthMechanism::Gssapi => {
+                gssapi::authenticate_stream(
+                    stream,
+                    credential,
+                    server_api,
+                    resolver_config,
+                    dns_resolver,
+                )
+                .await
+            }
+            #[cfg(not(feature = "gssapi-auth"))]
+            AuthMechanism::Gssapi => Err(ErrorKind::Authentication {
+                message: "GSSAPI authentication is only supported with the gssapi-auth feature \
+                          flag"
+                    .into(),
+            }
+            .into()),
+            AuthMechanism::MongoDbOidc => {
+                oidc::authenticate_stream(stream, credential, server_api, None).await
+            }
             #[cfg(feature = "aws-auth")]
             AuthMechanism::MongoDbAws => {
                 aws::authenticate_stream(stream, credential, server_api, http_client).await
@@ -285,10 +395,6 @@ impl AuthMechanism {
                     .into(),
             }
             .into()),
-            _ => Err(ErrorKind::Authentication {
-                message: format!("Authentication mechanism {:?} not yet implemented.", self),
-            }
-            .into()),
         }
     }
 }
@@ -304,6 +410,7 @@ impl FromStr for AuthMechanism {
             MONGODB_X509_STR => Ok(AuthMechanism::MongoDbX509),
             GSSAPI_STR => Ok(AuthMechanism::Gssapi),
             PLAIN_STR => Ok(AuthMechanism::Plain),
+            MONGODB_OIDC_STR => Ok(AuthMechanism::MongoDbOidc),
 
             #[cfg(feature = "aws-auth")]
             MONGODB_AWS_STR => Ok(AuthMechanism::MongoDbAws),
@@ -327,7 +434,8 @@ impl FromStr for AuthMechanism {
 ///
 /// Some fields (mechanism and source) may be omitted and will either be negotiated or assigned a
 /// default value, depending on the values of other fields in the credential.
-#[derive(Clone, Default, Deserialize, TypedBuilder, PartialEq)]
+#[derive(Clone, Default, Deserialize, TypedBuilder, Derivative)]
+#[derivative(PartialEq)]
 #[builder(field_defaults(default, setter(into)))]
 #[non_exhaustive]
 pub struct Credential {
@@ -349,6 +457,19 @@ pub struct Credential {
 
     /// Additional properties for the given mechanism.
     pub mechanism_properties: Option<Document>,
+
+    /// The callback used to obtain access tokens for MONGODB-OIDC authentication. This must be
+    /// set when using that mechanism.
+    #[serde(skip)]
+    #[derivative(PartialEq = "ignore")]
+    pub oidc_callback: Option<Arc<dyn OidcCallback>>,
+
+    /// The MONGODB-OIDC token cache. Each `Client` replaces this with its own cache, which is
+    /// then shared by all of its connections.
+    #[serde(skip)]
+    #[derivative(PartialEq = "ignore")]
+    #[builder(setter(skip))]
+    pub(crate) oidc_cache: Arc<oidc::Cache>,
 }
 
 impl Credential {
@@ -395,13 +516,16 @@ impl Credential {
 
     /// Attempts to authenticate a stream according this credential, returning an error
     /// result on failure. A mechanism may be negotiated if one is not provided as part of the
-    /// credential.
+    /// credential. The resolver configuration and custom resolver are used for the DNS lookups
+    /// done by GSSAPI authentication.
     pub(crate) async fn authenticate_stream(
         &self,
         conn: &mut Connection,
         http_client: &HttpClient,
         server_api: Option<&ServerApi>,
         first_round: Option<FirstRound>,
+        resolver_config: Option<&ResolverConfig>,
+        dns_resolver: Option<&dyn DnsResolver>,
     ) -> Result<()> {
         let stream_description = conn.stream_description()?;
 
@@ -422,6 +546,9 @@ impl Credential {
                 FirstRound::X509(server_first) => {
                     x509::authenticate_stream(conn, self, server_api, server_first).await
                 }
+                FirstRound::Oidc(first_round) => {
+                    oidc::authenticate_stream(conn, self, server_api, Some(first_round)).await
+                }
             };
         }
 
@@ -432,10 +559,37 @@ impl Credential {
 
         // Authenticate according to the chosen mechanism.
         mechanism
-            .authenticate_stream(conn, self, server_api, http_client)
+            .authenticate_stream(
+                conn,
+                self,
+                server_api,
+                http_client,
+                resolver_config,
+                dns_resolver,
+            )
             .await
     }
 
+    /// Reauthenticates a connection after the server has returned a ReauthenticationRequired
+    /// error. Only MONGODB-OIDC supports reauthentication.
+    pub(crate) async fn reauthenticate_stream(
+        &self,
+        conn: &mut Connection,
+        server_api: Option<&ServerApi>,
+    ) -> Result<()> {
+        match self.mechanism {
+            Some(AuthMechanism::MongoDbOidc) => {
+                oidc::reauthenticate_stream(conn, self, server_api).await
+            }
+            _ => Err(ErrorKind::Authentication {
+                message: "the server requested reauthentication, which is only supported for \
+                          MONGODB-OIDC"
+                    .into(),
+            }
+            .into()),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn serialize_for_client_options<S>(
         credential: &Option<Credential>,
@@ -474,6 +628,7 @@ impl Debug for Credential {
 pub(crate) enum ClientFirst {
     Scram(ScramVersion, scram::ClientFirst),
     X509(Box<Command>),
+    Oidc(Box<oidc::ClientFirst>),
 }
 
 impl ClientFirst {
@@ -481,6 +636,7 @@ impl ClientFirst {
         match self {
             Self::Scram(version, client_first) => client_first.to_command(version).body,
             Self::X509(command) => command.body.clone(),
+            Self::Oidc(client_first) => client_first.command.body.clone(),
         }
     }
 
@@ -494,6 +650,9 @@ impl ClientFirst {
                 },
             ),
             Self::X509(..) => FirstRound::X509(server_first),
+            Self::Oidc(client_first) => {
+                FirstRound::Oidc(client_first.into_first_round(server_first))
+            }
         }
     }
 }
@@ -504,6 +663,7 @@ impl ClientFirst {
 pub(crate) enum FirstRound {
     Scram(ScramVersion, scram::FirstRound),
     X509(Document),
+    Oidc(oidc::FirstRound),
 }
 
 pub(crate) fn generate_nonce_bytes() -> [u8; 32] {
@@ -0,0 +1,464 @@
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    bson::{doc, Bson, Document},
+    client::{
+        auth::{
+            sasl::{SaslContinue, SaslResponse, SaslStart},
+            AuthMechanism,
+            Credential,
+            MONGODB_OIDC_STR,
+        },
+        options::ServerApi,
+    },
+    cmap::{Command, Connection},
+    error::{Error, Result},
+    runtime,
+};
+
+/// The version of the callback API implemented by the driver.
+const CALLBACK_API_VERSION: u32 = 1;
+
+/// How long a callback that does not use the identity provider information is given to return a
+/// token.
+const MACHINE_CALLBACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a callback that uses the identity provider information is given to return a token. This
+/// is longer because such callbacks may require the user to log in interactively.
+const HUMAN_CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The minimum amount of time between consecutive calls to a callback.
+const CALLBACK_THROTTLE: Duration = Duration::from_millis(100);
+
+/// The error code returned by the server when a token is rejected.
+const AUTHENTICATION_FAILED_CODE: i32 = 18;
+
+/// A callback used to obtain access tokens for MONGODB-OIDC authentication, set via
+/// [`Credential::oidc_callback`](crate::options::Credential::oidc_callback).
+///
+/// The tokens returned are cached by the `Client` and shared by all of its connections, so the
+/// callback is only called again once the cached token has expired or has been rejected by the
+/// server. Calls to the callback are never made concurrently.
+///
+/// Errors returned by the callback can be created from a `std::io::Error`.
+///
+/// ```rust
+/// # use std::{future::Future, pin::Pin};
+/// # use mongodb::{error::Result, options::{OidcCallback, OidcCallbackContext, OidcToken}};
+/// struct WorkloadIdentity;
+///
+/// impl OidcCallback for WorkloadIdentity {
+///     fn request_token<'a>(
+///         &'a self,
+///         _context: OidcCallbackContext,
+///     ) -> Pin<Box<dyn Future<Output = Result<OidcToken>> + Send + 'a>> {
+///         Box::pin(async move {
+///             let access_token = std::fs::read_to_string("/var/run/secrets/tokens/mongodb")?;
+///             Ok(OidcToken::builder().access_token(access_token).build())
+///         })
+///     }
+/// }
+/// ```
+pub trait OidcCallback: Send + Sync {
+    /// Returns a new access token. The callback is abandoned if it does not return before
+    /// [`OidcCallbackContext::deadline`].
+    fn request_token<'a>(
+        &'a self,
+        context: OidcCallbackContext,
+    ) -> Pin<Box<dyn Future<Output = Result<OidcToken>> + Send + 'a>>;
+
+    /// Whether the callback needs the information about the identity provider that the server
+    /// advertises, e.g. because it performs an interactive login with that provider. If this
+    /// returns `true`, [`OidcCallbackContext::idp_info`] is always set and the callback is given
+    /// more time to return.
+    ///
+    /// The default implementation returns `false`.
+    fn requires_idp_info(&self) -> bool {
+        false
+    }
+}
+
+/// The information given to an [`OidcCallback`] when a new token is requested.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct OidcCallbackContext {
+    /// The time by which the callback must return a token.
+    pub deadline: Instant,
+
+    /// The version of the callback API, which is currently 1.
+    pub version: u32,
+
+    /// The username of the credential, if one was specified.
+    pub username: Option<String>,
+
+    /// The information about the identity provider advertised by the server. This is only set if
+    /// [`OidcCallback::requires_idp_info`] returns `true`.
+    pub idp_info: Option<IdpInfo>,
+
+    /// The refresh token returned by the previous call to the callback, if any, which can be used
+    /// to obtain a new access token without user interaction.
+    pub refresh_token: Option<String>,
+}
+
+/// Information about the identity provider that the server accepts tokens from.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IdpInfo {
+    /// The URL of the identity provider.
+    pub issuer: String,
+
+    /// The ID of the client application registered with the identity provider.
+    pub client_id: Option<String>,
+
+    /// The scopes to request from the identity provider.
+    pub request_scopes: Option<Vec<String>>,
+}
+
+/// An access token returned by an [`OidcCallback`].
+#[derive(Clone, TypedBuilder)]
+#[non_exhaustive]
+pub struct OidcToken {
+    /// The access token used to authenticate with the server.
+    #[builder(setter(into))]
+    pub access_token: String,
+
+    /// When the access token expires. Expired tokens are not reused for new connections.
+    #[builder(default, setter(strip_option))]
+    pub expires: Option<Instant>,
+
+    /// A refresh token, which is given to the callback the next time a token is requested.
+    #[builder(default, setter(into, strip_option))]
+    pub refresh_token: Option<String>,
+}
+
+impl Debug for OidcToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("OidcToken")
+            .field(&"REDACTED".to_string())
+            .finish()
+    }
+}
+
+/// The MONGODB-OIDC tokens of a `Client`, which are shared by all of its connections.
+#[derive(Default)]
+pub(crate) struct Cache {
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    access_token: Option<String>,
+    expires: Option<Instant>,
+    refresh_token: Option<String>,
+    idp_info: Option<IdpInfo>,
+    last_call: Option<Instant>,
+}
+
+impl CacheState {
+    fn valid_access_token(&self) -> Option<String> {
+        match self.expires {
+            Some(expires) if expires <= Instant::now() => None,
+            _ => self.access_token.clone(),
+        }
+    }
+}
+
+impl Debug for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cache").finish()
+    }
+}
+
+impl Cache {
+    /// Returns the cached access token if it has not expired. This does not wait for a token that
+    /// is currently being requested.
+    fn try_access_token(&self) -> Option<String> {
+        self.state
+            .try_lock()
+            .ok()
+            .and_then(|state| state.valid_access_token())
+    }
+
+    pub(super) async fn access_token(&self) -> Option<String> {
+        self.state.lock().await.valid_access_token()
+    }
+
+    async fn has_idp_info(&self) -> bool {
+        self.state.lock().await.idp_info.is_some()
+    }
+
+    /// Removes `access_token` from the cache, unless it has already been replaced by a newer one.
+    pub(super) async fn invalidate(&self, access_token: &str) {
+        let mut state = self.state.lock().await;
+        if state.access_token.as_deref() == Some(access_token) {
+            state.access_token = None;
+            state.expires = None;
+        }
+    }
+
+    /// Returns a valid access token, calling the callback if the cache does not contain one. If
+    /// `idp_info` is provided, it replaces the cached identity provider information.
+    pub(super) async fn request_token(
+        &self,
+        callback: &dyn OidcCallback,
+        credential: &Credential,
+        idp_info: Option<IdpInfo>,
+    ) -> Result<String> {
+        let mut state = self.state.lock().await;
+        if let Some(idp_info) = idp_info {
+            state.idp_info = Some(idp_info);
+        }
+
+        // Another connection may have obtained a new token while this one was waiting.
+        if let Some(access_token) = state.valid_access_token() {
+            return Ok(access_token);
+        }
+
+        if let Some(last_call) = state.last_call {
+            let elapsed = last_call.elapsed();
+            if elapsed < CALLBACK_THROTTLE {
+                runtime::delay_for(CALLBACK_THROTTLE - elapsed).await;
+            }
+        }
+
+        let (timeout, idp_info) = if callback.requires_idp_info() {
+            (HUMAN_CALLBACK_TIMEOUT, state.idp_info.clone())
+        } else {
+            (MACHINE_CALLBACK_TIMEOUT, None)
+        };
+        // The refresh token is only replaced once the callback returns a new token, so that it
+        // isn't lost if the callback fails.
+        let context = OidcCallbackContext {
+            deadline: Instant::now() + timeout,
+            version: CALLBACK_API_VERSION,
+            username: credential.username.clone(),
+            idp_info,
+            refresh_token: state.refresh_token.clone(),
+        };
+
+        state.last_call = Some(Instant::now());
+        let token = match runtime::timeout(timeout, callback.request_token(context)).await {
+            Ok(token) => token?,
+            Err(_) => {
+                return Err(Error::authentication_error(
+                    MONGODB_OIDC_STR,
+                    "the callback did not return a token before the deadline",
+                ))
+            }
+        };
+
+        state.access_token = Some(token.access_token.clone());
+        state.expires = token.expires;
+        state.refresh_token = token.refresh_token;
+
+        Ok(token.access_token)
+    }
+}
+
+/// Contains the first client message sent as part of speculative authentication.
+pub(crate) struct ClientFirst {
+    pub(crate) command: Command,
+    access_token: String,
+}
+
+/// Contains the speculative authentication attempt made during the handshake and the server's
+/// response to it.
+#[derive(Debug)]
+pub(crate) struct FirstRound {
+    access_token: String,
+    server_first: Document,
+}
+
+impl ClientFirst {
+    pub(crate) fn into_first_round(self, server_first: Document) -> FirstRound {
+        FirstRound {
+            access_token: self.access_token,
+            server_first,
+        }
+    }
+}
+
+/// Constructs the speculative authentication message if a valid access token is cached. The
+/// server's response determines whether the token is accepted.
+pub(crate) fn build_speculative_client_first(credential: &Credential) -> Option<ClientFirst> {
+    let access_token = credential.oidc_cache.try_access_token()?;
+    let source = credential.source.as_deref().unwrap_or("$external");
+
+    let mut command = SaslStart::new(
+        source.into(),
+        AuthMechanism::MongoDbOidc,
+        jwt_payload(&access_token).ok()?,
+        None,
+    )
+    .into_command();
+    command.body.insert("db", source);
+
+    Some(ClientFirst {
+        command,
+        access_token,
+    })
+}
+
+/// Performs MONGODB-OIDC authentication for a given stream.
+pub(super) async fn authenticate_stream(
+    conn: &mut Connection,
+    credential: &Credential,
+    server_api: Option<&ServerApi>,
+    first_round: Option<FirstRound>,
+) -> Result<()> {
+    if let Some(first_round) = first_round {
+        let response = SaslResponse::parse(MONGODB_OIDC_STR, first_round.server_first)?;
+        if !response.done {
+            return Err(Error::invalid_authentication_response(MONGODB_OIDC_STR));
+        }
+        conn.oidc_access_token = Some(first_round.access_token);
+        return Ok(());
+    }
+
+    let callback = credential
+        .oidc_callback
+        .as_deref()
+        .ok_or_else(|| Error::authentication_error(MONGODB_OIDC_STR, "no callback was provided"))?;
+    let cache = &credential.oidc_cache;
+
+    // A cached token is tried first, and is replaced with a new one if the server rejects it.
+    if let Some(access_token) = cache.access_token().await {
+        match send_access_token(conn, credential, server_api, &access_token).await {
+            Ok(()) => {
+                conn.oidc_access_token = Some(access_token);
+                return Ok(());
+            }
+            Err(e) if e.code() == Some(AUTHENTICATION_FAILED_CODE) => {
+                cache.invalidate(&access_token).await
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let access_token = if callback.requires_idp_info() && !cache.has_idp_info().await {
+        // The server provides the identity provider information in response to the first message,
+        // which is then used to obtain the token sent in the second.
+        let (conversation_id, idp_info) = request_idp_info(conn, credential, server_api).await?;
+        let access_token = cache
+            .request_token(callback, credential, Some(idp_info))
+            .await?;
+
+        let command = SaslContinue::new(
+            source(credential).into(),
+            conversation_id,
+            jwt_payload(&access_token)?,
+            server_api.cloned(),
+        )
+        .into_command();
+        let response = conn.send_command(command, None).await?;
+        let response = SaslResponse::parse(
+            MONGODB_OIDC_STR,
+            response.auth_response_body(MONGODB_OIDC_STR)?,
+        )?;
+        if !response.done {
+            return Err(Error::invalid_authentication_response(MONGODB_OIDC_STR));
+        }
+
+        access_token
+    } else {
+        let access_token = cache.request_token(callback, credential, None).await?;
+        send_access_token(conn, credential, server_api, &access_token).await?;
+        access_token
+    };
+
+    conn.oidc_access_token = Some(access_token);
+    Ok(())
+}
+
+/// Reauthenticates a connection after the server has reported that its token is no longer valid.
+/// The token previously used by the connection is removed from the cache.
+pub(super) async fn reauthenticate_stream(
+    conn: &mut Connection,
+    credential: &Credential,
+    server_api: Option<&ServerApi>,
+) -> Result<()> {
+    if let Some(access_token) = conn.oidc_access_token.take() {
+        credential.oidc_cache.invalidate(&access_token).await;
+    }
+
+    authenticate_stream(conn, credential, server_api, None).await
+}
+
+/// Authenticates with `access_token` in a single `saslStart` command.
+async fn send_access_token(
+    conn: &mut Connection,
+    credential: &Credential,
+    server_api: Option<&ServerApi>,
+    access_token: &str,
+) -> Result<()> {
+    let command = SaslStart::new(
+        source(credential).into(),
+        AuthMechanism::MongoDbOidc,
+        jwt_payload(access_token)?,
+        server_api.cloned(),
+    )
+    .into_command();
+
+    let response = conn.send_command(command, None).await?;
+    let response = SaslResponse::parse(
+        MONGODB_OIDC_STR,
+        response.auth_response_body(MONGODB_OIDC_STR)?,
+    )?;
+    if !response.done {
+        return Err(Error::invalid_authentication_response(MONGODB_OIDC_STR));
+    }
+
+    Ok(())
+}
+
+/// Starts a conversation to obtain the identity provider information from the server.
+async fn request_idp_info(
+    conn: &mut Connection,
+    credential: &Credential,
+    server_api: Option<&ServerApi>,
+) -> Result<(Bson, IdpInfo)> {
+    let mut payload = Document::new();
+    if let Some(ref username) = credential.username {
+        payload.insert("n", username);
+    }
+    let mut payload_bytes = Vec::new();
+    payload.to_writer(&mut payload_bytes)?;
+
+    let command = SaslStart::new(
+        source(credential).into(),
+        AuthMechanism::MongoDbOidc,
+        payload_bytes,
+        server_api.cloned(),
+    )
+    .into_command();
+
+    let response = conn.send_command(command, None).await?;
+    let response = SaslResponse::parse(
+        MONGODB_OIDC_STR,
+        response.auth_response_body(MONGODB_OIDC_STR)?,
+    )?;
+    let idp_info = bson::from_slice(response.payload.as_slice())
+        .map_err(|_| Error::invalid_authentication_response(MONGODB_OIDC_STR))?;
+
+    Ok((response.conversation_id, idp_info))
+}
+
+fn source(credential: &Credential) -> &str {
+    credential.source.as_deref().unwrap_or("$external")
+}
+
+fn jwt_payload(access_token: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    doc! { "jwt": access_token }.to_writer(&mut bytes)?;
+    Ok(bytes)
+}
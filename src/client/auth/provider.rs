@@ -0,0 +1,71 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    client::auth::{oidc, Credential},
+    error::Result,
+};
+
+/// A source of the credential used to authenticate new connections, set via
+/// [`ClientOptions::credential_provider`](crate::options::ClientOptions::credential_provider).
+///
+/// The provider is consulted each time a connection is established, so credentials that are
+/// rotated while the `Client` is in use (e.g. a password that changes every few hours) are picked
+/// up by new connections without recreating the `Client` or clearing its connection pools.
+/// Connections that have already authenticated are unaffected by changes to the credential.
+///
+/// Errors returned by the provider can be created from a `std::io::Error`, and are returned from
+/// the operation that needed the new connection.
+///
+/// ```rust
+/// # use std::{future::Future, pin::Pin};
+/// # use mongodb::{error::Result, options::{Credential, CredentialProvider}};
+/// struct MountedSecret;
+///
+/// impl CredentialProvider for MountedSecret {
+///     fn credential<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Credential>> + Send + 'a>> {
+///         Box::pin(async move {
+///             let password = std::fs::read_to_string("/var/run/secrets/mongodb/password")?;
+///             Ok(Credential::builder()
+///                 .username("app".to_string())
+///                 .password(password.trim().to_string())
+///                 .build())
+///         })
+///     }
+/// }
+/// ```
+pub trait CredentialProvider: Send + Sync {
+    /// Returns the credential to authenticate a new connection with.
+    fn credential<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Credential>> + Send + 'a>>;
+}
+
+/// Obtains a credential from `provider`, validating it in the same way as a credential specified
+/// in the `ClientOptions`.
+pub(crate) async fn provide_credential(provider: &dyn CredentialProvider) -> Result<Credential> {
+    let credential = provider.credential().await?;
+    if let Some(ref mechanism) = credential.mechanism {
+        mechanism.validate_credential(&credential)?;
+    }
+
+    Ok(credential)
+}
+
+/// Wraps the provider set on a `Client`'s options so that the credentials it provides share the
+/// `Client`'s MONGODB-OIDC token cache and use the authSource found in the SRV TXT record.
+pub(crate) struct ClientCredentialProvider {
+    pub(crate) inner: Arc<dyn CredentialProvider>,
+    pub(crate) oidc_cache: Arc<oidc::Cache>,
+    pub(crate) srv_auth_source: Option<String>,
+}
+
+impl CredentialProvider for ClientCredentialProvider {
+    fn credential<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Credential>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut credential = self.inner.credential().await?;
+            credential.oidc_cache = self.oidc_cache.clone();
+            if credential.source.is_none() {
+                credential.source = self.srv_auth_source.clone();
+            }
+            Ok(credential)
+        })
+    }
+}
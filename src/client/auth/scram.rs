@@ -55,8 +55,12 @@ lazy_static! {
     };
 }
 
+/// The key of a salted password in the cache. The username and password are both part of the key
+/// so that entries are never shared between users, and so that a password that has been rotated
+/// does not match the entry for its previous value.
 #[derive(Hash, Eq, PartialEq)]
 struct CacheEntry {
+    username: String,
     password: String,
     salt: Vec<u8>,
     i: u32,
@@ -188,6 +192,7 @@ impl ScramVersion {
         server_first.validate(client_first.nonce.as_str())?;
 
         let cache_entry_key = CacheEntry {
+            username: username.to_string(),
             password: password.to_string(),
             salt: server_first.salt().to_vec(),
             i: server_first.i(),
@@ -1,8 +1,39 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
 use lazy_static::lazy_static;
 
-use crate::{cmap::StreamDescription, options::AuthMechanism};
+use crate::{
+    bson::doc,
+    cmap::StreamDescription,
+    error::{Error, Result},
+    options::{
+        AuthMechanism,
+        ClientOptions,
+        Credential,
+        CredentialProvider,
+        OidcCallback,
+        OidcCallbackContext,
+        OidcToken,
+        ServerAddress,
+    },
+    test::StaticDnsResolver,
+    Client,
+};
 
-use super::sasl::SaslStart;
+use super::{
+    gssapi::{CanonicalizeHostName, GssapiProperties},
+    oidc,
+    sasl::SaslStart,
+};
 
 lazy_static! {
     static ref MECHS: [String; 2] = [
@@ -99,3 +130,381 @@ fn sasl_first_options_not_specified() {
         "SaslStart should not contain options document for X.509 authentication"
     );
 }
+
+fn gssapi_credential(properties: bson::Document) -> Credential {
+    Credential::builder()
+        .username("user@EXAMPLE.COM".to_string())
+        .mechanism(AuthMechanism::Gssapi)
+        .mechanism_properties(properties)
+        .build()
+}
+
+#[test]
+fn gssapi_properties() {
+    let properties = GssapiProperties::from_credential(&gssapi_credential(doc! {})).unwrap();
+    assert_eq!(properties.service_name, "mongodb");
+    assert_eq!(
+        properties.canonicalize_host_name,
+        CanonicalizeHostName::None
+    );
+    assert_eq!(
+        properties.service_principal("db.example.com"),
+        "mongodb/db.example.com"
+    );
+
+    let properties = GssapiProperties::from_credential(&gssapi_credential(doc! {
+        "SERVICE_NAME": "other",
+        "CANONICALIZE_HOST_NAME": "forward",
+        "SERVICE_REALM": "SERVICES.EXAMPLE.COM",
+        "SERVICE_HOST": "alias.example.com",
+    }))
+    .unwrap();
+    assert_eq!(
+        properties.canonicalize_host_name,
+        CanonicalizeHostName::Forward
+    );
+    assert_eq!(
+        properties.service_host.as_deref(),
+        Some("alias.example.com")
+    );
+    assert_eq!(
+        properties.service_principal("alias.example.com"),
+        "other/alias.example.com@SERVICES.EXAMPLE.COM"
+    );
+
+    let properties = GssapiProperties::from_credential(&gssapi_credential(doc! {
+        "CANONICALIZE_HOST_NAME": true,
+    }))
+    .unwrap();
+    assert_eq!(
+        properties.canonicalize_host_name,
+        CanonicalizeHostName::ForwardAndReverse
+    );
+}
+
+#[test]
+fn gssapi_invalid_properties() {
+    for properties in &[
+        doc! { "CANONICALIZE_HOST_NAME": "sometimes" },
+        doc! { "SERVICE_NAME": 1 },
+        doc! { "SERVICE_PORT": "27017" },
+    ] {
+        let credential = gssapi_credential(properties.clone());
+        assert!(
+            AuthMechanism::Gssapi
+                .validate_credential(&credential)
+                .is_err(),
+            "{}",
+            properties
+        );
+    }
+
+    let credential = Credential::builder()
+        .mechanism(AuthMechanism::Gssapi)
+        .build();
+    assert!(AuthMechanism::Gssapi
+        .validate_credential(&credential)
+        .is_err());
+}
+
+/// Returns numbered tokens, recording the context of each call. Calls fail while `fail` is set.
+#[derive(Default)]
+struct CountingCallback {
+    contexts: Mutex<Vec<OidcCallbackContext>>,
+    expires: Option<Instant>,
+    fail: AtomicBool,
+}
+
+impl OidcCallback for CountingCallback {
+    fn request_token<'a>(
+        &'a self,
+        context: OidcCallbackContext,
+    ) -> Pin<Box<dyn Future<Output = Result<OidcToken>> + Send + 'a>> {
+        Box::pin(async move {
+            let mut contexts = self.contexts.lock().unwrap();
+            contexts.push(context);
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(Error::authentication_error(
+                    super::MONGODB_OIDC_STR,
+                    "callback failed",
+                ));
+            }
+            let token = OidcToken {
+                access_token: format!("access-{}", contexts.len()),
+                expires: self.expires,
+                refresh_token: Some(format!("refresh-{}", contexts.len())),
+            };
+            Ok(token)
+        })
+    }
+}
+
+fn oidc_credential(callback: Arc<CountingCallback>) -> Credential {
+    Credential::builder()
+        .mechanism(AuthMechanism::MongoDbOidc)
+        .oidc_callback(callback as Arc<dyn OidcCallback>)
+        .build()
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn oidc_tokens_are_cached() {
+    let callback = Arc::new(CountingCallback::default());
+    let credential = oidc_credential(callback.clone());
+    let cache = &credential.oidc_cache;
+
+    assert_eq!(
+        cache
+            .request_token(&*callback, &credential, None)
+            .await
+            .unwrap(),
+        "access-1"
+    );
+    assert_eq!(
+        cache
+            .request_token(&*callback, &credential, None)
+            .await
+            .unwrap(),
+        "access-1"
+    );
+    assert_eq!(callback.contexts.lock().unwrap().len(), 1);
+
+    // Invalidating a token that has already been replaced has no effect.
+    cache.invalidate("access-0").await;
+    assert_eq!(cache.access_token().await.as_deref(), Some("access-1"));
+
+    cache.invalidate("access-1").await;
+    assert_eq!(cache.access_token().await, None);
+    assert_eq!(
+        cache
+            .request_token(&*callback, &credential, None)
+            .await
+            .unwrap(),
+        "access-2"
+    );
+
+    let contexts = callback.contexts.lock().unwrap();
+    assert_eq!(contexts.len(), 2);
+    assert_eq!(contexts[0].refresh_token, None);
+    assert_eq!(contexts[1].refresh_token.as_deref(), Some("refresh-1"));
+    assert_eq!(contexts[1].version, 1);
+    assert!(contexts[1].idp_info.is_none());
+    // Consecutive calls are spaced out.
+    assert!(contexts[1].deadline - contexts[0].deadline >= Duration::from_millis(100));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn oidc_expired_tokens_are_not_reused() {
+    let callback = Arc::new(CountingCallback {
+        expires: Some(Instant::now()),
+        ..Default::default()
+    });
+    let credential = oidc_credential(callback.clone());
+    let cache = &credential.oidc_cache;
+
+    cache
+        .request_token(&*callback, &credential, None)
+        .await
+        .unwrap();
+    assert_eq!(cache.access_token().await, None);
+    assert!(oidc::build_speculative_client_first(&credential).is_none());
+
+    cache
+        .request_token(&*callback, &credential, None)
+        .await
+        .unwrap();
+    assert_eq!(callback.contexts.lock().unwrap().len(), 2);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn oidc_refresh_token_survives_callback_failure() {
+    let callback = Arc::new(CountingCallback::default());
+    let credential = oidc_credential(callback.clone());
+    let cache = &credential.oidc_cache;
+
+    cache
+        .request_token(&*callback, &credential, None)
+        .await
+        .unwrap();
+    cache.invalidate("access-1").await;
+
+    callback.fail.store(true, Ordering::SeqCst);
+    assert!(cache
+        .request_token(&*callback, &credential, None)
+        .await
+        .is_err());
+
+    callback.fail.store(false, Ordering::SeqCst);
+    assert_eq!(
+        cache
+            .request_token(&*callback, &credential, None)
+            .await
+            .unwrap(),
+        "access-3"
+    );
+
+    let contexts = callback.contexts.lock().unwrap();
+    assert_eq!(contexts[1].refresh_token.as_deref(), Some("refresh-1"));
+    assert_eq!(contexts[2].refresh_token.as_deref(), Some("refresh-1"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn oidc_speculative_authentication() {
+    let callback = Arc::new(CountingCallback::default());
+    let credential = oidc_credential(callback.clone());
+
+    // Speculative authentication is only attempted once a token has been cached.
+    assert!(AuthMechanism::MongoDbOidc
+        .build_speculative_client_first(&credential)
+        .unwrap()
+        .is_none());
+
+    credential
+        .oidc_cache
+        .request_token(&*callback, &credential, None)
+        .await
+        .unwrap();
+    let client_first = AuthMechanism::MongoDbOidc
+        .build_speculative_client_first(&credential)
+        .unwrap()
+        .unwrap();
+    let document = client_first.to_document();
+    assert_eq!(document.get_str("mechanism"), Ok("MONGODB-OIDC"));
+    assert_eq!(document.get_str("db"), Ok("$external"));
+
+    let payload = document.get_binary_generic("payload").unwrap();
+    let payload = bson::Document::from_reader(payload.as_slice()).unwrap();
+    assert_eq!(payload, doc! { "jwt": "access-1" });
+}
+
+#[test]
+fn oidc_credential_validation() {
+    let credential = Credential::builder()
+        .mechanism(AuthMechanism::MongoDbOidc)
+        .password("password".to_string())
+        .build();
+    assert!(AuthMechanism::MongoDbOidc
+        .validate_credential(&credential)
+        .is_err());
+
+    let credential = Credential::builder()
+        .mechanism(AuthMechanism::MongoDbOidc)
+        .source("admin".to_string())
+        .build();
+    assert!(AuthMechanism::MongoDbOidc
+        .validate_credential(&credential)
+        .is_err());
+}
+
+/// Provides clones of a fixed credential.
+struct FixedCredential(Credential);
+
+impl CredentialProvider for FixedCredential {
+    fn credential<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<Credential>> + Send + 'a>> {
+        Box::pin(async move { Ok(self.0.clone()) })
+    }
+}
+
+async fn provided_credential(client: &Client) -> Credential {
+    let provider = client.inner.options.credential_provider.as_ref().unwrap();
+    super::provide_credential(provider.as_ref()).await.unwrap()
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn provided_credentials_share_oidc_cache() {
+    let callback = Arc::new(CountingCallback::default());
+    let mut options = ClientOptions::builder()
+        .hosts(vec![ServerAddress::default()])
+        .build();
+    options.credential_provider =
+        Some(Arc::new(FixedCredential(oidc_credential(callback.clone()))));
+    let client = Client::with_options(options.clone()).unwrap();
+
+    // A token obtained while authenticating one connection is reused by the others.
+    let first = provided_credential(&client).await;
+    let second = provided_credential(&client).await;
+    assert!(Arc::ptr_eq(&first.oidc_cache, &second.oidc_cache));
+    first
+        .oidc_cache
+        .request_token(&*callback, &first, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        second.oidc_cache.access_token().await.as_deref(),
+        Some("access-1")
+    );
+
+    // Tokens are not shared between clients.
+    let other_client = Client::with_options(options).unwrap();
+    let other = provided_credential(&other_client).await;
+    assert!(!Arc::ptr_eq(&first.oidc_cache, &other.oidc_cache));
+    assert_eq!(other.oidc_cache.access_token().await, None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn provided_credentials_use_srv_auth_source() {
+    let resolver = Arc::new(
+        StaticDnsResolver::new()
+            .srv(
+                "_mongodb._tcp.cluster.example.com",
+                "a.example.com.",
+                27017,
+                Duration::from_secs(60),
+            )
+            .txt("cluster.example.com", "authSource=thisDB"),
+    );
+    let credential = Credential::builder()
+        .username("user".to_string())
+        .password("pencil".to_string())
+        .build();
+
+    let mut options = ClientOptions::parse_uri_with_dns_resolver(
+        "mongodb+srv://cluster.example.com",
+        None,
+        Some(resolver.clone()),
+    )
+    .await
+    .unwrap();
+    options.credential_provider = Some(Arc::new(FixedCredential(credential.clone())));
+    let client = Client::with_options(options).unwrap();
+    assert_eq!(
+        provided_credential(&client).await.source.as_deref(),
+        Some("thisDB")
+    );
+
+    // A source specified by the provided credential takes precedence.
+    let mut options = ClientOptions::parse_uri_with_dns_resolver(
+        "mongodb+srv://cluster.example.com",
+        None,
+        Some(resolver.clone()),
+    )
+    .await
+    .unwrap();
+    options.credential_provider = Some(Arc::new(FixedCredential(Credential {
+        source: Some("admin".to_string()),
+        ..credential.clone()
+    })));
+    let client = Client::with_options(options).unwrap();
+    assert_eq!(
+        provided_credential(&client).await.source.as_deref(),
+        Some("admin")
+    );
+
+    // As does an authSource specified in the connection string.
+    let mut options = ClientOptions::parse_uri_with_dns_resolver(
+        "mongodb+srv://cluster.example.com/?authSource=otherDB",
+        None,
+        Some(resolver),
+    )
+    .await
+    .unwrap();
+    options.credential = None;
+    options.credential_provider = Some(Arc::new(FixedCredential(credential)));
+    let client = Client::with_options(options).unwrap();
+    assert_eq!(provided_credential(&client).await.source, None);
+}
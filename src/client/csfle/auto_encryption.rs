@@ -0,0 +1,650 @@
+//! Automatic encryption of outgoing commands and decryption of replies based on a schema map.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+
+use super::{
+    ciphertext_key_id,
+    client_encryption::Algorithm,
+    decrypt_value,
+    encrypt_value,
+    options::AutoEncryptionOptions,
+    validate_key_id,
+    KeyVault,
+};
+use crate::{
+    bson::{spec::BinarySubtype, Binary, Bson, Document, RawBsonRef, RawDocument, RawDocumentBuf},
+    cmap::RawCommandResponse,
+    error::{Error, Result},
+    options::ClientOptions,
+    Client,
+};
+
+/// The decrypted material of data keys, keyed by key id.
+pub(super) type KeyMaterials = HashMap<Vec<u8>, Arc<Vec<u8>>>;
+
+/// The commands that can be sent for a collection with encrypted fields without any encryption,
+/// since they do not contain values of the collection's documents.
+const UNENCRYPTED_COMMANDS: &[&str] = &[
+    "createIndexes",
+    "drop",
+    "dropIndexes",
+    "killCursors",
+    "listIndexes",
+];
+
+/// The aggregation stages that can be sent for a collection with encrypted fields without any
+/// encryption.
+const UNENCRYPTED_STAGES: &[&str] = &["$count", "$limit", "$sample", "$skip", "$sort"];
+
+/// How a single field is encrypted, as specified by the `encrypt` keyword of a schema.
+#[derive(Clone, Debug)]
+pub(super) struct FieldEncryption {
+    pub(super) key_id: Binary,
+    pub(super) algorithm: Algorithm,
+}
+
+/// A node of a parsed JSON schema, containing only the information relevant to encryption.
+#[derive(Clone, Debug)]
+pub(super) enum SchemaNode {
+    /// The field is encrypted.
+    Encrypt(FieldEncryption),
+
+    /// The field is a document with the given properties, at least one of which is encrypted.
+    Object(HashMap<String, SchemaNode>),
+}
+
+/// The defaults specified by the `encryptMetadata` keyword, which are inherited by all nested
+/// `encrypt` keywords.
+#[derive(Clone, Debug, Default)]
+pub(super) struct EncryptMetadata {
+    key_id: Option<Binary>,
+    algorithm: Option<Algorithm>,
+}
+
+impl EncryptMetadata {
+    fn update(&mut self, metadata: &Document) -> Result<()> {
+        match metadata.get("keyId") {
+            Some(Bson::Array(key_ids)) => match key_ids.as_slice() {
+                [Bson::Binary(key_id)] => {
+                    validate_key_id(key_id)?;
+                    self.key_id = Some(key_id.clone());
+                }
+                _ => {
+                    return Err(Error::invalid_argument(
+                        "keyId in a schema must be an array containing exactly one UUID",
+                    ))
+                }
+            },
+            Some(Bson::String(_)) => {
+                return Err(Error::invalid_argument(
+                    "JSON pointer keyIds are not supported by automatic encryption",
+                ))
+            }
+            Some(other) => {
+                return Err(Error::invalid_argument(format!(
+                    "invalid keyId in schema: {}",
+                    other
+                )))
+            }
+            None => {}
+        }
+
+        if let Some(algorithm) = metadata.get("algorithm") {
+            self.algorithm = Some(bson::from_bson(algorithm.clone()).map_err(|_| {
+                Error::invalid_argument(format!("invalid algorithm in schema: {}", algorithm))
+            })?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the parts of `schema` relevant to encryption, returning `None` if it contains no
+/// encrypted fields.
+pub(super) fn parse_schema(
+    schema: &Document,
+    inherited: &EncryptMetadata,
+) -> Result<Option<SchemaNode>> {
+    let mut metadata = inherited.clone();
+    if let Some(encrypt_metadata) = schema.get("encryptMetadata") {
+        let encrypt_metadata = encrypt_metadata
+            .as_document()
+            .ok_or_else(|| Error::invalid_argument("encryptMetadata must be a document"))?;
+        metadata.update(encrypt_metadata)?;
+    }
+
+    if let Some(encrypt) = schema.get("encrypt") {
+        let encrypt = encrypt
+            .as_document()
+            .ok_or_else(|| Error::invalid_argument("encrypt must be a document"))?;
+        metadata.update(encrypt)?;
+        return match metadata {
+            EncryptMetadata {
+                key_id: Some(key_id),
+                algorithm: Some(algorithm),
+            } => Ok(Some(SchemaNode::Encrypt(FieldEncryption {
+                key_id,
+                algorithm,
+            }))),
+            _ => Err(Error::invalid_argument(
+                "encrypted fields in a schema must specify a keyId and an algorithm",
+            )),
+        };
+    }
+
+    let mut properties = HashMap::new();
+    if let Some(Bson::Document(schema_properties)) = schema.get("properties") {
+        for (name, property) in schema_properties {
+            if let Bson::Document(property) = property {
+                if let Some(node) = parse_schema(property, &metadata)? {
+                    properties.insert(name.clone(), node);
+                }
+            }
+        }
+    }
+
+    if properties.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(SchemaNode::Object(properties)))
+    }
+}
+
+/// Finds the schema node for the dotted `path`, if it refers to an encrypted field or a document
+/// containing encrypted fields.
+fn resolve_path<'a>(
+    properties: &'a HashMap<String, SchemaNode>,
+    path: &str,
+) -> Result<Option<&'a SchemaNode>> {
+    let mut current = properties;
+    let mut parts = path.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let node = match current.get(part) {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+        match (node, parts.peek()) {
+            (_, None) => return Ok(Some(node)),
+            (SchemaNode::Object(properties), Some(_)) => current = properties,
+            (SchemaNode::Encrypt(_), Some(_)) => {
+                return Err(Error::invalid_argument(format!(
+                    "cannot reference {}, which is inside an encrypted field",
+                    path
+                )))
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn collect_key_ids(properties: &HashMap<String, SchemaNode>, key_ids: &mut Vec<Vec<u8>>) {
+    for node in properties.values() {
+        match node {
+            SchemaNode::Encrypt(field) => key_ids.push(field.key_id.bytes.clone()),
+            SchemaNode::Object(properties) => collect_key_ids(properties, key_ids),
+        }
+    }
+}
+
+fn is_operator_document(document: &Document) -> bool {
+    document
+        .keys()
+        .next()
+        .map(|key| key.starts_with('$'))
+        .unwrap_or(false)
+}
+
+/// Automatically encrypts the commands sent by a client and decrypts the replies it receives.
+#[derive(Debug)]
+pub(crate) struct AutoEncrypter {
+    key_vault: KeyVault,
+
+    /// The parsed schemas, keyed by namespace.
+    schemas: HashMap<String, HashMap<String, SchemaNode>>,
+
+    bypass_auto_encryption: bool,
+}
+
+impl AutoEncrypter {
+    pub(crate) fn new(
+        client_options: &ClientOptions,
+        options: &AutoEncryptionOptions,
+    ) -> Result<Self> {
+        let key_vault_client = match options.key_vault_client {
+            Some(ref client) => client.clone(),
+            None => {
+                let mut key_vault_options = client_options.clone();
+                key_vault_options.auto_encryption_opts = None;
+                Client::with_options(key_vault_options)?
+            }
+        };
+        let key_vault = KeyVault::new(
+            &key_vault_client,
+            &options.key_vault_namespace,
+            options.kms_providers.clone(),
+        )?;
+
+        let mut schemas = HashMap::new();
+        for (namespace, schema) in options.schema_map.iter().flatten() {
+            match parse_schema(schema, &EncryptMetadata::default())? {
+                Some(SchemaNode::Object(properties)) => {
+                    schemas.insert(namespace.clone(), properties);
+                }
+                Some(SchemaNode::Encrypt(_)) => {
+                    return Err(Error::invalid_argument(format!(
+                        "the schema for {} cannot encrypt the entire document",
+                        namespace
+                    )))
+                }
+                None => {}
+            }
+        }
+
+        Ok(Self {
+            key_vault,
+            schemas,
+            bypass_auto_encryption: options.bypass_auto_encryption.unwrap_or(false),
+        })
+    }
+
+    /// Gets the decrypted material of the data keys with the given ids.
+    async fn fetch_keys(&self, key_ids: Vec<Vec<u8>>) -> Result<KeyMaterials> {
+        let mut keys = KeyMaterials::new();
+        for key_id in key_ids {
+            if let Entry::Vacant(entry) = keys.entry(key_id) {
+                let material = self.key_vault.key_material(entry.key()).await?;
+                entry.insert(material);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Encrypts the fields of the serialized command `command` that are marked as encrypted in the
+    /// schema of the collection it targets.
+    pub(crate) async fn encrypt_command(
+        &self,
+        name: &str,
+        target_db: &str,
+        command: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        if self.bypass_auto_encryption {
+            return Ok(command);
+        }
+        if name == "bulkWrite" {
+            return Err(Error::invalid_argument(
+                "Client::bulk_write is not supported with automatic encryption",
+            ));
+        }
+
+        let mut document = Document::from_reader(command.as_slice())?;
+        let properties = match target_collection(name, &document)
+            .and_then(|coll| self.schemas.get(&format!("{}.{}", target_db, coll)))
+        {
+            Some(properties) => properties,
+            None => return Ok(command),
+        };
+
+        let mut key_ids = Vec::new();
+        collect_key_ids(properties, &mut key_ids);
+        let keys = self.fetch_keys(key_ids).await?;
+
+        FieldEncrypter {
+            keys: &keys,
+            properties,
+        }
+        .encrypt_command(name, &mut document)?;
+
+        let mut bytes = Vec::new();
+        document.to_writer(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decrypts any encrypted values in `response`.
+    pub(crate) async fn decrypt_response(
+        &self,
+        mut response: RawCommandResponse,
+    ) -> Result<RawCommandResponse> {
+        let mut key_ids = Vec::new();
+        collect_ciphertext_key_ids(response.raw_body(), &mut key_ids)?;
+        if key_ids.is_empty() {
+            return Ok(response);
+        }
+
+        let keys = self.fetch_keys(key_ids).await?;
+
+        let mut document: Document = response.body()?;
+        for (_, value) in document.iter_mut() {
+            decrypt_in_place(value, &keys)?;
+        }
+        response.set_raw_body(RawDocumentBuf::from_document(&document)?);
+
+        Ok(response)
+    }
+}
+
+/// Encrypts the fields of a command according to a collection's schema.
+pub(super) struct FieldEncrypter<'a> {
+    pub(super) keys: &'a KeyMaterials,
+    pub(super) properties: &'a HashMap<String, SchemaNode>,
+}
+
+impl<'a> FieldEncrypter<'a> {
+    /// Encrypts the fields of the command `name`, returning an error if the command cannot be
+    /// sent without revealing the values of encrypted fields.
+    pub(super) fn encrypt_command(&self, name: &str, command: &mut Document) -> Result<()> {
+        match name {
+            "insert" => {
+                for document in documents_mut(command, "documents") {
+                    self.encrypt_document(document, self.properties)?;
+                }
+            }
+            "update" => {
+                for statement in documents_mut(command, "updates") {
+                    if let Some(Bson::Document(filter)) = statement.get_mut("q") {
+                        self.encrypt_filter(filter)?;
+                    }
+                    if let Some(update) = statement.get_mut("u") {
+                        self.encrypt_update(update)?;
+                    }
+                }
+            }
+            "delete" => {
+                for statement in documents_mut(command, "deletes") {
+                    if let Some(Bson::Document(filter)) = statement.get_mut("q") {
+                        self.encrypt_filter(filter)?;
+                    }
+                }
+            }
+            "findAndModify" => {
+                if let Some(Bson::Document(filter)) = command.get_mut("query") {
+                    self.encrypt_filter(filter)?;
+                }
+                if let Some(update) = command.get_mut("update") {
+                    self.encrypt_update(update)?;
+                }
+            }
+            "find" => {
+                if let Some(Bson::Document(filter)) = command.get_mut("filter") {
+                    self.encrypt_filter(filter)?;
+                }
+            }
+            "count" | "distinct" => {
+                if let Some(Bson::Document(filter)) = command.get_mut("query") {
+                    self.encrypt_filter(filter)?;
+                }
+            }
+            "aggregate" => {
+                for stage in documents_mut(command, "pipeline") {
+                    match stage.iter_mut().next() {
+                        Some((name, Bson::Document(filter))) if name == "$match" => {
+                            self.encrypt_filter(filter)?;
+                        }
+                        Some((name, _)) if UNENCRYPTED_STAGES.contains(&name.as_str()) => {}
+                        Some((name, _)) => {
+                            return Err(Error::invalid_argument(format!(
+                                "the {} aggregation stage is not supported on collections with \
+                                 encrypted fields",
+                                name
+                            )))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            "explain" => match command.get_mut("explain") {
+                Some(Bson::Document(explained)) => {
+                    let name = explained.keys().next().cloned().unwrap_or_default();
+                    self.encrypt_command(&name, explained)?;
+                }
+                _ => return Err(Error::invalid_argument("explain must contain a command")),
+            },
+            name if UNENCRYPTED_COMMANDS.contains(&name) => {}
+            name => {
+                return Err(Error::invalid_argument(format!(
+                    "the {} command is not supported on collections with encrypted fields",
+                    name
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    fn encrypt_value(&self, value: &Bson, field: &FieldEncryption) -> Result<Bson> {
+        let material = self
+            .keys
+            .get(&field.key_id.bytes)
+            .ok_or_else(|| Error::internal("data key for automatic encryption was not fetched"))?;
+        Ok(Bson::Binary(encrypt_value(
+            value,
+            &field.key_id,
+            field.algorithm,
+            material,
+        )?))
+    }
+
+    /// Encrypts the fields of a document that is being written to the collection.
+    fn encrypt_document(
+        &self,
+        document: &mut Document,
+        properties: &HashMap<String, SchemaNode>,
+    ) -> Result<()> {
+        for (key, value) in document.iter_mut() {
+            match (properties.get(key), value) {
+                (Some(SchemaNode::Encrypt(field)), value) => {
+                    *value = self.encrypt_value(value, field)?;
+                }
+                (Some(SchemaNode::Object(properties)), Bson::Document(document)) => {
+                    self.encrypt_document(document, properties)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypts the values compared against encrypted fields in a query filter.
+    fn encrypt_filter(&self, filter: &mut Document) -> Result<()> {
+        for (key, value) in filter.iter_mut() {
+            match key.as_str() {
+                "$and" | "$or" | "$nor" => {
+                    if let Bson::Array(clauses) = value {
+                        for clause in clauses {
+                            if let Bson::Document(clause) = clause {
+                                self.encrypt_filter(clause)?;
+                            }
+                        }
+                    }
+                }
+                key if key.starts_with('$') => {}
+                path => match (resolve_path(self.properties, path)?, value) {
+                    (Some(SchemaNode::Encrypt(field)), value) => {
+                        self.encrypt_predicate(value, field)?;
+                    }
+                    (Some(SchemaNode::Object(properties)), Bson::Document(document))
+                        if !is_operator_document(document) =>
+                    {
+                        self.encrypt_document(document, properties)?;
+                    }
+                    (Some(SchemaNode::Object(_)), _) => {
+                        return Err(Error::invalid_argument(format!(
+                            "unsupported query on {}, which contains encrypted fields",
+                            path
+                        )))
+                    }
+                    (None, _) => {}
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn encrypt_predicate(&self, predicate: &mut Bson, field: &FieldEncryption) -> Result<()> {
+        if field.algorithm != Algorithm::AeadAes256CbcHmacSha512Deterministic {
+            return Err(Error::invalid_argument(
+                "cannot query on fields encrypted with the random algorithm",
+            ));
+        }
+
+        match predicate {
+            Bson::Document(operators) if is_operator_document(operators) => {
+                for (operator, operand) in operators.iter_mut() {
+                    match (operator.as_str(), operand) {
+                        ("$eq", operand) | ("$ne", operand) => {
+                            *operand = self.encrypt_value(operand, field)?;
+                        }
+                        ("$in", Bson::Array(values)) | ("$nin", Bson::Array(values)) => {
+                            for value in values {
+                                *value = self.encrypt_value(value, field)?;
+                            }
+                        }
+                        (operator, _) => {
+                            return Err(Error::invalid_argument(format!(
+                                "unsupported operator {} on an encrypted field",
+                                operator
+                            )))
+                        }
+                    }
+                }
+            }
+            value => *value = self.encrypt_value(value, field)?,
+        }
+        Ok(())
+    }
+
+    /// Encrypts the values written to encrypted fields by an update or replacement document.
+    fn encrypt_update(&self, update: &mut Bson) -> Result<()> {
+        match update {
+            Bson::Document(operators) if is_operator_document(operators) => {
+                for (operator, fields) in operators.iter_mut() {
+                    let fields = match fields {
+                        Bson::Document(fields) => fields,
+                        _ => continue,
+                    };
+                    for (path, value) in fields.iter_mut() {
+                        let node = match resolve_path(self.properties, path)? {
+                            Some(node) => node,
+                            None => continue,
+                        };
+                        match (operator.as_str(), node, value) {
+                            ("$unset", ..) => {}
+                            ("$set", SchemaNode::Encrypt(field), value)
+                            | ("$setOnInsert", SchemaNode::Encrypt(field), value) => {
+                                *value = self.encrypt_value(value, field)?;
+                            }
+                            ("$set", SchemaNode::Object(properties), Bson::Document(document))
+                            | (
+                                "$setOnInsert",
+                                SchemaNode::Object(properties),
+                                Bson::Document(document),
+                            ) => {
+                                self.encrypt_document(document, properties)?;
+                            }
+                            (operator, ..) => {
+                                return Err(Error::invalid_argument(format!(
+                                    "unsupported update operator {} on {}, which is or contains \
+                                     an encrypted field",
+                                    operator, path
+                                )))
+                            }
+                        }
+                    }
+                }
+            }
+            Bson::Document(replacement) => self.encrypt_document(replacement, self.properties)?,
+            Bson::Array(_) => {
+                return Err(Error::invalid_argument(
+                    "pipeline updates are not supported on collections with encrypted fields",
+                ))
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of the collection targeted by the command `name`, looking inside explained
+/// commands.
+fn target_collection<'a>(name: &str, command: &'a Document) -> Option<&'a str> {
+    match (name, command.get(name)) {
+        ("explain", Some(Bson::Document(explained))) => {
+            let (name, _) = explained.iter().next()?;
+            explained.get_str(name).ok()
+        }
+        (_, Some(Bson::String(coll))) => Some(coll),
+        _ => None,
+    }
+}
+
+/// Returns the documents in the array field `key` of `command`.
+fn documents_mut<'a>(
+    command: &'a mut Document,
+    key: &str,
+) -> impl Iterator<Item = &'a mut Document> {
+    let values = match command.get_mut(key) {
+        Some(Bson::Array(values)) => values.as_mut_slice(),
+        _ => &mut [],
+    };
+    values.iter_mut().filter_map(|value| match value {
+        Bson::Document(document) => Some(document),
+        _ => None,
+    })
+}
+
+/// Collects the ids of the data keys used to encrypt the values in `document`.
+fn collect_ciphertext_key_ids(document: &RawDocument, key_ids: &mut Vec<Vec<u8>>) -> Result<()> {
+    for element in document {
+        let (_, value) = element?;
+        collect_ciphertext_key_ids_from_value(value, key_ids)?;
+    }
+    Ok(())
+}
+
+fn collect_ciphertext_key_ids_from_value(
+    value: RawBsonRef,
+    key_ids: &mut Vec<Vec<u8>>,
+) -> Result<()> {
+    match value {
+        RawBsonRef::Binary(binary) if binary.subtype == BinarySubtype::Encrypted => {
+            let ciphertext = Binary {
+                subtype: binary.subtype,
+                bytes: binary.bytes.to_vec(),
+            };
+            key_ids.push(ciphertext_key_id(&ciphertext)?.to_vec());
+        }
+        RawBsonRef::Document(document) => collect_ciphertext_key_ids(document, key_ids)?,
+        RawBsonRef::Array(array) => {
+            for value in array {
+                collect_ciphertext_key_ids_from_value(value?, key_ids)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replaces any encrypted values in `value` with their decrypted values.
+pub(super) fn decrypt_in_place(value: &mut Bson, keys: &KeyMaterials) -> Result<()> {
+    match value {
+        Bson::Binary(ciphertext) if ciphertext.subtype == BinarySubtype::Encrypted => {
+            let material = keys
+                .get(ciphertext_key_id(ciphertext)?)
+                .ok_or_else(|| Error::internal("data key for decryption was not fetched"))?;
+            *value = decrypt_value(ciphertext, material)?;
+        }
+        Bson::Document(document) => {
+            for (_, value) in document.iter_mut() {
+                decrypt_in_place(value, keys)?;
+            }
+        }
+        Bson::Array(values) => {
+            for value in values {
+                decrypt_in_place(value, keys)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
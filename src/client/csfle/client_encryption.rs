@@ -0,0 +1,345 @@
+//! Contains the types for explicit client-side field level encryption.
+
+use std::fmt::Debug;
+
+use futures_util::stream::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use super::{crypto, crypto::IvMode, KeyVault};
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, DateTime, Document},
+    cursor::Cursor,
+    error::{Error, Result},
+    options::{UpdateModifications, UpdateOneModel, WriteModel},
+    results::{BulkWriteResult, DeleteResult},
+    Client,
+    Namespace,
+};
+
+/// A handle used to explicitly encrypt and decrypt values, and to manage the data keys used to do
+/// so. Data keys are stored in a key vault collection, encrypted with a master key from one of the
+/// configured KMS providers.
+///
+/// ```no_run
+/// # use mongodb::{bson::Bson, error::Result, Client, Namespace};
+/// # use mongodb::client_encryption::{Algorithm, ClientEncryption, EncryptOptions, KmsProviders, MasterKey};
+/// # async fn func(client: Client, local_master_key: Vec<u8>) -> Result<()> {
+/// let client_encryption = ClientEncryption::new(
+///     client,
+///     Namespace {
+///         db: "keyvault".to_string(),
+///         coll: "datakeys".to_string(),
+///     },
+///     KmsProviders::builder().local(local_master_key).build(),
+/// )?;
+/// let key_id = client_encryption.create_data_key(MasterKey::Local, None).await?;
+/// let encrypted = client_encryption
+///     .encrypt(
+///         "123-45-6789",
+///         EncryptOptions::builder()
+///             .key(key_id)
+///             .algorithm(Algorithm::AeadAes256CbcHmacSha512Deterministic)
+///             .build(),
+///     )
+///     .await?;
+/// let decrypted = client_encryption.decrypt(&encrypted).await?;
+/// assert_eq!(decrypted, Bson::from("123-45-6789"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ClientEncryption {
+    key_vault: KeyVault,
+}
+
+impl ClientEncryption {
+    /// Creates a new `ClientEncryption` that stores data keys in the `key_vault_namespace`
+    /// collection using `key_vault_client`, and encrypts them with the master keys configured in
+    /// `kms_providers`.
+    pub fn new(
+        key_vault_client: Client,
+        key_vault_namespace: Namespace,
+        kms_providers: KmsProviders,
+    ) -> Result<Self> {
+        Ok(Self {
+            key_vault: KeyVault::new(&key_vault_client, &key_vault_namespace, kms_providers)?,
+        })
+    }
+
+    /// Creates a new data key, encrypts it with `master_key` and inserts it into the key vault
+    /// collection. Returns the id of the new key.
+    pub async fn create_data_key(
+        &self,
+        master_key: MasterKey,
+        options: impl Into<Option<DataKeyOptions>>,
+    ) -> Result<Binary> {
+        let options = options.into().unwrap_or_default();
+
+        let material = match options.key_material {
+            Some(material) if material.len() != crypto::KEY_LENGTH => {
+                return Err(Error::invalid_argument(format!(
+                    "key material must be {} bytes long, got {} bytes",
+                    crypto::KEY_LENGTH,
+                    material.len()
+                )));
+            }
+            Some(material) => material,
+            None => crypto::generate_key(),
+        };
+        let (key_material, master_key) = self
+            .key_vault
+            .encrypt_key_material(&master_key, &material)?;
+
+        let id = Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: uuid::Uuid::new_v4().as_bytes().to_vec(),
+        };
+        let now = DateTime::now();
+        let mut key = doc! {
+            "_id": id.clone(),
+            "keyMaterial": Binary { subtype: BinarySubtype::Generic, bytes: key_material },
+            "creationDate": now,
+            "updateDate": now,
+            "status": 0,
+            "masterKey": master_key,
+        };
+        if let Some(key_alt_names) = options.key_alt_names {
+            key.insert("keyAltNames", key_alt_names);
+        }
+
+        self.key_vault.collection().insert_one(key, None).await?;
+
+        Ok(id)
+    }
+
+    /// Deletes the data key with the given id from the key vault collection.
+    pub async fn delete_key(&self, id: &Binary) -> Result<DeleteResult> {
+        self.key_vault
+            .collection()
+            .delete_one(doc! { "_id": id.clone() }, None)
+            .await
+    }
+
+    /// Finds the data key with the given id in the key vault collection.
+    pub async fn get_key(&self, id: &Binary) -> Result<Option<Document>> {
+        self.key_vault
+            .collection()
+            .find_one(doc! { "_id": id.clone() }, None)
+            .await
+    }
+
+    /// Finds the data key with the given alternate name in the key vault collection.
+    pub async fn get_key_by_alt_name(
+        &self,
+        key_alt_name: impl AsRef<str>,
+    ) -> Result<Option<Document>> {
+        self.key_vault
+            .collection()
+            .find_one(doc! { "keyAltNames": key_alt_name.as_ref() }, None)
+            .await
+    }
+
+    /// Finds all of the data keys in the key vault collection.
+    pub async fn get_keys(&self) -> Result<Cursor<Document>> {
+        self.key_vault.collection().find(None, None).await
+    }
+
+    /// Decrypts the data keys matching `filter` and re-encrypts them with the master key specified
+    /// in the options, which defaults to [`MasterKey::Local`].
+    pub async fn rewrap_many_data_key(
+        &self,
+        filter: Document,
+        options: impl Into<Option<RewrapManyDataKeyOptions>>,
+    ) -> Result<RewrapManyDataKeyResult> {
+        // Only the local provider is currently supported, so every key is re-encrypted with the
+        // local master key by default.
+        let master_key = options
+            .into()
+            .and_then(|options| options.master_key)
+            .unwrap_or(MasterKey::Local);
+        let keys: Vec<Document> = self
+            .key_vault
+            .collection()
+            .find(filter, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut models = Vec::with_capacity(keys.len());
+        for key in keys {
+            let material = self.key_vault.decrypt_key_material(&key)?;
+            let (key_material, master_key_doc) = self
+                .key_vault
+                .encrypt_key_material(&master_key, &material)?;
+
+            let id = key.get("_id").cloned().unwrap_or(Bson::Null);
+            let update = doc! {
+                "$set": {
+                    "keyMaterial": Binary { subtype: BinarySubtype::Generic, bytes: key_material },
+                    "masterKey": master_key_doc,
+                },
+                "$currentDate": { "updateDate": true },
+            };
+            models.push(WriteModel::UpdateOne(
+                UpdateOneModel::builder()
+                    .filter(doc! { "_id": id })
+                    .update(UpdateModifications::Document(update))
+                    .build(),
+            ));
+        }
+
+        if models.is_empty() {
+            return Ok(RewrapManyDataKeyResult {
+                bulk_write_result: None,
+            });
+        }
+
+        let bulk_write_result = self.key_vault.collection().bulk_write(models, None).await?;
+        Ok(RewrapManyDataKeyResult {
+            bulk_write_result: Some(bulk_write_result),
+        })
+    }
+
+    /// Encrypts `value` with the data key and algorithm specified in `options`, returning the
+    /// encrypted value as a binary with subtype 6.
+    pub async fn encrypt(&self, value: impl Into<Bson>, options: EncryptOptions) -> Result<Binary> {
+        let key_id = match options.key {
+            EncryptKey::Id(id) => id,
+            EncryptKey::AltName(name) => self.key_vault.key_id_by_alt_name(&name).await?,
+        };
+        self.key_vault
+            .encrypt(&value.into(), &key_id, options.algorithm)
+            .await
+    }
+
+    /// Decrypts a binary value with subtype 6 that was encrypted with [`ClientEncryption::encrypt`]
+    /// or by automatic encryption, returning the original value.
+    pub async fn decrypt(&self, value: &Binary) -> Result<Bson> {
+        self.key_vault.decrypt(value).await
+    }
+}
+
+/// The master keys available for encrypting data keys.
+#[derive(Clone, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct KmsProviders {
+    /// The 96-byte master key used by the local KMS provider.
+    pub local: Option<Vec<u8>>,
+}
+
+impl Debug for KmsProviders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KmsProviders")
+            .field("local", &self.local.as_ref().map(|_| "REDACTED"))
+            .finish()
+    }
+}
+
+/// The master key used to encrypt a data key.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MasterKey {
+    /// The master key configured for the local KMS provider in [`KmsProviders::local`].
+    Local,
+}
+
+/// The algorithms that can be used to encrypt values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// Deterministic encryption: encrypting the same value with the same key always produces the
+    /// same ciphertext, which allows encrypted fields to be queried by equality.
+    #[serde(rename = "AEAD_AES_256_CBC_HMAC_SHA_512-Deterministic")]
+    AeadAes256CbcHmacSha512Deterministic,
+
+    /// Randomized encryption: encrypting the same value twice produces different ciphertexts.
+    #[serde(rename = "AEAD_AES_256_CBC_HMAC_SHA_512-Random")]
+    AeadAes256CbcHmacSha512Random,
+}
+
+impl Algorithm {
+    /// The value stored in the first byte of a ciphertext to identify the algorithm.
+    pub(crate) fn subtype(self) -> u8 {
+        match self {
+            Self::AeadAes256CbcHmacSha512Deterministic => 1,
+            Self::AeadAes256CbcHmacSha512Random => 2,
+        }
+    }
+
+    pub(crate) fn from_subtype(subtype: u8) -> Option<Self> {
+        match subtype {
+            1 => Some(Self::AeadAes256CbcHmacSha512Deterministic),
+            2 => Some(Self::AeadAes256CbcHmacSha512Random),
+            _ => None,
+        }
+    }
+
+    pub(super) fn iv_mode(self) -> IvMode {
+        match self {
+            Self::AeadAes256CbcHmacSha512Deterministic => IvMode::Deterministic,
+            Self::AeadAes256CbcHmacSha512Random => IvMode::Random,
+        }
+    }
+}
+
+/// Specifies the data key used to encrypt a value.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EncryptKey {
+    /// The id of the data key.
+    Id(Binary),
+
+    /// An alternate name of the data key.
+    AltName(String),
+}
+
+impl From<Binary> for EncryptKey {
+    fn from(id: Binary) -> Self {
+        Self::Id(id)
+    }
+}
+
+/// Contains the options for [`ClientEncryption::encrypt`].
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(setter(into)))]
+#[non_exhaustive]
+pub struct EncryptOptions {
+    /// The data key to encrypt the value with.
+    pub key: EncryptKey,
+
+    /// The algorithm to encrypt the value with.
+    pub algorithm: Algorithm,
+}
+
+/// Contains the options for [`ClientEncryption::create_data_key`].
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct DataKeyOptions {
+    /// Alternate names that can be used to refer to the data key instead of its id.
+    pub key_alt_names: Option<Vec<String>>,
+
+    /// The 96 bytes of key material to use for the data key. If unset, the key material is
+    /// randomly generated.
+    pub key_material: Option<Vec<u8>>,
+}
+
+/// Contains the options for [`ClientEncryption::rewrap_many_data_key`].
+#[derive(Clone, Debug, Default, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct RewrapManyDataKeyOptions {
+    /// The master key to re-encrypt the data keys with.
+    pub master_key: Option<MasterKey>,
+}
+
+/// The result of a [`ClientEncryption::rewrap_many_data_key`] operation.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct RewrapManyDataKeyResult {
+    /// The result of the bulk write used to update the data keys, or `None` if no keys matched
+    /// the filter.
+    pub bulk_write_result: Option<BulkWriteResult>,
+}
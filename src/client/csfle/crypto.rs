@@ -0,0 +1,160 @@
+//! An implementation of the AEAD_AES_256_CBC_HMAC_SHA_512 authenticated encryption scheme used by
+//! client-side field level encryption.
+
+use aes::Aes256;
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha512;
+
+use crate::error::{Error, Result};
+
+type Aes256CbcEncryptor = cbc::Encryptor<Aes256>;
+type Aes256CbcDecryptor = cbc::Decryptor<Aes256>;
+
+/// The length in bytes of a data key or local master key.
+pub(crate) const KEY_LENGTH: usize = 96;
+
+const SUBKEY_LENGTH: usize = 32;
+const IV_LENGTH: usize = 16;
+const BLOCK_LENGTH: usize = 16;
+const TAG_LENGTH: usize = 32;
+
+/// How the initialization vector for an encryption is chosen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum IvMode {
+    /// The IV is derived from the key, associated data and plaintext, so encrypting the same
+    /// plaintext twice produces the same ciphertext.
+    Deterministic,
+
+    /// The IV is generated randomly.
+    Random,
+}
+
+/// The three subkeys a 96-byte key is split into.
+struct Subkeys<'a> {
+    mac: &'a [u8],
+    enc: &'a [u8],
+    iv: &'a [u8],
+}
+
+impl<'a> Subkeys<'a> {
+    fn new(key: &'a [u8]) -> Result<Self> {
+        if key.len() != KEY_LENGTH {
+            return Err(Error::encryption(format!(
+                "expected a key of {} bytes, got {} bytes",
+                KEY_LENGTH,
+                key.len()
+            )));
+        }
+        Ok(Self {
+            mac: &key[..SUBKEY_LENGTH],
+            enc: &key[SUBKEY_LENGTH..2 * SUBKEY_LENGTH],
+            iv: &key[2 * SUBKEY_LENGTH..],
+        })
+    }
+}
+
+/// Encrypts `plaintext` with the given 96-byte `key`, authenticating both the ciphertext and
+/// `associated_data`. The output is laid out as `IV || AES-256-CBC ciphertext || HMAC tag`.
+pub(crate) fn encrypt(
+    key: &[u8],
+    plaintext: &[u8],
+    associated_data: &[u8],
+    iv_mode: IvMode,
+) -> Result<Vec<u8>> {
+    let keys = Subkeys::new(key)?;
+    let associated_data_length = associated_data_length(associated_data);
+
+    let mut iv = [0u8; IV_LENGTH];
+    match iv_mode {
+        IvMode::Deterministic => {
+            let mut mac = new_hmac(keys.iv)?;
+            mac.update(associated_data);
+            mac.update(&associated_data_length);
+            mac.update(plaintext);
+            iv.copy_from_slice(&mac.finalize().into_bytes()[..IV_LENGTH]);
+        }
+        IvMode::Random => rand::thread_rng().fill_bytes(&mut iv),
+    }
+
+    seal(&keys, &iv, plaintext, associated_data)
+}
+
+/// Encrypts `plaintext` like [`encrypt`], but with a caller-provided IV.
+#[cfg(test)]
+pub(crate) fn encrypt_with_iv(
+    key: &[u8],
+    iv: &[u8; IV_LENGTH],
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    seal(&Subkeys::new(key)?, iv, plaintext, associated_data)
+}
+
+/// Encrypts and authenticates `plaintext` under `keys` with the given IV.
+fn seal(
+    keys: &Subkeys,
+    iv: &[u8; IV_LENGTH],
+    plaintext: &[u8],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let ciphertext = Aes256CbcEncryptor::new_from_slices(keys.enc, iv)
+        .map_err(|e| Error::encryption(e.to_string()))?
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+
+    let mut output = Vec::with_capacity(IV_LENGTH + ciphertext.len() + TAG_LENGTH);
+    output.extend_from_slice(iv);
+    output.extend_from_slice(&ciphertext);
+
+    let mut mac = new_hmac(keys.mac)?;
+    mac.update(associated_data);
+    mac.update(&output);
+    mac.update(&associated_data_length(associated_data));
+    output.extend_from_slice(&mac.finalize().into_bytes()[..TAG_LENGTH]);
+
+    Ok(output)
+}
+
+/// Decrypts `ciphertext` that was produced by [`encrypt`] with the same `key` and
+/// `associated_data`, returning an error if authentication fails.
+pub(crate) fn decrypt(key: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+    let keys = Subkeys::new(key)?;
+
+    if ciphertext.len() < IV_LENGTH + BLOCK_LENGTH + TAG_LENGTH {
+        return Err(Error::encryption(format!(
+            "ciphertext of {} bytes is too short",
+            ciphertext.len()
+        )));
+    }
+    let (data, tag) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
+
+    let mut mac = new_hmac(keys.mac)?;
+    mac.update(associated_data);
+    mac.update(data);
+    mac.update(&associated_data_length(associated_data));
+    mac.verify_truncated_left(tag)
+        .map_err(|_| Error::encryption("HMAC validation failure"))?;
+
+    let (iv, encrypted) = data.split_at(IV_LENGTH);
+    Aes256CbcDecryptor::new_from_slices(keys.enc, iv)
+        .map_err(|e| Error::encryption(e.to_string()))?
+        .decrypt_padded_vec_mut::<Pkcs7>(encrypted)
+        .map_err(|_| Error::encryption("invalid padding in decrypted data"))
+}
+
+/// Generates a new random 96-byte key.
+pub(crate) fn generate_key() -> Vec<u8> {
+    let mut key = vec![0u8; KEY_LENGTH];
+    rand::thread_rng().fill_bytes(&mut key);
+    key
+}
+
+fn new_hmac(key: &[u8]) -> Result<Hmac<Sha512>> {
+    <Hmac<Sha512> as Mac>::new_from_slice(key).map_err(|e| Error::encryption(e.to_string()))
+}
+
+/// The length of the associated data in bits, encoded as a big-endian 64-bit integer.
+fn associated_data_length(associated_data: &[u8]) -> [u8; 8] {
+    ((associated_data.len() as u64) * 8).to_be_bytes()
+}
@@ -0,0 +1,319 @@
+//! Contains the functionality shared by explicit and automatic client-side field level encryption.
+
+mod auto_encryption;
+pub mod client_encryption;
+mod crypto;
+pub mod options;
+#[cfg(test)]
+mod test;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub(crate) use self::auto_encryption::AutoEncrypter;
+use self::{
+    client_encryption::{Algorithm, KmsProviders, MasterKey},
+    crypto::IvMode,
+};
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
+    concern::{Acknowledgment, ReadConcern, WriteConcern},
+    error::{Error, Result},
+    options::CollectionOptions,
+    Client,
+    Collection,
+    Namespace,
+};
+
+/// How long decrypted data keys are cached before they are fetched from the key vault again.
+const KEY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// The length in bytes of the UUID identifying a data key.
+const KEY_ID_LENGTH: usize = 16;
+
+/// The length in bytes of the header preceding the encrypted value in a ciphertext: the
+/// algorithm, the data key's UUID and the original BSON type of the value.
+const CIPHERTEXT_HEADER_LENGTH: usize = 1 + KEY_ID_LENGTH + 1;
+
+/// Provides access to the data keys stored in a key vault collection, and uses them to encrypt and
+/// decrypt BSON values.
+#[derive(Debug)]
+pub(crate) struct KeyVault {
+    collection: Collection<Document>,
+    kms_providers: KmsProviders,
+    cache: Mutex<HashMap<Vec<u8>, CachedKey>>,
+}
+
+#[derive(Debug)]
+struct CachedKey {
+    material: Arc<Vec<u8>>,
+    fetched_at: Instant,
+}
+
+impl KeyVault {
+    pub(crate) fn new(
+        client: &Client,
+        namespace: &Namespace,
+        kms_providers: KmsProviders,
+    ) -> Result<Self> {
+        match kms_providers.local {
+            Some(ref key) if key.len() != crypto::KEY_LENGTH => {
+                return Err(Error::invalid_argument(format!(
+                    "the local master key must be {} bytes long, got {} bytes",
+                    crypto::KEY_LENGTH,
+                    key.len()
+                )));
+            }
+            Some(_) => {}
+            None => {
+                return Err(Error::invalid_argument(
+                    "at least one KMS provider must be configured",
+                ))
+            }
+        }
+
+        // Reads and writes of data keys must be majority-acknowledged so that keys are never
+        // rolled back after being used to encrypt data.
+        let options = CollectionOptions::builder()
+            .read_concern(ReadConcern::majority())
+            .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+            .build();
+        let collection = client
+            .database(&namespace.db)
+            .collection_with_options(&namespace.coll, options);
+
+        Ok(Self {
+            collection,
+            kms_providers,
+            cache: Default::default(),
+        })
+    }
+
+    /// Gets a handle to the key vault collection.
+    pub(crate) fn collection(&self) -> &Collection<Document> {
+        &self.collection
+    }
+
+    /// Encrypts the material of a data key with the given master key, returning the encrypted
+    /// material and the `masterKey` document describing how it was encrypted.
+    pub(crate) fn encrypt_key_material(
+        &self,
+        master_key: &MasterKey,
+        material: &[u8],
+    ) -> Result<(Vec<u8>, Document)> {
+        match master_key {
+            MasterKey::Local => {
+                let encrypted =
+                    crypto::encrypt(self.local_master_key()?, material, &[], IvMode::Random)?;
+                Ok((encrypted, doc! { "provider": "local" }))
+            }
+        }
+    }
+
+    /// Decrypts the material of the given data key document using the master key it was encrypted
+    /// with.
+    pub(crate) fn decrypt_key_material(&self, key: &Document) -> Result<Vec<u8>> {
+        let provider = key
+            .get_document("masterKey")
+            .and_then(|master_key| master_key.get_str("provider"))
+            .map_err(|_| Error::encryption("data key is missing a valid masterKey document"))?;
+        let material = match key.get("keyMaterial") {
+            Some(Bson::Binary(material)) => &material.bytes,
+            _ => return Err(Error::encryption("data key is missing its key material")),
+        };
+
+        match provider {
+            "local" => crypto::decrypt(self.local_master_key()?, material, &[]),
+            other => Err(Error::encryption(format!(
+                "unsupported KMS provider {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn local_master_key(&self) -> Result<&[u8]> {
+        self.kms_providers
+            .local
+            .as_deref()
+            .ok_or_else(|| Error::encryption("the local KMS provider is not configured"))
+    }
+
+    /// Gets the decrypted material of the data key with the given id, fetching it from the key
+    /// vault if it is not cached.
+    pub(crate) async fn key_material(&self, id: &[u8]) -> Result<Arc<Vec<u8>>> {
+        if let Some(cached) = self.cache.lock().unwrap().get(id) {
+            if cached.fetched_at.elapsed() < KEY_CACHE_TTL {
+                return Ok(cached.material.clone());
+            }
+        }
+
+        let key_id = Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: id.to_vec(),
+        };
+        let key = self
+            .collection
+            .find_one(doc! { "_id": key_id }, None)
+            .await?
+            .ok_or_else(|| {
+                Error::encryption(format!("no data key found with id {}", hex::encode(id)))
+            })?;
+        let material = Arc::new(self.decrypt_key_material(&key)?);
+
+        self.cache.lock().unwrap().insert(
+            id.to_vec(),
+            CachedKey {
+                material: material.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(material)
+    }
+
+    /// Gets the id of the data key with the given alternate name.
+    pub(crate) async fn key_id_by_alt_name(&self, key_alt_name: &str) -> Result<Binary> {
+        let key = self
+            .collection
+            .find_one(doc! { "keyAltNames": key_alt_name }, None)
+            .await?
+            .ok_or_else(|| {
+                Error::encryption(format!(
+                    "no data key found with keyAltName {:?}",
+                    key_alt_name
+                ))
+            })?;
+        match key.get("_id") {
+            Some(Bson::Binary(id)) => Ok(id.clone()),
+            _ => Err(Error::encryption("data key has an invalid _id")),
+        }
+    }
+
+    /// Encrypts `value` with the data key with the given id, returning the resulting ciphertext.
+    pub(crate) async fn encrypt(
+        &self,
+        value: &Bson,
+        key_id: &Binary,
+        algorithm: Algorithm,
+    ) -> Result<Binary> {
+        validate_key_id(key_id)?;
+        let material = self.key_material(&key_id.bytes).await?;
+        encrypt_value(value, key_id, algorithm, &material)
+    }
+
+    /// Decrypts a ciphertext produced by [`KeyVault::encrypt`], returning the original value.
+    pub(crate) async fn decrypt(&self, ciphertext: &Binary) -> Result<Bson> {
+        let material = self.key_material(ciphertext_key_id(ciphertext)?).await?;
+        decrypt_value(ciphertext, &material)
+    }
+}
+
+/// Returns an error if `key_id` is not a valid data key id.
+pub(crate) fn validate_key_id(key_id: &Binary) -> Result<()> {
+    if key_id.subtype != BinarySubtype::Uuid || key_id.bytes.len() != KEY_ID_LENGTH {
+        return Err(Error::invalid_argument(
+            "data key ids must be UUIDs with binary subtype 4",
+        ));
+    }
+    Ok(())
+}
+
+/// Encrypts `value` with the given decrypted data key material.
+pub(crate) fn encrypt_value(
+    value: &Bson,
+    key_id: &Binary,
+    algorithm: Algorithm,
+    material: &[u8],
+) -> Result<Binary> {
+    validate_encryptable(value, algorithm)?;
+
+    let (element_type, plaintext) = value_to_bytes(value)?;
+    let mut bytes = Vec::with_capacity(CIPHERTEXT_HEADER_LENGTH);
+    bytes.push(algorithm.subtype());
+    bytes.extend_from_slice(&key_id.bytes);
+    bytes.push(element_type);
+
+    let encrypted = crypto::encrypt(material, &plaintext, &bytes, algorithm.iv_mode())?;
+    bytes.extend_from_slice(&encrypted);
+
+    Ok(Binary {
+        subtype: BinarySubtype::Encrypted,
+        bytes,
+    })
+}
+
+/// Gets the id of the data key that was used to encrypt `ciphertext`.
+pub(crate) fn ciphertext_key_id(ciphertext: &Binary) -> Result<&[u8]> {
+    if ciphertext.subtype != BinarySubtype::Encrypted {
+        return Err(Error::invalid_argument(
+            "only binary values with subtype 6 can be decrypted",
+        ));
+    }
+    let bytes = &ciphertext.bytes;
+    if bytes.len() < CIPHERTEXT_HEADER_LENGTH || Algorithm::from_subtype(bytes[0]).is_none() {
+        return Err(Error::encryption("malformed ciphertext"));
+    }
+    Ok(&bytes[1..=KEY_ID_LENGTH])
+}
+
+/// Decrypts `ciphertext` with the given decrypted data key material.
+pub(crate) fn decrypt_value(ciphertext: &Binary, material: &[u8]) -> Result<Bson> {
+    ciphertext_key_id(ciphertext)?;
+    let (associated_data, encrypted) = ciphertext.bytes.split_at(CIPHERTEXT_HEADER_LENGTH);
+    let plaintext = crypto::decrypt(material, encrypted, associated_data)?;
+
+    value_from_bytes(associated_data[CIPHERTEXT_HEADER_LENGTH - 1], &plaintext)
+}
+
+/// Returns an error if `value` cannot be encrypted with `algorithm`.
+fn validate_encryptable(value: &Bson, algorithm: Algorithm) -> Result<()> {
+    let prohibited = match value {
+        Bson::Null | Bson::Undefined | Bson::MinKey | Bson::MaxKey => true,
+        Bson::Binary(binary) => binary.subtype == BinarySubtype::Encrypted,
+        Bson::Double(_)
+        | Bson::Decimal128(_)
+        | Bson::Boolean(_)
+        | Bson::Document(_)
+        | Bson::Array(_)
+        | Bson::JavaScriptCodeWithScope(_) => {
+            algorithm == Algorithm::AeadAes256CbcHmacSha512Deterministic
+        }
+        _ => false,
+    };
+
+    if prohibited {
+        return Err(Error::invalid_argument(format!(
+            "values of type {:?} cannot be encrypted with {:?}",
+            value.element_type(),
+            algorithm
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the BSON element type of `value` and its raw bytes, excluding the type and key.
+fn value_to_bytes(value: &Bson) -> Result<(u8, Vec<u8>)> {
+    let mut bytes = Vec::new();
+    doc! { "v": value.clone() }.to_writer(&mut bytes)?;
+
+    // The document is laid out as: length (4 bytes), element type (1 byte), key ("v\0"), value,
+    // terminating null byte.
+    Ok((bytes[4], bytes[7..bytes.len() - 1].to_vec()))
+}
+
+/// Reconstructs a BSON value from its element type and raw bytes.
+fn value_from_bytes(element_type: u8, value: &[u8]) -> Result<Bson> {
+    let length = 4 + 1 + 2 + value.len() + 1;
+    let mut bytes = Vec::with_capacity(length);
+    bytes.extend_from_slice(&(length as i32).to_le_bytes());
+    bytes.push(element_type);
+    bytes.extend_from_slice(b"v\0");
+    bytes.extend_from_slice(value);
+    bytes.push(0);
+
+    Document::from_reader(bytes.as_slice())?
+        .remove("v")
+        .ok_or_else(|| Error::encryption("decrypted value is missing"))
+}
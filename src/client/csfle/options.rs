@@ -0,0 +1,44 @@
+//! Contains the options for automatic client-side field level encryption.
+
+use std::collections::HashMap;
+
+use typed_builder::TypedBuilder;
+
+use super::client_encryption::KmsProviders;
+use crate::{bson::Document, Client, Namespace};
+
+/// Contains the options for automatically encrypting and decrypting fields of the documents sent to
+/// and received from the server, configured via
+/// [`ClientOptions::auto_encryption_opts`](crate::options::ClientOptions::auto_encryption_opts).
+///
+/// Outgoing commands on collections with a schema in the `schema_map` have the fields marked with
+/// the `encrypt` keyword encrypted. Inserted and replacement documents, `$set` and `$setOnInsert`
+/// updates, and equality predicates (`$eq`, `$ne`, `$in` and `$nin`) in query filters and
+/// `$match` stages are supported. Other commands and aggregation stages on those collections
+/// return an error without being sent, unless they cannot contain field values (e.g. `drop`,
+/// `createIndexes`, or `$sort` and `$limit` stages). Any value of binary subtype 6 in a reply is
+/// decrypted before it is returned, regardless of the schema map.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct AutoEncryptionOptions {
+    /// The client used to access the key vault collection. Defaults to an internal client
+    /// created with the same options as the encrypting client, without automatic encryption.
+    pub key_vault_client: Option<Client>,
+
+    /// The namespace of the key vault collection.
+    #[builder(!default)]
+    pub key_vault_namespace: Namespace,
+
+    /// The master keys used to decrypt the data keys in the key vault collection.
+    #[builder(!default)]
+    pub kms_providers: KmsProviders,
+
+    /// A map from collection namespaces (e.g. `"db.coll"`) to the JSON schemas describing which
+    /// fields of the collection's documents are encrypted.
+    pub schema_map: Option<HashMap<String, Document>>,
+
+    /// If true, outgoing commands are not encrypted, but encrypted values in replies are still
+    /// decrypted. Defaults to false.
+    pub bypass_auto_encryption: Option<bool>,
+}
@@ -0,0 +1,541 @@
+use std::{collections::HashMap, sync::Arc};
+
+use super::{
+    auto_encryption::{
+        decrypt_in_place,
+        parse_schema,
+        EncryptMetadata,
+        FieldEncrypter,
+        KeyMaterials,
+        SchemaNode,
+    },
+    client_encryption::Algorithm,
+    crypto::{self, IvMode},
+    decrypt_value,
+    validate_encryptable,
+    value_from_bytes,
+    value_to_bytes,
+};
+use crate::bson::{doc, oid::ObjectId, spec::BinarySubtype, Binary, Bson, DateTime, Document};
+
+#[test]
+fn crypto_round_trip() {
+    let key = crypto::generate_key();
+    let associated_data = b"associated data";
+
+    for plaintext in [
+        &b""[..],
+        b"a",
+        b"exactly 16 bytes",
+        b"a value spanning several blocks",
+    ] {
+        for iv_mode in [IvMode::Deterministic, IvMode::Random] {
+            let ciphertext = crypto::encrypt(&key, plaintext, associated_data, iv_mode).unwrap();
+            // IV, at least one padded block and the 32-byte tag.
+            assert_eq!(ciphertext.len(), 16 + (plaintext.len() / 16 + 1) * 16 + 32);
+            assert_eq!(
+                crypto::decrypt(&key, &ciphertext, associated_data).unwrap(),
+                plaintext
+            );
+        }
+    }
+}
+
+#[test]
+fn crypto_iv_modes() {
+    let key = crypto::generate_key();
+
+    let first = crypto::encrypt(&key, b"value", b"", IvMode::Deterministic).unwrap();
+    let second = crypto::encrypt(&key, b"value", b"", IvMode::Deterministic).unwrap();
+    assert_eq!(first, second);
+
+    let other = crypto::encrypt(&key, b"other", b"", IvMode::Deterministic).unwrap();
+    assert_ne!(first[..16], other[..16]);
+
+    let first = crypto::encrypt(&key, b"value", b"", IvMode::Random).unwrap();
+    let second = crypto::encrypt(&key, b"value", b"", IvMode::Random).unwrap();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn crypto_rejects_tampering() {
+    let key = crypto::generate_key();
+    let ciphertext = crypto::encrypt(&key, b"value", b"ad", IvMode::Random).unwrap();
+
+    let mut tampered = ciphertext.clone();
+    tampered[20] ^= 1;
+    assert!(crypto::decrypt(&key, &tampered, b"ad").is_err());
+
+    assert!(crypto::decrypt(&key, &ciphertext, b"other ad").is_err());
+    assert!(crypto::decrypt(&crypto::generate_key(), &ciphertext, b"ad").is_err());
+    assert!(crypto::decrypt(&key, &ciphertext[..40], b"ad").is_err());
+    assert!(crypto::encrypt(&key[..64], b"value", b"ad", IvMode::Random).is_err());
+}
+
+/// The inputs of the AEAD_AES_256_CBC_HMAC_SHA_512 example in section 5.4 of
+/// draft-mcgrew-aead-aes-cbc-hmac-sha2-05, extended with a 32-byte IV key.
+fn known_answer_inputs() -> (Vec<u8>, &'static [u8], &'static [u8]) {
+    let key: Vec<u8> = (0..96).collect();
+    let plaintext =
+        b"A cipher system must not be required to be secret, and it must be able to fall \
+                      into the hands of the enemy without inconvenience";
+    let associated_data = b"The second principle of Auguste Kerckhoffs";
+    (key, plaintext, associated_data)
+}
+
+#[test]
+fn crypto_known_answer_random() {
+    let (key, plaintext, associated_data) = known_answer_inputs();
+    let iv = [
+        0x1a, 0xf3, 0x8c, 0x2d, 0xc2, 0xb9, 0x6f, 0xfd, 0xd8, 0x66, 0x94, 0x09, 0x23, 0x41, 0xbc,
+        0x04,
+    ];
+    let expected = hex::decode(concat!(
+        "1af38c2dc2b96ffdd86694092341bc04",
+        "4affaaadb78c31c5da4b1b590d10ffbd3dd8d5d302423526912da037ecbcc7bd",
+        "822c301dd67c373bccb584ad3e9279c2e6d12a1374b77f077553df829410446b",
+        "36ebd97066296ae6427ea75c2e0846a11a09ccf5370dc80bfecbad28c73f09b3",
+        "a3b75e662a2594410ae496b2e2e6609e31e6e02cc837f053d21f37ff4f51950b",
+        "be2638d09dd7a4930930806d0703b1f6",
+        "4dd3b4c088a7f45c216839645b2012bf2e6269a8c56a816dbc1b267761955bc5",
+    ))
+    .unwrap();
+
+    let ciphertext = crypto::encrypt_with_iv(&key, &iv, plaintext, associated_data).unwrap();
+    assert_eq!(ciphertext, expected);
+    assert_eq!(
+        crypto::decrypt(&key, &expected, associated_data).unwrap(),
+        plaintext
+    );
+}
+
+#[test]
+fn crypto_known_answer_deterministic() {
+    let (key, plaintext, associated_data) = known_answer_inputs();
+    // the IV is the first 16 bytes of HMAC-SHA-512(IV key, AD || AL || plaintext).
+    let expected = hex::decode(concat!(
+        "789303e6989b65feacfa48ce9a4c292c",
+        "758b56c0cca8bfa2aa3805aa710fb0c3110234e78b5a8464783dda10b7c8b796",
+        "3b3740d6b2023dd6371680b76568ade53e33c4c70204dcf002f947de43a2ff82",
+        "39013a85210a94b70e7c3e29e999453cbe03be70fb733e03a2b7648bfc9314c8",
+        "e459a8be7303d10009942101eba6a558832acd86740de7e6b295987203c8f7aa",
+        "37d7902caf8e8b828a76249b86ebb62d",
+        "e015b4a913b549a23d35dea3343390299b3ef0786de205ccce81a92cde0b349b",
+    ))
+    .unwrap();
+
+    let ciphertext =
+        crypto::encrypt(&key, plaintext, associated_data, IvMode::Deterministic).unwrap();
+    assert_eq!(ciphertext, expected);
+    assert_eq!(
+        crypto::decrypt(&key, &expected, associated_data).unwrap(),
+        plaintext
+    );
+}
+
+#[test]
+fn value_bytes_round_trip() {
+    let values = vec![
+        Bson::String("hello".to_string()),
+        Bson::Int32(5),
+        Bson::Int64(-5),
+        Bson::Double(1.5),
+        Bson::ObjectId(ObjectId::new()),
+        Bson::DateTime(DateTime::now()),
+        Bson::Document(doc! { "a": [1, "b"] }),
+        Bson::Binary(Binary {
+            subtype: BinarySubtype::Generic,
+            bytes: vec![1, 2, 3],
+        }),
+    ];
+
+    for value in values {
+        let (element_type, bytes) = value_to_bytes(&value).unwrap();
+        assert_eq!(element_type, value.element_type() as u8);
+        assert_eq!(value_from_bytes(element_type, &bytes).unwrap(), value);
+    }
+}
+
+#[test]
+fn validate_encryptable_types() {
+    let deterministic = Algorithm::AeadAes256CbcHmacSha512Deterministic;
+    let random = Algorithm::AeadAes256CbcHmacSha512Random;
+
+    for value in [Bson::Null, Bson::MinKey, Bson::MaxKey, Bson::Undefined] {
+        assert!(validate_encryptable(&value, deterministic).is_err());
+        assert!(validate_encryptable(&value, random).is_err());
+    }
+
+    let encrypted = Bson::Binary(Binary {
+        subtype: BinarySubtype::Encrypted,
+        bytes: vec![1],
+    });
+    assert!(validate_encryptable(&encrypted, random).is_err());
+
+    for value in [
+        Bson::Double(1.0),
+        Bson::Boolean(true),
+        Bson::Document(doc! {}),
+    ] {
+        assert!(validate_encryptable(&value, deterministic).is_err());
+        assert!(validate_encryptable(&value, random).is_ok());
+    }
+
+    assert!(validate_encryptable(&Bson::String("a".to_string()), deterministic).is_ok());
+}
+
+fn key_id(byte: u8) -> Binary {
+    Binary {
+        subtype: BinarySubtype::Uuid,
+        bytes: vec![byte; 16],
+    }
+}
+
+fn test_schema() -> Document {
+    doc! {
+        "bsonType": "object",
+        "encryptMetadata": {
+            "keyId": [key_id(1)],
+            "algorithm": "AEAD_AES_256_CBC_HMAC_SHA_512-Deterministic",
+        },
+        "properties": {
+            "ssn": { "encrypt": { "bsonType": "string" } },
+            "notes": {
+                "encrypt": {
+                    "keyId": [key_id(2)],
+                    "algorithm": "AEAD_AES_256_CBC_HMAC_SHA_512-Random",
+                },
+            },
+            "address": {
+                "bsonType": "object",
+                "properties": {
+                    "street": { "encrypt": { "bsonType": "string" } },
+                    "city": { "bsonType": "string" },
+                },
+            },
+            "name": { "bsonType": "string" },
+        },
+    }
+}
+
+fn test_properties() -> HashMap<String, SchemaNode> {
+    match parse_schema(&test_schema(), &EncryptMetadata::default()) {
+        Ok(Some(SchemaNode::Object(properties))) => properties,
+        other => panic!("expected object schema, got {:?}", other),
+    }
+}
+
+fn test_keys() -> KeyMaterials {
+    let mut keys = KeyMaterials::new();
+    keys.insert(key_id(1).bytes, Arc::new(crypto::generate_key()));
+    keys.insert(key_id(2).bytes, Arc::new(crypto::generate_key()));
+    keys
+}
+
+fn assert_encrypted(value: Option<&Bson>, keys: &KeyMaterials, expected: Bson) {
+    let ciphertext = match value {
+        Some(Bson::Binary(binary)) if binary.subtype == BinarySubtype::Encrypted => binary,
+        other => panic!("expected encrypted value, got {:?}", other),
+    };
+    let material = &keys[&ciphertext.bytes[1..17].to_vec()];
+    assert_eq!(decrypt_value(ciphertext, material).unwrap(), expected);
+}
+
+#[test]
+fn parse_schema_inherits_metadata() {
+    let properties = test_properties();
+    assert_eq!(properties.len(), 3);
+
+    match &properties["ssn"] {
+        SchemaNode::Encrypt(field) => {
+            assert_eq!(field.key_id, key_id(1));
+            assert_eq!(
+                field.algorithm,
+                Algorithm::AeadAes256CbcHmacSha512Deterministic
+            );
+        }
+        other => panic!("expected encrypted field, got {:?}", other),
+    }
+    match &properties["notes"] {
+        SchemaNode::Encrypt(field) => {
+            assert_eq!(field.key_id, key_id(2));
+            assert_eq!(field.algorithm, Algorithm::AeadAes256CbcHmacSha512Random);
+        }
+        other => panic!("expected encrypted field, got {:?}", other),
+    }
+    match &properties["address"] {
+        SchemaNode::Object(nested) => {
+            assert_eq!(nested.len(), 1);
+            assert!(matches!(nested["street"], SchemaNode::Encrypt(_)));
+        }
+        other => panic!("expected object schema, got {:?}", other),
+    }
+
+    let no_encryption = doc! { "properties": { "name": { "bsonType": "string" } } };
+    assert!(parse_schema(&no_encryption, &EncryptMetadata::default())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn parse_schema_errors() {
+    let invalid = [
+        doc! { "properties": { "a": { "encrypt": { "keyId": [key_id(1)] } } } },
+        doc! { "properties": { "a": { "encrypt": {
+            "keyId": "/keyName",
+            "algorithm": "AEAD_AES_256_CBC_HMAC_SHA_512-Random",
+        } } } },
+        doc! { "properties": { "a": { "encrypt": {
+            "keyId": [key_id(1)],
+            "algorithm": "unknown",
+        } } } },
+        doc! { "properties": { "a": { "encrypt": {
+            "keyId": [Binary { subtype: BinarySubtype::Generic, bytes: vec![1; 16] }],
+            "algorithm": "AEAD_AES_256_CBC_HMAC_SHA_512-Random",
+        } } } },
+    ];
+    for schema in invalid.iter() {
+        assert!(
+            parse_schema(schema, &EncryptMetadata::default()).is_err(),
+            "expected error for {}",
+            schema
+        );
+    }
+}
+
+#[test]
+fn encrypt_insert_and_update() {
+    let properties = test_properties();
+    let keys = test_keys();
+    let encrypter = FieldEncrypter {
+        keys: &keys,
+        properties: &properties,
+    };
+
+    let mut insert = doc! {
+        "insert": "coll",
+        "documents": [{
+            "name": "Alice",
+            "ssn": "123-45-6789",
+            "notes": "private",
+            "address": { "street": "1 Main St", "city": "Springfield" },
+        }],
+    };
+    encrypter.encrypt_command("insert", &mut insert).unwrap();
+    let document = insert.get_array("documents").unwrap()[0]
+        .as_document()
+        .unwrap();
+    assert_eq!(document.get_str("name"), Ok("Alice"));
+    assert_encrypted(document.get("ssn"), &keys, Bson::from("123-45-6789"));
+    assert_encrypted(document.get("notes"), &keys, Bson::from("private"));
+    let address = document.get_document("address").unwrap();
+    assert_encrypted(address.get("street"), &keys, Bson::from("1 Main St"));
+    assert_eq!(address.get_str("city"), Ok("Springfield"));
+
+    let mut update = doc! {
+        "update": "coll",
+        "updates": [{
+            "q": { "ssn": "123-45-6789" },
+            "u": { "$set": { "notes": "updated", "address.street": "2 Main St" } },
+        }],
+    };
+    encrypter.encrypt_command("update", &mut update).unwrap();
+    let statement = update.get_array("updates").unwrap()[0]
+        .as_document()
+        .unwrap();
+    assert_encrypted(
+        statement.get_document("q").unwrap().get("ssn"),
+        &keys,
+        Bson::from("123-45-6789"),
+    );
+    let set = statement
+        .get_document("u")
+        .and_then(|u| u.get_document("$set"))
+        .unwrap();
+    assert_encrypted(set.get("notes"), &keys, Bson::from("updated"));
+    assert_encrypted(set.get("address.street"), &keys, Bson::from("2 Main St"));
+
+    for u in [
+        Bson::Array(vec![Bson::Document(doc! { "$set": { "ssn": "1" } })]),
+        Bson::Document(doc! { "$inc": { "ssn": 1 } }),
+    ] {
+        let mut update = doc! { "update": "coll", "updates": [{ "q": {}, "u": u }] };
+        assert!(encrypter.encrypt_command("update", &mut update).is_err());
+    }
+}
+
+#[test]
+fn encrypt_filters() {
+    let properties = test_properties();
+    let keys = test_keys();
+    let encrypter = FieldEncrypter {
+        keys: &keys,
+        properties: &properties,
+    };
+
+    let mut find = doc! {
+        "find": "coll",
+        "filter": {
+            "$or": [
+                { "ssn": { "$in": ["a", "b"] } },
+                { "address.street": { "$eq": "c" } },
+            ],
+            "name": { "$gt": "A" },
+        },
+    };
+    encrypter.encrypt_command("find", &mut find).unwrap();
+    let clauses = find
+        .get_document("filter")
+        .and_then(|filter| filter.get_array("$or"))
+        .unwrap();
+    let values = clauses[0]
+        .as_document()
+        .and_then(|clause| clause.get_document("ssn").ok())
+        .and_then(|ssn| ssn.get_array("$in").ok())
+        .unwrap();
+    assert_encrypted(values.get(0), &keys, Bson::from("a"));
+    assert_encrypted(values.get(1), &keys, Bson::from("b"));
+    let street = clauses[1]
+        .as_document()
+        .and_then(|clause| clause.get_document("address.street").ok())
+        .unwrap();
+    assert_encrypted(street.get("$eq"), &keys, Bson::from("c"));
+
+    let mut aggregate = doc! {
+        "aggregate": "coll",
+        "pipeline": [{ "$match": { "ssn": "d" } }],
+    };
+    encrypter
+        .encrypt_command("aggregate", &mut aggregate)
+        .unwrap();
+    let stage = aggregate.get_array("pipeline").unwrap()[0]
+        .as_document()
+        .and_then(|stage| stage.get_document("$match").ok())
+        .unwrap();
+    assert_encrypted(stage.get("ssn"), &keys, Bson::from("d"));
+
+    for filter in [
+        doc! { "notes": "random" },
+        doc! { "ssn": { "$gt": "a" } },
+        doc! { "ssn.part": "a" },
+    ] {
+        let mut find = doc! { "find": "coll", "filter": filter };
+        assert!(encrypter.encrypt_command("find", &mut find).is_err());
+    }
+}
+
+#[test]
+fn reject_unsupported_commands() {
+    let properties = test_properties();
+    let keys = test_keys();
+    let encrypter = FieldEncrypter {
+        keys: &keys,
+        properties: &properties,
+    };
+
+    for stage in [
+        doc! { "$group": { "_id": "$ssn" } },
+        doc! { "$lookup": { "from": "other", "localField": "ssn", "foreignField": "ssn", "as": "o" } },
+        doc! { "$graphLookup": {
+            "from": "other",
+            "startWith": "$ssn",
+            "connectFromField": "ssn",
+            "connectToField": "ssn",
+            "as": "o",
+        } },
+        doc! { "$facet": { "a": [{ "$match": { "ssn": "a" } }] } },
+        doc! { "$unionWith": "other" },
+        doc! { "$project": { "matches": { "$eq": ["$ssn", "a"] } } },
+        doc! { "$merge": "other" },
+        doc! { "$out": "other" },
+    ] {
+        let mut aggregate = doc! {
+            "aggregate": "coll",
+            "pipeline": [{ "$match": { "ssn": "a" } }, stage.clone()],
+        };
+        assert!(
+            encrypter
+                .encrypt_command("aggregate", &mut aggregate)
+                .is_err(),
+            "expected error for {}",
+            stage
+        );
+    }
+
+    let mut aggregate = doc! {
+        "aggregate": "coll",
+        "pipeline": [{ "$sort": { "name": 1 } }, { "$skip": 1 }, { "$limit": 1 }],
+    };
+    let unchanged = aggregate.clone();
+    encrypter
+        .encrypt_command("aggregate", &mut aggregate)
+        .unwrap();
+    assert_eq!(aggregate, unchanged);
+
+    for (name, mut command) in [
+        (
+            "collMod",
+            doc! { "collMod": "coll", "validator": { "ssn": "a" } },
+        ),
+        (
+            "mapReduce",
+            doc! { "mapReduce": "coll", "query": { "ssn": "a" } },
+        ),
+        ("explain", doc! { "explain": { "mapReduce": "coll" } }),
+    ] {
+        assert!(
+            encrypter.encrypt_command(name, &mut command).is_err(),
+            "expected error for {}",
+            command
+        );
+    }
+
+    for (name, mut command) in [
+        ("drop", doc! { "drop": "coll" }),
+        (
+            "killCursors",
+            doc! { "killCursors": "coll", "cursors": [1_i64] },
+        ),
+    ] {
+        let unchanged = command.clone();
+        encrypter.encrypt_command(name, &mut command).unwrap();
+        assert_eq!(command, unchanged);
+    }
+
+    let mut explain = doc! { "explain": { "find": "coll", "filter": { "ssn": "a" } } };
+    encrypter.encrypt_command("explain", &mut explain).unwrap();
+    let filter = explain
+        .get_document("explain")
+        .and_then(|find| find.get_document("filter"))
+        .unwrap();
+    assert_encrypted(filter.get("ssn"), &keys, Bson::from("a"));
+}
+
+#[test]
+fn decrypt_reply() {
+    let keys = test_keys();
+    let properties = test_properties();
+    let encrypter = FieldEncrypter {
+        keys: &keys,
+        properties: &properties,
+    };
+
+    let mut insert = doc! {
+        "insert": "coll",
+        "documents": [{ "ssn": "123", "address": { "street": "1 Main St" } }],
+    };
+    encrypter.encrypt_command("insert", &mut insert).unwrap();
+    let mut reply = Bson::Document(doc! {
+        "cursor": { "firstBatch": insert.get_array("documents").unwrap().clone() },
+        "ok": 1,
+    });
+
+    decrypt_in_place(&mut reply, &keys).unwrap();
+    assert_eq!(
+        reply,
+        Bson::Document(doc! {
+            "cursor": { "firstBatch": [{ "ssn": "123", "address": { "street": "1 Main St" } }] },
+            "ok": 1,
+        })
+    );
+}
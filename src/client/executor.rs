@@ -1,10 +1,15 @@
-use bson::{doc, RawBsonRef, RawDocument, Timestamp};
+use bson::{doc, Bson, RawBsonRef, RawDocument, RawDocumentBuf, Timestamp};
 use lazy_static::lazy_static;
 use serde::de::DeserializeOwned;
 
-use std::{collections::HashSet, sync::Arc, time::Instant};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use super::{session::TransactionState, Client, ClientSession};
+use super::{auth, session::TransactionState, Client, ClientSession};
 use crate::{
     bson::Document,
     change_stream::{
@@ -21,7 +26,7 @@ use crate::{
         RawCommand,
         RawCommandResponse,
     },
-    cursor::{session::SessionCursor, Cursor, CursorSpecification},
+    cursor::{session::SessionCursor, Cursor, CursorSpecification, CursorTimeout},
     error::{
         Error,
         ErrorKind,
@@ -38,10 +43,12 @@ use crate::{
         ChangeStreamAggregate,
         CommandErrorBody,
         CommitTransaction,
+        GetMore,
         Operation,
         Retryability,
     },
     options::{ChangeStreamOptions, SelectionCriteria},
+    runtime,
     sdam::{
         HandshakePhase,
         SelectedServer,
@@ -54,6 +61,10 @@ use crate::{
     ClusterTime,
 };
 
+/// The error code returned by the server when an operation exceeds its `maxTimeMS`.
+const MAX_TIME_MS_EXPIRED_CODE: i32 = 50;
+const REAUTHENTICATION_REQUIRED_CODE: i32 = 391;
+
 lazy_static! {
     pub(crate) static ref REDACTED_COMMANDS: HashSet<&'static str> = {
         let mut hash_set = HashSet::new();
@@ -97,7 +108,32 @@ impl Client {
         op: T,
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<ExecutionDetails<T>> {
-        Box::pin(async {
+        let _in_flight = self.begin_operation()?;
+        self.execute_operation_with_details_unchecked(op, session)
+            .await
+    }
+
+    /// Execute the given operation while this `Client` is shutting down. This is used for the
+    /// `killCursors` and `endSessions` commands sent by `Client::shutdown`, which would otherwise
+    /// be rejected.
+    pub(crate) async fn execute_operation_during_shutdown<T: Operation>(
+        &self,
+        op: T,
+    ) -> Result<T::O> {
+        self.execute_operation_with_details_unchecked(op, None)
+            .await
+            .map(|details| details.output.operation_output)
+    }
+
+    /// Execute the given operation without checking whether this `Client` has been shut down.
+    async fn execute_operation_with_details_unchecked<T: Operation>(
+        &self,
+        op: T,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<ExecutionDetails<T>> {
+        let timeout = self.operation_timeout(&op);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let execution = Box::pin(async {
             // TODO RUST-9: allow unacknowledged write concerns
             if !op.is_acknowledged() {
                 return Err(ErrorKind::InvalidArgument {
@@ -134,13 +170,62 @@ impl Client {
                     implicit_session.as_mut()
                 }
             };
-            let output = self.execute_operation_with_retry(op, session).await?;
+            let output = self
+                .execute_operation_with_retry(op, session, deadline)
+                .await?;
             Ok(ExecutionDetails {
                 output,
                 implicit_session,
             })
-        })
-        .await
+        });
+
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return execution.await,
+        };
+        match runtime::timeout(timeout, execution).await {
+            Ok(Err(err)) if err.code() == Some(MAX_TIME_MS_EXPIRED_CODE) => {
+                Err(Error::timeout(format!(
+                    "the server did not complete the operation within its timeout of {:?}",
+                    timeout
+                ))
+                .with_source(err))
+            }
+            Ok(result) => result,
+            Err(_) => Err(Error::timeout(format!(
+                "the operation did not complete within its timeout of {:?}",
+                timeout
+            ))),
+        }
+    }
+
+    /// Gets the deadline for an operation made up of several commands with the given timeout,
+    /// falling back to the client's default timeout. Each command should be given only the time
+    /// remaining before the deadline.
+    pub(crate) fn operation_deadline(&self, timeout: Option<Duration>) -> Option<Instant> {
+        timeout
+            .or(self.inner.options.timeout)
+            .filter(|timeout| *timeout != Duration::from_secs(0))
+            .map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Gets the timeout for `op`, falling back to the client's default timeout. A timeout of zero
+    /// disables the timeout.
+    fn operation_timeout<T: Operation>(&self, op: &T) -> Option<Duration> {
+        op.timeout()
+            .or(self.inner.options.timeout)
+            .filter(|timeout| *timeout != Duration::from_secs(0))
+    }
+
+    /// Gets how the timeout of `op`, which creates a cursor, applies to the cursor's `getMore`s.
+    /// The timeout bounds the whole lifetime of the cursor unless it is tailable.
+    fn cursor_timeout<T: Operation>(&self, op: &T) -> Option<CursorTimeout> {
+        let timeout = self.operation_timeout(op)?;
+        if op.is_tailable() {
+            Some(CursorTimeout::Iteration(timeout))
+        } else {
+            Some(CursorTimeout::Lifetime(Instant::now() + timeout))
+        }
     }
 
     /// Execute the given operation, returning the cursor created by the operation.
@@ -151,7 +236,9 @@ impl Client {
         Op: Operation<O = CursorSpecification>,
     {
         Box::pin(async {
+            let timeout = self.cursor_timeout(&op);
             let mut details = self.execute_operation_with_details(op, None).await?;
+            details.output.operation_output.info.timeout = timeout;
             let pinned = self.pin_connection_for_cursor(
                 &details.output.operation_output,
                 &mut details.output.connection,
@@ -174,9 +261,11 @@ impl Client {
     where
         Op: Operation<O = CursorSpecification>,
     {
+        let timeout = self.cursor_timeout(&op);
         let mut details = self
             .execute_operation_with_details(op, &mut *session)
             .await?;
+        details.output.operation_output.info.timeout = timeout;
 
         let pinned = self.pin_connection_for_session(
             &details.output.operation_output,
@@ -190,6 +279,57 @@ impl Client {
         ))
     }
 
+    /// Execute the given operation, returning the rest of its output along with all of the results
+    /// from the cursor created by the operation.
+    pub(crate) async fn execute_operation_and_collect_cursor<Op, O, T>(
+        &self,
+        op: Op,
+        session: Option<&mut ClientSession>,
+    ) -> Result<(O, Vec<T>)>
+    where
+        Op: Operation<O = (O, CursorSpecification)>,
+        T: DeserializeOwned,
+    {
+        Box::pin(async {
+            let timeout = self.cursor_timeout(&op);
+            let mut results = Vec::new();
+            match session {
+                Some(session) => {
+                    let mut details = self
+                        .execute_operation_with_details(op, &mut *session)
+                        .await?;
+                    let (output, mut spec) = details.output.operation_output;
+                    spec.info.timeout = timeout;
+                    let pinned = self.pin_connection_for_session(
+                        &spec,
+                        &mut details.output.connection,
+                        session,
+                    )?;
+                    let mut cursor: SessionCursor<T> =
+                        SessionCursor::new(self.clone(), spec, pinned);
+                    while cursor.advance(session).await? {
+                        results.push(bson::from_slice(cursor.current().as_bytes())?);
+                    }
+                    Ok((output, results))
+                }
+                None => {
+                    let mut details = self.execute_operation_with_details(op, None).await?;
+                    let (output, mut spec) = details.output.operation_output;
+                    spec.info.timeout = timeout;
+                    let pinned =
+                        self.pin_connection_for_cursor(&spec, &mut details.output.connection)?;
+                    let mut cursor: Cursor<T> =
+                        Cursor::new(self.clone(), spec, details.implicit_session, pinned);
+                    while cursor.advance().await? {
+                        results.push(bson::from_slice(cursor.current().as_bytes())?);
+                    }
+                    Ok((output, results))
+                }
+            }
+        })
+        .await
+    }
+
     fn is_load_balanced(&self) -> bool {
         self.inner.options.load_balanced.unwrap_or(false)
     }
@@ -300,6 +440,7 @@ impl Client {
         &self,
         mut op: T,
         mut session: Option<&mut ClientSession>,
+        deadline: Option<Instant>,
     ) -> Result<ExecutionOutput<T>> {
         // If the current transaction has been committed/aborted and it is not being
         // re-committed/re-aborted, reset the transaction's state to TransactionState::None.
@@ -320,7 +461,7 @@ impl Client {
             .and_then(|s| s.transaction.pinned_mongos())
             .or_else(|| op.selection_criteria());
 
-        let server = match self.select_server(selection_criteria).await {
+        let server = match self.select_server(selection_criteria, op.name()).await {
             Ok(server) => server,
             Err(mut err) => {
                 err.add_labels_and_update_pin(None, &mut session, None)?;
@@ -342,7 +483,9 @@ impl Client {
                     _ => false,
                 };
                 if err.is_pool_cleared() || op_retry {
-                    return self.execute_retry(&mut op, &mut session, None, err).await;
+                    return self
+                        .execute_retry(&mut op, &mut session, None, err, deadline)
+                        .await;
                 } else {
                     return Err(err);
                 }
@@ -360,6 +503,7 @@ impl Client {
                 &mut session,
                 txn_number,
                 retryability,
+                deadline,
             )
             .await
         {
@@ -399,7 +543,7 @@ impl Client {
                 if retryability == Retryability::Read && err.is_read_retryable()
                     || retryability == Retryability::Write && err.is_write_retryable()
                 {
-                    self.execute_retry(&mut op, &mut session, txn_number, err)
+                    self.execute_retry(&mut op, &mut session, txn_number, err, deadline)
                         .await
                 } else {
                     Err(err)
@@ -414,10 +558,11 @@ impl Client {
         session: &mut Option<&mut ClientSession>,
         prior_txn_number: Option<i64>,
         first_error: Error,
+        deadline: Option<Instant>,
     ) -> Result<ExecutionOutput<T>> {
         op.update_for_retry();
 
-        let server = match self.select_server(op.selection_criteria()).await {
+        let server = match self.select_server(op.selection_criteria(), op.name()).await {
             Ok(server) => server,
             Err(_) => {
                 return Err(first_error);
@@ -437,7 +582,14 @@ impl Client {
         let txn_number = prior_txn_number.or_else(|| get_txn_number(session, retryability));
 
         match self
-            .execute_operation_on_connection(op, &mut conn, session, txn_number, retryability)
+            .execute_operation_on_connection(
+                op,
+                &mut conn,
+                session,
+                txn_number,
+                retryability,
+                deadline,
+            )
             .await
         {
             Ok(operation_output) => Ok(ExecutionOutput {
@@ -464,7 +616,9 @@ impl Client {
         }
     }
 
-    /// Executes an operation on a given connection, optionally using a provided session.
+    /// Executes an operation on a given connection, optionally using a provided session. If the
+    /// server requires the connection to reauthenticate, the operation is run again once the
+    /// connection has done so.
     async fn execute_operation_on_connection<T: Operation>(
         &self,
         op: &mut T,
@@ -472,6 +626,54 @@ impl Client {
         session: &mut Option<&mut ClientSession>,
         txn_number: Option<i64>,
         retryability: Retryability,
+        deadline: Option<Instant>,
+    ) -> Result<T::O> {
+        match self
+            .execute_operation_on_connection_once(
+                op,
+                connection,
+                session,
+                txn_number,
+                retryability,
+                deadline,
+            )
+            .await
+        {
+            Err(err) if err.code() == Some(REAUTHENTICATION_REQUIRED_CODE) => {
+                let options = &self.inner.options;
+                let credential = match (&options.credential, &options.credential_provider) {
+                    (Some(credential), _) => Cow::Borrowed(credential),
+                    (None, Some(provider)) => {
+                        Cow::Owned(auth::provide_credential(provider.as_ref()).await?)
+                    }
+                    (None, None) => return Err(err),
+                };
+                credential
+                    .reauthenticate_stream(connection, options.server_api.as_ref())
+                    .await?;
+
+                self.execute_operation_on_connection_once(
+                    op,
+                    connection,
+                    session,
+                    txn_number,
+                    retryability,
+                    deadline,
+                )
+                .await
+            }
+            result => result,
+        }
+    }
+
+    async fn execute_operation_on_connection_once<T: Operation>(
+        &self,
+        op: &mut T,
+        connection: &mut Connection,
+        session: &mut Option<&mut ClientSession>,
+        txn_number: Option<i64>,
+        retryability: Retryability,
+        deadline: Option<Instant>,
     ) -> Result<T::O> {
         if let Some(wc) = op.write_concern() {
             wc.validate()?;
@@ -596,19 +798,43 @@ impl Client {
 
         let cmd_name = cmd.name.clone();
         let target_db = cmd.target_db.clone();
-
-        let serialized = op.serialize_command(cmd)?;
+        let document_sequences = std::mem::take(&mut cmd.document_sequences);
+
+        let mut serialized = op.serialize_command(cmd)?;
+        if let Some(deadline) = deadline {
+            // getMores and commands in a transaction other than those that end it are only bounded
+            // by the timeout client-side, so maxTimeMS is not appended to them.
+            let in_transaction = session.as_ref().map_or(false, |session| {
+                session.in_transaction()
+                    && cmd_name != CommitTransaction::NAME
+                    && cmd_name != AbortTransaction::NAME
+            });
+            if op.name() != GetMore::NAME && !in_transaction {
+                serialized = append_max_time(serialized, deadline)?;
+            }
+        }
+        #[cfg(feature = "csfle")]
+        let serialized = match self.inner.auto_encrypter {
+            Some(ref auto_encrypter) => {
+                auto_encrypter
+                    .encrypt_command(&cmd_name, &target_db, serialized)
+                    .await?
+            }
+            None => serialized,
+        };
         let raw_cmd = RawCommand {
             name: cmd_name.clone(),
             target_db,
             bytes: serialized,
+            document_sequences,
+            exhaust_allowed: false,
         };
 
         self.emit_command_event(|handler| {
             let command_body = if should_redact {
                 Document::new()
             } else {
-                Document::from_reader(raw_cmd.bytes.as_slice())
+                command_event_body(&raw_cmd)
                     .unwrap_or_else(|e| doc! { "serialization error": e.to_string() })
             };
             let command_started_event = CommandStartedEvent {
@@ -750,6 +976,12 @@ impl Client {
                     handler.handle_command_succeeded_event(command_succeeded_event);
                 });
 
+                #[cfg(feature = "csfle")]
+                let response = match self.inner.auto_encrypter {
+                    Some(ref auto_encrypter) => auto_encrypter.decrypt_response(response).await?,
+                    None => response,
+                };
+
                 match op.handle_response(response, connection.stream_description()?) {
                     Ok(response) => Ok(response),
                     Err(mut err) => {
@@ -785,7 +1017,9 @@ impl Client {
             (matches!(topology_type, TopologyType::Single) && server_type.is_available())
                 || server_type.is_data_bearing()
         }));
-        let _: SelectedServer = self.select_server(Some(&criteria)).await?;
+        let _: SelectedServer = self
+            .select_server(Some(&criteria), "Check sessions support")
+            .await?;
         Ok(())
     }
 
@@ -1005,6 +1239,47 @@ impl Error {
     }
 }
 
+/// Builds the command document reported in command started events. Any document sequences sent
+/// alongside the command are included as arrays, as if they had been part of the command document.
+fn command_event_body(raw_cmd: &RawCommand) -> Result<Document> {
+    let mut body = Document::from_reader(raw_cmd.bytes.as_slice())?;
+    for sequence in &raw_cmd.document_sequences {
+        let documents = sequence
+            .documents
+            .iter()
+            .map(|d| d.to_document().map(Bson::Document))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        body.insert(sequence.identifier.clone(), documents);
+    }
+    Ok(body)
+}
+
+/// Gets the time remaining before `deadline`. Returns a timeout error if the deadline has already
+/// passed.
+pub(crate) fn remaining_time(deadline: Instant) -> Result<Duration> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| *remaining != Duration::from_secs(0))
+        .ok_or_else(|| Error::timeout("the operation's timeout expired before it was sent"))
+}
+
+/// Appends a `maxTimeMS` derived from the time remaining before `deadline` to the serialized
+/// command, unless the command already specifies one. Returns a timeout error if the deadline has
+/// already passed.
+fn append_max_time(serialized: Vec<u8>, deadline: Instant) -> Result<Vec<u8>> {
+    let remaining = remaining_time(deadline)?;
+
+    let mut command = RawDocumentBuf::from_bytes(serialized)?;
+    if command.get("maxTimeMS")?.is_some() {
+        return Ok(command.into_bytes());
+    }
+    // A maxTimeMS of zero means no limit, so less than a millisecond of remaining time is sent as
+    // one millisecond.
+    let max_time_ms = remaining.as_millis().max(1);
+    command.append("maxTimeMS", max_time_ms.min(i64::MAX as u128) as i64);
+    Ok(command.into_bytes())
+}
+
 struct ExecutionDetails<T: Operation> {
     output: ExecutionOutput<T>,
     implicit_session: Option<ClientSession>,
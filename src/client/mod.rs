@@ -1,19 +1,27 @@
 pub mod auth;
+#[cfg(feature = "csfle")]
+pub(crate) mod csfle;
 mod executor;
 pub mod options;
 pub mod session;
 
 use std::{
-    sync::Arc,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+        Mutex as SyncMutex,
+    },
     time::{Duration, Instant},
 };
 
 use derivative::Derivative;
+use tokio::sync::Notify;
 
-#[cfg(test)]
-use crate::options::ServerAddress;
+#[cfg(feature = "tracing-unstable")]
+use crate::trace::server_selection::ServerSelectionTracer;
 use crate::{
-    bson::Document,
+    bson::{doc, Document},
     change_stream::{
         event::ChangeStreamEvent,
         options::ChangeStreamOptions,
@@ -21,29 +29,37 @@ use crate::{
         ChangeStream,
     },
     concern::{ReadConcern, WriteConcern},
+    cursor::CursorInformation,
     db::Database,
-    error::{ErrorKind, Result},
+    error::{ClientBulkWriteFailure, Error, ErrorKind, Result},
     event::command::CommandEventHandler,
-    operation::{AggregateTarget, ListDatabases},
+    operation::{AggregateTarget, ClientBulkWrite, ListDatabases, RunCommand, SingleWriteResponse},
     options::{
+        ClientBulkWriteOptions,
         ClientOptions,
         DatabaseOptions,
         ListDatabasesOptions,
+        NamespacedWriteModel,
         ReadPreference,
         SelectionCriteria,
+        ServerAddress,
         SessionOptions,
     },
-    results::DatabaseSpecification,
+    results::{ClientBulkWriteResult, DatabaseSpecification},
     sdam::{server_selection, SelectedServer, SessionSupportStatus, Topology},
     ClientSession,
+    Namespace,
 };
-pub(crate) use executor::{HELLO_COMMAND_NAMES, REDACTED_COMMANDS};
+pub(crate) use executor::{remaining_time, HELLO_COMMAND_NAMES, REDACTED_COMMANDS};
 pub(crate) use session::{ClusterTime, SESSIONS_UNSUPPORTED_COMMANDS};
 
 use session::{ServerSession, ServerSessionPool};
 
 const DEFAULT_SERVER_SELECTION_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// The maximum number of session ids that can be included in a single `endSessions` command.
+const MAX_END_SESSIONS_BATCH_SIZE: usize = 10_000;
+
 /// This is the main entry point for the API. A `Client` is used to connect to a MongoDB cluster.
 /// By default, it will monitor the topology of the cluster, keeping track of any changes, such
 /// as servers being added or removed.
@@ -101,6 +117,44 @@ struct ClientInner {
     topology: Topology,
     options: ClientOptions,
     session_pool: ServerSessionPool,
+    shutdown: ShutdownState,
+    #[cfg(feature = "csfle")]
+    auto_encrypter: Option<csfle::AutoEncrypter>,
+}
+
+/// The state tracked by a `Client` so that it can be shut down cleanly.
+#[derive(Debug, Default)]
+struct ShutdownState {
+    /// Whether `Client::shutdown` or `Client::shutdown_immediate` has been called. This is set
+    /// before waiting for in-flight operations, so no new operations can start in the meantime.
+    shutting_down: AtomicBool,
+
+    /// The number of operations currently being executed.
+    in_flight_operations: AtomicUsize,
+
+    /// Notified whenever the number of in-flight operations drops to zero.
+    operation_finished: Notify,
+
+    /// The cursors created by this client that have not yet been exhausted or killed.
+    open_cursors: SyncMutex<HashMap<(ServerAddress, i64), Namespace>>,
+}
+
+/// Marks an operation as in flight for as long as it is alive.
+struct InFlightOperation<'a> {
+    state: &'a ShutdownState,
+}
+
+impl<'a> Drop for InFlightOperation<'a> {
+    fn drop(&mut self) {
+        if self
+            .state
+            .in_flight_operations
+            .fetch_sub(1, Ordering::SeqCst)
+            == 1
+        {
+            self.state.operation_finished.notify_waiters();
+        }
+    }
 }
 
 impl Client {
@@ -116,12 +170,43 @@ impl Client {
     }
 
     /// Creates a new `Client` connected to the cluster specified by `options`.
-    pub fn with_options(options: ClientOptions) -> Result<Self> {
+    pub fn with_options(mut options: ClientOptions) -> Result<Self> {
         options.validate()?;
 
+        // Tokens for MONGODB-OIDC authentication are cached separately for each client, and shared
+        // by the credential in the options and every credential obtained from the provider.
+        let oidc_cache: Arc<auth::oidc::Cache> = Default::default();
+        if let Some(ref mut credential) = options.credential {
+            credential.oidc_cache = oidc_cache.clone();
+        }
+        if let Some(provider) = options.credential_provider.take() {
+            let srv_auth_source = options
+                .original_srv_info
+                .as_ref()
+                .and_then(|info| info.auth_source.clone());
+            options.credential_provider = Some(Arc::new(auth::ClientCredentialProvider {
+                inner: provider,
+                oidc_cache,
+                srv_auth_source,
+            }));
+        }
+
+        #[cfg(feature = "csfle")]
+        let auto_encrypter = options
+            .auto_encryption_opts
+            .as_ref()
+            .map(|auto_encryption_opts| csfle::AutoEncrypter::new(&options, auto_encryption_opts))
+            .transpose()?;
+
+        #[cfg(feature = "tracing-unstable")]
+        let options = crate::trace::with_tracing_event_emitters(options);
+
         let inner = Arc::new(ClientInner {
             topology: Topology::new(options.clone())?,
             session_pool: ServerSessionPool::new(),
+            shutdown: Default::default(),
+            #[cfg(feature = "csfle")]
+            auto_encrypter,
             options,
         });
 
@@ -149,6 +234,11 @@ impl Client {
         self.inner.options.write_concern.as_ref()
     }
 
+    /// Gets the default timeout the `Client` uses for operations.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.inner.options.timeout
+    }
+
     /// Gets a handle to a database specified by `name` in the cluster the `Client` is connected to.
     /// The `Database` options (e.g. read preference and write concern) will default to those of the
     /// `Client`.
@@ -243,6 +333,142 @@ impl Client {
         }
     }
 
+    async fn bulk_write_common(
+        &self,
+        models: impl IntoIterator<Item = NamespacedWriteModel>,
+        options: impl Into<Option<ClientBulkWriteOptions>>,
+        mut session: Option<&mut ClientSession>,
+    ) -> Result<ClientBulkWriteResult> {
+        let models: Vec<NamespacedWriteModel> = models.into_iter().collect();
+        let mut options = options.into();
+        resolve_write_concern_with_session!(self, options, session.as_ref())?;
+
+        if models.is_empty() {
+            return Err(ErrorKind::InvalidArgument {
+                message: "No write models provided to bulk_write".to_string(),
+            }
+            .into());
+        }
+
+        let ordered = options.as_ref().and_then(|o| o.ordered).unwrap_or(true);
+        let verbose = options
+            .as_ref()
+            .and_then(|o| o.verbose_results)
+            .unwrap_or(false);
+
+        let mut cumulative_result = ClientBulkWriteResult::new(verbose);
+        let mut write_errors = HashMap::new();
+        let mut write_concern_errors = Vec::new();
+
+        let deadline = self.operation_deadline(options.as_ref().and_then(|o| o.timeout));
+        let mut n_attempted = 0;
+
+        while n_attempted < models.len() {
+            // each batch is only given the time remaining in the operation's timeout, and running
+            // out of time before a batch is reported in the same way as a failed batch.
+            let batch = match deadline.map(remaining_time).transpose() {
+                Ok(timeout) => {
+                    if timeout.is_some() {
+                        options.get_or_insert_with(Default::default).timeout = timeout;
+                    }
+                    let bulk_write = ClientBulkWrite::new(&models[n_attempted..], options.clone());
+                    self.execute_operation_and_collect_cursor::<_, _, SingleWriteResponse>(
+                        bulk_write,
+                        session.as_deref_mut(),
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match batch {
+                Ok((batch, responses)) => {
+                    let n_errors = write_errors.len();
+                    let batch_n_attempted = batch.n_attempted;
+                    write_concern_errors.extend(batch.write_concern_error.clone());
+                    batch.merge_into(
+                        responses,
+                        &models[n_attempted..],
+                        n_attempted,
+                        &mut cumulative_result,
+                        &mut write_errors,
+                    );
+
+                    // write concern errors don't halt ordered bulk writes, so only stop early if
+                    // a write itself failed.
+                    if ordered && write_errors.len() > n_errors {
+                        break;
+                    }
+                    n_attempted += batch_n_attempted;
+                }
+                // if nothing has been written yet, the error can be returned as-is.
+                Err(e) if n_attempted == 0 => return Err(e),
+                Err(e) => {
+                    let labels = e.labels().clone();
+                    let failure = ClientBulkWriteFailure {
+                        error: Some(e),
+                        write_errors,
+                        write_concern_errors,
+                        partial_result: Some(cumulative_result),
+                    };
+                    return Err(Error::new(
+                        ErrorKind::ClientBulkWrite(failure),
+                        Some(labels),
+                    ));
+                }
+            }
+        }
+
+        if write_errors.is_empty() && write_concern_errors.is_empty() {
+            return Ok(cumulative_result);
+        }
+
+        let failure = ClientBulkWriteFailure {
+            error: None,
+            write_errors,
+            write_concern_errors,
+            partial_result: Some(cumulative_result),
+        };
+        Err(ErrorKind::ClientBulkWrite(failure).into())
+    }
+
+    /// Executes the writes described by `models`, each of which may target a different namespace,
+    /// using as few `bulkWrite` commands as the server's batch limits allow. This method requires
+    /// MongoDB 8.0 or later.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any errors are reported in a
+    /// [`ClientBulkWriteFailure`](error/struct.ClientBulkWriteFailure.html) with indexes
+    /// corresponding to positions in `models`.
+    ///
+    /// By default, only the summary counts of the writes are returned. Set
+    /// [`ClientBulkWriteOptions::verbose_results`](options/struct.ClientBulkWriteOptions.html#
+    /// structfield.verbose_results) to also receive the result of each individual write.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub async fn bulk_write(
+        &self,
+        models: impl IntoIterator<Item = NamespacedWriteModel>,
+        options: impl Into<Option<ClientBulkWriteOptions>>,
+    ) -> Result<ClientBulkWriteResult> {
+        self.bulk_write_common(models, options, None).await
+    }
+
+    /// Executes the writes described by `models` using the provided `ClientSession`. See
+    /// [`Client::bulk_write`] for more information.
+    pub async fn bulk_write_with_session(
+        &self,
+        models: impl IntoIterator<Item = NamespacedWriteModel>,
+        options: impl Into<Option<ClientBulkWriteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ClientBulkWriteResult> {
+        self.bulk_write_common(models, options, Some(session)).await
+    }
+
     /// Starts a new `ClientSession`.
     pub async fn start_session(
         &self,
@@ -351,6 +577,139 @@ impl Client {
         )
     }
 
+    /// Shuts down this `Client`, waiting for any operations that are currently executing to
+    /// finish. This will:
+    ///   - send `killCursors` for any cursors created by this `Client` that are still open
+    ///   - send `endSessions` for the server sessions pooled by this `Client`
+    ///   - close all of the connection pools and stop monitoring the topology
+    ///
+    /// Because `Client` is reference counted, this affects every clone of it: any operation
+    /// started after shutdown will return an [`ErrorKind::Shutdown`] error. Callers should make
+    /// sure that no other tasks are holding cursors or sessions that they expect to keep using.
+    ///
+    /// Calling this method is not required; dropping every clone of a `Client` will still close
+    /// its pools and stop its monitors in the background.
+    pub async fn shutdown(self) {
+        self.shutdown_inner(true).await
+    }
+
+    /// Shuts down this `Client` without waiting for in-flight operations to finish. Any such
+    /// operations may fail with an [`ErrorKind::Shutdown`] error.
+    ///
+    /// See [`Client::shutdown`] for more details.
+    pub async fn shutdown_immediate(self) {
+        self.shutdown_inner(false).await
+    }
+
+    async fn shutdown_inner(self, graceful: bool) {
+        let state = &self.inner.shutdown;
+        if state.shutting_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if graceful {
+            loop {
+                let operation_finished = state.operation_finished.notified();
+                if state.in_flight_operations.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                operation_finished.await;
+            }
+        }
+
+        self.kill_open_cursors().await;
+        self.end_pooled_sessions().await;
+
+        self.inner.topology.shutdown().await;
+    }
+
+    /// Sends `killCursors` for every open cursor created by this `Client`, ignoring any errors.
+    async fn kill_open_cursors(&self) {
+        let open_cursors: Vec<_> = self
+            .inner
+            .shutdown
+            .open_cursors
+            .lock()
+            .unwrap()
+            .drain()
+            .collect();
+
+        let mut grouped: Vec<(ServerAddress, Namespace, Vec<i64>)> = Vec::new();
+        for ((address, id), ns) in open_cursors {
+            match grouped
+                .iter_mut()
+                .find(|(a, n, _)| a == &address && n == &ns)
+            {
+                Some((_, _, ids)) => ids.push(id),
+                None => grouped.push((address, ns, vec![id])),
+            }
+        }
+
+        for (address, ns, ids) in grouped {
+            let kill_cursors = RunCommand::new(
+                ns.db,
+                doc! { "killCursors": ns.coll.as_str(), "cursors": ids },
+                Some(SelectionCriteria::from_address(address)),
+                self.inner.options.timeout,
+                None,
+            );
+            if let Ok(kill_cursors) = kill_cursors {
+                let _ = self.execute_operation_during_shutdown(kill_cursors).await;
+            }
+        }
+    }
+
+    /// Sends `endSessions` for every server session in the pool, ignoring any errors.
+    async fn end_pooled_sessions(&self) {
+        let ids = self.inner.session_pool.drain_ids().await;
+        let selection_criteria =
+            SelectionCriteria::ReadPreference(ReadPreference::PrimaryPreferred {
+                options: Default::default(),
+            });
+        for batch in ids.chunks(MAX_END_SESSIONS_BATCH_SIZE) {
+            let end_sessions = RunCommand::new(
+                "admin".to_string(),
+                doc! { "endSessions": batch.to_vec() },
+                Some(selection_criteria.clone()),
+                self.inner.options.timeout,
+                None,
+            );
+            if let Ok(end_sessions) = end_sessions {
+                let _ = self.execute_operation_during_shutdown(end_sessions).await;
+            }
+        }
+    }
+
+    /// Marks an operation as in flight until the returned guard is dropped, or returns an error if
+    /// this `Client` has been shut down.
+    fn begin_operation(&self) -> Result<InFlightOperation<'_>> {
+        let state = &self.inner.shutdown;
+        state.in_flight_operations.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightOperation { state };
+        if state.shutting_down.load(Ordering::SeqCst) {
+            return Err(ErrorKind::Shutdown.into());
+        }
+        Ok(guard)
+    }
+
+    pub(crate) fn register_open_cursor(&self, info: &CursorInformation) {
+        self.inner
+            .shutdown
+            .open_cursors
+            .lock()
+            .unwrap()
+            .insert((info.address.clone(), info.id), info.ns.clone());
+    }
+
+    pub(crate) fn unregister_open_cursor(&self, address: &ServerAddress, id: i64) {
+        self.inner
+            .shutdown
+            .open_cursors
+            .lock()
+            .unwrap()
+            .remove(&(address.clone(), id));
+    }
+
     #[cfg(test)]
     pub(crate) async fn clear_session_pool(&self) {
         self.inner.session_pool.clear().await;
@@ -368,13 +727,18 @@ impl Client {
         &self,
         criteria: Option<&SelectionCriteria>,
     ) -> Result<ServerAddress> {
-        let server = self.select_server(criteria).await?;
+        let server = self.select_server(criteria, "Test select server").await?;
         Ok(server.address.clone())
     }
 
     /// Select a server using the provided criteria. If none is provided, a primary read preference
     /// will be used instead.
-    async fn select_server(&self, criteria: Option<&SelectionCriteria>) -> Result<SelectedServer> {
+    #[cfg_attr(not(feature = "tracing-unstable"), allow(unused_variables))]
+    async fn select_server(
+        &self,
+        criteria: Option<&SelectionCriteria>,
+        operation_name: &str,
+    ) -> Result<SelectedServer> {
         let criteria =
             criteria.unwrap_or(&SelectionCriteria::ReadPreference(ReadPreference::Primary));
 
@@ -386,14 +750,36 @@ impl Client {
             .unwrap_or(DEFAULT_SERVER_SELECTION_TIMEOUT);
 
         let mut watcher = self.inner.topology.watch();
+
+        #[cfg(feature = "tracing-unstable")]
+        let tracer = ServerSelectionTracer::new(operation_name, criteria);
+        #[cfg(feature = "tracing-unstable")]
+        tracer.started(&watcher.peek_latest().description);
+        #[cfg(feature = "tracing-unstable")]
+        let mut emitted_waiting = false;
+
         loop {
             let state = watcher.observe_latest();
 
-            if let Some(server) = server_selection::attempt_to_select_server(
+            let selected = server_selection::attempt_to_select_server(
                 criteria,
                 &state.description,
                 &state.servers,
-            )? {
+            );
+            #[cfg(feature = "tracing-unstable")]
+            match selected {
+                Ok(Some(ref server)) => tracer.succeeded(&server.address),
+                Ok(None) if !emitted_waiting => {
+                    tracer.waiting(
+                        timeout.saturating_sub(start_time.elapsed()),
+                        &state.description,
+                    );
+                    emitted_waiting = true;
+                }
+                Ok(None) => {}
+                Err(ref error) => tracer.failed(error),
+            }
+            if let Some(server) = selected? {
                 return Ok(server);
             }
 
@@ -404,13 +790,16 @@ impl Client {
                     .wait_for_update(timeout - start_time.elapsed())
                     .await;
             if !change_occurred {
-                return Err(ErrorKind::ServerSelection {
+                let error: Error = ErrorKind::ServerSelection {
                     message: self
                         .inner
                         .topology
                         .server_selection_timeout_error_message(criteria),
                 }
-                .into());
+                .into();
+                #[cfg(feature = "tracing-unstable")]
+                tracer.failed(&error);
+                return Err(error);
             }
         }
     }
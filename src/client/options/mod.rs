@@ -4,6 +4,7 @@ mod test;
 mod resolver_config;
 
 use std::{
+    borrow::Cow,
     cmp::Ordering,
     collections::HashSet,
     convert::TryFrom,
@@ -17,31 +18,36 @@ use std::{
 
 use derivative::Derivative;
 use lazy_static::lazy_static;
+use rand::seq::SliceRandom;
 use serde::{de::Unexpected, Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
 use strsim::jaro_winkler;
 use typed_builder::TypedBuilder;
 
+#[cfg(feature = "csfle")]
+use crate::client::csfle::options::AutoEncryptionOptions;
 #[cfg(test)]
 use crate::srv::LookupHosts;
 use crate::{
     bson::{doc, Bson, Document},
     bson_util,
-    client::auth::{AuthMechanism, Credential},
+    client::auth::{AuthMechanism, Credential, CredentialProvider},
     compression::Compressor,
     concern::{Acknowledgment, ReadConcern, WriteConcern},
     error::{Error, ErrorKind, Result},
     event::{cmap::CmapEventHandler, command::CommandEventHandler, sdam::SdamEventHandler},
-    options::ReadConcernLevel,
+    options::{ReadConcernLevel, WriteModel},
+    runtime::{Socks5Proxy, DEFAULT_PROXY_PORT},
     sdam::{DEFAULT_HEARTBEAT_FREQUENCY, IDLE_WRITE_PERIOD, MIN_HEARTBEAT_FREQUENCY},
     selection_criteria::{ReadPreference, SelectionCriteria, TagSet},
     srv::{OriginalSrvInfo, SrvResolver},
+    Namespace,
 };
 
 #[cfg(any(feature = "sync", feature = "tokio-sync"))]
 use crate::runtime;
 
-pub use resolver_config::ResolverConfig;
+pub use resolver_config::{DnsResolver, ResolverConfig, SrvRecord};
 
 const DEFAULT_PORT: u16 = 27017;
 
@@ -60,6 +66,10 @@ const URI_OPTIONS: &[&str] = &[
     "maxstalenessseconds",
     "maxpoolsize",
     "minpoolsize",
+    "proxyhost",
+    "proxypassword",
+    "proxyport",
+    "proxyusername",
     "readconcernlevel",
     "readpreference",
     "readpreferencetags",
@@ -68,12 +78,18 @@ const URI_OPTIONS: &[&str] = &[
     "retryreads",
     "serverselectiontimeoutms",
     "sockettimeoutms",
+    "srvmaxhosts",
+    "srvservicename",
+    "timeoutms",
     "tls",
     "ssl",
     "tlsinsecure",
     "tlsallowinvalidcertificates",
     "tlscafile",
     "tlscertificatekeyfile",
+    "tlscertificatekeyfilepassword",
+    "tlsdisablecertificaterevocationcheck",
+    "tlsdisableocspendpointcheck",
     "w",
     "waitqueuetimeoutms",
     "wtimeoutms",
@@ -88,15 +104,15 @@ lazy_static! {
         [':', '/', '?', '#', '[', ']', '@'].iter().collect()
     };
 
+    /// Characters that cannot appear in the database of a connection string. Dots are checked
+    /// separately, as they are only allowed when the database is used as the auth database of a
+    /// connection string whose hosts are all Unix domain sockets (e.g. "admin.sock").
     static ref ILLEGAL_DATABASE_CHARACTERS: HashSet<&'static char> = {
-        ['/', '\\', ' ', '"', '$', '.'].iter().collect()
+        ['/', '\\', ' ', '"', '$'].iter().collect()
     };
 }
 
 /// An enum representing the address of a MongoDB server.
-///
-/// Currently this just supports addresses that can be connected to over TCP, but alternative
-/// address types may be supported in the future (e.g. Unix Domain Socket paths).
 #[derive(Clone, Debug, Eq, Serialize)]
 #[non_exhaustive]
 pub enum ServerAddress {
@@ -110,6 +126,17 @@ pub enum ServerAddress {
         /// The default is 27017.
         port: Option<u16>,
     },
+
+    /// A Unix Domain Socket path.
+    ///
+    /// In a connection string, the path must be percent-encoded and end in `.sock`, e.g.
+    /// `mongodb://%2Ftmp%2Fmongodb-27017.sock`. TLS is not used for connections over a Unix
+    /// Domain Socket.
+    #[cfg(unix)]
+    Unix {
+        /// The path to the Unix Domain Socket that the MongoDB server is listening on.
+        path: PathBuf,
+    },
 }
 
 impl<'de> Deserialize<'de> for ServerAddress {
@@ -142,6 +169,10 @@ impl PartialEq for ServerAddress {
                     port: other_port,
                 },
             ) => host == other_host && port.unwrap_or(27017) == other_port.unwrap_or(27017),
+            #[cfg(unix)]
+            (Self::Unix { path }, Self::Unix { path: other_path }) => path == other_path,
+            #[cfg(unix)]
+            _ => false,
         }
     }
 }
@@ -156,6 +187,8 @@ impl Hash for ServerAddress {
                 host.hash(state);
                 port.unwrap_or(27017).hash(state);
             }
+            #[cfg(unix)]
+            Self::Unix { path } => path.hash(state),
         }
     }
 }
@@ -171,6 +204,13 @@ impl ServerAddress {
     /// Parses an address string into a `ServerAddress`.
     pub fn parse(address: impl AsRef<str>) -> Result<Self> {
         let address = address.as_ref();
+
+        #[cfg(unix)]
+        if address.ends_with(".sock") {
+            let path = percent_decode(address, "unix domain socket paths must be URL encoded")?;
+            return Ok(ServerAddress::Unix { path: path.into() });
+        }
+
         let mut parts = address.split(':');
         let hostname = match parts.next() {
             Some(part) => {
@@ -241,18 +281,29 @@ impl ServerAddress {
                     "port": port.map(|i| Bson::Int32(i.into())).unwrap_or(Bson::Null)
                 }
             }
+            #[cfg(unix)]
+            Self::Unix { path } => {
+                doc! {
+                    "host": path.to_string_lossy().into_owned(),
+                    "port": Bson::Null,
+                }
+            }
         }
     }
 
-    pub(crate) fn host(&self) -> &str {
+    pub(crate) fn host(&self) -> Cow<'_, str> {
         match self {
-            Self::Tcp { host, .. } => host.as_str(),
+            Self::Tcp { host, .. } => Cow::Borrowed(host.as_str()),
+            #[cfg(unix)]
+            Self::Unix { path } => path.to_string_lossy(),
         }
     }
 
     pub(crate) fn port(&self) -> Option<u16> {
         match self {
             Self::Tcp { port, .. } => *port,
+            #[cfg(unix)]
+            Self::Unix { .. } => None,
         }
     }
 }
@@ -263,6 +314,8 @@ impl fmt::Display for ServerAddress {
             Self::Tcp { host, port } => {
                 write!(fmt, "{}:{}", host, port.unwrap_or(DEFAULT_PORT))
             }
+            #[cfg(unix)]
+            Self::Unix { path } => write!(fmt, "{}", path.display()),
         }
     }
 }
@@ -355,6 +408,15 @@ pub struct ClientOptions {
     #[serde(default = "default_hosts")]
     pub hosts: Vec<ServerAddress>,
 
+    /// The options for automatically encrypting and decrypting document fields. See the
+    /// [`AutoEncryptionOptions`] type documentation for more details.
+    #[cfg(feature = "csfle")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "csfle")))]
+    #[builder(default)]
+    #[serde(skip)]
+    #[derivative(PartialEq = "ignore")]
+    pub auto_encryption_opts: Option<AutoEncryptionOptions>,
+
     /// The application name that the Client will send to the server as part of the handshake. This
     /// can be used in combination with the server logs to determine which Client is connected to a
     /// server.
@@ -396,6 +458,17 @@ pub struct ClientOptions {
     #[builder(default)]
     pub credential: Option<Credential>,
 
+    /// A provider that is consulted for the credential each time a new connection is established,
+    /// which allows credentials to be rotated without recreating the client. See the
+    /// [`CredentialProvider`] documentation for more details.
+    ///
+    /// Provided credentials without a `source` use the authSource from the SRV TXT record, if
+    /// any. This cannot be specified together with `credential`.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub credential_provider: Option<Arc<dyn CredentialProvider>>,
+
     /// Specifies whether the Client should directly connect to a single host rather than
     /// autodiscover all servers in the cluster.
     ///
@@ -457,6 +530,37 @@ pub struct ClientOptions {
     #[builder(default)]
     pub min_pool_size: Option<u32>,
 
+    /// The hostname or IP address of a SOCKS5 proxy that all connections to the servers should be
+    /// tunneled through. The hostnames of the servers are resolved by the proxy. Requests to the
+    /// OCSP responders listed in the servers' certificates are tunneled through the proxy as well.
+    /// Connections over a Unix domain socket are not proxied.
+    ///
+    /// By default, connections are made directly to the servers.
+    #[builder(default)]
+    pub proxy_host: Option<String>,
+
+    /// The port of the SOCKS5 proxy. Can only be specified along with `proxy_host`.
+    ///
+    /// The default value is 1080.
+    #[builder(default)]
+    pub proxy_port: Option<u16>,
+
+    /// The username to authenticate to the SOCKS5 proxy with. Must be specified along with
+    /// `proxy_password` if either is specified, and requires `proxy_host`.
+    ///
+    /// By default, no authentication is done with the proxy.
+    #[builder(default)]
+    #[derivative(Debug = "ignore")]
+    pub proxy_username: Option<String>,
+
+    /// The password to authenticate to the SOCKS5 proxy with. Must be specified along with
+    /// `proxy_username` if either is specified, and requires `proxy_host`.
+    ///
+    /// By default, no authentication is done with the proxy.
+    #[builder(default)]
+    #[derivative(Debug = "ignore")]
+    pub proxy_password: Option<String>,
+
     /// Specifies the default read concern for operations performed on the Client. See the
     /// ReadConcern type documentation for more details.
     #[builder(default)]
@@ -511,15 +615,51 @@ pub struct ClientOptions {
     #[builder(default)]
     pub server_selection_timeout: Option<Duration>,
 
+    /// The maximum number of hosts from the SRV lookup of a "mongodb+srv" connection string that
+    /// the Client will connect to. When the lookup returns more hosts than this, a random subset
+    /// is chosen initially, and hosts that disappear from later lookups are replaced with randomly
+    /// chosen new ones. A value of zero means there is no limit.
+    ///
+    /// This option cannot be combined with `repl_set_name` or `load_balanced`.
+    ///
+    /// By default, there is no limit.
+    #[builder(default)]
+    pub srv_max_hosts: Option<u32>,
+
+    /// The service name used in the SRV lookup of a "mongodb+srv" connection string, i.e. the
+    /// `{service}` in `_{service}._tcp.{hostname}`.
+    ///
+    /// The default value is "mongodb".
+    #[builder(default)]
+    pub srv_service_name: Option<String>,
+
+    /// The default amount of time to allow operations to run, including server selection,
+    /// connection checkout, retries and the execution of the command on the server. The time
+    /// remaining when a command is sent is passed to the server as `maxTimeMS`, unless the
+    /// operation specifies its own `max_time`. Operations that do not complete in time fail with
+    /// an [`ErrorKind::Timeout`](crate::error::ErrorKind::Timeout) error.
+    ///
+    /// Cursors apply the timeout to each `getMore` they issue. The timeout can be overridden by
+    /// databases, collections and individual operations, and a timeout of zero disables it.
+    ///
+    /// By default, operations are not bounded by a timeout.
+    #[builder(default)]
+    pub timeout: Option<Duration>,
+
     /// Default database for this client.
     ///
     /// By default, no default database is specified.
     #[builder(default)]
     pub default_database: Option<String>,
 
-    #[builder(default, setter(skip))]
-    #[derivative(Debug = "ignore")]
-    pub(crate) socket_timeout: Option<Duration>,
+    /// The amount of time to wait for a single message to be written to or read from a connection
+    /// before giving up. When the timeout is exceeded, the connection is closed and the operation
+    /// fails with a network timeout error. Connections used for monitoring are bounded by
+    /// `connect_timeout` instead.
+    ///
+    /// A value of zero, like the default, means that reads and writes will not time out.
+    #[builder(default)]
+    pub socket_timeout: Option<Duration>,
 
     /// The TLS configuration for the Client to use in its connections with the server.
     ///
@@ -527,6 +667,17 @@ pub struct ClientOptions {
     #[builder(default)]
     pub tls: Option<Tls>,
 
+    /// The maximum length, in bytes, of the extended JSON representation of the documents (e.g.
+    /// commands and replies) included in the `tracing` events emitted by the driver. Longer
+    /// documents are truncated.
+    ///
+    /// The default value is 1000.
+    #[cfg(feature = "tracing-unstable")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing-unstable")))]
+    #[builder(default)]
+    #[serde(skip)]
+    pub tracing_max_document_length_bytes: Option<usize>,
+
     /// Specifies the default write concern for operations performed on the Client. See the
     /// WriteConcern type documentation for more details.
     #[builder(default)]
@@ -542,7 +693,8 @@ pub struct ClientOptions {
     #[derivative(Debug = "ignore")]
     pub(crate) original_uri: Option<String>,
 
-    /// Configuration of the trust-dns resolver used for SRV and TXT lookups.
+    /// Configuration of the trust-dns resolver used for SRV and TXT lookups, and for
+    /// canonicalizing the hostname of a server during GSSAPI authentication.
     /// By default, the host system's resolver configuration will be used.
     ///
     /// On Windows, there is a known performance issue in trust-dns with using the default system
@@ -552,6 +704,21 @@ pub struct ClientOptions {
     #[derivative(Debug = "ignore")]
     pub(crate) resolver_config: Option<ResolverConfig>,
 
+    /// A custom resolver to use for the SRV and TXT lookups of a "mongodb+srv" connection string
+    /// (including the periodic rescans of its hosts) and for resolving the IP addresses of the
+    /// servers being connected to or authenticated with GSSAPI. See the [`DnsResolver`]
+    /// documentation for more details.
+    ///
+    /// Note that for the initial lookups of a "mongodb+srv" connection string to use the resolver,
+    /// the connection string must be parsed with [`ClientOptions::parse_with_dns_resolver`].
+    ///
+    /// By default, lookups are done with trust-dns using the `resolver_config`, if one was given
+    /// when parsing the connection string, or the host system's configuration otherwise.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[builder(default)]
+    #[serde(skip)]
+    pub dns_resolver: Option<Arc<dyn DnsResolver>>,
+
     /// Control test behavior of the client.
     #[cfg(test)]
     #[builder(default, setter(skip))]
@@ -635,6 +802,9 @@ impl Serialize for ClientOptions {
             #[serde(serialize_with = "bson_util::serialize_duration_option_as_int_millis")]
             sockettimeoutms: &'a Option<Duration>,
 
+            #[serde(serialize_with = "bson_util::serialize_duration_option_as_int_millis")]
+            timeoutms: &'a Option<Duration>,
+
             #[serde(flatten, serialize_with = "Tls::serialize_for_client_options")]
             tls: &'a Option<Tls>,
 
@@ -663,6 +833,7 @@ impl Serialize for ClientOptions {
             selectioncriteria: &self.selection_criteria,
             serverselectiontimeoutms: &self.server_selection_timeout,
             sockettimeoutms: &self.socket_timeout,
+            timeoutms: &self.timeout,
             tls: &self.tls,
             writeconcern: &self.write_concern,
             loadbalanced: &self.load_balanced,
@@ -686,8 +857,14 @@ struct ClientOptionsParser {
     pub repl_set_name: Option<String>,
     pub write_concern: Option<WriteConcern>,
     pub server_selection_timeout: Option<Duration>,
+    pub srv_max_hosts: Option<u32>,
+    pub srv_service_name: Option<String>,
     pub max_pool_size: Option<u32>,
     pub min_pool_size: Option<u32>,
+    pub proxy_host: Option<String>,
+    pub proxy_port: Option<u16>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
     pub max_idle_time: Option<Duration>,
     pub wait_queue_timeout: Option<Duration>,
     pub compressors: Option<Vec<Compressor>>,
@@ -695,6 +872,7 @@ struct ClientOptionsParser {
     pub retry_reads: Option<bool>,
     pub retry_writes: Option<bool>,
     pub socket_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
     pub zlib_compression: Option<i32>,
     pub direct_connection: Option<bool>,
     pub credential: Option<Credential>,
@@ -752,7 +930,8 @@ impl Tls {
 }
 
 /// Specifies the TLS configuration that the [`Client`](../struct.Client.html) should use.
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, TypedBuilder)]
+#[derive(Clone, Derivative, Default, Deserialize, PartialEq, TypedBuilder)]
+#[derivative(Debug)]
 #[builder(field_defaults(default, setter(into)))]
 #[non_exhaustive]
 pub struct TlsOptions {
@@ -764,16 +943,60 @@ pub struct TlsOptions {
     pub allow_invalid_certificates: Option<bool>,
 
     /// The path to the CA file that the [`Client`](../struct.Client.html) should use for TLS. If
-    /// none is specified, then the driver will use the Mozilla root certificates from the
-    /// `webpki-roots` crate.
+    /// neither this nor `ca_pem` is specified, then the driver will use the Mozilla root
+    /// certificates from the `webpki-roots` crate.
     pub ca_file_path: Option<PathBuf>,
 
+    /// PEM-encoded CA certificates that the [`Client`](../struct.Client.html) should use for TLS,
+    /// for when they are held in memory rather than in a file. These are used in addition to any
+    /// certificates read from `ca_file_path`.
+    #[derivative(Debug = "ignore")]
+    pub ca_pem: Option<Vec<u8>>,
+
     /// The path to the certificate file that the [`Client`](../struct.Client.html) should present
     /// to the server to verify its identify. If none is specified, then the
     /// [`Client`](../struct.Client.html) will not attempt to verify its identity to the
     /// server.
     pub cert_key_file_path: Option<PathBuf>,
 
+    /// The PEM-encoded certificate and private key that the [`Client`](../struct.Client.html)
+    /// should present to the server to verify its identity, for when they are held in memory
+    /// rather than in a file. This cannot be specified along with `cert_key_file_path`.
+    #[derivative(Debug = "ignore")]
+    pub cert_key_pem: Option<Vec<u8>>,
+
+    /// The password used to decrypt the client private key from `cert_key_file_path` or
+    /// `cert_key_pem`. The key must be encrypted as a PKCS#8 `ENCRYPTED PRIVATE KEY`.
+    #[cfg(feature = "cert-key-password")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "cert-key-password")))]
+    #[derivative(Debug = "ignore")]
+    pub cert_key_file_password: Option<Vec<u8>>,
+
+    /// The path to a file containing one or more PEM-encoded certificate revocation lists. If
+    /// specified, the [`Client`](../struct.Client.html) will return an error if the certificate
+    /// presented by the server has been revoked, or if the file does not contain a correctly
+    /// signed and current CRL from the issuer of that certificate. The certificates of
+    /// intermediate CAs are not checked against the CRLs.
+    ///
+    /// When using rustls, checking CRLs requires the `cert-revocation` feature flag, and the
+    /// [`Client`](../struct.Client.html) will return an error if this is specified without it.
+    pub crl_file_path: Option<PathBuf>,
+
+    /// Whether or not the [`Client`](../struct.Client.html) should skip contacting the OCSP
+    /// responders listed in the certificate presented by the server when the server does not
+    /// staple an OCSP response to the handshake. Stapled responses are still checked.
+    ///
+    /// The default value is to contact the responders.
+    pub disable_ocsp_endpoint_check: Option<bool>,
+
+    /// Whether or not the [`Client`](../struct.Client.html) should skip checking the OCSP status
+    /// of the certificate presented by the server entirely, both via stapled responses and by
+    /// contacting OCSP responders.
+    ///
+    /// The default value is to check the OCSP status of the certificate. OCSP status is only
+    /// checked if the `cert-revocation` feature flag is enabled.
+    pub disable_certificate_revocation_check: Option<bool>,
+
     /// Whether or not the [`Client`](../struct.Client.html) should return an error if the hostname
     /// is invalid.
     ///
@@ -798,6 +1021,8 @@ impl TlsOptions {
             tlscafile: Option<&'a str>,
             tlscertificatekeyfile: Option<&'a str>,
             tlsallowinvalidcertificates: Option<bool>,
+            #[cfg(feature = "cert-key-password")]
+            tlscertificatekeyfilepassword: Option<&'a str>,
         }
 
         let state = TlsOptionsHelper {
@@ -811,6 +1036,11 @@ impl TlsOptions {
                 .as_ref()
                 .map(|s| s.to_str().unwrap()),
             tlsallowinvalidcertificates: tls_options.allow_invalid_certificates,
+            #[cfg(feature = "cert-key-password")]
+            tlscertificatekeyfilepassword: tls_options
+                .cert_key_file_password
+                .as_deref()
+                .map(|b| std::str::from_utf8(b).unwrap()),
         };
         state.serialize(serializer)
     }
@@ -839,7 +1069,11 @@ impl From<ClientOptionsParser> for ClientOptions {
         Self {
             hosts: parser.hosts,
             app_name: parser.app_name,
+            #[cfg(feature = "csfle")]
+            auto_encryption_opts: None,
             tls: parser.tls,
+            #[cfg(feature = "tracing-unstable")]
+            tracing_max_document_length_bytes: None,
             heartbeat_freq: parser.heartbeat_freq,
             local_threshold: parser.local_threshold,
             read_concern: parser.read_concern,
@@ -848,22 +1082,31 @@ impl From<ClientOptionsParser> for ClientOptions {
             write_concern: parser.write_concern,
             max_pool_size: parser.max_pool_size,
             min_pool_size: parser.min_pool_size,
+            proxy_host: parser.proxy_host,
+            proxy_port: parser.proxy_port,
+            proxy_username: parser.proxy_username,
+            proxy_password: parser.proxy_password,
             max_idle_time: parser.max_idle_time,
             server_selection_timeout: parser.server_selection_timeout,
+            srv_max_hosts: parser.srv_max_hosts,
+            srv_service_name: parser.srv_service_name,
             compressors: parser.compressors,
             connect_timeout: parser.connect_timeout,
             retry_reads: parser.retry_reads,
             retry_writes: parser.retry_writes,
             socket_timeout: parser.socket_timeout,
+            timeout: parser.timeout,
             direct_connection: parser.direct_connection,
             default_database: parser.default_database,
             driver_info: None,
             credential: parser.credential,
+            credential_provider: None,
             cmap_event_handler: None,
             command_event_handler: None,
             original_srv_info: None,
             original_uri: Some(parser.original_uri),
             resolver_config: None,
+            dns_resolver: None,
             server_api: None,
             load_balanced: parser.load_balanced,
             sdam_event_handler: None,
@@ -882,6 +1125,7 @@ impl ClientOptions {
             original_srv_info: Some(OriginalSrvInfo {
                 hostname: "localhost.test.test.build.10gen.cc".into(),
                 min_ttl: Duration::from_secs(60),
+                auth_source: None,
             }),
             ..Default::default()
         }
@@ -916,6 +1160,10 @@ impl ClientOptions {
     ///     field
     ///   * `maxPoolSize`: maps to the `max_pool_size` field
     ///   * `minPoolSize`: maps to the `min_pool_size` field
+    ///   * `proxyHost`: maps to the `proxy_host` field
+    ///   * `proxyPort`: maps to the `proxy_port` field
+    ///   * `proxyUsername`: maps to the `proxy_username` field
+    ///   * `proxyPassword`: maps to the `proxy_password` field
     ///   * `readConcernLevel`: maps to the `read_concern` field
     ///   * `readPreferenceField`: maps to the ReadPreference enum variant of the
     ///     `selection_criteria` field
@@ -926,7 +1174,10 @@ impl ClientOptions {
     ///   * `retryWrites`: not yet implemented
     ///   * `retryReads`: maps to the `retry_reads` field
     ///   * `serverSelectionTimeoutMS`: maps to the `server_selection_timeout` field
-    ///   * `socketTimeoutMS`: unsupported, does not map to any field
+    ///   * `socketTimeoutMS`: maps to the `socket_timeout` field
+    ///   * `srvMaxHosts`: maps to the `srv_max_hosts` field
+    ///   * `srvServiceName`: maps to the `srv_service_name` field
+    ///   * `timeoutMS`: maps to the `timeout` field
     ///   * `ssl`: an alias of the `tls` option
     ///   * `tls`: maps to the TLS variant of the `tls` field`.
     ///   * `tlsInsecure`: relaxes the TLS constraints on connections being made; currently is just
@@ -936,6 +1187,12 @@ impl ClientOptions {
     ///     `tls` field
     ///   * `tlsCAFile`: maps to the `ca_file_path` field of the `tls` field
     ///   * `tlsCertificateKeyFile`: maps to the `cert_key_file_path` field of the `tls` field
+    ///   * `tlsCertificateKeyFilePassword`: maps to the `cert_key_file_password` field of the `tls`
+    ///     field (which requires the `cert-key-password` feature flag)
+    ///   * `tlsDisableCertificateRevocationCheck`: maps to the
+    ///     `disable_certificate_revocation_check` field of the `tls` field
+    ///   * `tlsDisableOCSPEndpointCheck`: maps to the `disable_ocsp_endpoint_check` field of the
+    ///     `tls` field
     ///   * `w`: maps to the `w` field of the `write_concern` field
     ///   * `waitQueueTimeoutMS`: unsupported, does not map to any field
     ///   * `wTimeoutMS`: maps to the `w_timeout` field of the `write_concern` field
@@ -987,33 +1244,91 @@ impl ClientOptions {
         runtime::block_on(Self::parse_uri(uri, Some(resolver_config)))
     }
 
+    /// Parses a MongoDB connection string into a `ClientOptions` struct.
+    /// If the string is malformed or one of the options has an invalid value, an error will be
+    /// returned.
+    ///
+    /// In the case that "mongodb+srv" is used, SRV and TXT record lookups will be done using the
+    /// provided [`DnsResolver`] as part of this method. The resolver is also stored in the
+    /// `dns_resolver` field of the returned options, so that it will be used for any further
+    /// lookups done by a `Client` created from them.
+    ///
+    /// See the docstring on `ClientOptions::parse` for information on how the various URI options
+    /// map to fields on `ClientOptions`.
+    ///
+    /// Note: if the `sync` feature is enabled, then this method will be replaced with [the sync
+    /// version](#method.parse_with_dns_resolver-1).
+    #[cfg(all(not(feature = "sync"), not(feature = "tokio-sync")))]
+    pub async fn parse_with_dns_resolver(
+        uri: impl AsRef<str>,
+        dns_resolver: Arc<dyn DnsResolver>,
+    ) -> Result<Self> {
+        Self::parse_uri_with_dns_resolver(uri, None, Some(dns_resolver)).await
+    }
+
+    /// This method will be present if the `sync` feature is enabled. It's otherwise identical to
+    /// [the async version](#method.parse_with_dns_resolver)
+    #[cfg(any(feature = "sync", feature = "tokio-sync", docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "sync", feature = "tokio-sync"))))]
+    pub fn parse_with_dns_resolver(uri: &str, dns_resolver: Arc<dyn DnsResolver>) -> Result<Self> {
+        runtime::block_on(Self::parse_uri_with_dns_resolver(
+            uri,
+            None,
+            Some(dns_resolver),
+        ))
+    }
+
     /// Populate this `ClientOptions` from the given URI, optionally using the resolver config for
     /// DNS lookups.
     pub(crate) async fn parse_uri(
         uri: impl AsRef<str>,
         resolver_config: Option<ResolverConfig>,
+    ) -> Result<Self> {
+        Self::parse_uri_with_dns_resolver(uri, resolver_config, None).await
+    }
+
+    /// Populate this `ClientOptions` from the given URI, using the custom resolver or otherwise
+    /// the resolver config for DNS lookups.
+    pub(crate) async fn parse_uri_with_dns_resolver(
+        uri: impl AsRef<str>,
+        resolver_config: Option<ResolverConfig>,
+        dns_resolver: Option<Arc<dyn DnsResolver>>,
     ) -> Result<Self> {
         let parser = ClientOptionsParser::parse(uri.as_ref())?;
         let srv = parser.srv;
         let auth_source_present = parser.auth_source.is_some();
         let mut options: Self = parser.into();
         options.resolver_config = resolver_config.clone();
+        options.dns_resolver = dns_resolver.clone();
 
         if srv {
-            let mut resolver = SrvResolver::new(resolver_config.map(|config| config.inner)).await?;
+            let mut resolver = SrvResolver::new(
+                resolver_config.map(|config| config.inner),
+                dns_resolver,
+                options.srv_service_name.clone(),
+            )
+            .await?;
             let mut config = resolver
-                .resolve_client_options(options.hosts[0].host())
+                .resolve_client_options(&options.hosts[0].host())
                 .await?;
 
             // Save the original SRV info to allow mongos polling.
-            options.original_srv_info = OriginalSrvInfo {
+            let mut srv_info = OriginalSrvInfo {
                 hostname: options.hosts[0].host().to_string(),
                 min_ttl: config.min_ttl,
-            }
-            .into();
+                auth_source: None,
+            };
 
-            // Set the ClientOptions hosts to those found during the SRV lookup.
+            // Set the ClientOptions hosts to those found during the SRV lookup, randomly choosing
+            // at most srvMaxHosts of them if a limit was specified.
             options.hosts = config.hosts;
+            if let Some(max_hosts) = options.srv_max_hosts {
+                let max_hosts = max_hosts as usize;
+                if max_hosts > 0 && max_hosts < options.hosts.len() {
+                    options.hosts.shuffle(&mut rand::thread_rng());
+                    options.hosts.truncate(max_hosts);
+                }
+            }
 
             // Enable TLS unless the user explicitly disabled it.
             if options.tls.is_none() {
@@ -1026,10 +1341,14 @@ impl ClientOptions {
             if !auth_source_present {
                 if let Some(auth_source) = config.auth_source.take() {
                     if let Some(ref mut credential) = options.credential {
-                        credential.source = Some(auth_source);
+                        credential.source = Some(auth_source.clone());
                     }
+                    // Credentials obtained from a provider are given this authSource unless they
+                    // specify their own.
+                    srv_info.auth_source = Some(auth_source);
                 }
             }
+            options.original_srv_info = Some(srv_info);
 
             // Set the replica set name TXT option found during SRV lookup unless the user already
             // set it.
@@ -1064,6 +1383,15 @@ impl ClientOptions {
         }
     }
 
+    pub(crate) fn socks5_proxy(&self) -> Option<Socks5Proxy> {
+        self.proxy_host.as_ref().map(|host| Socks5Proxy {
+            host: host.clone(),
+            port: self.proxy_port.unwrap_or(DEFAULT_PROXY_PORT),
+            username: self.proxy_username.clone(),
+            password: self.proxy_password.clone(),
+        })
+    }
+
     /// Ensure the options set are valid, returning an error describing the problem if they are not.
     pub(crate) fn validate(&self) -> Result<()> {
         if let Some(true) = self.direct_connection {
@@ -1079,6 +1407,12 @@ impl ClientOptions {
             write_concern.validate()?;
         }
 
+        if self.credential.is_some() && self.credential_provider.is_some() {
+            return Err(Error::invalid_argument(
+                "cannot specify both a credential and a credential provider",
+            ));
+        }
+
         if self.load_balanced.unwrap_or(false) {
             if self.hosts.len() > 1 {
                 return Err(ErrorKind::InvalidArgument {
@@ -1101,6 +1435,51 @@ impl ClientOptions {
             }
         }
 
+        if self.srv_max_hosts.unwrap_or(0) > 0 {
+            if self.repl_set_name.is_some() {
+                return Err(Error::invalid_argument(
+                    "cannot specify replicaSet with a positive srvMaxHosts",
+                ));
+            }
+            if self.load_balanced == Some(true) {
+                return Err(Error::invalid_argument(
+                    "cannot specify loadBalanced=true with a positive srvMaxHosts",
+                ));
+            }
+        }
+
+        if self.proxy_host.is_none() {
+            if self.proxy_port.is_some() {
+                return Err(Error::invalid_argument(
+                    "cannot specify proxyPort without proxyHost",
+                ));
+            }
+            if self.proxy_username.is_some() || self.proxy_password.is_some() {
+                return Err(Error::invalid_argument(
+                    "cannot specify proxyUsername or proxyPassword without proxyHost",
+                ));
+            }
+        }
+
+        match (&self.proxy_username, &self.proxy_password) {
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(Error::invalid_argument(
+                    "proxyUsername and proxyPassword must be specified together",
+                ));
+            }
+            (Some(username), Some(password)) => {
+                // Username/password authentication with a SOCKS5 proxy limits each of them to
+                // between 1 and 255 bytes.
+                let valid_length = |value: &str| (1..=255).contains(&value.len());
+                if !valid_length(username) || !valid_length(password) {
+                    return Err(Error::invalid_argument(
+                        "proxyUsername and proxyPassword must each be between 1 and 255 bytes long",
+                    ));
+                }
+            }
+            (None, None) => {}
+        }
+
         if let Some(ref compressors) = self.compressors {
             for compressor in compressors {
                 compressor.validate()?;
@@ -1146,7 +1525,9 @@ impl ClientOptions {
                 command_event_handler,
                 connect_timeout,
                 credential,
+                credential_provider,
                 direct_connection,
+                dns_resolver,
                 driver_info,
                 heartbeat_freq,
                 load_balanced,
@@ -1154,6 +1535,10 @@ impl ClientOptions {
                 max_idle_time,
                 max_pool_size,
                 min_pool_size,
+                proxy_host,
+                proxy_password,
+                proxy_port,
+                proxy_username,
                 read_concern,
                 repl_set_name,
                 retry_reads,
@@ -1162,7 +1547,10 @@ impl ClientOptions {
                 server_api,
                 server_selection_timeout,
                 socket_timeout,
+                srv_max_hosts,
+                srv_service_name,
                 test_options,
+                timeout,
                 tls,
                 write_concern,
                 original_srv_info,
@@ -1325,6 +1713,28 @@ impl ClientOptionsParser {
 
         let hosts = hosts?;
 
+        // The connection string spec accepts auth databases such as "admin.sock" after a list of
+        // Unix domain sockets, but a dotted name is never used as the default database.
+        let default_database = match db {
+            Some(ref db) if db.contains('.') => {
+                #[cfg(unix)]
+                let unix_hosts = hosts
+                    .iter()
+                    .all(|host| matches!(host, ServerAddress::Unix { .. }));
+                #[cfg(not(unix))]
+                let unix_hosts = false;
+
+                if !unix_hosts {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "illegal character in database name".to_string(),
+                    }
+                    .into());
+                }
+                None
+            }
+            _ => db.clone(),
+        };
+
         if srv {
             if hosts.len() != 1 {
                 return Err(ErrorKind::InvalidArgument {
@@ -1339,6 +1749,14 @@ impl ClientOptionsParser {
                 }
                 .into());
             }
+
+            #[cfg(unix)]
+            if let ServerAddress::Unix { .. } = hosts[0] {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "a unix domain socket cannot be specified with 'mongodb+srv'".into(),
+                }
+                .into());
+            }
         }
 
         let mut options = ClientOptionsParser {
@@ -1404,6 +1822,14 @@ impl ClientOptionsParser {
                     credential.mechanism_properties = Some(doc);
                 }
 
+                if mechanism == &AuthMechanism::Gssapi {
+                    credential
+                        .mechanism_properties
+                        .get_or_insert_with(Document::new)
+                        .entry("SERVICE_NAME".to_string())
+                        .or_insert_with(|| "mongodb".into());
+                }
+
                 mechanism.validate_credential(credential)?;
                 credential.mechanism = options.auth_mechanism.take();
             }
@@ -1430,7 +1856,7 @@ impl ClientOptionsParser {
         };
 
         // set default database.
-        options.default_database = db;
+        options.default_database = default_database;
 
         if options.tls.is_none() && options.srv {
             options.tls = Some(Tls::Enabled(Default::default()));
@@ -1479,6 +1905,30 @@ impl ClientOptionsParser {
             )?;
         }
 
+        // These TLS options are mutually exclusive regardless of their values.
+        const CONFLICTING_TLS_OPTIONS: &[(&str, &str)] = &[
+            ("tlsInsecure", "tlsDisableOCSPEndpointCheck"),
+            ("tlsInsecure", "tlsDisableCertificateRevocationCheck"),
+            ("tlsAllowInvalidCertificates", "tlsDisableOCSPEndpointCheck"),
+            (
+                "tlsAllowInvalidCertificates",
+                "tlsDisableCertificateRevocationCheck",
+            ),
+            (
+                "tlsDisableOCSPEndpointCheck",
+                "tlsDisableCertificateRevocationCheck",
+            ),
+        ];
+        let is_present = |option: &str| keys.iter().any(|key| key.eq_ignore_ascii_case(option));
+        for (first, second) in CONFLICTING_TLS_OPTIONS {
+            if is_present(first) && is_present(second) {
+                return Err(ErrorKind::InvalidArgument {
+                    message: format!("'{}' and '{}' cannot both be specified", first, second),
+                }
+                .into());
+            }
+        }
+
         if let Some(tags) = self.read_preference_tags.take() {
             self.read_preference = match self.read_preference.take() {
                 Some(read_pref) => Some(read_pref.with_tags(tags)?),
@@ -1518,6 +1968,19 @@ impl ClientOptionsParser {
             }
         }
 
+        if !self.srv {
+            if self.srv_max_hosts.is_some() {
+                return Err(Error::invalid_argument(
+                    "srvMaxHosts can only be specified with a 'mongodb+srv' URI",
+                ));
+            }
+            if self.srv_service_name.is_some() {
+                return Err(Error::invalid_argument(
+                    "srvServiceName can only be specified with a 'mongodb+srv' URI",
+                ));
+            }
+        }
+
         // If zlib and zlib_compression_level are specified then write zlib_compression_level into
         // zlib enum
         if let (Some(compressors), Some(zlib_compression_level)) =
@@ -1768,12 +2231,40 @@ impl ClientOptionsParser {
             k @ "retryreads" => {
                 self.retry_reads = Some(get_bool!(value, k));
             }
+            "proxyhost" => {
+                self.proxy_host = Some(value.to_string());
+            }
+            k @ "proxyport" => {
+                self.proxy_port = Some(value.parse().map_err(|_| {
+                    Error::invalid_argument(format!(
+                        "connection string `{}` option must be a port number between 0 and 65535",
+                        k
+                    ))
+                })?);
+            }
+            "proxyusername" => {
+                self.proxy_username =
+                    Some(percent_decode(value, "proxyUsername must be URL encoded")?);
+            }
+            "proxypassword" => {
+                self.proxy_password =
+                    Some(percent_decode(value, "proxyPassword must be URL encoded")?);
+            }
             k @ "serverselectiontimeoutms" => {
                 self.server_selection_timeout = Some(Duration::from_millis(get_duration!(value, k)))
             }
             k @ "sockettimeoutms" => {
                 self.socket_timeout = Some(Duration::from_millis(get_duration!(value, k)));
             }
+            k @ "srvmaxhosts" => {
+                self.srv_max_hosts = Some(get_u32!(value, k));
+            }
+            "srvservicename" => {
+                self.srv_service_name = Some(value.to_string());
+            }
+            k @ "timeoutms" => {
+                self.timeout = Some(Duration::from_millis(get_duration!(value, k)));
+            }
             k @ "tls" | k @ "ssl" => {
                 let tls = get_bool!(value, k);
 
@@ -1873,6 +2364,81 @@ impl ClientOptionsParser {
                     ))
                 }
             },
+            #[cfg(feature = "cert-key-password")]
+            "tlscertificatekeyfilepassword" => match self.tls {
+                Some(Tls::Disabled) => {
+                    return Err(ErrorKind::InvalidArgument {
+                        message: "'tlsCertificateKeyFilePassword' can't be set if tls=false".into(),
+                    }
+                    .into());
+                }
+                Some(Tls::Enabled(ref mut options)) => {
+                    options.cert_key_file_password = Some(value.as_bytes().to_vec());
+                }
+                None => {
+                    self.tls = Some(Tls::Enabled(
+                        TlsOptions::builder()
+                            .cert_key_file_password(value.as_bytes().to_vec())
+                            .build(),
+                    ))
+                }
+            },
+            #[cfg(not(feature = "cert-key-password"))]
+            "tlscertificatekeyfilepassword" => {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "'tlsCertificateKeyFilePassword' requires the 'cert-key-password' \
+                              feature flag"
+                        .into(),
+                }
+                .into());
+            }
+            k @ "tlsdisablecertificaterevocationcheck" => {
+                let val = get_bool!(value, k);
+
+                match self.tls {
+                    Some(Tls::Disabled) => {
+                        return Err(ErrorKind::InvalidArgument {
+                            message: "'tlsDisableCertificateRevocationCheck' can't be set if \
+                                      tls=false"
+                                .into(),
+                        }
+                        .into());
+                    }
+                    Some(Tls::Enabled(ref mut options)) => {
+                        options.disable_certificate_revocation_check = Some(val);
+                    }
+                    None => {
+                        self.tls = Some(Tls::Enabled(
+                            TlsOptions::builder()
+                                .disable_certificate_revocation_check(val)
+                                .build(),
+                        ))
+                    }
+                }
+            }
+            k @ "tlsdisableocspendpointcheck" => {
+                let val = get_bool!(value, k);
+
+                match self.tls {
+                    Some(Tls::Disabled) => {
+                        return Err(ErrorKind::InvalidArgument {
+                            message: "'tlsDisableOCSPEndpointCheck' can't be set if tls=false"
+                                .into(),
+                        }
+                        .into());
+                    }
+                    Some(Tls::Enabled(ref mut options)) => {
+                        options.disable_ocsp_endpoint_check = Some(val);
+                    }
+                    None => {
+                        self.tls = Some(Tls::Enabled(
+                            TlsOptions::builder()
+                                .disable_ocsp_endpoint_check(val)
+                                .build(),
+                        ))
+                    }
+                }
+            }
             "w" => {
                 let mut write_concern = self.write_concern.get_or_insert_with(Default::default);
 
@@ -1981,15 +2547,23 @@ mod tests {
     #[test]
     fn test_parse_address_with_from_str() {
         let x = "localhost:27017".parse::<ServerAddress>().unwrap();
-        let ServerAddress::Tcp { host, port } = x;
-        assert_eq!(host, "localhost");
-        assert_eq!(port, Some(27017));
+        match x {
+            ServerAddress::Tcp { host, port } => {
+                assert_eq!(host, "localhost");
+                assert_eq!(port, Some(27017));
+            }
+            other => panic!("expected TCP address, got {:?}", other),
+        }
 
         // Port defaults to 27017 (so this doesn't fail)
         let x = "localhost".parse::<ServerAddress>().unwrap();
-        let ServerAddress::Tcp { host, port } = x;
-        assert_eq!(host, "localhost");
-        assert_eq!(port, None);
+        match x {
+            ServerAddress::Tcp { host, port } => {
+                assert_eq!(host, "localhost");
+                assert_eq!(port, None);
+            }
+            other => panic!("expected TCP address, got {:?}", other),
+        }
 
         let x = "localhost:not a number".parse::<ServerAddress>();
         assert!(x.is_err());
@@ -2388,3 +2962,76 @@ pub struct TransactionOptions {
     )]
     pub max_commit_time: Option<Duration>,
 }
+
+/// Specifies the options to a [`Client::bulk_write`](../struct.Client.html#method.bulk_write)
+/// operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize, Deserialize)]
+#[builder(field_defaults(default, setter(into)))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ClientBulkWriteOptions {
+    /// Opt out of document-level validation.
+    pub bypass_document_validation: Option<bool>,
+
+    /// If true, when a write fails, return without performing the remaining writes. If false,
+    /// when a write fails, continue with the remaining writes, if any.
+    ///
+    /// Defaults to true.
+    pub ordered: Option<bool>,
+
+    /// Whether the result should include the outcome of each individual write in addition to the
+    /// summary counts.
+    ///
+    /// Defaults to false.
+    #[serde(skip_serializing)]
+    pub verbose_results: Option<bool>,
+
+    /// A document with any amount of parameter names, each followed by definitions of constants in
+    /// the MQL Aggregate Expression language. Each parameter name is then usable to access the
+    /// value of the corresponding MQL Expression with the "$$" syntax within Aggregate Expression
+    /// contexts.
+    #[serde(rename = "let")]
+    pub let_vars: Option<Document>,
+
+    /// Tags the query with an arbitrary string to help trace the operation through the database
+    /// profiler, currentOp and logs.
+    pub comment: Option<String>,
+
+    /// The write concern for the operation.
+    #[serde(skip_deserializing)]
+    pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the client.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+/// A write to perform as part of a
+/// [`Client::bulk_write`](../struct.Client.html#method.bulk_write) operation, along with the
+/// namespace it targets.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct NamespacedWriteModel {
+    /// The namespace the write targets.
+    pub namespace: Namespace,
+
+    /// The write to perform.
+    pub model: WriteModel<Document>,
+}
+
+impl NamespacedWriteModel {
+    /// Creates a new `NamespacedWriteModel` that performs `model` against `namespace`.
+    pub fn new(namespace: Namespace, model: impl Into<WriteModel<Document>>) -> Self {
+        Self {
+            namespace,
+            model: model.into(),
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use std::{future::Future, net::IpAddr, pin::Pin, time::Duration};
+
 use trust_dns_resolver::config::ResolverConfig as TrustDnsResolverConfig;
 
+use crate::error::Result;
+
 /// Configuration for the upstream nameservers to use for resolution.
 ///
 /// This is a thin wrapper around a `trust_dns_resolver::config::ResolverConfig` provided to ensure
@@ -41,3 +45,86 @@ impl ResolverConfig {
         }
     }
 }
+
+/// A record returned by an SRV lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SrvRecord {
+    /// The hostname of the target of the record. A trailing '.' is permitted.
+    pub target: String,
+
+    /// The port on the target host where the service can be found.
+    pub port: u16,
+
+    /// The time-to-live of the record, which determines how often the hosts of a "mongodb+srv"
+    /// connection string will be rescanned.
+    pub ttl: Duration,
+}
+
+/// A custom DNS resolver used for the lookups performed by the driver, allowing records to be
+/// resolved through a mechanism other than the system's DNS configuration (e.g. a service discovery
+/// system or a static table).
+///
+/// A resolver can be provided by setting
+/// [`ClientOptions::dns_resolver`](crate::options::ClientOptions::dns_resolver) or by parsing a
+/// connection string with
+/// [`ClientOptions::parse_with_dns_resolver`](crate::options::ClientOptions::parse_with_dns_resolver),
+/// which is required for the resolver to be used for the initial lookups of a "mongodb+srv"
+/// connection string.
+///
+/// Errors returned by the resolver can be created from a `std::io::Error`.
+///
+/// ```rust
+/// # use std::{future::Future, pin::Pin, time::Duration};
+/// # use mongodb::{error::Result, options::{DnsResolver, SrvRecord}};
+/// struct StaticResolver;
+///
+/// impl DnsResolver for StaticResolver {
+///     fn srv_lookup<'a>(
+///         &'a self,
+///         name: &'a str,
+///     ) -> Pin<Box<dyn Future<Output = Result<Vec<SrvRecord>>> + Send + 'a>> {
+///         Box::pin(async move {
+///             match name {
+///                 "_mongodb._tcp.cluster.example.com" => Ok(vec![SrvRecord {
+///                     target: "node1.example.com".to_string(),
+///                     port: 27017,
+///                     ttl: Duration::from_secs(60),
+///                 }]),
+///                 _ => Ok(Vec::new()),
+///             }
+///         })
+///     }
+///
+///     fn txt_lookup<'a>(
+///         &'a self,
+///         _name: &'a str,
+///     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + 'a>> {
+///         Box::pin(async move { Ok(vec!["authSource=admin".to_string()]) })
+///     }
+/// }
+/// ```
+pub trait DnsResolver: Send + Sync {
+    /// Looks up the SRV records for `name`, e.g. "_mongodb._tcp.cluster.example.com".
+    fn srv_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SrvRecord>>> + Send + 'a>>;
+
+    /// Looks up the TXT records for `name`. Each record is returned as a single string, with the
+    /// character-strings it is made up of concatenated. An empty list should be returned if there
+    /// are no records.
+    fn txt_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + 'a>>;
+
+    /// Looks up the IP addresses of `host` when connecting to a server. Returning `None` falls back
+    /// to the system's resolution of the host, which is what the default implementation does.
+    fn ip_lookup<'a>(
+        &'a self,
+        host: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Vec<IpAddr>>>> + Send + 'a>> {
+        let _ = host;
+        Box::pin(async { Ok(None) })
+    }
+}
@@ -9,13 +9,20 @@ use std::{
     time::{Duration, Instant},
 };
 
+use futures_util::future::BoxFuture;
 use lazy_static::lazy_static;
 use uuid::Uuid;
 
 use crate::{
     bson::{doc, spec::BinarySubtype, Binary, Bson, Document, Timestamp},
     cmap::conn::PinnedConnectionHandle,
-    error::{ErrorKind, Result},
+    error::{
+        Error,
+        ErrorKind,
+        Result,
+        TRANSIENT_TRANSACTION_ERROR,
+        UNKNOWN_TRANSACTION_COMMIT_RESULT,
+    },
     operation::{AbortTransaction, CommitTransaction, Operation},
     options::{SessionOptions, TransactionOptions},
     runtime,
@@ -32,11 +39,59 @@ lazy_static! {
     pub(crate) static ref SESSIONS_UNSUPPORTED_COMMANDS: HashSet<&'static str> = {
         let mut hash_set = HashSet::new();
         hash_set.insert("killcursors");
+        hash_set.insert("endsessions");
         hash_set.insert("parallelcollectionscan");
         hash_set
     };
 }
 
+/// The maximum amount of time [`ClientSession::with_transaction`] will spend retrying a
+/// transaction before giving up.
+const WITH_TRANSACTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Decides whether [`ClientSession::with_transaction`] should retry after a failure. This is
+/// shared with the sync API so that both retry in the same way.
+pub(crate) struct WithTransactionRetrier {
+    start: Instant,
+}
+
+/// How [`ClientSession::with_transaction`] should proceed after committing failed.
+pub(crate) enum CommitErrorAction {
+    RetryCommit,
+    RetryTransaction,
+    Return,
+}
+
+impl WithTransactionRetrier {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+
+    fn timed_out(&self) -> bool {
+        self.start.elapsed() >= WITH_TRANSACTION_TIMEOUT
+    }
+
+    /// Whether the whole transaction should be run again after the callback returned `error`.
+    pub(crate) fn retry_after_callback_error(&self, error: &Error) -> bool {
+        error.contains_label(TRANSIENT_TRANSACTION_ERROR) && !self.timed_out()
+    }
+
+    /// How to proceed after committing the transaction failed with `error`.
+    pub(crate) fn after_commit_error(&self, error: &Error) -> CommitErrorAction {
+        if error.is_max_time_ms_expired_error() || self.timed_out() {
+            CommitErrorAction::Return
+        } else if error.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) {
+            CommitErrorAction::RetryCommit
+        } else if error.contains_label(TRANSIENT_TRANSACTION_ERROR) {
+            CommitErrorAction::RetryTransaction
+        } else {
+            CommitErrorAction::Return
+        }
+    }
+}
+
 /// A MongoDB client session. This struct represents a logical session used for ordering sequential
 /// operations. To create a `ClientSession`, call `start_session` on a `Client`.
 ///
@@ -53,6 +108,9 @@ lazy_static! {
 /// [`ClientSession::start_transaction`] on a `ClientSession`. The `ClientSession` must be passed to
 /// operations to be executed within the transaction.
 ///
+/// [`ClientSession::with_transaction`] implements the retry logic shown below and is the
+/// recommended way to run a transaction in most cases.
+///
 /// ```rust
 /// use mongodb::{
 ///     bson::{doc, Document},
@@ -561,6 +619,93 @@ impl ClientSession {
         }
     }
 
+    /// Starts a transaction, runs the given callback, and commits the transaction.
+    ///
+    /// If the callback or the commit fails with an error carrying a
+    /// [`crate::error::TRANSIENT_TRANSACTION_ERROR`] label, the transaction is aborted and the
+    /// whole callback is run again in a new transaction. If the commit fails with an error
+    /// carrying a [`crate::error::UNKNOWN_TRANSACTION_COMMIT_RESULT`] label, only the commit is
+    /// retried. Any other error causes the transaction to be aborted and is returned to the
+    /// caller. Retries stop once 120 seconds have elapsed since `with_transaction` was called, at
+    /// which point the most recent error is returned.
+    ///
+    /// Because the callback may be executed several times and returns a future that borrows the
+    /// session, values it needs should be passed through the `context` argument rather than
+    /// captured, since the closure borrowing rules would otherwise require them to be moved into
+    /// the first returned future.
+    ///
+    /// ```rust
+    /// # use futures::FutureExt;
+    /// # use mongodb::{bson::{doc, Document}, error::Result, Client};
+    /// #
+    /// # async fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com").await?;
+    /// # let mut session = client.start_session(None).await?;
+    /// let coll = client.database("foo").collection::<Document>("bar");
+    /// let name = "x".to_string();
+    /// session
+    ///     .with_transaction(
+    ///         (&coll, &name),
+    ///         |session, (coll, name)| {
+    ///             async move {
+    ///                 coll.insert_one_with_session(doc! { "name": name.as_str() }, None, session)
+    ///                     .await?;
+    ///                 coll.delete_one_with_session(doc! { "y": 2 }, None, session).await?;
+    ///                 Ok(())
+    ///             }
+    ///             .boxed()
+    ///         },
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn with_transaction<R, C, F>(
+        &mut self,
+        mut context: C,
+        mut callback: F,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<R>
+    where
+        F: for<'a> FnMut(&'a mut ClientSession, &'a mut C) -> BoxFuture<'a, Result<R>>,
+    {
+        let options = options.into();
+        let retrier = WithTransactionRetrier::new();
+
+        'transaction: loop {
+            self.start_transaction(options.clone()).await?;
+            let ret = match callback(self, &mut context).await {
+                Ok(v) => v,
+                Err(e) => {
+                    if self.in_transaction() {
+                        self.abort_transaction().await?;
+                    }
+                    if retrier.retry_after_callback_error(&e) {
+                        continue 'transaction;
+                    }
+                    return Err(e);
+                }
+            };
+
+            // The callback may have committed or aborted the transaction itself.
+            if !self.in_transaction() {
+                return Ok(ret);
+            }
+
+            loop {
+                match self.commit_transaction().await {
+                    Ok(()) => return Ok(ret),
+                    Err(e) => match retrier.after_commit_error(&e) {
+                        CommitErrorAction::RetryCommit => continue,
+                        CommitErrorAction::RetryTransaction => continue 'transaction,
+                        CommitErrorAction::Return => return Err(e),
+                    },
+                }
+            }
+        }
+    }
+
     fn default_transaction_options(&self) -> Option<&TransactionOptions> {
         self.options
             .as_ref()
@@ -8,8 +8,15 @@ use tokio::sync::RwLockReadGuard;
 
 use crate::{
     bson::{doc, Bson},
+    client::session::{CommitErrorAction, WithTransactionRetrier},
     coll::options::{CountOptions, InsertManyOptions},
-    error::Result,
+    error::{
+        Error,
+        ErrorKind,
+        Result,
+        TRANSIENT_TRANSACTION_ERROR,
+        UNKNOWN_TRANSACTION_COMMIT_RESULT,
+    },
     options::{Acknowledgment, FindOptions, ReadConcern, ReadPreference, WriteConcern},
     runtime,
     sdam::ServerInfo,
@@ -552,3 +559,33 @@ async fn find_and_getmore_share_session() {
         run_test(&client, &coll, read_pref).await;
     }
 }
+
+#[test]
+fn with_transaction_retry_decisions() {
+    let retrier = WithTransactionRetrier::new();
+    let error = |label: Option<&str>| {
+        Error::new(
+            ErrorKind::Internal {
+                message: "error".to_string(),
+            },
+            label.map(|label| vec![label.to_string()]),
+        )
+    };
+
+    assert!(retrier.retry_after_callback_error(&error(Some(TRANSIENT_TRANSACTION_ERROR))));
+    assert!(!retrier.retry_after_callback_error(&error(Some(UNKNOWN_TRANSACTION_COMMIT_RESULT))));
+    assert!(!retrier.retry_after_callback_error(&error(None)));
+
+    assert!(matches!(
+        retrier.after_commit_error(&error(Some(UNKNOWN_TRANSACTION_COMMIT_RESULT))),
+        CommitErrorAction::RetryCommit
+    ));
+    assert!(matches!(
+        retrier.after_commit_error(&error(Some(TRANSIENT_TRANSACTION_ERROR))),
+        CommitErrorAction::RetryTransaction
+    ));
+    assert!(matches!(
+        retrier.after_commit_error(&error(None)),
+        CommitErrorAction::Return
+    ));
+}
@@ -21,6 +21,8 @@ pub(crate) struct RawCommand {
     pub(crate) name: String,
     pub(crate) target_db: String,
     pub(crate) bytes: Vec<u8>,
+    pub(crate) document_sequences: Vec<DocumentSequence>,
+    pub(crate) exhaust_allowed: bool,
 }
 
 impl RawCommand {
@@ -30,6 +32,24 @@ impl RawCommand {
     }
 }
 
+/// A sequence of documents sent alongside a command as an OP_MSG payload type 1 section rather
+/// than as an array within the command document.
+#[derive(Clone, Debug)]
+pub(crate) struct DocumentSequence {
+    /// The name of the command field the documents belong to.
+    pub(crate) identifier: String,
+    pub(crate) documents: Vec<RawDocumentBuf>,
+}
+
+impl DocumentSequence {
+    pub(crate) fn new(identifier: impl Into<String>, documents: Vec<RawDocumentBuf>) -> Self {
+        Self {
+            identifier: identifier.into(),
+            documents,
+        }
+    }
+}
+
 /// Driver-side model of a database command.
 #[serde_with::skip_serializing_none]
 #[derive(Clone, Debug, Serialize, Default)]
@@ -64,6 +84,13 @@ pub(crate) struct Command<T = Document> {
     read_concern: Option<ReadConcernInternal>,
 
     recovery_token: Option<Document>,
+
+    #[serde(skip)]
+    pub(crate) document_sequences: Vec<DocumentSequence>,
+
+    /// Whether the server may stream multiple replies to this command without further requests.
+    #[serde(skip)]
+    pub(crate) exhaust_allowed: bool,
 }
 
 impl<T> Command<T> {
@@ -81,6 +108,8 @@ impl<T> Command<T> {
             autocommit: None,
             read_concern: None,
             recovery_token: None,
+            document_sequences: Vec::new(),
+            exhaust_allowed: false,
         }
     }
 
@@ -103,6 +132,8 @@ impl<T> Command<T> {
             autocommit: None,
             read_concern: read_concern.map(Into::into),
             recovery_token: None,
+            document_sequences: Vec::new(),
+            exhaust_allowed: false,
         }
     }
 
@@ -209,6 +240,12 @@ impl RawCommandResponse {
         })
     }
 
+    /// Replaces the body of the response, e.g. after decrypting its encrypted values.
+    #[cfg(feature = "csfle")]
+    pub(crate) fn set_raw_body(&mut self, raw: RawDocumentBuf) {
+        self.raw = raw;
+    }
+
     pub(crate) fn body<'a, T: Deserialize<'a>>(&'a self) -> Result<T> {
         bson::from_slice(self.raw.as_bytes()).map_err(|e| {
             Error::from(ErrorKind::InvalidResponse {
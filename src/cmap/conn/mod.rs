@@ -10,7 +10,7 @@ use std::{
 use derivative::Derivative;
 use tokio::sync::{mpsc, Mutex};
 
-use self::wire::Message;
+use self::wire::{Message, MessageFlags};
 use super::manager::PoolManager;
 use crate::{
     bson::oid::ObjectId,
@@ -29,10 +29,10 @@ use crate::{
         ConnectionCreatedEvent,
         ConnectionReadyEvent,
     },
-    options::{ServerAddress, TlsOptions},
-    runtime::AsyncStream,
+    options::{DnsResolver, ServerAddress, TlsOptions},
+    runtime::{AsyncStream, Socks5Proxy},
 };
-pub(crate) use command::{Command, RawCommand, RawCommandResponse};
+pub(crate) use command::{Command, DocumentSequence, RawCommand, RawCommandResponse};
 pub(crate) use stream_description::StreamDescription;
 pub(crate) use wire::next_request_id;
 
@@ -75,8 +75,16 @@ pub(crate) struct Connection {
     /// into a pool.
     error: bool,
 
+    /// Whether the last message received from the server had the `moreToCome` flag set, meaning
+    /// that the server will send another reply without waiting for a new request.
+    more_to_come: bool,
+
     stream: AsyncStream,
 
+    /// The maximum amount of time to spend writing or reading a single message. When exceeded, the
+    /// connection is marked as errored so that it is closed rather than reused.
+    socket_timeout: Option<Duration>,
+
     /// Compressor that the client will use before sending messages.
     /// This compressor does not get used to decompress server messages.
     /// The client will decompress server messages using whichever compressor
@@ -89,6 +97,11 @@ pub(crate) struct Connection {
     /// connection to the pin holder.
     pinned_sender: Option<mpsc::Sender<Connection>>,
 
+    /// The access token this connection authenticated with when using MONGODB-OIDC. This is
+    /// removed from the client's token cache if the connection is required to reauthenticate.
+    #[derivative(Debug = "ignore")]
+    pub(crate) oidc_access_token: Option<String>,
+
     #[derivative(Debug = "ignore")]
     handler: Option<Arc<dyn CmapEventHandler>>,
 }
@@ -104,6 +117,8 @@ impl Connection {
             address: address.clone(),
             connect_timeout: options.as_ref().and_then(|opts| opts.connect_timeout),
             tls_options: options.as_ref().and_then(|opts| opts.tls_options.clone()),
+            dns_resolver: options.as_ref().and_then(|opts| opts.dns_resolver.clone()),
+            proxy: options.as_ref().and_then(|opts| opts.proxy.clone()),
         };
 
         let conn = Self {
@@ -113,12 +128,18 @@ impl Connection {
             command_executing: false,
             ready_and_available_time: None,
             stream: AsyncStream::connect(stream_options).await?,
+            socket_timeout: options
+                .as_ref()
+                .and_then(|opts| opts.socket_timeout)
+                .filter(|timeout| !timeout.is_zero()),
             address,
             handler: options.and_then(|options| options.event_handler),
             stream_description: None,
             error: false,
+            more_to_come: false,
             pinned_sender: None,
             compressor: None,
+            oidc_access_token: None,
         };
 
         Ok(conn)
@@ -146,6 +167,8 @@ impl Connection {
         address: ServerAddress,
         connect_timeout: Option<Duration>,
         tls_options: Option<TlsOptions>,
+        dns_resolver: Option<Arc<dyn DnsResolver>>,
+        proxy: Option<Socks5Proxy>,
     ) -> Result<Self> {
         Self::new(
             0,
@@ -153,7 +176,10 @@ impl Connection {
             0,
             Some(ConnectionOptions {
                 connect_timeout,
+                socket_timeout: None,
                 tls_options,
+                dns_resolver,
+                proxy,
                 event_handler: None,
             }),
         )
@@ -266,26 +292,56 @@ impl Connection {
         let write_result = match self.compressor {
             Some(ref compressor) if to_compress => {
                 message
-                    .write_compressed_to(&mut self.stream, compressor)
+                    .write_compressed_to(&mut self.stream, compressor, self.socket_timeout)
+                    .await
+            }
+            _ => {
+                message
+                    .write_to(&mut self.stream, self.socket_timeout)
                     .await
             }
-            _ => message.write_to(&mut self.stream).await,
         };
 
         self.error = write_result.is_err();
         write_result?;
 
+        self.read_response().await
+    }
+
+    /// Reads the next reply to a command whose previous reply had the `moreToCome` flag set. This
+    /// is used to receive the replies streamed by the server in response to an awaitable `hello`.
+    pub(crate) async fn receive_message(&mut self) -> Result<RawCommandResponse> {
+        if !self.more_to_come {
+            return Err(Error::internal(
+                "attempted to receive a streamed reply on a connection with no replies pending",
+            ));
+        }
+
+        self.command_executing = true;
+        self.read_response().await
+    }
+
+    async fn read_response(&mut self) -> Result<RawCommandResponse> {
         let response_message_result = Message::read_from(
             &mut self.stream,
             self.stream_description
                 .as_ref()
                 .map(|d| d.max_message_size_bytes),
+            self.socket_timeout,
         )
         .await;
         self.command_executing = false;
         self.error = response_message_result.is_err();
 
-        RawCommandResponse::new(self.address.clone(), response_message_result?)
+        let response_message = response_message_result?;
+        self.more_to_come = response_message.flags.contains(MessageFlags::MORE_TO_COME);
+        RawCommandResponse::new(self.address.clone(), response_message)
+    }
+
+    /// Whether the server will send another reply on this connection without waiting for a new
+    /// request.
+    pub(crate) fn is_streaming(&self) -> bool {
+        self.more_to_come
     }
 
     /// Executes a `Command` and returns a `CommandResponse` containing the result from the server.
@@ -372,14 +428,17 @@ impl Connection {
             address: self.address.clone(),
             generation: self.generation.clone(),
             stream: std::mem::replace(&mut self.stream, AsyncStream::Null),
+            socket_timeout: self.socket_timeout,
             handler: self.handler.take(),
             stream_description: self.stream_description.take(),
             command_executing: self.command_executing,
             error: self.error,
+            more_to_come: false,
             pool_manager: None,
             ready_and_available_time: None,
             pinned_sender: self.pinned_sender.clone(),
             compressor: self.compressor.clone(),
+            oidc_access_token: self.oidc_access_token.take(),
         }
     }
 }
@@ -432,8 +491,11 @@ impl Drop for Connection {
                 pool_manager.check_in(dropped_connection)
             };
             if let Err(mut conn) = result {
-                // the check in failed because the pool has been dropped, so we emit the event
-                // here and drop the connection.
+                // the check in failed because the pool has been closed or dropped, so we emit the
+                // events here and drop the connection.
+                if let Some(ref handler) = conn.handler {
+                    handler.handle_connection_checked_in_event(conn.checked_in_event());
+                }
                 conn.close(ConnectionClosedReason::PoolClosed);
             }
         }
@@ -1,6 +1,7 @@
-use std::io::Read;
+use std::{io::Read, time::Duration};
 
 use bitflags::bitflags;
+use bson::RawDocumentBuf;
 use futures_io::AsyncWrite;
 use futures_util::{
     io::{BufReader, BufWriter},
@@ -12,11 +13,14 @@ use super::header::{Header, OpCode};
 use crate::{
     bson_util,
     cmap::{
-        conn::{command::RawCommand, wire::util::SyncCountReader},
+        conn::{
+            command::{DocumentSequence, RawCommand},
+            wire::util::SyncCountReader,
+        },
         Command,
     },
     error::{Error, ErrorKind, Result},
-    runtime::{AsyncLittleEndianWrite, AsyncStream, SyncLittleEndianRead},
+    runtime::{self, AsyncLittleEndianWrite, AsyncStream, SyncLittleEndianRead},
 };
 
 use crate::compression::{Compressor, Decoder};
@@ -42,6 +46,8 @@ impl Message {
                 bytes,
                 target_db: command.target_db,
                 name: command.name,
+                document_sequences: command.document_sequences,
+                exhaust_allowed: command.exhaust_allowed,
             },
             request_id,
         ))
@@ -51,10 +57,23 @@ impl Message {
     ///
     /// Note that `response_to` will need to be set manually.
     pub(crate) fn with_raw_command(command: RawCommand, request_id: Option<i32>) -> Self {
+        let mut sections = vec![MessageSection::Document(command.bytes)];
+        sections.extend(
+            command
+                .document_sequences
+                .into_iter()
+                .map(MessageSection::from_document_sequence),
+        );
+
+        let mut flags = MessageFlags::empty();
+        if command.exhaust_allowed {
+            flags |= MessageFlags::EXHAUST_ALLOWED;
+        }
+
         Self {
             response_to: 0,
-            flags: MessageFlags::empty(),
-            sections: vec![MessageSection::Document(command.bytes)],
+            flags,
+            sections,
             checksum: None,
             request_id,
         }
@@ -81,10 +100,19 @@ impl Message {
         })
     }
 
-    /// Reads bytes from `reader` and deserializes them into a Message.
+    /// Reads bytes from `reader` and deserializes them into a Message. If a timeout is provided
+    /// and reading the message takes longer than it, a network timeout error is returned.
     pub(crate) async fn read_from(
         reader: &mut AsyncStream,
         max_message_size_bytes: Option<i32>,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        with_socket_timeout(timeout, Self::read_message(reader, max_message_size_bytes)).await
+    }
+
+    async fn read_message(
+        reader: &mut AsyncStream,
+        max_message_size_bytes: Option<i32>,
     ) -> Result<Self> {
         let mut reader = BufReader::new(reader);
         let header = Header::read_from(&mut reader).await?;
@@ -225,8 +253,17 @@ impl Message {
         })
     }
 
-    /// Serializes the Message to bytes and writes them to `writer`.
-    pub(crate) async fn write_to(&self, stream: &mut AsyncStream) -> Result<()> {
+    /// Serializes the Message to bytes and writes them to `writer`. If a timeout is provided and
+    /// writing the message takes longer than it, a network timeout error is returned.
+    pub(crate) async fn write_to(
+        &self,
+        stream: &mut AsyncStream,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        with_socket_timeout(timeout, self.write_message(stream)).await
+    }
+
+    async fn write_message(&self, stream: &mut AsyncStream) -> Result<()> {
         let mut writer = BufWriter::new(stream);
         let mut sections_bytes = Vec::new();
 
@@ -263,11 +300,21 @@ impl Message {
         Ok(())
     }
 
-    /// Serializes message to bytes, compresses those bytes, and writes the bytes.
+    /// Serializes message to bytes, compresses those bytes, and writes the bytes. If a timeout is
+    /// provided and writing the message takes longer than it, a network timeout error is returned.
     pub async fn write_compressed_to(
         &self,
         stream: &mut AsyncStream,
         compressor: &Compressor,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        with_socket_timeout(timeout, self.write_compressed_message(stream, compressor)).await
+    }
+
+    async fn write_compressed_message(
+        &self,
+        stream: &mut AsyncStream,
+        compressor: &Compressor,
     ) -> Result<()> {
         let mut encoder = compressor.to_encoder()?;
         let compressor_id = compressor.id() as u8;
@@ -318,6 +365,18 @@ impl Message {
 
 const DEFAULT_MAX_MESSAGE_SIZE_BYTES: i32 = 48 * 1024 * 1024;
 
+/// Runs the given read or write of a message, failing with a network timeout error if it does not
+/// complete within `timeout`.
+async fn with_socket_timeout<T>(
+    timeout: Option<Duration>,
+    io: impl std::future::Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => runtime::timeout(timeout, io).await?,
+        None => io.await,
+    }
+}
+
 bitflags! {
     /// Represents the bitwise flags for an OP_MSG as defined in the spec.
     pub(crate) struct MessageFlags: u32 {
@@ -339,6 +398,25 @@ pub(crate) enum MessageSection {
 }
 
 impl MessageSection {
+    fn from_document_sequence(sequence: DocumentSequence) -> Self {
+        let documents: Vec<Vec<u8>> = sequence
+            .documents
+            .into_iter()
+            .map(RawDocumentBuf::into_bytes)
+            .collect();
+        // The size includes the size field itself and the null terminator of the identifier.
+        let size = std::mem::size_of::<i32>()
+            + sequence.identifier.len()
+            + 1
+            + documents.iter().map(Vec::len).sum::<usize>();
+
+        MessageSection::Sequence {
+            size: size as i32,
+            identifier: sequence.identifier,
+            documents,
+        }
+    }
+
     /// Reads bytes from `reader` and deserializes them into a MessageSection.
     fn read<R: Read>(reader: &mut R) -> Result<Self> {
         let payload_type = reader.read_u8()?;
@@ -2,4 +2,7 @@ mod header;
 mod message;
 mod util;
 
-pub(crate) use self::{message::Message, util::next_request_id};
+pub(crate) use self::{
+    message::{Message, MessageFlags},
+    util::next_request_id,
+};
@@ -158,6 +158,7 @@ impl Handshaker {
             options.as_ref().and_then(|opts| opts.server_api.as_ref()),
             options.as_ref().and_then(|opts| opts.load_balanced.into()),
             None,
+            None,
         );
 
         if let Some(options) = options {
@@ -180,11 +181,7 @@ impl Handshaker {
                 }
             }
 
-            if let Some(cred) = options.credential {
-                cred.append_needed_mechanism_negotiation(&mut command.body);
-                command.target_db = cred.resolved_source().to_string();
-                credential = Some(cred);
-            }
+            credential = options.credential;
 
             if options.load_balanced {
                 command.body.insert("loadBalanced", true);
@@ -219,10 +216,28 @@ impl Handshaker {
         conn: &mut Connection,
         topology: Option<&Topology>,
         handler: &Option<Arc<dyn SdamEventHandler>>,
+    ) -> Result<HandshakeResult> {
+        self.handshake_with_credential(conn, topology, handler, self.credential.as_ref())
+            .await
+    }
+
+    /// Handshakes a connection that will be authenticated with `credential` rather than the
+    /// credential the handshaker was created with.
+    pub(crate) async fn handshake_with_credential(
+        &self,
+        conn: &mut Connection,
+        topology: Option<&Topology>,
+        handler: &Option<Arc<dyn SdamEventHandler>>,
+        credential: Option<&Credential>,
     ) -> Result<HandshakeResult> {
         let mut command = self.command.clone();
 
-        let client_first = set_speculative_auth_info(&mut command.body, self.credential.as_ref())?;
+        if let Some(credential) = credential {
+            credential.append_needed_mechanism_negotiation(&mut command.body);
+            command.target_db = credential.resolved_source().to_string();
+        }
+
+        let client_first = set_speculative_auth_info(&mut command.body, credential)?;
 
         let mut hello_reply = run_hello(conn, command, topology, handler).await?;
 
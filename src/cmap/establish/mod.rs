@@ -2,6 +2,10 @@ pub(super) mod handshake;
 #[cfg(test)]
 mod test;
 
+use std::sync::Arc;
+
+use derivative::Derivative;
+
 use self::handshake::Handshaker;
 use super::{
     conn::{ConnectionGeneration, PendingConnection},
@@ -10,7 +14,10 @@ use super::{
     PoolGeneration,
 };
 use crate::{
-    client::{auth::Credential, options::ServerApi},
+    client::{
+        auth::{self, Credential, CredentialProvider},
+        options::{DnsResolver, ResolverConfig, ServerApi},
+    },
     error::{Error as MongoError, ErrorKind},
     runtime::HttpClient,
     sdam::HandshakePhase,
@@ -18,13 +25,19 @@ use crate::{
 
 /// Contains the logic to establish a connection, including handshaking, authenticating, and
 /// potentially more.
-#[derive(Clone, Debug)]
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
 pub(super) struct ConnectionEstablisher {
     /// Contains the logic for handshaking a connection.
     handshaker: Handshaker,
     http_client: HttpClient,
     credential: Option<Credential>,
+    #[derivative(Debug = "ignore")]
+    credential_provider: Option<Arc<dyn CredentialProvider>>,
     server_api: Option<ServerApi>,
+    #[derivative(Debug = "ignore")]
+    dns_resolver: Option<Arc<dyn DnsResolver>>,
+    resolver_config: Option<ResolverConfig>,
 }
 
 impl ConnectionEstablisher {
@@ -36,7 +49,10 @@ impl ConnectionEstablisher {
             handshaker,
             http_client,
             credential: options.and_then(|options| options.credential.clone()),
+            credential_provider: options.and_then(|options| options.credential_provider.clone()),
             server_api: options.and_then(|options| options.server_api.clone()),
+            dns_resolver: options.and_then(|options| options.dns_resolver.clone()),
+            resolver_config: options.and_then(|options| options.resolver_config.clone()),
         }
     }
 
@@ -46,13 +62,26 @@ impl ConnectionEstablisher {
         pending_connection: PendingConnection,
     ) -> std::result::Result<Connection, EstablishError> {
         let pool_gen = pending_connection.generation.clone();
+
+        // A credential from the provider is obtained before connecting, since failing to obtain
+        // one says nothing about the state of the server.
+        let provided_credential = match self.credential_provider {
+            Some(ref provider) => Some(
+                auth::provide_credential(provider.as_ref())
+                    .await
+                    .map_err(EstablishError::before_connect)?,
+            ),
+            None => None,
+        };
+        let credential = provided_credential.as_ref().or(self.credential.as_ref());
+
         let mut connection = Connection::connect(pending_connection)
             .await
             .map_err(|e| EstablishError::pre_hello(e, pool_gen.clone()))?;
 
         let handshake = self
             .handshaker
-            .handshake(&mut connection, None, &None)
+            .handshake_with_credential(&mut connection, None, &None, credential)
             .await
             .map_err(|e| EstablishError::pre_hello(e, pool_gen.clone()))?;
         let service_id = handshake.hello_reply.command_response.service_id;
@@ -78,13 +107,15 @@ impl ConnectionEstablisher {
             }
         }
 
-        if let Some(ref credential) = self.credential {
+        if let Some(credential) = credential {
             credential
                 .authenticate_stream(
                     &mut connection,
                     &self.http_client,
                     self.server_api.as_ref(),
                     handshake.first_round,
+                    self.resolver_config.as_ref(),
+                    self.dns_resolver.as_deref(),
                 )
                 .await
                 .map_err(|e| EstablishError::post_hello(e, connection.generation.clone()))?
@@ -97,20 +128,29 @@ impl ConnectionEstablisher {
 #[derive(Debug, Clone)]
 pub(crate) struct EstablishError {
     pub(crate) cause: MongoError,
-    pub(crate) handshake_phase: HandshakePhase,
+
+    /// The phase of the handshake in which the error occurred, or `None` if it occurred before
+    /// connecting to the server.
+    pub(crate) handshake_phase: Option<HandshakePhase>,
 }
 
 impl EstablishError {
+    fn before_connect(cause: MongoError) -> Self {
+        Self {
+            cause,
+            handshake_phase: None,
+        }
+    }
     fn pre_hello(cause: MongoError, generation: PoolGeneration) -> Self {
         Self {
             cause,
-            handshake_phase: HandshakePhase::PreHello { generation },
+            handshake_phase: Some(HandshakePhase::PreHello { generation }),
         }
     }
     fn post_hello(cause: MongoError, generation: ConnectionGeneration) -> Self {
         Self {
             cause,
-            handshake_phase: HandshakePhase::PostHello { generation },
+            handshake_phase: Some(HandshakePhase::PostHello { generation }),
         }
     }
 }
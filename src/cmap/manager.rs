@@ -51,6 +51,20 @@ impl PoolManager {
         }
     }
 
+    /// Close the pool, waiting until its available connections have been closed.
+    pub(super) async fn close(&self) {
+        let (message, acknowledgment_receiver) = AcknowledgedMessage::package(());
+        if self
+            .sender
+            .send(PoolManagementRequest::Close {
+                completion_handler: message,
+            })
+            .is_ok()
+        {
+            acknowledgment_receiver.wait_for_acknowledgment().await;
+        }
+    }
+
     /// Check in the given connection to the pool.
     /// This returns an error containing the connection if the pool has been dropped already.
     pub(crate) fn check_in(&self, connection: Connection) -> std::result::Result<(), Connection> {
@@ -112,6 +126,11 @@ pub(super) enum PoolManagementRequest {
         _completion_handler: AcknowledgedMessage<()>,
     },
 
+    /// Close the pool, stopping the worker.
+    Close {
+        completion_handler: AcknowledgedMessage<()>,
+    },
+
     /// Check in the given connection.
     CheckIn(Box<Connection>),
 
@@ -17,7 +17,14 @@ use tokio::sync::oneshot;
 
 pub use self::conn::ConnectionInfo;
 pub(crate) use self::{
-    conn::{Command, Connection, RawCommand, RawCommandResponse, StreamDescription},
+    conn::{
+        Command,
+        Connection,
+        DocumentSequence,
+        RawCommand,
+        RawCommandResponse,
+        StreamDescription,
+    },
     establish::handshake::Handshaker,
     status::PoolGenerationSubscriber,
     worker::PoolGeneration,
@@ -25,7 +32,7 @@ pub(crate) use self::{
 use self::{connection_requester::ConnectionRequestResult, options::ConnectionPoolOptions};
 use crate::{
     bson::oid::ObjectId,
-    error::{Error, Result},
+    error::{Error, ErrorKind, Result},
     event::cmap::{
         CmapEventHandler,
         ConnectionCheckoutFailedEvent,
@@ -133,12 +140,17 @@ impl ConnectionPool {
 
         let response = self.connection_requester.request().await;
 
+        let mut failure_reason = ConnectionCheckoutFailedReason::ConnectionError;
         let conn = match response {
             ConnectionRequestResult::Pooled(c) => Ok(*c),
             ConnectionRequestResult::Establishing(task) => task.await,
             ConnectionRequestResult::PoolCleared(e) => {
                 Err(Error::pool_cleared_error(&self.address, &e))
             }
+            ConnectionRequestResult::PoolClosed => {
+                failure_reason = ConnectionCheckoutFailedReason::PoolClosed;
+                Err(ErrorKind::Shutdown.into())
+            }
         };
 
         match conn {
@@ -151,7 +163,7 @@ impl ConnectionPool {
                 self.emit_event(|handler| {
                     handler.handle_connection_checkout_failed_event(ConnectionCheckoutFailedEvent {
                         address: self.address.clone(),
-                        reason: ConnectionCheckoutFailedReason::ConnectionError,
+                        reason: failure_reason,
                     })
                 });
             }
@@ -166,6 +178,12 @@ impl ConnectionPool {
         self.manager.clear(cause, service_id).await
     }
 
+    /// Close the pool, closing its available connections and rejecting any further check outs.
+    /// Connections that are currently checked out will be closed when they are checked back in.
+    pub(crate) async fn close(&self) {
+        self.manager.close().await;
+    }
+
     /// Mark the pool as "ready", allowing connections to be created and checked out.
     pub(crate) async fn mark_as_ready(&self) {
         self.manager.mark_as_ready().await;
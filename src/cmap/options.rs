@@ -10,10 +10,14 @@ use typed_builder::TypedBuilder;
 
 use crate::{
     bson_util,
-    client::{auth::Credential, options::ServerApi},
+    client::{
+        auth::{Credential, CredentialProvider},
+        options::ServerApi,
+    },
     compression::Compressor,
     event::cmap::{CmapEventHandler, ConnectionPoolOptions as EventOptions},
-    options::{ClientOptions, DriverInfo, ServerAddress, TlsOptions},
+    options::{ClientOptions, DnsResolver, DriverInfo, ResolverConfig, ServerAddress, TlsOptions},
+    runtime::Socks5Proxy,
 };
 
 /// Contains the options for creating a connection pool.
@@ -34,6 +38,11 @@ pub(crate) struct ConnectionPoolOptions {
     #[serde(skip)]
     pub(crate) credential: Option<Credential>,
 
+    /// The provider to obtain the credential from for each new connection, if any.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[serde(skip)]
+    pub(crate) credential_provider: Option<Arc<dyn CredentialProvider>>,
+
     /// Extra information to append to the driver version in the metadata of the handshake with the
     /// server. This should be used by libraries wrapping the driver, e.g. ODMs.
     #[serde(skip)]
@@ -87,6 +96,13 @@ pub(crate) struct ConnectionPoolOptions {
     /// The default value is to have no declared API version
     pub(crate) server_api: Option<ServerApi>,
 
+    /// The amount of time to wait for a single message to be written to or read from each
+    /// connection in this pool.
+    ///
+    /// The default is that reads and writes will not time out.
+    #[serde(skip)]
+    pub(crate) socket_timeout: Option<Duration>,
+
     /// The options specifying how a TLS connection should be configured. If `tls_options` is
     /// `None`, then TLS will not be used for the connections.
     ///
@@ -96,6 +112,22 @@ pub(crate) struct ConnectionPoolOptions {
 
     /// Whether or not the client is connecting to a MongoDB cluster through a load balancer.
     pub(crate) load_balanced: Option<bool>,
+
+    /// The custom resolver used to look up the IP addresses of the server, if any.
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    #[serde(skip)]
+    pub(crate) dns_resolver: Option<Arc<dyn DnsResolver>>,
+
+    /// The configuration of the trust-dns resolver used for lookups that are not done by
+    /// `dns_resolver`, if any.
+    #[derivative(Debug = "ignore")]
+    #[serde(skip)]
+    pub(crate) resolver_config: Option<ResolverConfig>,
+
+    /// The SOCKS5 proxy that connections should be tunneled through, if any.
+    #[derivative(Debug = "ignore")]
+    #[serde(skip)]
+    pub(crate) proxy: Option<Socks5Proxy>,
 }
 
 impl ConnectionPoolOptions {
@@ -108,8 +140,10 @@ impl ConnectionPoolOptions {
             min_pool_size: options.min_pool_size,
             max_pool_size: options.max_pool_size,
             server_api: options.server_api.clone(),
+            socket_timeout: options.socket_timeout,
             tls_options: options.tls_options(),
             credential: options.credential.clone(),
+            credential_provider: options.credential_provider.clone(),
             cmap_event_handler: options.cmap_event_handler.clone(),
             compressors: options.compressors.clone(),
             #[cfg(test)]
@@ -117,6 +151,9 @@ impl ConnectionPoolOptions {
             #[cfg(test)]
             ready: None,
             load_balanced: options.load_balanced,
+            dns_resolver: options.dns_resolver.clone(),
+            resolver_config: options.resolver_config.clone(),
+            proxy: options.socks5_proxy(),
         }
     }
 
@@ -160,8 +197,16 @@ impl<'de> Deserialize<'de> for BackgroundThreadInterval {
 pub(crate) struct ConnectionOptions {
     pub(crate) connect_timeout: Option<Duration>,
 
+    pub(crate) socket_timeout: Option<Duration>,
+
     pub(crate) tls_options: Option<TlsOptions>,
 
+    #[derivative(Debug = "ignore")]
+    pub(crate) dns_resolver: Option<Arc<dyn DnsResolver>>,
+
+    #[derivative(Debug = "ignore")]
+    pub(crate) proxy: Option<Socks5Proxy>,
+
     #[derivative(Debug = "ignore")]
     pub(crate) event_handler: Option<Arc<dyn CmapEventHandler>>,
 }
@@ -170,13 +215,17 @@ impl From<ConnectionPoolOptions> for ConnectionOptions {
     fn from(pool_options: ConnectionPoolOptions) -> Self {
         Self {
             connect_timeout: pool_options.connect_timeout,
+            socket_timeout: pool_options.socket_timeout,
             tls_options: pool_options.tls_options,
+            dns_resolver: pool_options.dns_resolver,
+            proxy: pool_options.proxy,
             event_handler: pool_options.cmap_event_handler,
         }
     }
 }
 
-#[derive(Clone, Debug, TypedBuilder)]
+#[derive(Clone, Derivative, TypedBuilder)]
+#[derivative(Debug)]
 #[builder(field_defaults(setter(into)))]
 pub(crate) struct StreamOptions {
     pub(crate) address: ServerAddress,
@@ -186,4 +235,12 @@ pub(crate) struct StreamOptions {
 
     #[builder(default)]
     pub(crate) tls_options: Option<TlsOptions>,
+
+    #[builder(default)]
+    #[derivative(Debug = "ignore")]
+    pub(crate) dns_resolver: Option<Arc<dyn DnsResolver>>,
+
+    #[builder(default)]
+    #[derivative(Debug = "ignore")]
+    pub(crate) proxy: Option<Socks5Proxy>,
 }
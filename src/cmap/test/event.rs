@@ -240,15 +240,11 @@ where
 {
     let helper = ConnectionCheckoutFailedHelper::deserialize(deserializer)?;
 
-    // The driver doesn't have a concept of a "closed pool", instead having the pool closed when the
-    // pool is dropped. Because of this, the driver doesn't implement the "poolClosed" reason for a
-    // connection checkout failure. While we skip over the corresponding tests in our spec test
-    // runner, we still need to be able to deserialize the "poolClosed" reason to avoid the test
-    // harness panicking, so we arbitrarily map the "poolClosed" to "connectionError".
     let reason = match helper.reason {
-        CheckoutFailedReasonHelper::PoolClosed | CheckoutFailedReasonHelper::ConnectionError => {
+        CheckoutFailedReasonHelper::ConnectionError => {
             ConnectionCheckoutFailedReason::ConnectionError
         }
+        CheckoutFailedReasonHelper::PoolClosed => ConnectionCheckoutFailedReason::PoolClosed,
         CheckoutFailedReasonHelper::Timeout => ConnectionCheckoutFailedReason::Timeout,
     };
 
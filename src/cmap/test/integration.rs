@@ -7,10 +7,16 @@ use super::{
 };
 use crate::{
     bson::{doc, Document},
-    cmap::{options::ConnectionPoolOptions, Command, ConnectionPool},
+    cmap::{
+        options::{ConnectionOptions, ConnectionPoolOptions},
+        Command,
+        Connection,
+        ConnectionPool,
+    },
     event::cmap::{CmapEventHandler, ConnectionClosedReason},
     hello::LEGACY_HELLO_COMMAND_NAME,
     operation::CommandResponse,
+    options::ServerAddress,
     runtime,
     sdam::TopologyUpdater,
     selection_criteria::ReadPreference,
@@ -19,6 +25,7 @@ use crate::{
         FailCommandOptions,
         FailPoint,
         FailPointMode,
+        StaticDnsResolver,
         TestClient,
         CLIENT_OPTIONS,
         LOCK,
@@ -272,3 +279,126 @@ async fn connection_error_during_operation() {
         .await
         .expect("closed event with error reason should have been seen");
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn socket_timeout_against_unresponsive_server() {
+    // Accept connections but never reply to anything sent on them.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let _ = receiver.recv();
+        drop(stream);
+    });
+
+    let mut connection = Connection::new_testing(
+        1,
+        ServerAddress::Tcp {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+        },
+        0,
+        Some(ConnectionOptions {
+            connect_timeout: None,
+            socket_timeout: Some(Duration::from_millis(100)),
+            tls_options: None,
+            dns_resolver: None,
+            proxy: None,
+            event_handler: None,
+        }),
+    )
+    .await
+    .unwrap();
+
+    let command = Command::new("ping".to_string(), "admin".to_string(), doc! { "ping": 1 });
+    let error = runtime::timeout(
+        Duration::from_secs(5),
+        connection.send_command(command, None),
+    )
+    .await
+    .expect("send_command should be bounded by the socket timeout")
+    .expect_err("send_command should time out");
+    assert!(error.is_network_timeout(), "{:?}", error);
+    assert!(connection.has_errored());
+
+    let _ = sender.send(());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn socket_timeout_during_operation() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let mut options = CLIENT_OPTIONS.clone();
+    let handler = Arc::new(EventHandler::new());
+    options.cmap_event_handler = Some(handler.clone() as Arc<dyn CmapEventHandler>);
+    options.hosts.drain(1..);
+    options.retry_reads = Some(false);
+    options.socket_timeout = Some(Duration::from_millis(200));
+
+    let client = TestClient::with_options(options.into()).await;
+    if !client.supports_block_connection() {
+        log_uncaptured(format!(
+            "skipping {} due to blockConnection not being supported",
+            function_name!()
+        ));
+        return;
+    }
+
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(1000))
+        .build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut subscriber = handler.subscribe();
+
+    let error = client
+        .database(function_name!())
+        .collection::<Document>(function_name!())
+        .find_one(None, None)
+        .await
+        .expect_err("find should exceed the socket timeout");
+    assert!(error.is_network_timeout(), "{:?}", error);
+
+    subscriber
+        .wait_for_event(EVENT_TIMEOUT, |e| match e {
+            Event::ConnectionClosed(event) => event.reason == ConnectionClosedReason::Error,
+            _ => false,
+        })
+        .await
+        .expect("closed event with error reason should have been seen");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn connect_with_custom_dns_resolver() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        let _ = listener.accept();
+    });
+
+    let resolver = StaticDnsResolver::new().ip("mongodb.internal", [127, 0, 0, 1].into());
+    Connection::new_testing(
+        1,
+        ServerAddress::Tcp {
+            host: "mongodb.internal".to_string(),
+            port: Some(port),
+        },
+        0,
+        Some(ConnectionOptions {
+            connect_timeout: None,
+            socket_timeout: None,
+            tls_options: None,
+            dns_resolver: Some(Arc::new(resolver)),
+            proxy: None,
+            event_handler: None,
+        }),
+    )
+    .await
+    .expect("mongodb.internal should resolve to the local listener");
+}
@@ -35,8 +35,6 @@ use crate::{
 use bson::doc;
 
 const TEST_DESCRIPTIONS_TO_SKIP: &[&str] = &[
-    "must destroy checked in connection if pool has been closed",
-    "must throw error if checkOut is called on a closed pool",
     // WaitQueueTimeoutMS is not supported
     "must aggressively timeout threads enqueued longer than waitQueueTimeoutMS",
     "waiting on maxConnecting is limited by WaitQueueTimeoutMS",
@@ -72,10 +70,9 @@ struct State {
     unlabeled_connections: Mutex<Vec<Connection>>,
     threads: RwLock<HashMap<String, CmapThread>>,
 
-    // In order to drop the pool when performing a `close` operation, we use an `Option` so that we
-    // can replace it with `None`. Since none of the tests should use the pool after its closed
-    // (besides the ones we manually skip over), it's fine for us to `unwrap` the pool during these
-    // tests, as panicking is sufficient to exit any aberrant test with a failure.
+    // The pool is only created once the executor starts running the test, so it's stored in an
+    // `Option`. A `close` operation closes the pool explicitly rather than dropping it, so that
+    // tests can check the behavior of a closed pool.
     pool: RwLock<Option<ConnectionPool>>,
 }
 
@@ -303,8 +300,9 @@ impl Operation {
             Operation::Close => {
                 let mut subscriber = state.handler.subscribe();
 
-                // pools are closed via their drop implementation
-                state.pool.write().await.take();
+                if let Some(pool) = state.pool.read().await.deref() {
+                    pool.close().await;
+                }
 
                 // wait for event to be emitted to ensure drop has completed.
                 subscriber
@@ -247,10 +247,11 @@ impl ConnectionPoolWorker {
     }
 
     /// Run the worker thread, listening on the various receivers until all handles have been
-    /// dropped. Once all handles are dropped, the pool will close any available connections and
-    /// emit a pool closed event.
+    /// dropped or the pool is explicitly closed. Once that happens, the pool will close any
+    /// available connections and emit a pool closed event.
     async fn execute(mut self) {
         let mut maintenance_interval = runtime::interval(self.maintenance_frequency);
+        let mut close_completion_handler = None;
 
         loop {
             let task = tokio::select! {
@@ -290,6 +291,10 @@ impl ConnectionPoolWorker {
                     }
                 },
                 PoolTask::HandleManagementRequest(request) => match *request {
+                    PoolManagementRequest::Close { completion_handler } => {
+                        close_completion_handler = Some(completion_handler);
+                        break;
+                    }
                     PoolManagementRequest::CheckIn(connection) => {
                         self.check_in(*connection);
                     }
@@ -336,6 +341,22 @@ impl ConnectionPoolWorker {
                 address: self.address.clone(),
             });
         });
+
+        // Reject any further requests, and close the connections that were checked in after the
+        // pool was closed.
+        self.management_receiver.receiver.close();
+        while let Ok(request) = self.management_receiver.receiver.try_recv() {
+            if let PoolManagementRequest::CheckIn(connection) = request {
+                self.emit_event(|handler| {
+                    handler.handle_connection_checked_in_event(connection.checked_in_event());
+                });
+                connection.close_and_drop(ConnectionClosedReason::PoolClosed);
+            }
+        }
+
+        if let Some(completion_handler) = close_completion_handler {
+            completion_handler.acknowledge(());
+        }
     }
 
     fn below_max_connections(&self) -> bool {
@@ -639,13 +660,15 @@ async fn establish_connection(
 
     match establish_result {
         Err(ref e) => {
-            server_updater
-                .handle_application_error(
-                    address.clone(),
-                    e.cause.clone(),
-                    e.handshake_phase.clone(),
-                )
-                .await;
+            if let Some(ref handshake_phase) = e.handshake_phase {
+                server_updater
+                    .handle_application_error(
+                        address.clone(),
+                        e.cause.clone(),
+                        handshake_phase.clone(),
+                    )
+                    .await;
+            }
             if let Some(handler) = event_handler {
                 let event = ConnectionClosedEvent {
                     address,
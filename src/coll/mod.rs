@@ -1,6 +1,6 @@
 pub mod options;
 
-use std::{borrow::Borrow, collections::HashSet, fmt, fmt::Debug, sync::Arc};
+use std::{borrow::Borrow, collections::HashSet, fmt, fmt::Debug, sync::Arc, time::Duration};
 
 use futures_util::{
     future,
@@ -23,13 +23,15 @@ use crate::{
         session::SessionChangeStream,
         ChangeStream,
     },
-    client::options::ServerAddress,
+    client::{options::ServerAddress, remaining_time},
     cmap::conn::PinnedConnectionHandle,
     concern::{ReadConcern, WriteConcern},
+    db::options::ModifyCollectionOptions,
     error::{convert_bulk_errors, BulkWriteError, BulkWriteFailure, Error, ErrorKind, Result},
     index::IndexModel,
     operation::{
         Aggregate,
+        BulkWrite,
         Count,
         CountDocuments,
         CreateIndexes,
@@ -37,18 +39,23 @@ use crate::{
         Distinct,
         DropCollection,
         DropIndexes,
+        Explain,
         Find,
         FindAndModify,
         Insert,
         ListIndexes,
+        ModifyCollection,
+        RenameCollection,
         Update,
     },
     results::{
+        BulkWriteResult,
         CreateIndexResult,
         CreateIndexesResult,
         DeleteResult,
         InsertManyResult,
         InsertOneResult,
+        ModifyCollectionResult,
         UpdateResult,
     },
     selection_criteria::SelectionCriteria,
@@ -143,6 +150,7 @@ struct CollectionInner {
     selection_criteria: Option<SelectionCriteria>,
     read_concern: Option<ReadConcern>,
     write_concern: Option<WriteConcern>,
+    timeout: Option<Duration>,
 }
 
 impl<T> Collection<T> {
@@ -158,6 +166,8 @@ impl<T> Collection<T> {
             .write_concern
             .or_else(|| db.write_concern().cloned());
 
+        let timeout = options.timeout.or_else(|| db.timeout());
+
         Self {
             inner: Arc::new(CollectionInner {
                 client: db.client().clone(),
@@ -166,6 +176,7 @@ impl<T> Collection<T> {
                 selection_criteria,
                 read_concern,
                 write_concern,
+                timeout,
             }),
             _phantom: Default::default(),
         }
@@ -217,6 +228,11 @@ impl<T> Collection<T> {
         self.inner.write_concern.as_ref()
     }
 
+    /// Gets the default timeout of the `Collection`.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout
+    }
+
     async fn drop_common(
         &self,
         options: impl Into<Option<DropCollectionOptions>>,
@@ -225,7 +241,7 @@ impl<T> Collection<T> {
         let session = session.into();
 
         let mut options = options.into();
-        resolve_options!(self, options, [write_concern]);
+        resolve_options!(self, options, [write_concern, timeout]);
 
         let drop = DropCollection::new(self.namespace(), options);
         self.client().execute_operation(drop, session).await
@@ -246,6 +262,78 @@ impl<T> Collection<T> {
         self.drop_common(options, session).await
     }
 
+    async fn modify_common(
+        &self,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<ModifyCollectionResult> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern, timeout]);
+
+        let modify = ModifyCollection::new(self.namespace(), options);
+        self.client().execute_operation(modify, session).await
+    }
+
+    /// Modifies the collection using the `collMod` command, e.g. to change its validation rules or
+    /// the TTL of one of its indexes. If an index was modified, the returned result contains its
+    /// previous and new settings.
+    pub async fn modify(
+        &self,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+    ) -> Result<ModifyCollectionResult> {
+        self.modify_common(options, None).await
+    }
+
+    /// Modifies the collection using the `collMod` command and the provided `ClientSession`. If an
+    /// index was modified, the returned result contains its previous and new settings.
+    pub async fn modify_with_session(
+        &self,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ModifyCollectionResult> {
+        self.modify_common(options, session).await
+    }
+
+    async fn rename_common(
+        &self,
+        to: Namespace,
+        options: impl Into<Option<RenameCollectionOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<()> {
+        let session = session.into();
+
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern, timeout]);
+
+        let rename = RenameCollection::new(self.namespace(), to, options);
+        self.client().execute_operation(rename, session).await
+    }
+
+    /// Renames the collection to the namespace `to`, which may be in a different database. This
+    /// `Collection` handle continues to refer to the original namespace.
+    ///
+    /// See the documentation [here](https://www.mongodb.com/docs/manual/reference/command/renameCollection/)
+    /// for more information on the restrictions on renaming collections.
+    pub async fn rename(
+        &self,
+        to: Namespace,
+        options: impl Into<Option<RenameCollectionOptions>>,
+    ) -> Result<()> {
+        self.rename_common(to, options, None).await
+    }
+
+    /// Renames the collection to the namespace `to`, which may be in a different database, using
+    /// the provided `ClientSession`. This `Collection` handle continues to refer to the original
+    /// namespace.
+    pub async fn rename_with_session(
+        &self,
+        to: Namespace,
+        options: impl Into<Option<RenameCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<()> {
+        self.rename_common(to, options, session).await
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -259,7 +347,7 @@ impl<T> Collection<T> {
         resolve_options!(
             self,
             options,
-            [read_concern, write_concern, selection_criteria]
+            [read_concern, write_concern, selection_criteria, timeout]
         );
 
         let aggregate = Aggregate::new(self.namespace(), pipeline, options);
@@ -280,6 +368,7 @@ impl<T> Collection<T> {
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, Some(&mut *session))?;
         resolve_write_concern_with_session!(self, options, Some(&mut *session))?;
+        resolve_options!(self, options, [timeout]);
         resolve_selection_criteria_with_session!(self, options, Some(&mut *session))?;
 
         let aggregate = Aggregate::new(self.namespace(), pipeline, options);
@@ -305,7 +394,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<EstimatedDocumentCountOptions>>,
     ) -> Result<u64> {
         let mut options = options.into();
-        resolve_options!(self, options, [read_concern, selection_criteria]);
+        resolve_options!(self, options, [read_concern, selection_criteria, timeout]);
 
         let op = Count::new(self.namespace(), options);
 
@@ -323,6 +412,7 @@ impl<T> Collection<T> {
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, session.as_ref())?;
         resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let op = CountDocuments::new(self.namespace(), filter.into(), options)?;
         self.client().execute_operation(op, session).await
@@ -363,6 +453,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let delete = Delete::new(self.namespace(), query, None, options);
         self.client().execute_operation(delete, session).await
@@ -378,6 +469,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let indexes: Vec<IndexModel> = indexes.into_iter().collect();
 
@@ -467,6 +559,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let delete = Delete::new(self.namespace(), query, Some(1), options);
         self.client().execute_operation(delete, session).await
@@ -513,6 +606,7 @@ impl<T> Collection<T> {
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, session.as_ref())?;
         resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let op = Distinct::new(
             self.namespace(),
@@ -547,6 +641,127 @@ impl<T> Collection<T> {
             .await
     }
 
+    async fn explain_common(
+        &self,
+        explainable: Explainable,
+        verbosity: ExplainVerbosity,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<Document> {
+        let session = session.into();
+        let multi = matches!(
+            explainable,
+            Explainable::UpdateMany { .. } | Explainable::DeleteMany { .. }
+        );
+
+        match explainable {
+            Explainable::Find {
+                filter,
+                mut options,
+            } => {
+                resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+                resolve_options!(self, options, [timeout]);
+
+                let find = Find::new(self.namespace(), filter, options);
+                let op = Explain::new(find, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+            Explainable::Aggregate {
+                pipeline,
+                mut options,
+            } => {
+                resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+                resolve_options!(self, options, [timeout]);
+
+                let aggregate = Aggregate::new(self.namespace(), pipeline, options);
+                let op = Explain::new(aggregate, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+            Explainable::EstimatedDocumentCount { mut options } => {
+                resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+                resolve_options!(self, options, [timeout]);
+
+                let count = Count::new(self.namespace(), options);
+                let op = Explain::new(count, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+            Explainable::Distinct {
+                field_name,
+                filter,
+                mut options,
+            } => {
+                resolve_selection_criteria_with_session!(self, options, session.as_ref())?;
+                resolve_options!(self, options, [timeout]);
+
+                let distinct = Distinct::new(self.namespace(), field_name, filter, options);
+                let op = Explain::new(distinct, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+            Explainable::UpdateOne {
+                filter,
+                update,
+                mut options,
+            }
+            | Explainable::UpdateMany {
+                filter,
+                update,
+                mut options,
+            } => {
+                if let UpdateModifications::Document(ref d) = update {
+                    bson_util::update_document_check(d)?;
+                }
+                resolve_options!(self, options, [timeout]);
+
+                let update = Update::new(self.namespace(), filter, update, multi, options);
+                let op = Explain::new(update, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+            Explainable::DeleteOne {
+                filter,
+                mut options,
+            }
+            | Explainable::DeleteMany {
+                filter,
+                mut options,
+            } => {
+                let limit = if multi { None } else { Some(1) };
+                resolve_options!(self, options, [timeout]);
+
+                let delete = Delete::new(self.namespace(), filter, limit, options);
+                let op = Explain::new(delete, verbosity);
+                self.client().execute_operation(op, session).await
+            }
+        }
+    }
+
+    /// Asks the server to describe how it would execute the given operation rather than executing
+    /// it, returning the raw response to the `explain` command.
+    ///
+    /// The format of the response varies between server versions and topologies. See the official
+    /// MongoDB [documentation](https://www.mongodb.com/docs/manual/reference/command/explain/) for
+    /// more information.
+    pub async fn explain(
+        &self,
+        explainable: Explainable,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        self.explain_common(explainable, verbosity, None).await
+    }
+
+    /// Asks the server to describe how it would execute the given operation rather than executing
+    /// it using the provided `ClientSession`, returning the raw response to the `explain` command.
+    ///
+    /// The format of the response varies between server versions and topologies. See the official
+    /// MongoDB [documentation](https://www.mongodb.com/docs/manual/reference/command/explain/) for
+    /// more information.
+    pub async fn explain_with_session(
+        &self,
+        explainable: Explainable,
+        verbosity: ExplainVerbosity,
+        session: &mut ClientSession,
+    ) -> Result<Document> {
+        self.explain_common(explainable, verbosity, session).await
+    }
+
     async fn drop_indexes_common(
         &self,
         name: impl Into<Option<&str>>,
@@ -557,6 +772,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         // If there is no provided name, that means we should drop all indexes.
         let index_name = name.into().unwrap_or("*").to_string();
@@ -621,7 +837,10 @@ impl<T> Collection<T> {
         &self,
         options: impl Into<Option<ListIndexesOptions>>,
     ) -> Result<Cursor<IndexModel>> {
-        let list_indexes = ListIndexes::new(self.namespace(), options.into());
+        let mut options = options.into();
+        resolve_options!(self, options, [timeout]);
+
+        let list_indexes = ListIndexes::new(self.namespace(), options);
         let client = self.client();
         client.execute_cursor_operation(list_indexes).await
     }
@@ -632,7 +851,10 @@ impl<T> Collection<T> {
         options: impl Into<Option<ListIndexesOptions>>,
         session: &mut ClientSession,
     ) -> Result<SessionCursor<IndexModel>> {
-        let list_indexes = ListIndexes::new(self.namespace(), options.into());
+        let mut options = options.into();
+        resolve_options!(self, options, [timeout]);
+
+        let list_indexes = ListIndexes::new(self.namespace(), options);
         let client = self.client();
         client
             .execute_session_cursor_operation(list_indexes, session)
@@ -681,6 +903,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let update = Update::new(self.namespace(), query, update, true, options);
         self.client().execute_operation(update, session).await
@@ -734,6 +957,7 @@ impl<T> Collection<T> {
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let update = Update::new(self.namespace(), query, update, false, options);
         self.client().execute_operation(update, session).await
@@ -867,7 +1091,7 @@ impl<T> Collection<T> {
         options: impl Into<Option<FindOptions>>,
     ) -> Result<Cursor<T>> {
         let mut options = options.into();
-        resolve_options!(self, options, [read_concern, selection_criteria]);
+        resolve_options!(self, options, [read_concern, selection_criteria, timeout]);
 
         let find = Find::new(self.namespace(), filter.into(), options);
         let client = self.client();
@@ -885,6 +1109,7 @@ impl<T> Collection<T> {
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, Some(&mut *session))?;
         resolve_selection_criteria_with_session!(self, options, Some(&mut *session))?;
+        resolve_options!(self, options, [timeout]);
 
         let find = Find::new(self.namespace(), filter.into(), options);
         let client = self.client();
@@ -904,7 +1129,7 @@ where
         options: impl Into<Option<FindOneOptions>>,
     ) -> Result<Option<T>> {
         let mut options = options.into();
-        resolve_options!(self, options, [read_concern, selection_criteria]);
+        resolve_options!(self, options, [read_concern, selection_criteria, timeout]);
 
         let options: FindOptions = options.map(Into::into).unwrap_or_else(Default::default);
         let mut cursor = self.find(filter, Some(options)).await?;
@@ -922,6 +1147,7 @@ where
         let mut options = options.into();
         resolve_read_concern_with_session!(self, options, Some(&mut *session))?;
         resolve_selection_criteria_with_session!(self, options, Some(&mut *session))?;
+        resolve_options!(self, options, [timeout]);
 
         let options: FindOptions = options.map(Into::into).unwrap_or_else(Default::default);
         let mut cursor = self
@@ -946,6 +1172,7 @@ where
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let op = FindAndModify::<T>::with_delete(self.namespace(), filter, options);
         self.client().execute_operation(op, session).await
@@ -995,6 +1222,7 @@ where
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let op = FindAndModify::<T>::with_update(self.namespace(), filter, update, options)?;
         self.client().execute_operation(op, session).await
@@ -1057,6 +1285,7 @@ where
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let op = FindAndModify::<T>::with_replace(self.namespace(), filter, replacement, options)?;
         self.client().execute_operation(op, session).await
@@ -1102,6 +1331,167 @@ impl<T> Collection<T>
 where
     T: Serialize,
 {
+    #[allow(clippy::needless_option_as_deref)]
+    async fn bulk_write_common(
+        &self,
+        models: impl IntoIterator<Item = impl Into<WriteModel<T>>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+        mut session: Option<&mut ClientSession>,
+    ) -> Result<BulkWriteResult> {
+        let models: Vec<WriteModel<T>> = models.into_iter().map(Into::into).collect();
+        let mut options = options.into();
+        resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
+
+        if models.is_empty() {
+            return Err(ErrorKind::InvalidArgument {
+                message: "No write models provided to bulk_write".to_string(),
+            }
+            .into());
+        }
+
+        let ordered = options.as_ref().and_then(|o| o.ordered).unwrap_or(true);
+
+        let mut cumulative_failure: Option<BulkWriteFailure> = None;
+        let mut error_labels: HashSet<String> = Default::default();
+        let mut cumulative_result = BulkWriteResult::default();
+
+        let deadline = self
+            .client()
+            .operation_deadline(options.as_ref().and_then(|o| o.timeout));
+        let mut n_attempted = 0;
+
+        while n_attempted < models.len() {
+            // each batch is only given the time remaining in the operation's timeout, and running
+            // out of time before a batch is reported in the same way as a failed batch.
+            let batch = match deadline.map(remaining_time).transpose() {
+                Ok(timeout) => {
+                    if timeout.is_some() {
+                        options.get_or_insert_with(Default::default).timeout = timeout;
+                    }
+                    let bulk_write =
+                        BulkWrite::new(self.namespace(), &models[n_attempted..], options.clone());
+                    self.client()
+                        .execute_operation(bulk_write, session.as_deref_mut())
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match batch {
+                Ok(batch) => {
+                    cumulative_result.merge(batch.result, n_attempted);
+                    n_attempted += batch.n_attempted;
+                }
+                Err(e) => {
+                    let labels = e.labels().clone();
+                    match *e.kind {
+                        ErrorKind::BulkWrite(bw) => {
+                            if let Some(partial_result) = bw.partial_result {
+                                cumulative_result.merge(partial_result, n_attempted);
+                            }
+
+                            let failure_ref =
+                                cumulative_failure.get_or_insert_with(BulkWriteFailure::new);
+                            let has_write_errors = bw.write_errors.is_some();
+                            if let Some(write_errors) = bw.write_errors {
+                                for err in write_errors {
+                                    let index = n_attempted + err.index;
+
+                                    failure_ref
+                                        .write_errors
+                                        .get_or_insert_with(Default::default)
+                                        .push(BulkWriteError { index, ..err });
+                                }
+                            }
+
+                            if let Some(wc_error) = bw.write_concern_error {
+                                failure_ref.write_concern_error = Some(wc_error);
+                            }
+
+                            error_labels.extend(labels);
+
+                            // write concern errors don't halt ordered bulk writes, so only stop
+                            // early if a write itself failed.
+                            if ordered && has_write_errors {
+                                break;
+                            }
+                            n_attempted += bw.n_attempted;
+                        }
+                        // if nothing has been written yet, the error can be returned as-is.
+                        _ if n_attempted == 0 => return Err(e),
+                        _ => {
+                            error_labels.extend(labels);
+                            cumulative_failure
+                                .get_or_insert_with(BulkWriteFailure::new)
+                                .error = Some(e);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        match cumulative_failure {
+            Some(mut failure) => {
+                failure.partial_result = Some(cumulative_result);
+                Err(Error::new(
+                    ErrorKind::BulkWrite(failure),
+                    Some(error_labels),
+                ))
+            }
+            None => Ok(cumulative_result),
+        }
+    }
+
+    /// Executes the writes described by `models` against the collection, splitting them into as
+    /// few `insert`, `update` and `delete` commands as the server's batch limits allow.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any write errors are reported in a
+    /// [`BulkWriteFailure`](error/struct.BulkWriteFailure.html) with indexes corresponding to
+    /// positions in `models`, along with the results of the writes that succeeded.
+    /// An error that stops the operation after some writes were performed, such as the timeout
+    /// expiring, is reported in the same way.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub async fn bulk_write(
+        &self,
+        models: impl IntoIterator<Item = impl Into<WriteModel<T>>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+    ) -> Result<BulkWriteResult> {
+        self.bulk_write_common(models, options, None).await
+    }
+
+    /// Executes the writes described by `models` against the collection using the provided
+    /// `ClientSession`, splitting them into as few `insert`, `update` and `delete` commands as the
+    /// server's batch limits allow.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any write errors are reported in a
+    /// [`BulkWriteFailure`](error/struct.BulkWriteFailure.html) with indexes corresponding to
+    /// positions in `models`, along with the results of the writes that succeeded.
+    /// An error that stops the operation after some writes were performed, such as the timeout
+    /// expiring, is reported in the same way.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub async fn bulk_write_with_session(
+        &self,
+        models: impl IntoIterator<Item = impl Into<WriteModel<T>>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<BulkWriteResult> {
+        self.bulk_write_common(models, options, Some(session)).await
+    }
+
     #[allow(clippy::needless_option_as_deref)]
     async fn insert_many_common(
         &self,
@@ -1112,6 +1502,7 @@ where
         let ds: Vec<_> = docs.into_iter().collect();
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         if ds.is_empty() {
             return Err(ErrorKind::InvalidArgument {
@@ -1126,17 +1517,29 @@ where
         let mut error_labels: HashSet<String> = Default::default();
         let mut cumulative_result: Option<InsertManyResult> = None;
 
+        let deadline = self
+            .client()
+            .operation_deadline(options.as_ref().and_then(|o| o.timeout));
         let mut n_attempted = 0;
 
         while n_attempted < ds.len() {
-            let docs: Vec<&T> = ds.iter().skip(n_attempted).map(Borrow::borrow).collect();
-            let insert = Insert::new(self.namespace(), docs, options.clone());
-
-            match self
-                .client()
-                .execute_operation(insert, session.as_deref_mut())
-                .await
-            {
+            // each batch is only given the time remaining in the operation's timeout, and running
+            // out of time before a batch is reported in the same way as a failed batch.
+            let batch = match deadline.map(remaining_time).transpose() {
+                Ok(timeout) => {
+                    if timeout.is_some() {
+                        options.get_or_insert_with(Default::default).timeout = timeout;
+                    }
+                    let docs: Vec<&T> = ds.iter().skip(n_attempted).map(Borrow::borrow).collect();
+                    let insert = Insert::new(self.namespace(), docs, options.clone());
+                    self.client()
+                        .execute_operation(insert, session.as_deref_mut())
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+
+            match batch {
                 Ok(result) => {
                     let current_batch_size = result.inserted_ids.len();
 
@@ -1161,6 +1564,14 @@ where
                             let current_batch_size = bw.inserted_ids.len()
                                 + bw.write_errors.as_ref().map(|we| we.len()).unwrap_or(0);
 
+                            let cumulative_result =
+                                cumulative_result.get_or_insert_with(InsertManyResult::new);
+                            for (index, id) in bw.inserted_ids {
+                                cumulative_result
+                                    .inserted_ids
+                                    .insert(index + n_attempted, id);
+                            }
+
                             let failure_ref =
                                 cumulative_failure.get_or_insert_with(BulkWriteFailure::new);
                             if let Some(write_errors) = bw.write_errors {
@@ -1181,29 +1592,38 @@ where
                             error_labels.extend(labels);
 
                             if ordered {
-                                // this will always be true since we invoked get_or_insert_with
-                                // above.
-                                if let Some(failure) = cumulative_failure {
-                                    return Err(Error::new(
-                                        ErrorKind::BulkWrite(failure),
-                                        Some(error_labels),
-                                    ));
-                                }
+                                break;
                             }
                             n_attempted += current_batch_size;
                         }
-                        _ => return Err(e),
+                        // if nothing has been written yet, the error can be returned as-is.
+                        _ if n_attempted == 0 => return Err(e),
+                        _ => {
+                            error_labels.extend(labels);
+                            cumulative_failure
+                                .get_or_insert_with(BulkWriteFailure::new)
+                                .error = Some(e);
+                            break;
+                        }
                     }
                 }
             }
         }
 
+        let cumulative_result = cumulative_result.unwrap_or_else(InsertManyResult::new);
         match cumulative_failure {
-            Some(failure) => Err(Error::new(
-                ErrorKind::BulkWrite(failure),
-                Some(error_labels),
-            )),
-            None => Ok(cumulative_result.unwrap_or_else(InsertManyResult::new)),
+            Some(mut failure) => {
+                failure.partial_result = Some(BulkWriteResult {
+                    inserted_count: cumulative_result.inserted_ids.len() as u64,
+                    inserted_ids: cumulative_result.inserted_ids,
+                    ..Default::default()
+                });
+                Err(Error::new(
+                    ErrorKind::BulkWrite(failure),
+                    Some(error_labels),
+                ))
+            }
+            None => Ok(cumulative_result),
         }
     }
 
@@ -1252,6 +1672,7 @@ where
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let insert = Insert::new(
             self.namespace(),
@@ -1315,6 +1736,7 @@ where
 
         let mut options = options.into();
         resolve_write_concern_with_session!(self, options, session.as_ref())?;
+        resolve_options!(self, options, [timeout]);
 
         let update = Update::new(
             self.namespace(),
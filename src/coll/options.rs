@@ -28,6 +28,15 @@ pub struct CollectionOptions {
 
     /// The default write concern for operations.
     pub write_concern: Option<WriteConcern>,
+
+    /// The default timeout for operations. Defaults to the timeout of the database. See
+    /// [`ClientOptions::timeout`](crate::options::ClientOptions::timeout) for more details.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies whether a
@@ -105,6 +114,15 @@ pub struct InsertOneOptions {
 
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -127,6 +145,16 @@ pub struct InsertManyOptions {
     /// The write concern for the operation.
     #[serde(skip_deserializing)]
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 impl InsertManyOptions {
@@ -135,6 +163,7 @@ impl InsertManyOptions {
             bypass_document_validation: options.bypass_document_validation,
             ordered: None,
             write_concern: options.write_concern,
+            timeout: options.timeout,
         }
     }
 }
@@ -211,6 +240,15 @@ pub struct UpdateOptions {
 
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 impl UpdateOptions {
@@ -221,6 +259,7 @@ impl UpdateOptions {
             hint: options.hint,
             write_concern: options.write_concern,
             collation: options.collation,
+            timeout: options.timeout,
             ..Default::default()
         }
     }
@@ -253,6 +292,15 @@ pub struct ReplaceOptions {
 
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -276,6 +324,227 @@ pub struct DeleteOptions {
     /// The index to use for the operation.
     /// Only available in MongoDB 4.4+.
     pub hint: Option<Hint>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+/// Specifies the options to a
+/// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write) operation.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, TypedBuilder, Serialize, Deserialize)]
+#[builder(field_defaults(default, setter(into)))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BulkWriteOptions {
+    /// Opt out of document-level validation.
+    pub bypass_document_validation: Option<bool>,
+
+    /// If true, when a write fails, return without performing the remaining writes. If false,
+    /// when a write fails, continue with the remaining writes, if any.
+    ///
+    /// Defaults to true.
+    pub ordered: Option<bool>,
+
+    /// The write concern for the operation.
+    #[serde(skip_deserializing)]
+    pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+/// A single write to perform as part of a
+/// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write) operation.
+///
+/// Each of the model types can be converted into a `WriteModel` via `Into`.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum WriteModel<T> {
+    /// Inserts a single document.
+    InsertOne(InsertOneModel<T>),
+
+    /// Updates up to one document matching a filter.
+    UpdateOne(UpdateOneModel),
+
+    /// Updates all documents matching a filter.
+    UpdateMany(UpdateManyModel),
+
+    /// Replaces up to one document matching a filter.
+    ReplaceOne(ReplaceOneModel<T>),
+
+    /// Deletes up to one document matching a filter.
+    DeleteOne(DeleteOneModel),
+
+    /// Deletes all documents matching a filter.
+    DeleteMany(DeleteManyModel),
+}
+
+/// Inserts a single document as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct InsertOneModel<T> {
+    /// The document to insert.
+    #[builder(!default)]
+    pub document: T,
+}
+
+/// Updates up to one document as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct UpdateOneModel {
+    /// The filter used to select the document to update.
+    #[builder(!default)]
+    pub filter: Document,
+
+    /// The modifications to apply. Note: pipeline updates are only supported in MongoDB 4.2+.
+    #[builder(!default)]
+    pub update: UpdateModifications,
+
+    /// A set of filters specifying to which array elements an update should apply.
+    pub array_filters: Option<Vec<Document>>,
+
+    /// The collation to use for the operation.
+    pub collation: Option<Collation>,
+
+    /// The index to use for the operation. Only available in MongoDB 4.2+.
+    pub hint: Option<Hint>,
+
+    /// If true, insert a document if no matching document is found.
+    pub upsert: Option<bool>,
+}
+
+/// Updates all matching documents as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct UpdateManyModel {
+    /// The filter used to select the documents to update.
+    #[builder(!default)]
+    pub filter: Document,
+
+    /// The modifications to apply. Note: pipeline updates are only supported in MongoDB 4.2+.
+    #[builder(!default)]
+    pub update: UpdateModifications,
+
+    /// A set of filters specifying to which array elements an update should apply.
+    pub array_filters: Option<Vec<Document>>,
+
+    /// The collation to use for the operation.
+    pub collation: Option<Collation>,
+
+    /// The index to use for the operation. Only available in MongoDB 4.2+.
+    pub hint: Option<Hint>,
+
+    /// If true, insert a document if no matching document is found.
+    pub upsert: Option<bool>,
+}
+
+/// Replaces up to one document as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct ReplaceOneModel<T> {
+    /// The filter used to select the document to replace.
+    #[builder(!default)]
+    pub filter: Document,
+
+    /// The replacement document.
+    #[builder(!default)]
+    pub replacement: T,
+
+    /// The collation to use for the operation.
+    pub collation: Option<Collation>,
+
+    /// The index to use for the operation. Only available in MongoDB 4.2+.
+    pub hint: Option<Hint>,
+
+    /// If true, insert the replacement if no matching document is found.
+    pub upsert: Option<bool>,
+}
+
+/// Deletes up to one document as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct DeleteOneModel {
+    /// The filter used to select the document to delete.
+    #[builder(!default)]
+    pub filter: Document,
+
+    /// The collation to use for the operation.
+    pub collation: Option<Collation>,
+
+    /// The index to use for the operation. Only available in MongoDB 4.4+.
+    pub hint: Option<Hint>,
+}
+
+/// Deletes all matching documents as part of a bulk write.
+#[derive(Clone, Debug, TypedBuilder)]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct DeleteManyModel {
+    /// The filter used to select the documents to delete.
+    #[builder(!default)]
+    pub filter: Document,
+
+    /// The collation to use for the operation.
+    pub collation: Option<Collation>,
+
+    /// The index to use for the operation. Only available in MongoDB 4.4+.
+    pub hint: Option<Hint>,
+}
+
+impl<T> From<InsertOneModel<T>> for WriteModel<T> {
+    fn from(model: InsertOneModel<T>) -> Self {
+        WriteModel::InsertOne(model)
+    }
+}
+
+impl<T> From<UpdateOneModel> for WriteModel<T> {
+    fn from(model: UpdateOneModel) -> Self {
+        WriteModel::UpdateOne(model)
+    }
+}
+
+impl<T> From<UpdateManyModel> for WriteModel<T> {
+    fn from(model: UpdateManyModel) -> Self {
+        WriteModel::UpdateMany(model)
+    }
+}
+
+impl<T> From<ReplaceOneModel<T>> for WriteModel<T> {
+    fn from(model: ReplaceOneModel<T>) -> Self {
+        WriteModel::ReplaceOne(model)
+    }
+}
+
+impl<T> From<DeleteOneModel> for WriteModel<T> {
+    fn from(model: DeleteOneModel) -> Self {
+        WriteModel::DeleteOne(model)
+    }
+}
+
+impl<T> From<DeleteManyModel> for WriteModel<T> {
+    fn from(model: DeleteManyModel) -> Self {
+        WriteModel::DeleteMany(model)
+    }
 }
 
 /// Specifies the options to a
@@ -310,6 +579,15 @@ pub struct FindOneAndDeleteOptions {
     /// The index to use for the operation.
     /// Only available in MongoDB 4.4+.
     pub hint: Option<Hint>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -353,6 +631,15 @@ pub struct FindOneAndReplaceOptions {
     /// The index to use for the operation.
     /// Only available in MongoDB 4.4+.
     pub hint: Option<Hint>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -402,6 +689,15 @@ pub struct FindOneAndUpdateOptions {
     /// The index to use for the operation.
     /// Only available in MongoDB 4.4+.
     pub hint: Option<Hint>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a [`Collection::aggregate`](../struct.Collection.html#method.aggregate)
@@ -496,6 +792,19 @@ pub struct AggregateOptions {
     /// This feature is only available on server versions 5.0 and above.
     #[serde(rename = "let")]
     pub let_vars: Option<Document>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection or database
+    /// the aggregation is run on.
+    ///
+    /// The returned cursor applies the same timeout to each `getMore` it issues.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -538,6 +847,15 @@ pub struct CountOptions {
     /// The level of the read concern.
     #[serde(skip_serializing)]
     pub read_concern: Option<ReadConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 // rustfmt tries to split the link up when it's all on one line, which breaks the link, so we wrap
@@ -574,6 +892,16 @@ pub struct EstimatedDocumentCountOptions {
     /// The level of the read concern.
     #[serde(skip_serializing)]
     pub read_concern: Option<ReadConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a [`Collection::distinct`](../struct.Collection.html#method.distinct)
@@ -611,6 +939,16 @@ pub struct DistinctOptions {
     /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
     /// information on how to use this option.
     pub collation: Option<Collation>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a [`Collection::find`](../struct.Collection.html#method.find)
@@ -720,6 +1058,18 @@ pub struct FindOptions {
     /// See the [documentation](https://docs.mongodb.com/manual/reference/collation/) for more
     /// information on how to use this option.
     pub collation: Option<Collation>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    ///
+    /// The returned cursor applies the same timeout to each `getMore` it issues.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 impl From<FindOneOptions> for FindOptions {
@@ -746,6 +1096,7 @@ impl From<FindOneOptions> for FindOptions {
             max_await_time: None,
             no_cursor_timeout: None,
             sort: options.sort,
+            timeout: options.timeout,
         }
     }
 }
@@ -834,6 +1185,15 @@ pub struct FindOneOptions {
 
     /// The order of the documents for the purposes of the operation.
     pub sort: Option<Document>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -864,6 +1224,11 @@ pub struct CreateIndexOptions {
 
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(skip_serializing)]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a [`Collection::drop`](../struct.Collection.html#method.drop)
@@ -876,6 +1241,42 @@ pub struct CreateIndexOptions {
 pub struct DropCollectionOptions {
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+/// Specifies the options to a
+/// [`Collection::rename`](../struct.Collection.html#method.rename) operation.
+#[serde_with::skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct RenameCollectionOptions {
+    /// Whether to drop an existing collection with the target name before renaming. If this is
+    /// not set and the target collection exists, the rename fails.
+    pub drop_target: Option<bool>,
+
+    /// The write concern for the operation.
+    pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -901,6 +1302,16 @@ pub struct DropIndexOptions {
 
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -926,6 +1337,18 @@ pub struct ListIndexesOptions {
     /// The number of indexes the server should return per cursor batch.
     #[serde(default, skip_serializing)]
     pub batch_size: Option<u32>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the collection.
+    ///
+    /// The returned cursor applies the same timeout to each `getMore` it issues.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// The minimum number of data-bearing voting replica set members (i.e. commit quorum), including
@@ -988,3 +1411,107 @@ impl<'de> Deserialize<'de> for CommitQuorum {
         }
     }
 }
+
+/// The level of detail the server should include when describing an operation in response to a
+/// [`Collection::explain`](../struct.Collection.html#method.explain) call.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum ExplainVerbosity {
+    /// Describe the winning plan chosen by the query optimizer without executing it.
+    QueryPlanner,
+
+    /// Execute the winning plan and include statistics describing its execution.
+    ExecutionStats,
+
+    /// Execute the winning plan and include statistics describing the execution of both it and
+    /// the rejected candidate plans.
+    AllPlansExecution,
+}
+
+/// An operation that can be described by the server via
+/// [`Collection::explain`](../struct.Collection.html#method.explain) instead of being executed.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Explainable {
+    /// A [`Collection::find`](../struct.Collection.html#method.find) operation.
+    Find {
+        /// The filter used to match documents.
+        filter: Option<Document>,
+
+        /// The options for the operation.
+        options: Option<FindOptions>,
+    },
+
+    /// A [`Collection::aggregate`](../struct.Collection.html#method.aggregate) operation.
+    Aggregate {
+        /// The stages of the pipeline.
+        pipeline: Vec<Document>,
+
+        /// The options for the operation.
+        options: Option<AggregateOptions>,
+    },
+
+    /// A
+    /// [`Collection::estimated_document_count`](../struct.Collection.html#method.
+    /// estimated_document_count) operation.
+    EstimatedDocumentCount {
+        /// The options for the operation.
+        options: Option<EstimatedDocumentCountOptions>,
+    },
+
+    /// A [`Collection::distinct`](../struct.Collection.html#method.distinct) operation.
+    Distinct {
+        /// The field whose distinct values are found.
+        field_name: String,
+
+        /// The filter used to match documents.
+        filter: Option<Document>,
+
+        /// The options for the operation.
+        options: Option<DistinctOptions>,
+    },
+
+    /// A [`Collection::update_one`](../struct.Collection.html#method.update_one) operation.
+    UpdateOne {
+        /// The filter used to match documents.
+        filter: Document,
+
+        /// The modifications to apply to the matched document.
+        update: UpdateModifications,
+
+        /// The options for the operation.
+        options: Option<UpdateOptions>,
+    },
+
+    /// A [`Collection::update_many`](../struct.Collection.html#method.update_many) operation.
+    UpdateMany {
+        /// The filter used to match documents.
+        filter: Document,
+
+        /// The modifications to apply to the matched documents.
+        update: UpdateModifications,
+
+        /// The options for the operation.
+        options: Option<UpdateOptions>,
+    },
+
+    /// A [`Collection::delete_one`](../struct.Collection.html#method.delete_one) operation.
+    DeleteOne {
+        /// The filter used to match documents.
+        filter: Document,
+
+        /// The options for the operation.
+        options: Option<DeleteOptions>,
+    },
+
+    /// A [`Collection::delete_many`](../struct.Collection.html#method.delete_many) operation.
+    DeleteMany {
+        /// The filter used to match documents.
+        filter: Document,
+
+        /// The options for the operation.
+        options: Option<DeleteOptions>,
+    },
+}
@@ -3,7 +3,7 @@ use std::{
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bson::{RawDocument, RawDocumentBuf};
@@ -54,6 +54,9 @@ where
         get_more_provider: P,
     ) -> Self {
         let exhausted = spec.id() == 0;
+        if !exhausted {
+            client.register_open_cursor(&spec.info);
+        }
         Self {
             client,
             provider: get_more_provider,
@@ -153,6 +156,8 @@ where
     }
 
     fn mark_exhausted(&mut self) {
+        self.client
+            .unregister_open_cursor(&self.info.address, self.info.id);
         self.state_mut().exhausted = true;
         self.state_mut().pinned_connection = PinnedConnection::Unpinned;
     }
@@ -377,6 +382,7 @@ impl CursorSpecification {
                 address,
                 batch_size: batch_size.into(),
                 max_time: max_time.into(),
+                timeout: None,
             },
             initial_buffer: info.first_batch,
             post_batch_resume_token: ResumeToken::from_raw(info.post_batch_resume_token),
@@ -411,6 +417,20 @@ pub(crate) struct CursorInformation {
     pub(crate) id: i64,
     pub(crate) batch_size: Option<u32>,
     pub(crate) max_time: Option<Duration>,
+    /// The timeout of the operation that created the cursor, which also bounds each `getMore`.
+    pub(crate) timeout: Option<CursorTimeout>,
+}
+
+/// How the timeout of the operation that created a cursor applies to the cursor's `getMore`s.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum CursorTimeout {
+    /// The timeout bounds the whole lifetime of the cursor, so each `getMore` is only given the
+    /// time remaining before this deadline.
+    Lifetime(Instant),
+
+    /// The timeout bounds each `getMore` separately. This is used for tailable cursors, which may
+    /// wait for new results indefinitely.
+    Iteration(Duration),
 }
 
 #[derive(Debug)]
@@ -464,6 +484,7 @@ impl PinnedConnection {
 pub(super) fn kill_cursor(
     client: Client,
     ns: &Namespace,
+    address: &ServerAddress,
     cursor_id: i64,
     pinned_conn: PinnedConnection,
     drop_address: Option<ServerAddress>,
@@ -472,15 +493,24 @@ pub(super) fn kill_cursor(
     let coll = client
         .database(ns.db.as_str())
         .collection::<Document>(ns.coll.as_str());
+    let address = address.clone();
     runtime::execute(async move {
-        if !pinned_conn.is_invalid() {
-            let _ = coll
-                .kill_cursor(cursor_id, pinned_conn.handle(), drop_address)
-                .await;
-            #[cfg(test)]
-            if let Some(tx) = kill_watcher {
-                let _ = tx.send(());
-            }
+        if pinned_conn.is_invalid() {
+            client.unregister_open_cursor(&address, cursor_id);
+            return;
+        }
+
+        let result = coll
+            .kill_cursor(cursor_id, pinned_conn.handle(), drop_address)
+            .await;
+        // If the client started shutting down before the cursor could be killed, it stays
+        // registered so that `Client::shutdown` kills it instead.
+        if !matches!(result, Err(ref e) if matches!(*e.kind, ErrorKind::Shutdown)) {
+            client.unregister_open_cursor(&address, cursor_id);
+        }
+        #[cfg(test)]
+        if let Some(tx) = kill_watcher {
+            let _ = tx.send(());
         }
     });
 }
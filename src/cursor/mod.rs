@@ -29,6 +29,7 @@ pub(crate) use common::{
     CursorInformation,
     CursorSpecification,
     CursorStream,
+    CursorTimeout,
     NextInBatchFuture,
     PinnedConnection,
 };
@@ -305,6 +306,7 @@ impl<T> Drop for Cursor<T> {
         kill_cursor(
             self.client.clone(),
             wrapped_cursor.namespace(),
+            wrapped_cursor.address(),
             wrapped_cursor.id(),
             wrapped_cursor.pinned_connection().replicate(),
             self.drop_address.take(),
@@ -83,6 +83,9 @@ impl<T> SessionCursor<T> {
         pinned: Option<PinnedConnectionHandle>,
     ) -> Self {
         let exhausted = spec.info.id == 0;
+        if !exhausted {
+            client.register_open_cursor(&spec.info);
+        }
 
         Self {
             client,
@@ -359,6 +362,7 @@ impl<T> Drop for SessionCursor<T> {
         kill_cursor(
             self.client.clone(),
             &self.info.ns,
+            &self.info.address,
             self.info.id,
             self.state.as_ref().unwrap().pinned_connection.replicate(),
             self.drop_address.take(),
@@ -1,6 +1,6 @@
 pub mod options;
 
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::Duration};
 
 use futures_util::stream::TryStreamExt;
 
@@ -17,7 +17,16 @@ use crate::{
     concern::{ReadConcern, WriteConcern},
     cursor::Cursor,
     error::{Error, ErrorKind, Result},
-    operation::{Aggregate, AggregateTarget, Create, DropDatabase, ListCollections, RunCommand},
+    gridfs::{options::GridFsBucketOptions, GridFsBucket},
+    operation::{
+        Aggregate,
+        AggregateTarget,
+        Create,
+        DropDatabase,
+        ListCollections,
+        ModifyCollection,
+        RunCommand,
+    },
     options::{
         AggregateOptions,
         CollectionOptions,
@@ -25,8 +34,9 @@ use crate::{
         DatabaseOptions,
         DropDatabaseOptions,
         ListCollectionsOptions,
+        ModifyCollectionOptions,
     },
-    results::CollectionSpecification,
+    results::{CollectionSpecification, ModifyCollectionResult},
     selection_criteria::SelectionCriteria,
     Client,
     ClientSession,
@@ -84,6 +94,7 @@ struct DatabaseInner {
     selection_criteria: Option<SelectionCriteria>,
     read_concern: Option<ReadConcern>,
     write_concern: Option<WriteConcern>,
+    timeout: Option<Duration>,
 }
 
 impl Database {
@@ -101,6 +112,8 @@ impl Database {
             .write_concern
             .or_else(|| client.write_concern().cloned());
 
+        let timeout = options.timeout.or_else(|| client.timeout());
+
         Self {
             inner: Arc::new(DatabaseInner {
                 client,
@@ -108,6 +121,7 @@ impl Database {
                 selection_criteria,
                 read_concern,
                 write_concern,
+                timeout,
             }),
         }
     }
@@ -137,6 +151,11 @@ impl Database {
         self.inner.write_concern.as_ref()
     }
 
+    /// Gets the default timeout of the `Database`.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout
+    }
+
     /// Gets a handle to a collection in this database with the provided name. The
     /// [`Collection`] options (e.g. read preference and write concern) will default to those of
     /// this [`Database`].
@@ -167,13 +186,22 @@ impl Database {
         Collection::new(self.clone(), name, Some(options))
     }
 
+    /// Creates a new [`GridFsBucket`] in the database with the given options. The bucket's read
+    /// concern, write concern and selection criteria default to those of this [`Database`].
+    ///
+    /// This method does not send or receive anything across the wire to the database; the
+    /// indexes required by GridFS are created the first time a file is uploaded to the bucket.
+    pub fn gridfs_bucket(&self, options: impl Into<Option<GridFsBucketOptions>>) -> GridFsBucket {
+        GridFsBucket::new(self.clone(), options.into().unwrap_or_default())
+    }
+
     async fn drop_common(
         &self,
         options: impl Into<Option<DropDatabaseOptions>>,
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<()> {
         let mut options = options.into();
-        resolve_options!(self, options, [write_concern]);
+        resolve_options!(self, options, [write_concern, timeout]);
 
         let drop_database = DropDatabase::new(self.name().to_string(), options);
         self.client()
@@ -203,12 +231,11 @@ impl Database {
         filter: impl Into<Option<Document>>,
         options: impl Into<Option<ListCollectionsOptions>>,
     ) -> Result<Cursor<CollectionSpecification>> {
-        let list_collections = ListCollections::new(
-            self.name().to_string(),
-            filter.into(),
-            false,
-            options.into(),
-        );
+        let mut options = options.into();
+        resolve_options!(self, options, [timeout]);
+
+        let list_collections =
+            ListCollections::new(self.name().to_string(), filter.into(), false, options);
         self.client()
             .execute_cursor_operation(list_collections)
             .await
@@ -223,12 +250,11 @@ impl Database {
         options: impl Into<Option<ListCollectionsOptions>>,
         session: &mut ClientSession,
     ) -> Result<SessionCursor<CollectionSpecification>> {
-        let list_collections = ListCollections::new(
-            self.name().to_string(),
-            filter.into(),
-            false,
-            options.into(),
-        );
+        let mut options = options.into();
+        resolve_options!(self, options, [timeout]);
+
+        let list_collections =
+            ListCollections::new(self.name().to_string(), filter.into(), false, options);
         self.client()
             .execute_session_cursor_operation(list_collections, session)
             .await
@@ -258,8 +284,11 @@ impl Database {
         &self,
         filter: impl Into<Option<Document>>,
     ) -> Result<Vec<String>> {
+        let mut options: Option<ListCollectionsOptions> = None;
+        resolve_options!(self, options, [timeout]);
+
         let list_collections =
-            ListCollections::new(self.name().to_string(), filter.into(), true, None);
+            ListCollections::new(self.name().to_string(), filter.into(), true, options);
         let cursor: Cursor<Document> = self
             .client()
             .execute_cursor_operation(list_collections)
@@ -274,8 +303,11 @@ impl Database {
         filter: impl Into<Option<Document>>,
         session: &mut ClientSession,
     ) -> Result<Vec<String>> {
+        let mut options: Option<ListCollectionsOptions> = None;
+        resolve_options!(self, options, [timeout]);
+
         let list_collections =
-            ListCollections::new(self.name().to_string(), filter.into(), true, None);
+            ListCollections::new(self.name().to_string(), filter.into(), true, options);
         let mut cursor: SessionCursor<Document> = self
             .client()
             .execute_session_cursor_operation(list_collections, &mut *session)
@@ -292,7 +324,7 @@ impl Database {
         session: impl Into<Option<&mut ClientSession>>,
     ) -> Result<()> {
         let mut options = options.into();
-        resolve_options!(self, options, [write_concern]);
+        resolve_options!(self, options, [write_concern, timeout]);
 
         let create = Create::new(
             Namespace {
@@ -330,6 +362,48 @@ impl Database {
         self.create_collection_common(name, options, session).await
     }
 
+    async fn modify_collection_common(
+        &self,
+        name: impl AsRef<str>,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: impl Into<Option<&mut ClientSession>>,
+    ) -> Result<ModifyCollectionResult> {
+        let mut options = options.into();
+        resolve_options!(self, options, [write_concern, timeout]);
+
+        let modify = ModifyCollection::new(
+            Namespace {
+                db: self.name().to_string(),
+                coll: name.as_ref().to_string(),
+            },
+            options,
+        );
+        self.client().execute_operation(modify, session).await
+    }
+
+    /// Modifies the collection or view with the given `name` using the `collMod` command, e.g. to
+    /// change its validation rules or the TTL of one of its indexes. If an index was modified, the
+    /// returned result contains its previous and new settings.
+    pub async fn modify_collection(
+        &self,
+        name: impl AsRef<str>,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+    ) -> Result<ModifyCollectionResult> {
+        self.modify_collection_common(name, options, None).await
+    }
+
+    /// Modifies the collection or view with the given `name` using the `collMod` command and the
+    /// provided `ClientSession`. If an index was modified, the returned result contains its
+    /// previous and new settings.
+    pub async fn modify_collection_with_session(
+        &self,
+        name: impl AsRef<str>,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ModifyCollectionResult> {
+        self.modify_collection_common(name, options, session).await
+    }
+
     pub(crate) async fn run_command_common(
         &self,
         command: Document,
@@ -341,6 +415,7 @@ impl Database {
             self.name().into(),
             command,
             selection_criteria.into(),
+            self.timeout(),
             pinned_connection,
         )?;
         self.client().execute_operation(operation, session).await
@@ -414,7 +489,7 @@ impl Database {
         resolve_options!(
             self,
             options,
-            [read_concern, write_concern, selection_criteria]
+            [read_concern, write_concern, selection_criteria, timeout]
         );
 
         let aggregate = Aggregate::new(self.name().to_string(), pipeline, options);
@@ -436,7 +511,7 @@ impl Database {
         resolve_options!(
             self,
             options,
-            [read_concern, write_concern, selection_criteria]
+            [read_concern, write_concern, selection_criteria, timeout]
         );
 
         let aggregate = Aggregate::new(self.name().to_string(), pipeline, options);
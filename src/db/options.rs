@@ -26,6 +26,15 @@ pub struct DatabaseOptions {
 
     /// The default write concern for operations.
     pub write_concern: Option<WriteConcern>,
+
+    /// The default timeout for operations. Defaults to the timeout of the client. See
+    /// [`ClientOptions::timeout`](crate::options::ClientOptions::timeout) for more details.
+    #[serde(
+        default,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// These are the valid options for creating a collection with
@@ -106,6 +115,16 @@ pub struct CreateCollectionOptions {
 
     /// Options for supporting change stream pre- and post-images.
     pub change_stream_pre_and_post_images: Option<ChangeStreamPreAndPostImages>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the database.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies how strictly the database should apply validation rules to existing documents during
@@ -179,6 +198,98 @@ pub enum TimeseriesGranularity {
     Hours,
 }
 
+/// Specifies the changes to make to a collection with
+/// [`Database::modify_collection`](../struct.Database.html#method.modify_collection) or
+/// [`Collection::modify`](../struct.Collection.html#method.modify). Settings that are not set are
+/// left unchanged.
+///
+/// See the [`collMod` command
+/// documentation](https://www.mongodb.com/docs/manual/reference/command/collMod/) for more
+/// information.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct ModifyCollectionOptions {
+    /// The new validator to restrict the schema of documents which can exist in the collection.
+    pub validator: Option<Document>,
+
+    /// Specifies how strictly the database should apply the validation rules to existing documents
+    /// during an update.
+    pub validation_level: Option<ValidationLevel>,
+
+    /// Specifies whether the database should return an error or simply raise a warning if inserted
+    /// documents do not pass the validation.
+    pub validation_action: Option<ValidationAction>,
+
+    /// The name of the source collection or view that a view is based on. This can only be set
+    /// when modifying a view, and requires `pipeline` to be set as well.
+    pub view_on: Option<String>,
+
+    /// The new aggregation pipeline that defines a view. This can only be set when modifying a
+    /// view, and requires `view_on` to be set as well.
+    pub pipeline: Option<Vec<Document>>,
+
+    /// Changes the TTL or visibility of an index on the collection.
+    pub index: Option<IndexModification>,
+
+    /// The new time after which documents in a time series or clustered collection are
+    /// automatically deleted.
+    #[serde(
+        default,
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_seconds",
+        serialize_with = "bson_util::serialize_duration_option_as_int_secs"
+    )]
+    pub expire_after_seconds: Option<Duration>,
+
+    /// Whether change streams on the collection can include pre- and post-images.
+    pub change_stream_pre_and_post_images: Option<ChangeStreamPreAndPostImages>,
+
+    /// The write concern for the operation.
+    pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the database.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
+}
+
+/// Specifies an index to modify as part of a
+/// [`Database::modify_collection`](../struct.Database.html#method.modify_collection) operation,
+/// and the changes to make to it. The index is identified by exactly one of `name` or
+/// `key_pattern`.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Deserialize, TypedBuilder, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[builder(field_defaults(default, setter(into)))]
+#[non_exhaustive]
+pub struct IndexModification {
+    /// The name of the index to modify.
+    pub name: Option<String>,
+
+    /// The key pattern of the index to modify.
+    pub key_pattern: Option<Document>,
+
+    /// The new time after which documents are removed from the collection by a TTL index.
+    #[serde(
+        rename = "expireAfterSeconds",
+        default,
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_seconds",
+        serialize_with = "bson_util::serialize_duration_option_as_int_secs"
+    )]
+    pub expire_after: Option<Duration>,
+
+    /// Whether the index is hidden from the query planner. Hidden indexes are still maintained,
+    /// so they can be unhidden without being rebuilt.
+    pub hidden: Option<bool>,
+}
+
 /// Specifies the options to a [`Database::drop`](../struct.Database.html#method.drop) operation.
 #[derive(Clone, Debug, Default, TypedBuilder, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -187,6 +298,11 @@ pub enum TimeseriesGranularity {
 pub struct DropDatabaseOptions {
     /// The write concern for the operation.
     pub write_concern: Option<WriteConcern>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the database.
+    #[serde(skip_serializing)]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -207,6 +323,18 @@ pub struct ListCollectionsOptions {
         rename(serialize = "cursor")
     )]
     pub batch_size: Option<u32>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the database.
+    ///
+    /// The returned cursor applies the same timeout to each `getMore` it issues.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies the options to a
@@ -219,6 +347,16 @@ pub struct ListDatabasesOptions {
     /// Determines which databases to return based on the user's access privileges. This option is
     /// only supported on server versions 4.0.5+.
     pub authorized_databases: Option<bool>,
+
+    /// The maximum amount of time to allow the operation to run, including server selection,
+    /// connection checkout and any retries. Defaults to the timeout of the client.
+    #[serde(
+        default,
+        skip_serializing,
+        rename = "timeoutMS",
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_millis"
+    )]
+    pub timeout: Option<Duration>,
 }
 
 /// Specifies how change stream pre- and post-images should be supported.
@@ -10,7 +10,12 @@ use bson::Bson;
 use serde::Deserialize;
 use thiserror::Error;
 
-use crate::{bson::Document, options::ServerAddress};
+use crate::{
+    bson::Document,
+    hello::TopologyVersion,
+    options::ServerAddress,
+    results::{BulkWriteResult, ClientBulkWriteResult},
+};
 
 const RECOVERING_CODES: [i32; 5] = [11600, 11602, 13436, 189, 91];
 const NOTWRITABLEPRIMARY_CODES: [i32; 3] = [10107, 13435, 10058];
@@ -110,6 +115,21 @@ impl Error {
         .into()
     }
 
+    pub(crate) fn timeout(message: impl Into<String>) -> Error {
+        ErrorKind::Timeout {
+            message: message.into(),
+        }
+        .into()
+    }
+
+    #[cfg(feature = "csfle")]
+    pub(crate) fn encryption(message: impl Into<String>) -> Error {
+        ErrorKind::Encryption {
+            message: message.into(),
+        }
+        .into()
+    }
+
     pub(crate) fn is_state_change_error(&self) -> bool {
         self.is_recovering() || self.is_notwritableprimary()
     }
@@ -131,6 +151,12 @@ impl Error {
         matches!(self.kind.as_ref(), ErrorKind::Command(ref err) if err.code == 26)
     }
 
+    /// Whether this error is a "MaxTimeMSExpired" error, either as a command error or as a write
+    /// concern error.
+    pub(crate) fn is_max_time_ms_expired_error(&self) -> bool {
+        self.code() == Some(50)
+    }
+
     pub(crate) fn is_server_selection_error(&self) -> bool {
         matches!(self.kind.as_ref(), ErrorKind::ServerSelection { .. })
     }
@@ -187,6 +213,7 @@ impl Error {
             self.kind.as_ref(),
             ErrorKind::Authentication { .. }
                 | ErrorKind::BulkWrite(_)
+                | ErrorKind::ClientBulkWrite(_)
                 | ErrorKind::Command(_)
                 | ErrorKind::Write(_)
         )
@@ -231,6 +258,9 @@ impl Error {
     pub(crate) fn code(&self) -> Option<i32> {
         match self.kind.as_ref() {
             ErrorKind::Command(command_error) => Some(command_error.code),
+            ErrorKind::BulkWrite(BulkWriteFailure {
+                error: Some(error), ..
+            }) => error.code(),
             // According to SDAM spec, write concern error codes MUST also be checked, and
             // writeError codes MUST NOT be checked.
             ErrorKind::BulkWrite(BulkWriteFailure {
@@ -238,11 +268,27 @@ impl Error {
                 ..
             }) => Some(wc_error.code),
             ErrorKind::Write(WriteFailure::WriteConcernError(wc_error)) => Some(wc_error.code),
+            ErrorKind::ClientBulkWrite(ClientBulkWriteFailure {
+                error: Some(error), ..
+            }) => error.code(),
+            ErrorKind::ClientBulkWrite(ClientBulkWriteFailure {
+                write_concern_errors,
+                ..
+            }) => write_concern_errors.first().map(|wc_error| wc_error.code),
             _ => None,
         }
         .or_else(|| self.source.as_ref().and_then(|s| s.code()))
     }
 
+    /// Gets the topology version the server reported alongside this error, if any.
+    pub(crate) fn topology_version(&self) -> Option<TopologyVersion> {
+        match self.kind.as_ref() {
+            ErrorKind::Command(command_error) => command_error.topology_version,
+            _ => None,
+        }
+        .or_else(|| self.source.as_ref().and_then(|s| s.topology_version()))
+    }
+
     /// Gets the message for this error, if applicable, for use in testing.
     /// If this error is a BulkWriteError, the messages are concatenated.
     #[cfg(test)]
@@ -255,7 +301,7 @@ impl Error {
             ErrorKind::BulkWrite(BulkWriteFailure {
                 write_concern_error,
                 write_errors,
-                inserted_ids: _,
+                ..
             }) => {
                 let mut msg = "".to_string();
                 if let Some(wc_error) = write_concern_error {
@@ -425,6 +471,11 @@ pub enum ErrorKind {
     #[error("An error occurred when trying to execute a write operation: {0:?}")]
     BulkWrite(BulkWriteFailure),
 
+    /// An error occurred when trying to execute a
+    /// [`Client::bulk_write`](../struct.Client.html#method.bulk_write) operation.
+    #[error("An error occurred when trying to execute a client bulk write operation: {0:?}")]
+    ClientBulkWrite(ClientBulkWriteFailure),
+
     /// The server returned an error to an attempted operation.
     #[error("Command failed {0}")]
     Command(CommandError),
@@ -483,6 +534,28 @@ pub enum ErrorKind {
     /// No resume token was present in a change stream document.
     #[error("Cannot provide resume functionality when the resume token is missing")]
     MissingResumeToken,
+
+    /// An error occurred when performing a GridFS operation.
+    #[error("An error occurred during a GridFS operation: {0:?}")]
+    GridFs(GridFsErrorKind),
+
+    /// The operation did not complete within its configured timeout. See
+    /// [`ClientOptions::timeout`](../options/struct.ClientOptions.html#structfield.timeout) for
+    /// more details.
+    #[error("The operation timed out: {message}")]
+    #[non_exhaustive]
+    Timeout { message: String },
+
+    /// An error occurred during client-side field level encryption or decryption.
+    #[error("An error occurred during client-side encryption: {message}")]
+    #[non_exhaustive]
+    Encryption { message: String },
+
+    /// The `Client` was shut down via [`Client::shutdown`](crate::Client::shutdown) or
+    /// [`Client::shutdown_immediate`](crate::Client::shutdown_immediate) and can no longer be
+    /// used.
+    #[error("The client has been shut down")]
+    Shutdown,
 }
 
 impl ErrorKind {
@@ -515,6 +588,11 @@ pub struct CommandError {
     /// A description of the error that occurred.
     #[serde(rename = "errmsg")]
     pub message: String,
+
+    /// The topology version of the server at the time the error occurred, used to determine
+    /// whether the error reflects a stale view of the server.
+    #[serde(rename = "topologyVersion", default)]
+    pub(crate) topology_version: Option<TopologyVersion>,
 }
 
 impl fmt::Display for CommandError {
@@ -616,6 +694,25 @@ pub struct BulkWriteFailure {
 
     #[serde(skip)]
     pub(crate) inserted_ids: HashMap<usize, Bson>,
+
+    /// The results of the writes that succeeded before or alongside the failed ones, with indexes
+    /// corresponding to positions in the models passed to
+    /// [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write) or the documents
+    /// passed to [`Collection::insert_many`](../struct.Collection.html#method.insert_many). This
+    /// is only set for failures returned from those methods.
+    #[serde(skip)]
+    pub partial_result: Option<BulkWriteResult>,
+
+    /// The top-level error that prevented the remaining writes from being executed after some of
+    /// them were performed, if any (e.g. the operation's timeout expiring between batches). This
+    /// is only set for failures returned from `bulk_write` and `insert_many`.
+    #[serde(skip)]
+    pub error: Option<Error>,
+
+    /// The number of writes included in the batch that produced this failure, used to resume
+    /// unordered bulk writes after the failed batch.
+    #[serde(skip)]
+    pub(crate) n_attempted: usize,
 }
 
 impl BulkWriteFailure {
@@ -624,10 +721,94 @@ impl BulkWriteFailure {
             write_errors: None,
             write_concern_error: None,
             inserted_ids: Default::default(),
+            partial_result: None,
+            error: None,
+            n_attempted: 0,
         }
     }
 }
 
+/// The errors that occurred during a
+/// [`Client::bulk_write`](../struct.Client.html#method.bulk_write) operation.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ClientBulkWriteFailure {
+    /// The top-level error that prevented the remaining writes from being executed, if any.
+    pub error: Option<Error>,
+
+    /// The errors that occurred on account of a non write concern failure, keyed by the index of
+    /// the corresponding model.
+    pub write_errors: HashMap<usize, WriteError>,
+
+    /// The write concern errors that occurred. The server may return a write concern error for
+    /// each batch the writes were split into.
+    pub write_concern_errors: Vec<WriteConcernError>,
+
+    /// The results of the writes that were performed before the operation failed, if any writes
+    /// were performed.
+    pub partial_result: Option<ClientBulkWriteResult>,
+}
+
+/// An error that occurred during a GridFS operation.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum GridFsErrorKind {
+    /// The file with the given identifier was not found.
+    #[non_exhaustive]
+    FileNotFound {
+        /// The identifier of the file that was not found.
+        identifier: GridFsFileIdentifier,
+    },
+
+    /// The file with the given filename exists, but not at the requested revision.
+    #[non_exhaustive]
+    RevisionNotFound {
+        /// The requested revision.
+        revision: i32,
+    },
+
+    /// The chunk at index `n` was missing from the chunks collection.
+    #[non_exhaustive]
+    MissingChunk {
+        /// The index of the missing chunk.
+        n: u32,
+    },
+
+    /// The chunk at index `n` was not the expected size.
+    #[non_exhaustive]
+    WrongSizeChunk {
+        /// The size of the chunk in bytes.
+        actual_size: usize,
+
+        /// The size in bytes the chunk was expected to be.
+        expected_size: u32,
+
+        /// The index of the chunk.
+        n: u32,
+    },
+
+    /// The chunks collection contained more chunks for the file than expected.
+    #[non_exhaustive]
+    WrongNumberOfChunks {
+        /// The number of chunks found.
+        actual_number: u32,
+
+        /// The number of chunks expected based on the file's length and chunk size.
+        expected_number: u32,
+    },
+}
+
+/// An identifier for a file stored in a GridFS bucket.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum GridFsFileIdentifier {
+    /// The name of the file. Not guaranteed to be unique.
+    Filename(String),
+
+    /// The file's unique `_id`.
+    Id(Bson),
+}
+
 /// An error that occurred when trying to execute a write operation.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -201,6 +201,9 @@ pub enum ConnectionCheckoutFailedReason {
     /// An error occurred while trying to establish a connection (e.g. during the handshake or
     /// authentication).
     ConnectionError,
+
+    /// The pool was closed because the `Client` was shut down.
+    PoolClosed,
 }
 
 /// Event emitted when a connection is successfully checked out.
@@ -110,7 +110,12 @@ pub struct TopologyClosedEvent {
 pub struct ServerHeartbeatStartedEvent {
     /// The address of the server.
     pub server_address: ServerAddress,
-    // TODO RUST-560 add awaited field
+
+    /// Whether the heartbeat was started using the streaming protocol, i.e. as an awaitable
+    /// `hello` that the server only replies to once its state changes or the heartbeat frequency
+    /// elapses.
+    #[serde(default)]
+    pub awaited: bool,
 }
 
 /// Published when a server monitor's `hello` or legacy hello command succeeds.
@@ -125,7 +130,10 @@ pub struct ServerHeartbeatSucceededEvent {
 
     /// The address of the server.
     pub server_address: ServerAddress,
-    // TODO RUST-560 add awaited field
+
+    /// Whether the heartbeat used the streaming protocol. If so, `duration` includes the time the
+    /// server waited before replying and is not a measure of the round trip time.
+    pub awaited: bool,
 }
 
 /// Published when a server monitor's `hello` or legacy hello command fails.
@@ -140,7 +148,10 @@ pub struct ServerHeartbeatFailedEvent {
 
     /// The address of the server.
     pub server_address: ServerAddress,
-    // TODO RUST-560 add awaited field
+
+    /// Whether the heartbeat used the streaming protocol. If so, `duration` includes the time the
+    /// server waited before replying and is not a measure of the round trip time.
+    pub awaited: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,228 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use futures_io::AsyncWrite;
+use futures_util::{io::AsyncWriteExt, stream::TryStreamExt};
+
+use super::{options::GridFsDownloadByNameOptions, Chunk, FilesCollectionDocument, GridFsBucket};
+use crate::{
+    bson::{doc, Bson},
+    cursor::Cursor,
+    error::{ErrorKind, GridFsErrorKind, GridFsFileIdentifier, Result},
+    options::{FindOneOptions, FindOptions},
+};
+
+impl GridFsBucket {
+    /// Opens and returns a [`GridFsDownloadStream`] from which the contents of the file with the
+    /// given `id` can be read.
+    pub async fn open_download_stream(&self, id: Bson) -> Result<GridFsDownloadStream> {
+        let file = self
+            .files()
+            .find_one(doc! { "_id": id.clone() }, None)
+            .await?
+            .ok_or_else(|| {
+                ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+                    identifier: GridFsFileIdentifier::Id(id),
+                })
+            })?;
+
+        self.download_stream_for_file(file).await
+    }
+
+    /// Opens and returns a [`GridFsDownloadStream`] from which the contents of the file with the
+    /// given `filename` can be read. If there are multiple files with the same name, the revision
+    /// to download can be specified in the options; by default, the most recent revision is
+    /// downloaded.
+    pub async fn open_download_stream_by_name(
+        &self,
+        filename: impl AsRef<str>,
+        options: impl Into<Option<GridFsDownloadByNameOptions>>,
+    ) -> Result<GridFsDownloadStream> {
+        let file = self.find_file_by_name(filename.as_ref(), options).await?;
+        self.download_stream_for_file(file).await
+    }
+
+    /// Downloads the contents of the file with the given `id` and writes them to `destination`.
+    pub async fn download_to_futures_0_3_writer(
+        &self,
+        id: Bson,
+        destination: impl AsyncWrite + Unpin,
+    ) -> Result<()> {
+        let stream = self.open_download_stream(id).await?;
+        write_to(stream, destination).await
+    }
+
+    /// Downloads the contents of the file with the given `filename` and writes them to
+    /// `destination`. If there are multiple files with the same name, the revision to download
+    /// can be specified in the options; by default, the most recent revision is downloaded.
+    pub async fn download_to_futures_0_3_writer_by_name(
+        &self,
+        filename: impl AsRef<str>,
+        destination: impl AsyncWrite + Unpin,
+        options: impl Into<Option<GridFsDownloadByNameOptions>>,
+    ) -> Result<()> {
+        let stream = self.open_download_stream_by_name(filename, options).await?;
+        write_to(stream, destination).await
+    }
+
+    async fn find_file_by_name(
+        &self,
+        filename: &str,
+        options: impl Into<Option<GridFsDownloadByNameOptions>>,
+    ) -> Result<FilesCollectionDocument> {
+        let revision = options.into().and_then(|opts| opts.revision).unwrap_or(-1);
+        let (sort, skip) = if revision >= 0 {
+            (1, revision as u64)
+        } else {
+            (-1, (-revision - 1) as u64)
+        };
+
+        let find_options = FindOneOptions::builder()
+            .sort(doc! { "uploadDate": sort })
+            .skip(skip)
+            .build();
+        if let Some(file) = self
+            .files()
+            .find_one(doc! { "filename": filename }, find_options)
+            .await?
+        {
+            return Ok(file);
+        }
+
+        // Distinguish between the file not existing at all and the requested revision not
+        // existing.
+        let error = if self
+            .files()
+            .find_one(doc! { "filename": filename }, None)
+            .await?
+            .is_some()
+        {
+            GridFsErrorKind::RevisionNotFound { revision }
+        } else {
+            GridFsErrorKind::FileNotFound {
+                identifier: GridFsFileIdentifier::Filename(filename.to_string()),
+            }
+        };
+        Err(ErrorKind::GridFs(error).into())
+    }
+
+    async fn download_stream_for_file(
+        &self,
+        file: FilesCollectionDocument,
+    ) -> Result<GridFsDownloadStream> {
+        let cursor = if file.length == 0 {
+            None
+        } else {
+            let options = FindOptions::builder().sort(doc! { "n": 1 }).build();
+            let cursor = self
+                .chunks()
+                .find(doc! { "files_id": file.id.clone() }, options)
+                .await?;
+            Some(Box::new(cursor))
+        };
+
+        Ok(GridFsDownloadStream {
+            file,
+            cursor,
+            next_n: 0,
+        })
+    }
+}
+
+async fn write_to(
+    mut stream: GridFsDownloadStream,
+    mut destination: impl AsyncWrite + Unpin,
+) -> Result<()> {
+    while let Some(data) = stream.try_next().await? {
+        destination.write_all(&data).await?;
+    }
+    destination.flush().await?;
+    Ok(())
+}
+
+/// A stream over the contents of a file stored in a [`GridFsBucket`]. The file's data is yielded
+/// one chunk at a time, and each chunk is validated against the file's length and chunk size as
+/// it is read.
+///
+/// A `GridFsDownloadStream` can be obtained through
+/// [`GridFsBucket::open_download_stream`] or [`GridFsBucket::open_download_stream_by_name`].
+#[derive(Debug)]
+pub struct GridFsDownloadStream {
+    file: FilesCollectionDocument,
+
+    /// The cursor over the file's chunks. This is `None` if the file is empty or the stream has
+    /// finished.
+    cursor: Option<Box<Cursor<Chunk>>>,
+
+    /// The index of the next chunk expected from the cursor.
+    next_n: u32,
+}
+
+impl GridFsDownloadStream {
+    /// Gets the [`FilesCollectionDocument`] of the file being downloaded.
+    pub fn files_collection_document(&self) -> &FilesCollectionDocument {
+        &self.file
+    }
+
+    fn validate_chunk(&mut self, chunk: Chunk) -> Result<Vec<u8>> {
+        let expected_number = self.file.n();
+        if chunk.n >= expected_number {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::WrongNumberOfChunks {
+                actual_number: chunk.n + 1,
+                expected_number,
+            })
+            .into());
+        }
+        if chunk.n != self.next_n {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::MissingChunk { n: self.next_n }).into());
+        }
+
+        let expected_size = self.file.expected_chunk_length(chunk.n);
+        if chunk.data.bytes.len() != expected_size as usize {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::WrongSizeChunk {
+                actual_size: chunk.data.bytes.len(),
+                expected_size,
+                n: chunk.n,
+            })
+            .into());
+        }
+
+        self.next_n += 1;
+        Ok(chunk.data.bytes)
+    }
+}
+
+impl Stream for GridFsDownloadStream {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let cursor = match this.cursor.as_mut() {
+            Some(cursor) => cursor,
+            None => return Poll::Ready(None),
+        };
+
+        let result = match Pin::new(cursor.as_mut()).poll_next(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Some(Ok(chunk))) => this.validate_chunk(chunk),
+            Poll::Ready(Some(Err(error))) => Err(error),
+            Poll::Ready(None) => {
+                this.cursor = None;
+                if this.next_n < this.file.n() {
+                    Err(ErrorKind::GridFs(GridFsErrorKind::MissingChunk { n: this.next_n }).into())
+                } else {
+                    return Poll::Ready(None);
+                }
+            }
+        };
+
+        // Stop reading after the first error.
+        if result.is_err() {
+            this.cursor = None;
+        }
+        Poll::Ready(Some(result))
+    }
+}
@@ -0,0 +1,332 @@
+//! Contains the functionality for GridFS operations.
+
+mod download;
+pub mod options;
+mod upload;
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use futures_util::stream::TryStreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bson::{doc, oid::ObjectId, Binary, Bson, DateTime, Document},
+    bson_util,
+    concern::{ReadConcern, WriteConcern},
+    cursor::Cursor,
+    error::{ErrorKind, GridFsErrorKind, GridFsFileIdentifier, Result},
+    options::{CollectionOptions, FindOneOptions, IndexOptions, ReadPreference, SelectionCriteria},
+    Collection,
+    Database,
+    IndexModel,
+};
+
+pub use download::GridFsDownloadStream;
+use options::{GridFsBucketOptions, GridFsFindOptions};
+
+pub(crate) const DEFAULT_BUCKET_NAME: &str = "fs";
+pub(crate) const DEFAULT_CHUNK_SIZE_BYTES: u32 = 255 * 1024;
+
+/// The error code returned by the server when listing the indexes of a collection that does not
+/// exist.
+const NAMESPACE_NOT_FOUND_CODE: i32 = 26;
+
+/// A model for the documents stored in the chunks collection.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Chunk {
+    #[serde(rename = "_id")]
+    id: ObjectId,
+
+    files_id: Bson,
+
+    #[serde(serialize_with = "bson::serde_helpers::serialize_u32_as_i32")]
+    n: u32,
+
+    data: Binary,
+}
+
+/// A model for the documents stored in a GridFS bucket's files collection.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct FilesCollectionDocument {
+    /// The file's unique identifier.
+    #[serde(rename = "_id")]
+    pub id: Bson,
+
+    /// The length of the file in bytes.
+    #[serde(
+        serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64",
+        deserialize_with = "bson_util::deserialize_u64_from_bson_number"
+    )]
+    pub length: u64,
+
+    /// The size of the file's chunks in bytes.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u32_as_i32")]
+    pub chunk_size: u32,
+
+    /// The time at which the file was uploaded.
+    pub upload_date: DateTime,
+
+    /// The name of the file.
+    pub filename: Option<String>,
+
+    /// User-provided metadata associated with the file.
+    pub metadata: Option<Document>,
+}
+
+impl FilesCollectionDocument {
+    /// The number of chunks the file is split into.
+    fn n(&self) -> u32 {
+        if self.chunk_size == 0 {
+            return 0;
+        }
+        let chunk_size = self.chunk_size as u64;
+        let remainder = self.length % chunk_size;
+        let n = self.length / chunk_size;
+        if remainder == 0 {
+            n as u32
+        } else {
+            n as u32 + 1
+        }
+    }
+
+    /// The expected size in bytes of the chunk at index `n`.
+    fn expected_chunk_length(&self, n: u32) -> u32 {
+        let remainder = (self.length % self.chunk_size as u64) as u32;
+        if n == self.n() - 1 && remainder != 0 {
+            remainder
+        } else {
+            self.chunk_size
+        }
+    }
+}
+
+#[derive(Debug)]
+struct GridFsBucketInner {
+    options: GridFsBucketOptions,
+    files: Collection<FilesCollectionDocument>,
+    chunks: Collection<Chunk>,
+    created_indexes: AtomicBool,
+}
+
+/// A `GridFsBucket` provides the functionality for storing and retrieving binary BSON data that
+/// exceeds the 16 MiB size limit of a MongoDB document. Users may upload and download large
+/// amounts of data, called files, to the bucket. When a file is uploaded, its contents are
+/// divided into chunks and stored in a chunks collection. A corresponding
+/// [`FilesCollectionDocument`] is also stored in a files collection. When a user downloads a
+/// file, the bucket finds and returns the data stored in its chunks.
+///
+/// `GridFsBucket` uses [`std::sync::Arc`] internally, so it can be shared safely across threads
+/// or async tasks.
+///
+/// A `GridFsBucket` can only be obtained through
+/// [`Database::gridfs_bucket`](crate::Database::gridfs_bucket).
+#[derive(Debug, Clone)]
+pub struct GridFsBucket {
+    inner: Arc<GridFsBucketInner>,
+}
+
+impl GridFsBucket {
+    pub(crate) fn new(db: Database, mut options: GridFsBucketOptions) -> GridFsBucket {
+        if options.read_concern.is_none() {
+            options.read_concern = db.read_concern().cloned();
+        }
+        if options.write_concern.is_none() {
+            options.write_concern = db.write_concern().cloned();
+        }
+        if options.selection_criteria.is_none() {
+            options.selection_criteria = db.selection_criteria().cloned();
+        }
+
+        let bucket_name = options
+            .bucket_name
+            .as_deref()
+            .unwrap_or(DEFAULT_BUCKET_NAME);
+
+        let collection_options = CollectionOptions::builder()
+            .read_concern(options.read_concern.clone())
+            .write_concern(options.write_concern.clone())
+            .selection_criteria(options.selection_criteria.clone())
+            .build();
+        let files = db.collection_with_options::<FilesCollectionDocument>(
+            &format!("{}.files", bucket_name),
+            collection_options.clone(),
+        );
+        let chunks = db.collection_with_options::<Chunk>(
+            &format!("{}.chunks", bucket_name),
+            collection_options,
+        );
+
+        GridFsBucket {
+            inner: Arc::new(GridFsBucketInner {
+                options,
+                files,
+                chunks,
+                created_indexes: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Gets the read concern of the bucket.
+    pub fn read_concern(&self) -> Option<&ReadConcern> {
+        self.inner.options.read_concern.as_ref()
+    }
+
+    /// Gets the write concern of the bucket.
+    pub fn write_concern(&self) -> Option<&WriteConcern> {
+        self.inner.options.write_concern.as_ref()
+    }
+
+    /// Gets the selection criteria of the bucket.
+    pub fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.inner.options.selection_criteria.as_ref()
+    }
+
+    /// Gets the chunk size in bytes for the bucket.
+    fn chunk_size_bytes(&self) -> u32 {
+        self.inner
+            .options
+            .chunk_size_bytes
+            .unwrap_or(DEFAULT_CHUNK_SIZE_BYTES)
+    }
+
+    /// Gets a handle to the files collection for the bucket.
+    pub(crate) fn files(&self) -> &Collection<FilesCollectionDocument> {
+        &self.inner.files
+    }
+
+    /// Gets a handle to the chunks collection for the bucket.
+    pub(crate) fn chunks(&self) -> &Collection<Chunk> {
+        &self.inner.chunks
+    }
+
+    /// Deletes the [`FilesCollectionDocument`] with the given `id` and its associated chunks from
+    /// this bucket.
+    pub async fn delete(&self, id: Bson) -> Result<()> {
+        let delete_result = self
+            .files()
+            .delete_one(doc! { "_id": id.clone() }, None)
+            .await?;
+        // Delete chunks regardless of whether a file was found. This will remove any possibly
+        // orphaned chunks.
+        self.chunks()
+            .delete_many(doc! { "files_id": id.clone() }, None)
+            .await?;
+
+        if delete_result.deleted_count == 0 {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+                identifier: GridFsFileIdentifier::Id(id),
+            })
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Finds and returns the [`FilesCollectionDocument`]s within this bucket that match the given
+    /// filter.
+    pub async fn find(
+        &self,
+        filter: Document,
+        options: impl Into<Option<GridFsFindOptions>>,
+    ) -> Result<Cursor<FilesCollectionDocument>> {
+        let find_options = options.into().map(Into::into);
+        self.files().find(filter, find_options).await
+    }
+
+    /// Renames the file with the given `id` to `new_filename`.
+    pub async fn rename(&self, id: Bson, new_filename: impl AsRef<str>) -> Result<()> {
+        let update_result = self
+            .files()
+            .update_one(
+                doc! { "_id": id.clone() },
+                doc! { "$set": { "filename": new_filename.as_ref() } },
+                None,
+            )
+            .await?;
+
+        if update_result.matched_count == 0 {
+            return Err(ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+                identifier: GridFsFileIdentifier::Id(id),
+            })
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Drops all of the files and their associated chunks in this bucket.
+    pub async fn drop(&self) -> Result<()> {
+        self.files().drop(None).await?;
+        self.chunks().drop(None).await?;
+        self.inner.created_indexes.store(false, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Creates the indexes required by the GridFS spec on the files and chunks collections if
+    /// the files collection is empty. This is only checked once per bucket.
+    async fn create_indexes(&self) -> Result<()> {
+        if self.inner.created_indexes.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let find_options = FindOneOptions::builder()
+            .selection_criteria(SelectionCriteria::ReadPreference(ReadPreference::Primary))
+            .projection(doc! { "_id": 1 })
+            .build();
+        let is_empty = self
+            .files()
+            .clone_with_type::<Document>()
+            .find_one(None, find_options)
+            .await?
+            .is_none();
+
+        if is_empty {
+            let files_keys = doc! { "filename": 1, "uploadDate": 1 };
+            if !has_index(self.files(), &files_keys).await? {
+                let index = IndexModel::builder().keys(files_keys).build();
+                self.files().create_index(index, None).await?;
+            }
+
+            let chunks_keys = doc! { "files_id": 1, "n": 1 };
+            if !has_index(self.chunks(), &chunks_keys).await? {
+                let index = IndexModel::builder()
+                    .keys(chunks_keys)
+                    .options(IndexOptions::builder().unique(true).build())
+                    .build();
+                self.chunks().create_index(index, None).await?;
+            }
+        }
+
+        self.inner.created_indexes.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+/// Whether `coll` has an index with the given keys. Key values are compared numerically so that
+/// indexes created with e.g. `1.0` instead of `1` are also matched.
+async fn has_index<T>(coll: &Collection<T>, keys: &Document) -> Result<bool> {
+    let indexes = match coll.list_indexes(None).await {
+        Ok(cursor) => cursor.try_collect::<Vec<IndexModel>>().await?,
+        Err(e) if e.code() == Some(NAMESPACE_NOT_FOUND_CODE) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+
+    Ok(indexes.iter().any(|index| keys_match(&index.keys, keys)))
+}
+
+fn keys_match(actual: &Document, expected: &Document) -> bool {
+    actual.len() == expected.len()
+        && actual.iter().zip(expected.iter()).all(
+            |((actual_key, actual_value), (expected_key, expected_value))| {
+                actual_key == expected_key
+                    && bson_util::get_int(actual_value) == bson_util::get_int(expected_value)
+            },
+        )
+}
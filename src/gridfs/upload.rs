@@ -0,0 +1,153 @@
+use futures_io::AsyncRead;
+use futures_util::io::AsyncReadExt;
+
+use super::{options::GridFsUploadOptions, Chunk, FilesCollectionDocument, GridFsBucket};
+use crate::{
+    bson::{doc, oid::ObjectId, spec::BinarySubtype, Binary, Bson, DateTime},
+    error::{ErrorKind, Result},
+};
+
+impl GridFsBucket {
+    /// Uploads a user file to the bucket, reading its contents from `source`. The file is
+    /// assigned a newly generated [`ObjectId`], which is returned.
+    ///
+    /// Any chunks that were written before an error occurred are deleted before the error is
+    /// returned.
+    pub async fn upload_from_futures_0_3_reader(
+        &self,
+        filename: impl AsRef<str>,
+        source: impl AsyncRead + Unpin,
+        options: impl Into<Option<GridFsUploadOptions>>,
+    ) -> Result<ObjectId> {
+        let id = ObjectId::new();
+        self.upload_from_futures_0_3_reader_with_id(id.into(), filename, source, options)
+            .await?;
+        Ok(id)
+    }
+
+    /// Uploads a user file to the bucket with the given `id`, reading its contents from
+    /// `source`.
+    ///
+    /// Any chunks that were written before an error occurred are deleted before the error is
+    /// returned.
+    pub async fn upload_from_futures_0_3_reader_with_id(
+        &self,
+        id: Bson,
+        filename: impl AsRef<str>,
+        mut source: impl AsyncRead + Unpin,
+        options: impl Into<Option<GridFsUploadOptions>>,
+    ) -> Result<()> {
+        let options = options.into();
+        let chunk_size = options
+            .as_ref()
+            .and_then(|opts| opts.chunk_size_bytes)
+            .unwrap_or_else(|| self.chunk_size_bytes());
+        if chunk_size == 0 {
+            return Err(ErrorKind::InvalidArgument {
+                message: "chunk_size_bytes must be greater than 0".to_string(),
+            }
+            .into());
+        }
+
+        self.create_indexes().await?;
+
+        let length = match self.upload_chunks(&id, &mut source, chunk_size).await {
+            Ok(length) => length,
+            Err(error) => {
+                self.abort_upload(&id).await;
+                return Err(error);
+            }
+        };
+
+        let file = FilesCollectionDocument {
+            id: id.clone(),
+            length,
+            chunk_size,
+            upload_date: DateTime::now(),
+            filename: Some(filename.as_ref().to_string()),
+            metadata: options.and_then(|opts| opts.metadata),
+        };
+        if let Err(error) = self.files().insert_one(file, None).await {
+            self.abort_upload(&id).await;
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Uploads a user file to the bucket with the contents of `bytes`. The file is assigned a
+    /// newly generated [`ObjectId`], which is returned.
+    pub async fn upload_from_bytes(
+        &self,
+        filename: impl AsRef<str>,
+        bytes: impl AsRef<[u8]>,
+        options: impl Into<Option<GridFsUploadOptions>>,
+    ) -> Result<ObjectId> {
+        self.upload_from_futures_0_3_reader(filename, bytes.as_ref(), options)
+            .await
+    }
+
+    /// Reads `source` to completion, storing its contents in chunks of `chunk_size` bytes.
+    /// Returns the total number of bytes stored.
+    async fn upload_chunks(
+        &self,
+        id: &Bson,
+        source: &mut (impl AsyncRead + Unpin),
+        chunk_size: u32,
+    ) -> Result<u64> {
+        let mut buf = vec![0u8; chunk_size as usize];
+        let mut length = 0;
+        let mut n = 0;
+
+        loop {
+            let bytes_read = read_chunk(source, &mut buf).await?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            let chunk = Chunk {
+                id: ObjectId::new(),
+                files_id: id.clone(),
+                n,
+                data: Binary {
+                    subtype: BinarySubtype::Generic,
+                    bytes: buf[..bytes_read].to_vec(),
+                },
+            };
+            self.chunks().insert_one(chunk, None).await?;
+
+            length += bytes_read as u64;
+            n += 1;
+
+            if bytes_read < buf.len() {
+                break;
+            }
+        }
+
+        Ok(length)
+    }
+
+    /// Deletes the chunks written for the file with the given `id` after a failed upload.
+    async fn abort_upload(&self, id: &Bson) {
+        // The error from the upload is more useful than any error from the cleanup, so the latter
+        // is ignored.
+        let _: Result<_> = self
+            .chunks()
+            .delete_many(doc! { "files_id": id.clone() }, None)
+            .await;
+    }
+}
+
+/// Reads from `source` until `buf` is full or the end of `source` is reached, returning the number
+/// of bytes read.
+async fn read_chunk(source: &mut (impl AsyncRead + Unpin), buf: &mut [u8]) -> Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        let bytes_read = source.read(&mut buf[total..]).await?;
+        if bytes_read == 0 {
+            break;
+        }
+        total += bytes_read;
+    }
+    Ok(total)
+}
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -36,12 +37,17 @@ pub(crate) const LEGACY_HELLO_COMMAND_NAME_LOWERCASE: &str = "ismaster";
 /// If the server indicated `helloOk: true`, then `hello` will also be used.
 /// Otherwise, legacy hello will be used, and if it's unknown whether the server supports hello,
 /// the command also will contain `helloOk: true`.
+///
+/// If `awaitable_options` are provided, the command will be an awaitable hello that the server
+/// only replies to once its topology version changes or the max await time elapses, and the server
+/// will be allowed to stream subsequent replies using the `moreToCome` flag.
 pub(crate) fn hello_command(
     server_api: Option<&ServerApi>,
     load_balanced: Option<bool>,
     hello_ok: Option<bool>,
+    awaitable_options: Option<AwaitableHelloOptions>,
 ) -> Command {
-    let (command, command_name) = if server_api.is_some()
+    let (mut command, command_name) = if server_api.is_some()
         || matches!(load_balanced, Some(true))
         || matches!(hello_ok, Some(true))
     {
@@ -53,13 +59,40 @@ pub(crate) fn hello_command(
         }
         (cmd, LEGACY_HELLO_COMMAND_NAME)
     };
+
+    if let Some(ref awaitable_options) = awaitable_options {
+        command.insert(
+            "topologyVersion",
+            doc! {
+                "processId": awaitable_options.topology_version.process_id,
+                "counter": awaitable_options.topology_version.counter,
+            },
+        );
+        command.insert(
+            "maxAwaitTimeMS",
+            awaitable_options.max_await_time.as_millis() as i64,
+        );
+    }
+
     let mut command = Command::new(command_name.into(), "admin".into(), command);
     if let Some(server_api) = server_api {
         command.set_server_api(server_api);
     }
+    command.exhaust_allowed = awaitable_options.is_some();
     command
 }
 
+/// The options used to construct an awaitable hello command.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AwaitableHelloOptions {
+    /// The topology version most recently reported by the server.
+    pub(crate) topology_version: TopologyVersion,
+
+    /// The maximum amount of time the server should wait for its topology version to change
+    /// before replying.
+    pub(crate) max_await_time: Duration,
+}
+
 /// Execute a hello or legacy hello command, emiting events if a reference to the topology and a
 /// handler are provided.
 ///
@@ -74,6 +107,7 @@ pub(crate) async fn run_hello(
     emit_event(topology, handler, |handler| {
         let event = ServerHeartbeatStartedEvent {
             server_address: conn.address.clone(),
+            awaited: false,
         };
         handler.handle_server_heartbeat_started_event(event);
     });
@@ -101,6 +135,7 @@ pub(crate) async fn run_hello(
                     duration: round_trip_time,
                     reply,
                     server_address: conn.address.clone(),
+                    awaited: false,
                 };
                 handler.handle_server_heartbeat_succeeded_event(event);
             });
@@ -112,6 +147,7 @@ pub(crate) async fn run_hello(
                     duration: round_trip_time,
                     failure: err.clone(),
                     server_address: conn.address.clone(),
+                    awaited: false,
                 };
                 handler.handle_server_heartbeat_failed_event(event);
             });
@@ -232,8 +268,9 @@ pub(crate) struct HelloCommandResponse {
     /// If the connection is to a load balancer, the id of the selected backend.
     pub service_id: Option<ObjectId>,
 
-    /// For internal use.
-    pub topology_version: Option<Document>,
+    /// The server's current topology version, used for streaming monitoring and for ignoring
+    /// errors reported by stale connections.
+    pub topology_version: Option<TopologyVersion>,
 
     /// The maximum permitted size of a BSON wire protocol message.
     pub max_message_size_bytes: i32,
@@ -257,6 +294,7 @@ impl PartialEq for HelloCommandResponse {
             && self.max_bson_object_size == other.max_bson_object_size
             && self.max_write_batch_size == other.max_write_batch_size
             && self.service_id == other.service_id
+            && self.topology_version == other.topology_version
             && self.max_message_size_bytes == other.max_message_size_bytes
     }
 }
@@ -285,6 +323,28 @@ impl HelloCommandResponse {
     }
 }
 
+/// The version of a server's view of the topology, which the server increments whenever that view
+/// changes (e.g. on a state change such as a step-down).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TopologyVersion {
+    /// Identifies the server process; changes whenever the server restarts.
+    pub(crate) process_id: ObjectId,
+
+    /// Incremented whenever the server's topology view changes within a single process.
+    pub(crate) counter: i64,
+}
+
+/// Topology versions are only comparable when they come from the same server process.
+impl PartialOrd for TopologyVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.process_id != other.process_id {
+            return None;
+        }
+        Some(self.counter.cmp(&other.counter))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LastWrite {
@@ -55,7 +55,8 @@
 //! | `async-std-runtime`  | Enable support for the `async-std` runtime                                                                                            | `async-std` 1.0                     | no      |
 //! | `sync`               | Expose the synchronous API (`mongodb::sync`), using an async-std backend. Cannot be used with the `tokio-runtime` feature flag.       | `async-std` 1.0                     | no      |
 //! | `tokio-sync`         | Expose the synchronous API (`mongodb::sync`), using a tokio backend. Cannot be used with the `async-std-runtime` feature flag.        | `tokio` 1.0 with the `full` feature | no      |
-//! | `aws-auth`           | Enable support for the MONGODB-AWS authentication mechanism.                                                                          | `reqwest` 0.11                      | no      |
+//! | `aws-auth`           | Enable support for the MONGODB-AWS authentication mechanism.                                                                          | `serde_json` 1.0                    | no      |
+//! | `gssapi-auth`        | Enable support for the GSSAPI (Kerberos) authentication mechanism. Requires a system GSSAPI library on Unix.                          | `cross-krb5` 0.3                    | no      |
 //! | `bson-uuid-0_8`      | Enable support for v0.8 of the [`uuid`](docs.rs/uuid/0.8) crate in the public API of the re-exported `bson` crate.                    | n/a                                 | no      |
 //! | `bson-chrono-0_4`    | Enable support for v0.4 of the [`chrono`](docs.rs/chrono/0.4) crate in the public API of the re-exported `bson` crate.                | n/a                                 | no      |
 //! | `bson-serde_with`    | Enable support for the [`serde_with`](docs.rs/serde_with/latest) crate in the public API of the re-exported `bson` crate.             | `serde_with` 1.0                    | no      |
@@ -63,6 +64,9 @@
 //! | `zstd-compression`   | Enable support for compressing messages with [`zstd`](http://facebook.github.io/zstd/).  This flag requires Rust version 1.54.        | `zstd` 0.9.0                        | no      |
 //! | `snappy-compression` | Enable support for compressing messages with [`snappy`](http://google.github.io/snappy/)                                              | `snap` 1.0.5                        | no      |
 //! | `openssl-tls`        | Switch TLS connection handling to use ['openssl'](https://docs.rs/openssl/0.10.38/).                                                  | `openssl` 0.10.38                   | no      |
+//! | `csfle`              | Enable support for client-side field level encryption via the `client_encryption` module. This flag requires Rust version 1.56.       | `aes` 0.8, `cbc` 0.1                | no      |
+//! | `cert-revocation`    | Enable checking server certificates against OCSP responses, and against CRLs via the `tlsCRLFile` option when using rustls. This flag requires Rust version 1.57. | `ring` 0.16, `x509-parser` 0.14 | no      |
+//! | `tracing-unstable`   | Emit [`tracing`](https://docs.rs/tracing) events for commands, connection pools, server selection and SDAM. See [below](#tracing).    | `tracing` 0.1                       | no      |
 //!
 //! # Example Usage
 //!
@@ -281,6 +285,27 @@
 //! # }
 //! ```
 //!
+//! ## Tracing
+//!
+//! When the `tracing-unstable` feature flag is enabled, the driver emits
+//! [`tracing`](https://docs.rs/tracing) events describing what it is doing. Each event is emitted
+//! with a target naming the component it describes, which can be used to filter the events:
+//!
+//! | Target                       | Level              | Description                                                          |
+//! |:-----------------------------|:-------------------|:---------------------------------------------------------------------|
+//! | `mongodb::command`           | debug              | Commands being started, succeeding or failing.                       |
+//! | `mongodb::connection`        | debug              | The lifecycle of connection pools and their connections.             |
+//! | `mongodb::server_selection`  | debug, info        | Server selection attempts, and waits for a suitable server.          |
+//! | `mongodb::topology`          | debug              | Changes to the topology, server monitoring and heartbeats.           |
+//!
+//! Commands, replies and heartbeat replies are included in the events as extended JSON, truncated
+//! to [`ClientOptions::tracing_max_document_length_bytes`](options::ClientOptions) bytes (1000 by
+//! default). The names, levels and fields of the events are not yet stable and may change in a
+//! minor release.
+//!
+//! Command, connection and topology events are only emitted if their targets are enabled when the
+//! [`Client`] is created, so a subscriber should be installed before creating it.
+//!
 //! ## Minimum supported Rust version (MSRV)
 //!
 //! The MSRV for this crate is currently 1.53.0. This will be rarely be increased, and if it ever is,
@@ -303,14 +328,15 @@
 #![cfg_attr(test, type_length_limit = "80000000")]
 #![doc(html_root_url = "https://docs.rs/mongodb/2.2.0")]
 
-#[cfg(all(feature = "aws-auth", feature = "async-std-runtime"))]
-compile_error!("The `aws-auth` feature flag is only supported on the tokio runtime.");
-
 #[macro_use]
 pub mod options;
 
 pub use ::bson;
 
+#[cfg(feature = "csfle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csfle")))]
+pub use crate::client::csfle::client_encryption;
+
 mod bson_util;
 pub mod change_stream;
 mod client;
@@ -323,6 +349,7 @@ mod cursor;
 mod db;
 pub mod error;
 pub mod event;
+pub mod gridfs;
 mod index;
 mod hello;
 mod operation;
@@ -336,6 +363,8 @@ mod srv;
 pub mod sync;
 #[cfg(test)]
 mod test;
+#[cfg(feature = "tracing-unstable")]
+mod trace;
 
 #[cfg(test)]
 #[macro_use]
@@ -0,0 +1,446 @@
+#[cfg(test)]
+mod test;
+
+use std::{collections::HashMap, time::Duration};
+
+use bson::{RawArrayBuf, RawDocumentBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bson::{doc, to_document, Bson, Document},
+    bson_util,
+    cmap::{Command, RawCommandResponse, StreamDescription},
+    collation::Collation,
+    error::{BulkWriteFailure, Error, ErrorKind, Result},
+    operation::{
+        append_options,
+        insert::get_or_prepend_id,
+        remove_empty_write_concern,
+        Operation,
+        Retryability,
+        WriteResponseBody,
+    },
+    options::{BulkWriteOptions, Hint, UpdateModifications, WriteConcern, WriteModel},
+    results::BulkWriteResult,
+    Namespace,
+};
+
+/// The write command used to execute a batch of write models.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BatchKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl BatchKind {
+    fn command_name(self) -> &'static str {
+        match self {
+            BatchKind::Insert => "insert",
+            BatchKind::Update => "update",
+            BatchKind::Delete => "delete",
+        }
+    }
+
+    /// The name of the command field that holds the statements of the batch.
+    fn statements_field(self) -> &'static str {
+        match self {
+            BatchKind::Insert => "documents",
+            BatchKind::Update => "updates",
+            BatchKind::Delete => "deletes",
+        }
+    }
+}
+
+impl<T> WriteModel<T> {
+    fn batch_kind(&self) -> BatchKind {
+        match self {
+            WriteModel::InsertOne(_) => BatchKind::Insert,
+            WriteModel::UpdateOne(_) | WriteModel::UpdateMany(_) | WriteModel::ReplaceOne(_) => {
+                BatchKind::Update
+            }
+            WriteModel::DeleteOne(_) | WriteModel::DeleteMany(_) => BatchKind::Delete,
+        }
+    }
+
+    /// Whether this model may affect more than one document.
+    pub(crate) fn is_multi(&self) -> bool {
+        matches!(self, WriteModel::UpdateMany(_) | WriteModel::DeleteMany(_))
+    }
+}
+
+impl<T: Serialize> WriteModel<T> {
+    /// Serializes this model into a statement for its write command, returning the `_id` of the
+    /// document to insert for insert models.
+    fn to_statement(&self) -> Result<(RawDocumentBuf, Option<Bson>)> {
+        let statement = match self {
+            WriteModel::InsertOne(model) => {
+                let mut doc = bson::to_raw_document_buf(&model.document)?;
+                let id = get_or_prepend_id(&mut doc)?;
+                return Ok((doc, Some(id)));
+            }
+            WriteModel::UpdateOne(model) => {
+                if let UpdateModifications::Document(ref d) = model.update {
+                    bson_util::update_document_check(d)?;
+                }
+                update_statement(
+                    &model.filter,
+                    model.update.to_bson(),
+                    false,
+                    model.upsert,
+                    model.array_filters.as_ref(),
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::UpdateMany(model) => {
+                if let UpdateModifications::Document(ref d) = model.update {
+                    bson_util::update_document_check(d)?;
+                }
+                update_statement(
+                    &model.filter,
+                    model.update.to_bson(),
+                    true,
+                    model.upsert,
+                    model.array_filters.as_ref(),
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::ReplaceOne(model) => {
+                let replacement = to_document(&model.replacement)?;
+                bson_util::replacement_document_check(&replacement)?;
+                update_statement(
+                    &model.filter,
+                    Bson::Document(replacement),
+                    false,
+                    model.upsert,
+                    None,
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::DeleteOne(model) => delete_statement(
+                &model.filter,
+                1,
+                model.collation.as_ref(),
+                model.hint.as_ref(),
+            )?,
+            WriteModel::DeleteMany(model) => delete_statement(
+                &model.filter,
+                0,
+                model.collation.as_ref(),
+                model.hint.as_ref(),
+            )?,
+        };
+
+        Ok((RawDocumentBuf::from_document(&statement)?, None))
+    }
+}
+
+fn update_statement(
+    filter: &Document,
+    update: Bson,
+    multi: bool,
+    upsert: Option<bool>,
+    array_filters: Option<&Vec<Document>>,
+    collation: Option<&Collation>,
+    hint: Option<&Hint>,
+) -> Result<Document> {
+    let mut statement = doc! {
+        "q": filter.clone(),
+        "u": update,
+    };
+
+    if multi {
+        statement.insert("multi", true);
+    }
+
+    if let Some(upsert) = upsert {
+        statement.insert("upsert", upsert);
+    }
+
+    if let Some(array_filters) = array_filters {
+        statement.insert("arrayFilters", bson_util::to_bson_array(array_filters));
+    }
+
+    if let Some(collation) = collation {
+        statement.insert("collation", bson::to_bson(collation)?);
+    }
+
+    if let Some(hint) = hint {
+        statement.insert("hint", hint.to_bson());
+    }
+
+    Ok(statement)
+}
+
+fn delete_statement(
+    filter: &Document,
+    limit: u32,
+    collation: Option<&Collation>,
+    hint: Option<&Hint>,
+) -> Result<Document> {
+    let mut statement = doc! {
+        "q": filter.clone(),
+        "limit": limit,
+    };
+
+    if let Some(collation) = collation {
+        statement.insert("collation", bson::to_bson(collation)?);
+    }
+
+    if let Some(hint) = hint {
+        statement.insert("hint", hint.to_bson());
+    }
+
+    Ok(statement)
+}
+
+/// Executes the leading models of `models` that share a write command as a single batch. The
+/// batch is limited by the `maxWriteBatchSize` and message size reported by the server, so
+/// callers must execute the remaining models separately.
+#[derive(Debug)]
+pub(crate) struct BulkWrite<'a, T> {
+    ns: Namespace,
+    models: &'a [WriteModel<T>],
+    options: Option<BulkWriteOptions>,
+
+    /// The statements built for the batch, kept so that retries send the same documents
+    /// (including any generated `_id`s).
+    statements: Option<RawArrayBuf>,
+    n_attempted: usize,
+    inserted_ids: Vec<Bson>,
+}
+
+impl<'a, T> BulkWrite<'a, T> {
+    pub(crate) fn new(
+        ns: Namespace,
+        models: &'a [WriteModel<T>],
+        options: Option<BulkWriteOptions>,
+    ) -> Self {
+        Self {
+            ns,
+            models,
+            options,
+            statements: None,
+            n_attempted: 0,
+            inserted_ids: vec![],
+        }
+    }
+
+    fn is_ordered(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|o| o.ordered)
+            .unwrap_or(true)
+    }
+
+    fn batch_kind(&self) -> BatchKind {
+        self.models
+            .first()
+            .map(WriteModel::batch_kind)
+            .unwrap_or(BatchKind::Insert)
+    }
+
+    /// The models that can be sent in the same write command as the first model.
+    fn candidates(&self) -> impl Iterator<Item = &WriteModel<T>> {
+        let kind = self.batch_kind();
+        self.models
+            .iter()
+            .take_while(move |m| m.batch_kind() == kind)
+    }
+}
+
+impl<'a, T: Serialize> Operation for BulkWrite<'a, T> {
+    type O = BulkWriteBatchResult;
+    type Command = Document;
+
+    const NAME: &'static str = "bulkWrite";
+
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        let kind = self.batch_kind();
+
+        if self.statements.is_none() {
+            let max_size = std::cmp::min(
+                description.max_bson_object_size,
+                description.max_message_size_bytes as i64,
+            ) as u64;
+            let mut statements = RawArrayBuf::new();
+            let mut inserted_ids = Vec::new();
+            let mut size = 0;
+            let mut n_attempted = 0;
+
+            for (i, model) in self
+                .candidates()
+                .take(description.max_write_batch_size as usize)
+                .enumerate()
+            {
+                let (statement, id) = model.to_statement()?;
+                let statement_size =
+                    bson_util::array_entry_size_bytes(i, statement.as_bytes().len());
+
+                if size + statement_size > max_size {
+                    break;
+                }
+
+                inserted_ids.extend(id);
+                statements.push(statement);
+                size += statement_size;
+                n_attempted += 1;
+            }
+
+            if n_attempted == 0 {
+                return Err(ErrorKind::InvalidArgument {
+                    message: "write model exceeds maxBsonObjectSize".to_string(),
+                }
+                .into());
+            }
+
+            self.statements = Some(statements);
+            self.n_attempted = n_attempted;
+            self.inserted_ids = inserted_ids;
+        }
+
+        let mut options = self.options.clone().unwrap_or_default();
+        options.ordered = Some(self.is_ordered());
+        remove_empty_write_concern!(Some(&mut options));
+
+        let mut body = doc! {
+            kind.command_name(): self.ns.coll.clone(),
+        };
+        append_options(&mut body, Some(&options))?;
+
+        Ok(Command::new(
+            kind.command_name().to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn serialize_command(&mut self, cmd: Command<Self::Command>) -> Result<Vec<u8>> {
+        let mut doc = bson::to_raw_document_buf(&cmd)?;
+        // the statements are appended separately so that they don't need to be converted back
+        // into a `Document` each time the command is built.
+        if let Some(ref statements) = self.statements {
+            doc.append(self.batch_kind().statements_field(), statements.clone());
+        }
+        Ok(doc.into_bytes())
+    }
+
+    fn handle_response(
+        &self,
+        raw_response: RawCommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let response: WriteResponseBody<BulkWriteBody> = raw_response.body_utf8_lossy()?;
+
+        let mut result = BulkWriteResult::default();
+        match self.batch_kind() {
+            BatchKind::Insert => {
+                result.inserted_count = response.n;
+
+                if self.is_ordered() {
+                    // in ordered inserts, only the first n were attempted.
+                    result.inserted_ids = self
+                        .inserted_ids
+                        .iter()
+                        .cloned()
+                        .enumerate()
+                        .take(response.n as usize)
+                        .collect();
+                } else {
+                    // for unordered, add all the attempted ids and then remove the ones that
+                    // have associated write errors.
+                    result.inserted_ids = self.inserted_ids.iter().cloned().enumerate().collect();
+
+                    if let Some(write_errors) = response.write_errors.as_ref() {
+                        for err in write_errors {
+                            result.inserted_ids.remove(&err.index);
+                        }
+                    }
+                }
+            }
+            BatchKind::Update => {
+                let upserted: HashMap<usize, Bson> = response
+                    .upserted
+                    .iter()
+                    .flatten()
+                    .map(|u| (u.index, u.id.clone()))
+                    .collect();
+
+                result.upserted_count = upserted.len() as u64;
+                result.matched_count = response.n.saturating_sub(result.upserted_count);
+                result.modified_count = response.n_modified;
+                result.upserted_ids = upserted;
+            }
+            BatchKind::Delete => {
+                result.deleted_count = response.n;
+            }
+        }
+
+        if response.write_errors.is_some() || response.write_concern_error.is_some() {
+            return Err(Error::new(
+                ErrorKind::BulkWrite(BulkWriteFailure {
+                    write_errors: response.write_errors,
+                    write_concern_error: response.write_concern_error,
+                    n_attempted: self.n_attempted,
+                    partial_result: Some(result),
+                    ..BulkWriteFailure::new()
+                }),
+                response.labels,
+            ));
+        }
+
+        Ok(BulkWriteBatchResult {
+            result,
+            n_attempted: self.n_attempted,
+        })
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options.as_ref().and_then(|o| o.write_concern.as_ref())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
+    fn retryability(&self) -> Retryability {
+        if self.candidates().any(WriteModel::is_multi) {
+            Retryability::None
+        } else {
+            Retryability::Write
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.batch_kind().command_name()
+    }
+}
+
+/// The result of executing a single batch of a bulk write. The indexes in `result` are relative to
+/// the start of the batch.
+#[derive(Debug)]
+pub(crate) struct BulkWriteBatchResult {
+    pub(crate) result: BulkWriteResult,
+
+    /// The number of models included in the batch.
+    pub(crate) n_attempted: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkWriteBody {
+    #[serde(rename = "nModified", default)]
+    n_modified: u64,
+
+    upserted: Option<Vec<Upserted>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Upserted {
+    index: usize,
+
+    #[serde(rename = "_id")]
+    id: Bson,
+}
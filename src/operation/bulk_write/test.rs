@@ -0,0 +1,261 @@
+use crate::{
+    bson::{doc, Bson, Document},
+    cmap::StreamDescription,
+    error::ErrorKind,
+    operation::{test::handle_response_test, BulkWrite, Operation, Retryability},
+    options::{
+        BulkWriteOptions,
+        DeleteManyModel,
+        DeleteOneModel,
+        InsertOneModel,
+        ReplaceOneModel,
+        UpdateManyModel,
+        UpdateOneModel,
+        WriteModel,
+    },
+    Namespace,
+};
+
+fn ns() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+fn mixed_models() -> Vec<WriteModel<Document>> {
+    vec![
+        InsertOneModel::builder()
+            .document(doc! { "x": 1 })
+            .build()
+            .into(),
+        InsertOneModel::builder()
+            .document(doc! { "_id": 2, "x": 2 })
+            .build()
+            .into(),
+        UpdateOneModel::builder()
+            .filter(doc! { "x": 1 })
+            .update(doc! { "$set": { "y": 1 } })
+            .upsert(true)
+            .build()
+            .into(),
+        ReplaceOneModel::builder()
+            .filter(doc! { "x": 2 })
+            .replacement(doc! { "x": 3 })
+            .build()
+            .into(),
+        DeleteOneModel::builder()
+            .filter(doc! { "x": 3 })
+            .build()
+            .into(),
+    ]
+}
+
+fn serialize<T: serde::Serialize>(
+    op: &mut BulkWrite<T>,
+    description: &StreamDescription,
+) -> Document {
+    let cmd = op.build(description).unwrap();
+    let serialized = op.serialize_command(cmd).unwrap();
+    Document::from_reader(serialized.as_slice()).unwrap()
+}
+
+#[test]
+fn build_batches_by_command() {
+    let models = mixed_models();
+    let description = StreamDescription::new_testing();
+
+    let mut op = BulkWrite::new(ns(), &models, None);
+    let cmd_doc = serialize(&mut op, &description);
+    assert_eq!(cmd_doc.get_str("insert"), Ok("test_coll"));
+    assert_eq!(cmd_doc.get_bool("ordered"), Ok(true));
+    let documents = cmd_doc.get_array("documents").unwrap();
+    assert_eq!(documents.len(), 2);
+    assert!(documents[0]
+        .as_document()
+        .unwrap()
+        .get_object_id("_id")
+        .is_ok());
+    assert_eq!(
+        documents[1].as_document().unwrap().get("_id"),
+        Some(&Bson::Int32(2))
+    );
+
+    let mut op = BulkWrite::new(ns(), &models[2..], None);
+    let cmd_doc = serialize(&mut op, &description);
+    assert_eq!(cmd_doc.get_str("update"), Ok("test_coll"));
+    assert_eq!(
+        cmd_doc.get_array("updates").unwrap(),
+        &vec![
+            Bson::Document(doc! { "q": { "x": 1 }, "u": { "$set": { "y": 1 } }, "upsert": true }),
+            Bson::Document(doc! { "q": { "x": 2 }, "u": { "x": 3 } }),
+        ]
+    );
+
+    let mut op = BulkWrite::new(ns(), &models[4..], None);
+    let cmd_doc = serialize(&mut op, &description);
+    assert_eq!(cmd_doc.get_str("delete"), Ok("test_coll"));
+    assert_eq!(
+        cmd_doc.get_array("deletes").unwrap(),
+        &vec![Bson::Document(doc! { "q": { "x": 3 }, "limit": 1 })]
+    );
+}
+
+#[test]
+fn build_respects_max_write_batch_size() {
+    let models: Vec<WriteModel<Document>> = (0..5)
+        .map(|i| {
+            InsertOneModel::builder()
+                .document(doc! { "x": i })
+                .build()
+                .into()
+        })
+        .collect();
+    let mut description = StreamDescription::new_testing();
+    description.max_write_batch_size = 3;
+
+    let mut op = BulkWrite::new(ns(), &models, None);
+    let cmd_doc = serialize(&mut op, &description);
+    assert_eq!(cmd_doc.get_array("documents").unwrap().len(), 3);
+
+    let result = handle_response_test(&op, doc! { "ok": 1, "n": 3 }).unwrap();
+    assert_eq!(result.n_attempted, 3);
+    assert_eq!(result.result.inserted_count, 3);
+    assert_eq!(result.result.inserted_ids.len(), 3);
+}
+
+#[test]
+fn build_respects_max_message_size() {
+    let models: Vec<WriteModel<Document>> = (0..5)
+        .map(|i| {
+            InsertOneModel::builder()
+                .document(doc! { "_id": i, "s": "a".repeat(100) })
+                .build()
+                .into()
+        })
+        .collect();
+    let mut description = StreamDescription::new_testing();
+    description.max_message_size_bytes = 300;
+
+    let mut op = BulkWrite::new(ns(), &models, None);
+    let cmd_doc = serialize(&mut op, &description);
+    assert_eq!(cmd_doc.get_array("documents").unwrap().len(), 2);
+}
+
+#[test]
+fn build_reuses_generated_ids() {
+    let models: Vec<WriteModel<Document>> = vec![InsertOneModel::builder()
+        .document(doc! { "x": 1 })
+        .build()
+        .into()];
+    let description = StreamDescription::new_testing();
+
+    let mut op = BulkWrite::new(ns(), &models, None);
+    let first = serialize(&mut op, &description);
+    let second = serialize(&mut op, &description);
+    assert_eq!(first.get_array("documents"), second.get_array("documents"));
+}
+
+#[test]
+fn build_ordered() {
+    let models = mixed_models();
+    let mut op = BulkWrite::new(
+        ns(),
+        &models,
+        Some(BulkWriteOptions::builder().ordered(false).build()),
+    );
+    let cmd_doc = serialize(&mut op, &StreamDescription::new_testing());
+    assert_eq!(cmd_doc.get_bool("ordered"), Ok(false));
+}
+
+#[test]
+fn build_invalid_update() {
+    let models: Vec<WriteModel<Document>> = vec![UpdateOneModel::builder()
+        .filter(doc! {})
+        .update(doc! { "x": 1 })
+        .build()
+        .into()];
+    let mut op = BulkWrite::new(ns(), &models, None);
+    assert!(op.build(&StreamDescription::new_testing()).is_err());
+}
+
+#[test]
+fn retryability() {
+    let models = mixed_models();
+    let op = BulkWrite::new(ns(), &models, None);
+    assert_eq!(op.retryability(), Retryability::Write);
+
+    let models: Vec<WriteModel<Document>> = vec![
+        DeleteOneModel::builder().filter(doc! {}).build().into(),
+        DeleteManyModel::builder().filter(doc! {}).build().into(),
+    ];
+    let op = BulkWrite::new(ns(), &models, None);
+    assert_eq!(op.retryability(), Retryability::None);
+
+    let models: Vec<WriteModel<Document>> = vec![UpdateManyModel::builder()
+        .filter(doc! {})
+        .update(doc! { "$inc": { "x": 1 } })
+        .build()
+        .into()];
+    let op = BulkWrite::new(ns(), &models, None);
+    assert_eq!(op.retryability(), Retryability::None);
+}
+
+#[test]
+fn handle_update_response() {
+    let models = mixed_models();
+    let mut op = BulkWrite::new(ns(), &models[2..], None);
+    op.build(&StreamDescription::new_testing()).unwrap();
+
+    let result = handle_response_test(
+        &op,
+        doc! {
+            "ok": 1,
+            "n": 2,
+            "nModified": 1,
+            "upserted": [{ "index": 0, "_id": 10 }],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(result.n_attempted, 2);
+    assert_eq!(result.result.matched_count, 1);
+    assert_eq!(result.result.modified_count, 1);
+    assert_eq!(result.result.upserted_count, 1);
+    assert_eq!(result.result.upserted_ids.get(&0), Some(&Bson::Int32(10)));
+}
+
+#[test]
+fn handle_write_errors() {
+    let models = mixed_models();
+    let mut op = BulkWrite::new(
+        ns(),
+        &models,
+        Some(BulkWriteOptions::builder().ordered(false).build()),
+    );
+    op.build(&StreamDescription::new_testing()).unwrap();
+
+    let error = handle_response_test(
+        &op,
+        doc! {
+            "ok": 1,
+            "n": 1,
+            "writeErrors": [
+                { "index": 1, "code": 11000, "errmsg": "duplicate key" },
+            ],
+        },
+    )
+    .unwrap_err();
+
+    match *error.kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            assert_eq!(failure.n_attempted, 2);
+            assert_eq!(failure.write_errors.as_ref().unwrap()[0].index, 1);
+            let partial_result = failure.partial_result.as_ref().unwrap();
+            assert_eq!(partial_result.inserted_count, 1);
+            assert!(partial_result.inserted_ids.contains_key(&0));
+            assert!(!partial_result.inserted_ids.contains_key(&1));
+        }
+        ref other => panic!("expected bulk write error, got {:?}", other),
+    }
+}
@@ -0,0 +1,529 @@
+#[cfg(test)]
+mod test;
+
+use std::{collections::HashMap, time::Duration};
+
+use bson::RawDocumentBuf;
+use serde::Deserialize;
+
+use crate::{
+    bson::{doc, to_document, Bson, Document},
+    bson_util,
+    cmap::{Command, DocumentSequence, RawCommandResponse, StreamDescription},
+    collation::Collation,
+    cursor::CursorSpecification,
+    error::{ErrorKind, Result, WriteConcernError, WriteError},
+    operation::{
+        append_options,
+        insert::get_or_prepend_id,
+        remove_empty_write_concern,
+        CursorInfo,
+        Operation,
+        Retryability,
+    },
+    options::{
+        ClientBulkWriteOptions,
+        Hint,
+        NamespacedWriteModel,
+        UpdateModifications,
+        WriteConcern,
+        WriteModel,
+    },
+    results::{ClientBulkWriteResult, DeleteResult, InsertOneResult, UpdateResult},
+};
+
+/// The minimum wire version of servers that support the `bulkWrite` command (MongoDB 8.0).
+const SERVER_8_0_0_WIRE_VERSION: i32 = 25;
+
+/// The number of bytes reserved in each message for the command document itself, leaving the
+/// rest of the message for the `ops` and `nsInfo` document sequences.
+const COMMAND_OVERHEAD_SIZE: usize = 1000;
+
+impl WriteModel<Document> {
+    /// Serializes this model into an entry of the `ops` document sequence of a `bulkWrite`
+    /// command, returning the `_id` of the document to insert for insert models.
+    fn to_bulk_write_op(&self, ns_index: usize) -> Result<(RawDocumentBuf, Option<Bson>)> {
+        let op = match self {
+            WriteModel::InsertOne(model) => {
+                let mut document = RawDocumentBuf::from_document(&model.document)?;
+                let id = get_or_prepend_id(&mut document)?;
+
+                let mut op = RawDocumentBuf::new();
+                op.append("insert", ns_index as i32);
+                op.append("document", document);
+                return Ok((op, Some(id)));
+            }
+            WriteModel::UpdateOne(model) => {
+                if let UpdateModifications::Document(ref d) = model.update {
+                    bson_util::update_document_check(d)?;
+                }
+                update_op(
+                    ns_index,
+                    &model.filter,
+                    model.update.to_bson(),
+                    false,
+                    model.upsert,
+                    model.array_filters.as_ref(),
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::UpdateMany(model) => {
+                if let UpdateModifications::Document(ref d) = model.update {
+                    bson_util::update_document_check(d)?;
+                }
+                update_op(
+                    ns_index,
+                    &model.filter,
+                    model.update.to_bson(),
+                    true,
+                    model.upsert,
+                    model.array_filters.as_ref(),
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::ReplaceOne(model) => {
+                let replacement = to_document(&model.replacement)?;
+                bson_util::replacement_document_check(&replacement)?;
+                update_op(
+                    ns_index,
+                    &model.filter,
+                    Bson::Document(replacement),
+                    false,
+                    model.upsert,
+                    None,
+                    model.collation.as_ref(),
+                    model.hint.as_ref(),
+                )?
+            }
+            WriteModel::DeleteOne(model) => delete_op(
+                ns_index,
+                &model.filter,
+                false,
+                model.collation.as_ref(),
+                model.hint.as_ref(),
+            )?,
+            WriteModel::DeleteMany(model) => delete_op(
+                ns_index,
+                &model.filter,
+                true,
+                model.collation.as_ref(),
+                model.hint.as_ref(),
+            )?,
+        };
+
+        Ok((RawDocumentBuf::from_document(&op)?, None))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_op(
+    ns_index: usize,
+    filter: &Document,
+    update: Bson,
+    multi: bool,
+    upsert: Option<bool>,
+    array_filters: Option<&Vec<Document>>,
+    collation: Option<&Collation>,
+    hint: Option<&Hint>,
+) -> Result<Document> {
+    let mut op = doc! {
+        "update": ns_index as i32,
+        "filter": filter.clone(),
+        "updateMods": update,
+        "multi": multi,
+    };
+
+    if let Some(upsert) = upsert {
+        op.insert("upsert", upsert);
+    }
+
+    if let Some(array_filters) = array_filters {
+        op.insert("arrayFilters", bson_util::to_bson_array(array_filters));
+    }
+
+    if let Some(collation) = collation {
+        op.insert("collation", bson::to_bson(collation)?);
+    }
+
+    if let Some(hint) = hint {
+        op.insert("hint", hint.to_bson());
+    }
+
+    Ok(op)
+}
+
+fn delete_op(
+    ns_index: usize,
+    filter: &Document,
+    multi: bool,
+    collation: Option<&Collation>,
+    hint: Option<&Hint>,
+) -> Result<Document> {
+    let mut op = doc! {
+        "delete": ns_index as i32,
+        "filter": filter.clone(),
+        "multi": multi,
+    };
+
+    if let Some(collation) = collation {
+        op.insert("collation", bson::to_bson(collation)?);
+    }
+
+    if let Some(hint) = hint {
+        op.insert("hint", hint.to_bson());
+    }
+
+    Ok(op)
+}
+
+/// The `ops` and `nsInfo` document sequences built for a batch.
+#[derive(Debug)]
+struct Batch {
+    ops: Vec<RawDocumentBuf>,
+    ns_info: Vec<RawDocumentBuf>,
+    inserted_ids: HashMap<usize, Bson>,
+}
+
+/// Executes as many of the leading models of `models` as fit in a single `bulkWrite` command.
+/// The batch is limited by the `maxWriteBatchSize` and `maxMessageSizeBytes` reported by the
+/// server, so callers must execute the remaining models separately.
+#[derive(Debug)]
+pub(crate) struct ClientBulkWrite<'a> {
+    models: &'a [NamespacedWriteModel],
+    options: Option<ClientBulkWriteOptions>,
+
+    /// The batch built for this operation, kept so that retries send the same documents
+    /// (including any generated `_id`s).
+    batch: Option<Batch>,
+}
+
+impl<'a> ClientBulkWrite<'a> {
+    pub(crate) fn new(
+        models: &'a [NamespacedWriteModel],
+        options: Option<ClientBulkWriteOptions>,
+    ) -> Self {
+        Self {
+            models,
+            options,
+            batch: None,
+        }
+    }
+
+    fn is_ordered(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|o| o.ordered)
+            .unwrap_or(true)
+    }
+
+    fn is_verbose(&self) -> bool {
+        self.options
+            .as_ref()
+            .and_then(|o| o.verbose_results)
+            .unwrap_or(false)
+    }
+
+    fn build_batch(&self, description: &StreamDescription) -> Result<Batch> {
+        let max_payload_size =
+            (description.max_message_size_bytes as usize).saturating_sub(COMMAND_OVERHEAD_SIZE);
+        let mut ops = Vec::new();
+        let mut ns_info = Vec::new();
+        let mut ns_indexes: HashMap<String, usize> = HashMap::new();
+        let mut inserted_ids = HashMap::new();
+        let mut size = 0;
+
+        for (i, model) in self
+            .models
+            .iter()
+            .take(description.max_write_batch_size as usize)
+            .enumerate()
+        {
+            let ns = model.namespace.to_string();
+            let (ns_index, ns_doc) = match ns_indexes.get(&ns) {
+                Some(index) => (*index, None),
+                None => {
+                    let mut ns_doc = RawDocumentBuf::new();
+                    ns_doc.append("ns", ns.clone());
+                    (ns_info.len(), Some(ns_doc))
+                }
+            };
+
+            let (op, id) = model.model.to_bulk_write_op(ns_index)?;
+            let op_size = op.as_bytes().len() + ns_doc.as_ref().map_or(0, |d| d.as_bytes().len());
+
+            if size + op_size > max_payload_size {
+                break;
+            }
+
+            if let Some(ns_doc) = ns_doc {
+                ns_indexes.insert(ns, ns_index);
+                ns_info.push(ns_doc);
+            }
+            if let Some(id) = id {
+                inserted_ids.insert(i, id);
+            }
+            ops.push(op);
+            size += op_size;
+        }
+
+        if ops.is_empty() {
+            return Err(ErrorKind::InvalidArgument {
+                message: "write model exceeds maxMessageSizeBytes".to_string(),
+            }
+            .into());
+        }
+
+        Ok(Batch {
+            ops,
+            ns_info,
+            inserted_ids,
+        })
+    }
+}
+
+impl<'a> Operation for ClientBulkWrite<'a> {
+    type O = (ClientBulkWriteBatchResult, CursorSpecification);
+    type Command = Document;
+
+    const NAME: &'static str = "bulkWrite";
+
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        if description.max_wire_version.unwrap_or(0) < SERVER_8_0_0_WIRE_VERSION {
+            return Err(ErrorKind::IncompatibleServer {
+                message: "Client::bulk_write requires MongoDB 8.0 or later".to_string(),
+            }
+            .into());
+        }
+
+        if self.batch.is_none() {
+            self.batch = Some(self.build_batch(description)?);
+        }
+
+        let mut options = self.options.clone().unwrap_or_default();
+        options.ordered = Some(self.is_ordered());
+        remove_empty_write_concern!(Some(&mut options));
+
+        let mut body = doc! {
+            Self::NAME: 1,
+            "errorsOnly": !self.is_verbose(),
+        };
+        append_options(&mut body, Some(&options))?;
+
+        let mut command = Command::new(Self::NAME.to_string(), "admin".to_string(), body);
+        if let Some(ref batch) = self.batch {
+            command.document_sequences = vec![
+                DocumentSequence::new("ops", batch.ops.clone()),
+                DocumentSequence::new("nsInfo", batch.ns_info.clone()),
+            ];
+        }
+        Ok(command)
+    }
+
+    fn handle_response(
+        &self,
+        raw_response: RawCommandResponse,
+        description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let response: ClientBulkWriteResponseBody = raw_response.body_utf8_lossy()?;
+        let batch = self.batch.as_ref();
+
+        let result = ClientBulkWriteBatchResult {
+            summary: ClientBulkWriteResult {
+                inserted_count: response.n_inserted,
+                upserted_count: response.n_upserted,
+                matched_count: response.n_matched,
+                modified_count: response.n_modified,
+                deleted_count: response.n_deleted,
+                ..Default::default()
+            },
+            write_concern_error: response.write_concern_error,
+            n_attempted: batch.map_or(0, |b| b.ops.len()),
+            inserted_ids: batch.map(|b| b.inserted_ids.clone()).unwrap_or_default(),
+        };
+        let cursor = CursorSpecification::new(
+            response.cursor,
+            description.server_address.clone(),
+            None,
+            None,
+        );
+
+        Ok((result, cursor))
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options.as_ref().and_then(|o| o.write_concern.as_ref())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
+    fn retryability(&self) -> Retryability {
+        // This is first called before the batch is built, so all of the remaining models are
+        // considered until the batch is known.
+        let n_models = self
+            .batch
+            .as_ref()
+            .map_or(self.models.len(), |b| b.ops.len());
+        if self.models[..n_models].iter().any(|m| m.model.is_multi()) {
+            Retryability::None
+        } else {
+            Retryability::Write
+        }
+    }
+}
+
+/// The summary of a single `bulkWrite` command. The indexes in `inserted_ids` are relative to the
+/// start of the batch.
+#[derive(Debug)]
+pub(crate) struct ClientBulkWriteBatchResult {
+    pub(crate) summary: ClientBulkWriteResult,
+    pub(crate) write_concern_error: Option<WriteConcernError>,
+
+    /// The number of models included in the batch.
+    pub(crate) n_attempted: usize,
+    pub(crate) inserted_ids: HashMap<usize, Bson>,
+}
+
+impl ClientBulkWriteBatchResult {
+    /// Adds the outcome of this batch to the overall `result` and `write_errors` of the bulk write,
+    /// using the individual write `responses` returned from the batch's cursor. `models` are the
+    /// models the batch started at, and `offset` is the index of the first of them in the bulk
+    /// write.
+    pub(crate) fn merge_into(
+        self,
+        responses: Vec<SingleWriteResponse>,
+        models: &[NamespacedWriteModel],
+        offset: usize,
+        result: &mut ClientBulkWriteResult,
+        write_errors: &mut HashMap<usize, WriteError>,
+    ) {
+        result.inserted_count += self.summary.inserted_count;
+        result.upserted_count += self.summary.upserted_count;
+        result.matched_count += self.summary.matched_count;
+        result.modified_count += self.summary.modified_count;
+        result.deleted_count += self.summary.deleted_count;
+
+        for response in responses {
+            let index = response.idx + offset;
+
+            if let Some(error) = response.write_error() {
+                write_errors.insert(index, error);
+                continue;
+            }
+
+            match models.get(response.idx).map(|m| &m.model) {
+                Some(WriteModel::InsertOne(_)) => {
+                    if let (Some(insert_results), Some(id)) = (
+                        result.insert_results.as_mut(),
+                        self.inserted_ids.get(&response.idx),
+                    ) {
+                        insert_results.insert(
+                            index,
+                            InsertOneResult {
+                                inserted_id: id.clone(),
+                            },
+                        );
+                    }
+                }
+                Some(WriteModel::DeleteOne(_)) | Some(WriteModel::DeleteMany(_)) => {
+                    if let Some(delete_results) = result.delete_results.as_mut() {
+                        delete_results.insert(
+                            index,
+                            DeleteResult {
+                                deleted_count: response.n,
+                            },
+                        );
+                    }
+                }
+                Some(_) => {
+                    if let Some(update_results) = result.update_results.as_mut() {
+                        let upserted_id = response.upserted.map(|u| u.id);
+                        let matched_count = if upserted_id.is_some() { 0 } else { response.n };
+                        update_results.insert(
+                            index,
+                            UpdateResult {
+                                matched_count,
+                                modified_count: response.n_modified,
+                                upserted_id,
+                            },
+                        );
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientBulkWriteResponseBody {
+    cursor: CursorInfo,
+
+    #[serde(default)]
+    n_inserted: u64,
+
+    #[serde(default)]
+    n_upserted: u64,
+
+    #[serde(default)]
+    n_matched: u64,
+
+    #[serde(default)]
+    n_modified: u64,
+
+    #[serde(default)]
+    n_deleted: u64,
+
+    write_concern_error: Option<WriteConcernError>,
+}
+
+/// The outcome of an individual write, as returned in the cursor of a `bulkWrite` command.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SingleWriteResponse {
+    ok: f64,
+
+    /// The index of the write within the batch.
+    idx: usize,
+
+    #[serde(default)]
+    n: u64,
+
+    #[serde(default)]
+    n_modified: u64,
+
+    upserted: Option<Upserted>,
+
+    code: Option<i32>,
+
+    code_name: Option<String>,
+
+    errmsg: Option<String>,
+
+    err_info: Option<Document>,
+}
+
+impl SingleWriteResponse {
+    fn write_error(&self) -> Option<WriteError> {
+        if self.ok == 1.0 {
+            return None;
+        }
+
+        Some(WriteError {
+            code: self.code.unwrap_or_default(),
+            code_name: self.code_name.clone(),
+            message: self.errmsg.clone().unwrap_or_default(),
+            details: self.err_info.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Upserted {
+    #[serde(rename = "_id")]
+    id: Bson,
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use crate::{
+    bson::{doc, Bson, Document},
+    cmap::StreamDescription,
+    error::ErrorKind,
+    operation::{
+        test::handle_response_test,
+        ClientBulkWrite,
+        Operation,
+        Retryability,
+        SingleWriteResponse,
+    },
+    options::{
+        ClientBulkWriteOptions,
+        DeleteManyModel,
+        DeleteOneModel,
+        InsertOneModel,
+        NamespacedWriteModel,
+        UpdateOneModel,
+    },
+    results::ClientBulkWriteResult,
+    Namespace,
+};
+
+fn ns(coll: &str) -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: coll.to_string(),
+    }
+}
+
+fn description() -> StreamDescription {
+    StreamDescription::with_wire_version(25)
+}
+
+fn mixed_models() -> Vec<NamespacedWriteModel> {
+    vec![
+        NamespacedWriteModel::new(
+            ns("a"),
+            InsertOneModel::builder().document(doc! { "x": 1 }).build(),
+        ),
+        NamespacedWriteModel::new(
+            ns("b"),
+            UpdateOneModel::builder()
+                .filter(doc! { "x": 1 })
+                .update(doc! { "$set": { "y": 1 } })
+                .upsert(true)
+                .build(),
+        ),
+        NamespacedWriteModel::new(
+            ns("a"),
+            DeleteOneModel::builder().filter(doc! { "x": 2 }).build(),
+        ),
+    ]
+}
+
+#[test]
+fn build() {
+    let models = mixed_models();
+    let mut op = ClientBulkWrite::new(&models, None);
+    let cmd = op.build(&description()).unwrap();
+
+    assert_eq!(cmd.name, "bulkWrite");
+    assert_eq!(cmd.target_db, "admin");
+    assert_eq!(
+        cmd.body,
+        doc! { "bulkWrite": 1, "errorsOnly": true, "ordered": true }
+    );
+
+    assert_eq!(cmd.document_sequences.len(), 2);
+    let ops = &cmd.document_sequences[0];
+    assert_eq!(ops.identifier, "ops");
+    let ops: Vec<Document> = ops
+        .documents
+        .iter()
+        .map(|d| d.to_document().unwrap())
+        .collect();
+    assert_eq!(ops.len(), 3);
+    assert_eq!(ops[0].get_i32("insert"), Ok(0));
+    assert!(ops[0]
+        .get_document("document")
+        .unwrap()
+        .get_object_id("_id")
+        .is_ok());
+    assert_eq!(
+        ops[1],
+        doc! {
+            "update": 1,
+            "filter": { "x": 1 },
+            "updateMods": { "$set": { "y": 1 } },
+            "multi": false,
+            "upsert": true,
+        }
+    );
+    assert_eq!(
+        ops[2],
+        doc! { "delete": 0, "filter": { "x": 2 }, "multi": false }
+    );
+
+    let ns_info = &cmd.document_sequences[1];
+    assert_eq!(ns_info.identifier, "nsInfo");
+    let ns_info: Vec<Document> = ns_info
+        .documents
+        .iter()
+        .map(|d| d.to_document().unwrap())
+        .collect();
+    assert_eq!(
+        ns_info,
+        vec![doc! { "ns": "test_db.a" }, doc! { "ns": "test_db.b" }]
+    );
+}
+
+#[test]
+fn build_verbose_unordered() {
+    let models = mixed_models();
+    let options = ClientBulkWriteOptions::builder()
+        .ordered(false)
+        .verbose_results(true)
+        .build();
+    let mut op = ClientBulkWrite::new(&models, Some(options));
+    let cmd = op.build(&description()).unwrap();
+
+    assert_eq!(
+        cmd.body,
+        doc! { "bulkWrite": 1, "errorsOnly": false, "ordered": false }
+    );
+}
+
+#[test]
+fn build_requires_8_0() {
+    let models = mixed_models();
+    let mut op = ClientBulkWrite::new(&models, None);
+    let error = op
+        .build(&StreamDescription::with_wire_version(21))
+        .unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::IncompatibleServer { .. }));
+}
+
+#[test]
+fn build_respects_max_write_batch_size() {
+    let models: Vec<NamespacedWriteModel> = (0..5)
+        .map(|i| {
+            NamespacedWriteModel::new(
+                ns("a"),
+                InsertOneModel::builder().document(doc! { "x": i }).build(),
+            )
+        })
+        .collect();
+    let mut description = description();
+    description.max_write_batch_size = 3;
+
+    let mut op = ClientBulkWrite::new(&models, None);
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.document_sequences[0].documents.len(), 3);
+}
+
+#[test]
+fn build_respects_max_message_size() {
+    let models: Vec<NamespacedWriteModel> = (0..5)
+        .map(|i| {
+            NamespacedWriteModel::new(
+                ns("a"),
+                InsertOneModel::builder()
+                    .document(doc! { "_id": i, "s": "a".repeat(400) })
+                    .build(),
+            )
+        })
+        .collect();
+    let mut description = description();
+    description.max_message_size_bytes = 2000;
+
+    let mut op = ClientBulkWrite::new(&models, None);
+    let cmd = op.build(&description).unwrap();
+    assert_eq!(cmd.document_sequences[0].documents.len(), 2);
+}
+
+#[test]
+fn build_reuses_generated_ids() {
+    let models = mixed_models();
+    let mut op = ClientBulkWrite::new(&models, None);
+    let first = op.build(&description()).unwrap();
+    let second = op.build(&description()).unwrap();
+    assert_eq!(
+        first.document_sequences[0].documents,
+        second.document_sequences[0].documents
+    );
+}
+
+#[test]
+fn retryability() {
+    let models = mixed_models();
+    let op = ClientBulkWrite::new(&models, None);
+    assert_eq!(op.retryability(), Retryability::Write);
+
+    let mut models = mixed_models();
+    models.push(NamespacedWriteModel::new(
+        ns("b"),
+        DeleteManyModel::builder().filter(doc! {}).build(),
+    ));
+    let op = ClientBulkWrite::new(&models, None);
+    assert_eq!(op.retryability(), Retryability::None);
+}
+
+#[test]
+fn handle_response_verbose() {
+    let models = mixed_models();
+    let options = ClientBulkWriteOptions::builder()
+        .ordered(false)
+        .verbose_results(true)
+        .build();
+    let mut op = ClientBulkWrite::new(&models, Some(options));
+    let cmd = op.build(&description()).unwrap();
+    let inserted_id = cmd.document_sequences[0].documents[0]
+        .to_document()
+        .unwrap()
+        .get_document("document")
+        .unwrap()
+        .get("_id")
+        .cloned()
+        .unwrap();
+
+    let responses = vec![
+        doc! { "ok": 1, "idx": 0, "n": 1 },
+        doc! { "ok": 1, "idx": 1, "n": 1, "nModified": 0, "upserted": { "_id": 5 } },
+        doc! { "ok": 0, "idx": 2, "code": 2, "codeName": "BadValue", "errmsg": "bad" },
+    ];
+    let (batch, cursor) = handle_response_test(
+        &op,
+        doc! {
+            "ok": 1,
+            "nErrors": 1,
+            "nInserted": 1,
+            "nUpserted": 1,
+            "nMatched": 0,
+            "nModified": 0,
+            "nDeleted": 0,
+            "cursor": {
+                "id": 0_i64,
+                "firstBatch": responses.clone(),
+                "ns": "admin.$cmd.bulkWrite",
+            },
+        },
+    )
+    .unwrap();
+    assert_eq!(batch.n_attempted, 3);
+    assert_eq!(cursor.id(), 0);
+
+    let responses: Vec<SingleWriteResponse> = responses
+        .into_iter()
+        .map(|d| bson::from_document(d).unwrap())
+        .collect();
+    let mut result = ClientBulkWriteResult::new(true);
+    let mut write_errors = HashMap::new();
+    batch.merge_into(responses, &models, 10, &mut result, &mut write_errors);
+
+    assert_eq!(result.inserted_count, 1);
+    assert_eq!(result.upserted_count, 1);
+    assert_eq!(
+        result.insert_results.unwrap().get(&10).unwrap().inserted_id,
+        inserted_id
+    );
+    let update_results = result.update_results.unwrap();
+    let update_result = update_results.get(&11).unwrap();
+    assert_eq!(update_result.matched_count, 0);
+    assert_eq!(update_result.upserted_id, Some(Bson::Int32(5)));
+    assert!(result.delete_results.unwrap().is_empty());
+
+    assert_eq!(write_errors.len(), 1);
+    let error = write_errors.get(&12).unwrap();
+    assert_eq!(error.code, 2);
+    assert_eq!(error.code_name.as_deref(), Some("BadValue"));
+}
+
+#[test]
+fn handle_response_summary() {
+    let models = mixed_models();
+    let mut op = ClientBulkWrite::new(&models, None);
+    op.build(&description()).unwrap();
+
+    let (batch, _) = handle_response_test(
+        &op,
+        doc! {
+            "ok": 1,
+            "nErrors": 0,
+            "nInserted": 1,
+            "nUpserted": 0,
+            "nMatched": 1,
+            "nModified": 1,
+            "nDeleted": 1,
+            "cursor": { "id": 0_i64, "firstBatch": [], "ns": "admin.$cmd.bulkWrite" },
+            "writeConcernError": { "code": 64, "errmsg": "waiting for replication timed out" },
+        },
+    )
+    .unwrap();
+    assert_eq!(batch.write_concern_error.as_ref().unwrap().code, 64);
+
+    let mut result = ClientBulkWriteResult::new(false);
+    let mut write_errors = HashMap::new();
+    batch.merge_into(vec![], &models, 0, &mut result, &mut write_errors);
+    assert_eq!(result.matched_count, 1);
+    assert_eq!(result.modified_count, 1);
+    assert_eq!(result.deleted_count, 1);
+    assert!(result.insert_results.is_none());
+    assert!(write_errors.is_empty());
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod test;
 
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Duration};
 
 use serde::Deserialize;
 
@@ -66,6 +66,7 @@ impl CountDocuments {
                 .collation(opts.collation)
                 .selection_criteria(opts.selection_criteria)
                 .read_concern(opts.read_concern)
+                .timeout(opts.timeout)
                 .build()
         });
 
@@ -102,6 +103,10 @@ impl Operation for CountDocuments {
         self.aggregate.selection_criteria()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.aggregate.timeout()
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
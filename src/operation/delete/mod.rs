@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, RawCommandResponse, StreamDescription},
@@ -114,6 +116,10 @@ impl Operation for Delete {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         if self.limit == 1 {
             Retryability::Write
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use bson::RawBsonRef;
 use serde::Deserialize;
 
@@ -100,6 +102,10 @@ impl Operation for Distinct {
         None
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
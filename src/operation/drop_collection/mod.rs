@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use bson::Document;
 
 use crate::{
@@ -78,4 +80,8 @@ impl Operation for DropCollection {
             .as_ref()
             .and_then(|opts| opts.write_concern.as_ref())
     }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
 }
@@ -15,6 +15,7 @@ fn build() {
             w: Some(Acknowledgment::Custom("abc".to_string())),
             ..Default::default()
         }),
+        timeout: None,
     };
 
     let ns = Namespace {
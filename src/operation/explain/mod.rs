@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod test;
+
+use std::{convert::TryInto, time::Duration};
+
+use crate::{
+    bson::{doc, Document},
+    cmap::{Command, RawCommandResponse, StreamDescription},
+    coll::options::ExplainVerbosity,
+    error::Result,
+    operation::Operation,
+    selection_criteria::SelectionCriteria,
+};
+
+/// Wraps another operation in an `explain` command, returning the server's description of how it
+/// would execute that operation rather than its results.
+pub(crate) struct Explain<T> {
+    inner: T,
+    verbosity: ExplainVerbosity,
+}
+
+impl<T> Explain<T>
+where
+    T: Operation<Command = Document>,
+{
+    pub(crate) fn new(inner: T, verbosity: ExplainVerbosity) -> Self {
+        Self { inner, verbosity }
+    }
+}
+
+impl<T> Operation for Explain<T>
+where
+    T: Operation<Command = Document>,
+{
+    type O = Document;
+    type Command = Document;
+
+    const NAME: &'static str = "explain";
+
+    fn build(&mut self, description: &StreamDescription) -> Result<Command> {
+        let inner = self.inner.build(description)?;
+
+        // The explain command does not accept a write concern, either at the top level or within
+        // the explained command.
+        let mut explained = inner.body;
+        explained.remove("writeConcern");
+
+        let body = doc! {
+            Self::NAME: explained,
+            "verbosity": bson::to_bson(&self.verbosity)?,
+        };
+
+        Ok(Command::new(Self::NAME.to_string(), inner.target_db, body))
+    }
+
+    fn handle_response(
+        &self,
+        response: RawCommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        Ok(response.into_raw_document_buf().try_into()?)
+    }
+
+    fn selection_criteria(&self) -> Option<&SelectionCriteria> {
+        self.inner.selection_criteria()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.inner.timeout()
+    }
+}
@@ -0,0 +1,94 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    bson::doc,
+    cmap::StreamDescription,
+    concern::{Acknowledgment, WriteConcern},
+    operation::{Distinct, Explain, Find, Operation, Update},
+    options::{ExplainVerbosity, FindOptions, UpdateModifications, UpdateOptions},
+    Namespace,
+};
+
+fn namespace() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+#[test]
+fn build_find() {
+    let options = FindOptions::builder().limit(3).build();
+    let find = Find::new(namespace(), Some(doc! { "x": 1 }), Some(options));
+    let mut op = Explain::new(find, ExplainVerbosity::ExecutionStats);
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+
+    assert_eq!(cmd.name.as_str(), "explain");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "explain": {
+                "find": "test_coll",
+                "filter": { "x": 1 },
+                "limit": 3_i64,
+            },
+            "verbosity": "executionStats",
+        }
+    );
+}
+
+#[test]
+fn build_distinct() {
+    let distinct = Distinct::new(namespace(), "x".to_string(), None, None);
+    let mut op = Explain::new(distinct, ExplainVerbosity::QueryPlanner);
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "explain": {
+                "distinct": "test_coll",
+                "key": "x",
+            },
+            "verbosity": "queryPlanner",
+        }
+    );
+}
+
+#[test]
+fn build_update_omits_write_concern() {
+    let options = UpdateOptions {
+        write_concern: Some(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let update = Update::new(
+        namespace(),
+        doc! { "x": 1 },
+        UpdateModifications::Document(doc! { "$set": { "y": 1 } }),
+        false,
+        Some(options),
+    );
+    let mut op = Explain::new(update, ExplainVerbosity::AllPlansExecution);
+
+    let cmd = op.build(&StreamDescription::new_testing()).unwrap();
+
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "explain": {
+                "update": "test_coll",
+                "updates": [{ "q": { "x": 1 }, "u": { "$set": { "y": 1 } } }],
+                "ordered": true,
+            },
+            "verbosity": "allPlansExecution",
+        }
+    );
+    assert!(op.write_concern().is_none());
+}
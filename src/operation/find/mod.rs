@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, RawCommandResponse, StreamDescription},
@@ -129,6 +131,17 @@ impl Operation for Find {
             .and_then(|opts| opts.selection_criteria.as_ref())
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
+    fn is_tailable(&self) -> bool {
+        matches!(
+            self.options.as_ref().and_then(|opts| opts.cursor_type),
+            Some(CursorType::Tailable) | Some(CursorType::TailableAwait)
+        )
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -2,7 +2,7 @@ mod options;
 #[cfg(test)]
 mod test;
 
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
 use serde::{de::DeserializeOwned, Deserialize};
 
@@ -153,6 +153,10 @@ where
         self.options.write_concern.as_ref()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.timeout
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Write
     }
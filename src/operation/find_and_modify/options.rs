@@ -72,6 +72,10 @@ pub(super) struct FindAndModifyOptions {
 
     #[builder(default)]
     pub(crate) hint: Option<Hint>,
+
+    #[serde(skip_serializing)]
+    #[builder(default)]
+    pub(crate) timeout: Option<Duration>,
 }
 
 impl FindAndModifyOptions {
@@ -88,6 +92,7 @@ impl FindAndModifyOptions {
         modify_opts.sort = opts.sort;
         modify_opts.write_concern = opts.write_concern;
         modify_opts.hint = opts.hint;
+        modify_opts.timeout = opts.timeout;
         modify_opts
     }
 
@@ -109,6 +114,7 @@ impl FindAndModifyOptions {
         modify_opts.upsert = opts.upsert;
         modify_opts.write_concern = opts.write_concern;
         modify_opts.hint = opts.hint;
+        modify_opts.timeout = opts.timeout;
 
         modify_opts
     }
@@ -131,6 +137,7 @@ impl FindAndModifyOptions {
         modify_opts.upsert = opts.upsert;
         modify_opts.write_concern = opts.write_concern;
         modify_opts.hint = opts.hint;
+        modify_opts.timeout = opts.timeout;
 
         modify_opts
     }
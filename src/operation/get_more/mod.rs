@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod test;
 
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 use bson::{Document, RawDocumentBuf};
 use serde::Deserialize;
@@ -9,8 +12,9 @@ use serde::Deserialize;
 use crate::{
     bson::doc,
     change_stream::event::ResumeToken,
+    client::remaining_time,
     cmap::{conn::PinnedConnectionHandle, Command, RawCommandResponse, StreamDescription},
-    cursor::CursorInformation,
+    cursor::{CursorInformation, CursorTimeout},
     error::{ErrorKind, Result},
     operation::Operation,
     options::SelectionCriteria,
@@ -25,6 +29,7 @@ pub(crate) struct GetMore<'conn> {
     selection_criteria: SelectionCriteria,
     batch_size: Option<u32>,
     max_time: Option<Duration>,
+    timeout: Option<CursorTimeout>,
     pinned_connection: Option<&'conn PinnedConnectionHandle>,
 }
 
@@ -39,6 +44,7 @@ impl<'conn> GetMore<'conn> {
             selection_criteria: SelectionCriteria::from_address(info.address),
             batch_size: info.batch_size,
             max_time: info.max_time,
+            timeout: info.timeout,
             pinned_connection: pinned,
         }
     }
@@ -51,6 +57,10 @@ impl<'conn> Operation for GetMore<'conn> {
     const NAME: &'static str = "getMore";
 
     fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        if let Some(CursorTimeout::Lifetime(deadline)) = self.timeout {
+            remaining_time(deadline)?;
+        }
+
         let mut body = doc! {
             Self::NAME: self.cursor_id,
             "collection": self.ns.coll.clone(),
@@ -96,6 +106,15 @@ impl<'conn> Operation for GetMore<'conn> {
         Some(&self.selection_criteria)
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        match self.timeout? {
+            CursorTimeout::Lifetime(deadline) => {
+                Some(deadline.saturating_duration_since(Instant::now()))
+            }
+            CursorTimeout::Iteration(timeout) => Some(timeout),
+        }
+    }
+
     fn pinned_connection(&self) -> Option<&PinnedConnectionHandle> {
         self.pinned_connection
     }
@@ -25,6 +25,7 @@ fn build_test(
         address,
         batch_size,
         max_time,
+        timeout: None,
     };
     let mut get_more = GetMore::new(info, None);
 
@@ -114,6 +115,7 @@ fn build_batch_size() {
         id: cursor_id,
         batch_size: Some((std::i32::MAX as u32) + 1),
         max_time: None,
+        timeout: None,
     };
     let mut op = GetMore::new(info, None);
     assert!(op.build(&StreamDescription::new_testing()).is_err())
@@ -132,6 +134,7 @@ fn op_selection_criteria() {
         id: 123,
         batch_size: None,
         max_time: None,
+        timeout: None,
     };
     let get_more = GetMore::new(info, None);
     let server_description = ServerDescription {
@@ -140,6 +143,7 @@ fn op_selection_criteria() {
         reply: Ok(None),
         last_update_time: None,
         average_round_trip_time: None,
+        topology_version: None,
     };
     let server_info = ServerInfo::new_borrowed(&server_description);
 
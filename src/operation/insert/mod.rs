@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod test;
 
-use std::{collections::HashMap, convert::TryInto};
+use std::{collections::HashMap, convert::TryInto, time::Duration};
 
 use bson::{oid::ObjectId, Bson, RawArrayBuf, RawDocumentBuf};
 use serde::Serialize;
@@ -66,29 +66,7 @@ impl<'a, T: Serialize> Operation for Insert<'a, T> {
             .enumerate()
         {
             let mut doc = bson::to_raw_document_buf(d)?;
-            let id = match doc.get("_id")? {
-                Some(b) => b.try_into()?,
-                None => {
-                    let mut new_doc = RawDocumentBuf::new();
-                    let oid = ObjectId::new();
-                    new_doc.append("_id", oid);
-
-                    let mut new_bytes = new_doc.into_bytes();
-                    new_bytes.pop(); // remove trailing null byte
-
-                    let mut bytes = doc.into_bytes();
-                    let oid_slice = &new_bytes[4..];
-                    // insert oid at beginning of document
-                    bytes.splice(4..4, oid_slice.iter().cloned());
-
-                    // overwrite old length
-                    let new_length = (bytes.len() as i32).to_le_bytes();
-                    (&mut bytes[0..4]).copy_from_slice(&new_length);
-                    doc = RawDocumentBuf::from_bytes(bytes)?;
-
-                    Bson::ObjectId(oid)
-                }
-            };
+            let id = get_or_prepend_id(&mut doc)?;
 
             let doc_size = bson_util::array_entry_size_bytes(i, doc.as_bytes().len());
 
@@ -168,6 +146,7 @@ impl<'a, T: Serialize> Operation for Insert<'a, T> {
                     write_errors: response.write_errors,
                     write_concern_error: response.write_concern_error,
                     inserted_ids: map,
+                    ..BulkWriteFailure::new()
                 }),
                 response.labels,
             ));
@@ -180,11 +159,43 @@ impl<'a, T: Serialize> Operation for Insert<'a, T> {
         self.options.as_ref().and_then(|o| o.write_concern.as_ref())
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Write
     }
 }
 
+/// Returns the `_id` of the given document, generating an `ObjectId` and inserting it at the
+/// beginning of the document if one isn't already present.
+pub(crate) fn get_or_prepend_id(doc: &mut RawDocumentBuf) -> Result<Bson> {
+    match doc.get("_id")? {
+        Some(b) => Ok(b.try_into()?),
+        None => {
+            let mut new_doc = RawDocumentBuf::new();
+            let oid = ObjectId::new();
+            new_doc.append("_id", oid);
+
+            let mut new_bytes = new_doc.into_bytes();
+            new_bytes.pop(); // remove trailing null byte
+
+            let mut bytes = std::mem::replace(doc, RawDocumentBuf::new()).into_bytes();
+            let oid_slice = &new_bytes[4..];
+            // insert oid at beginning of document
+            bytes.splice(4..4, oid_slice.iter().cloned());
+
+            // overwrite old length
+            let new_length = (bytes.len() as i32).to_le_bytes();
+            (&mut bytes[0..4]).copy_from_slice(&new_length);
+            *doc = RawDocumentBuf::from_bytes(bytes)?;
+
+            Ok(Bson::ObjectId(oid))
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub(crate) struct InsertCommand {
     insert: String,
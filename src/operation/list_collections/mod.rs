@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, RawCommandResponse, StreamDescription},
@@ -83,6 +85,10 @@ impl Operation for ListCollections {
         Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)).as_ref()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use bson::RawDocumentBuf;
 use serde::Deserialize;
 
@@ -81,6 +83,10 @@ impl Operation for ListDatabases {
         Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)).as_ref()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::{
     bson::{doc, Document},
     cmap::{Command, RawCommandResponse, StreamDescription},
@@ -76,6 +78,10 @@ impl Operation for ListIndexes {
         Some(SelectionCriteria::ReadPreference(ReadPreference::Primary)).as_ref()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         Retryability::Read
     }
@@ -1,5 +1,7 @@
 mod abort_transaction;
 mod aggregate;
+mod bulk_write;
+mod client_bulk_write;
 mod commit_transaction;
 mod count;
 mod count_documents;
@@ -10,6 +12,7 @@ mod distinct;
 mod drop_collection;
 mod drop_database;
 mod drop_indexes;
+mod explain;
 mod find;
 mod find_and_modify;
 mod get_more;
@@ -17,13 +20,15 @@ mod insert;
 mod list_collections;
 mod list_databases;
 mod list_indexes;
+mod modify_collection;
+mod rename_collection;
 mod run_command;
 mod update;
 
 #[cfg(test)]
 mod test;
 
-use std::{collections::VecDeque, fmt::Debug, ops::Deref};
+use std::{collections::VecDeque, fmt::Debug, ops::Deref, time::Duration};
 
 use bson::{RawBsonRef, RawDocument, RawDocumentBuf, Timestamp};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -50,6 +55,8 @@ use crate::{
 
 pub(crate) use abort_transaction::AbortTransaction;
 pub(crate) use aggregate::{Aggregate, AggregateTarget, ChangeStreamAggregate};
+pub(crate) use bulk_write::BulkWrite;
+pub(crate) use client_bulk_write::{ClientBulkWrite, SingleWriteResponse};
 pub(crate) use commit_transaction::CommitTransaction;
 pub(crate) use count::Count;
 pub(crate) use count_documents::CountDocuments;
@@ -60,6 +67,7 @@ pub(crate) use distinct::Distinct;
 pub(crate) use drop_collection::DropCollection;
 pub(crate) use drop_database::DropDatabase;
 pub(crate) use drop_indexes::DropIndexes;
+pub(crate) use explain::Explain;
 pub(crate) use find::Find;
 pub(crate) use find_and_modify::FindAndModify;
 pub(crate) use get_more::GetMore;
@@ -67,6 +75,8 @@ pub(crate) use insert::Insert;
 pub(crate) use list_collections::ListCollections;
 pub(crate) use list_databases::ListDatabases;
 pub(crate) use list_indexes::ListIndexes;
+pub(crate) use modify_collection::ModifyCollection;
+pub(crate) use rename_collection::RenameCollection;
 pub(crate) use run_command::RunCommand;
 pub(crate) use update::Update;
 
@@ -117,6 +127,18 @@ pub(crate) trait Operation {
         None
     }
 
+    /// The timeout specified for this operation, if any. If `None`, the client's default timeout
+    /// is used.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether the cursor created by this operation is tailable, in which case the operation's
+    /// timeout bounds each `getMore` separately rather than the whole lifetime of the cursor.
+    fn is_tailable(&self) -> bool {
+        false
+    }
+
     /// Whether or not this operation will request acknowledgment from the server.
     fn is_acknowledged(&self) -> bool {
         self.write_concern()
@@ -303,7 +325,7 @@ impl<T> WriteResponseBody<T> {
         let failure = BulkWriteFailure {
             write_errors: self.write_errors.clone(),
             write_concern_error: self.write_concern_error.clone(),
-            inserted_ids: Default::default(),
+            ..BulkWriteFailure::new()
         };
 
         Err(Error::new(
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod test;
+
+use std::time::Duration;
+
+use bson::Document;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, RawCommandResponse, StreamDescription},
+    error::{Error, Result},
+    operation::{append_options, remove_empty_write_concern, Operation, WriteConcernOnlyBody},
+    options::{ModifyCollectionOptions, WriteConcern},
+    results::ModifyCollectionResult,
+    Namespace,
+};
+
+#[derive(Debug)]
+pub(crate) struct ModifyCollection {
+    ns: Namespace,
+    options: Option<ModifyCollectionOptions>,
+}
+
+impl ModifyCollection {
+    pub(crate) fn new(ns: Namespace, options: Option<ModifyCollectionOptions>) -> Self {
+        Self { ns, options }
+    }
+
+    #[cfg(test)]
+    fn empty() -> Self {
+        Self::new(Namespace::empty(), None)
+    }
+}
+
+impl Operation for ModifyCollection {
+    type O = ModifyCollectionResult;
+    type Command = Document;
+
+    const NAME: &'static str = "collMod";
+
+    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        if let Some(ref options) = self.options {
+            if options.view_on.is_some() != options.pipeline.is_some() {
+                return Err(Error::invalid_argument(
+                    "view_on and pipeline must be specified together when modifying a view",
+                ));
+            }
+            if let Some(ref index) = options.index {
+                if index.name.is_some() == index.key_pattern.is_some() {
+                    return Err(Error::invalid_argument(
+                        "exactly one of name or key_pattern must be specified to identify the \
+                         index to modify",
+                    ));
+                }
+            }
+        }
+
+        let mut body = doc! {
+            Self::NAME: self.ns.coll.clone(),
+        };
+
+        remove_empty_write_concern!(self.options);
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            self.ns.db.clone(),
+            body,
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        response: RawCommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let write_concern_body: WriteConcernOnlyBody = response.body()?;
+        write_concern_body.validate()?;
+
+        response.body()
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+}
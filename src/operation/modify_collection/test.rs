@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use crate::{
+    bson::doc,
+    cmap::StreamDescription,
+    concern::{Acknowledgment, WriteConcern},
+    error::{ErrorKind, WriteFailure},
+    operation::{test::handle_response_test, ModifyCollection, Operation},
+    options::{
+        ChangeStreamPreAndPostImages,
+        IndexModification,
+        ModifyCollectionOptions,
+        ValidationAction,
+        ValidationLevel,
+    },
+    results::ModifyCollectionResult,
+    Namespace,
+};
+
+fn namespace() -> Namespace {
+    Namespace {
+        db: "test_db".to_string(),
+        coll: "test_coll".to_string(),
+    }
+}
+
+#[test]
+fn build() {
+    let options = ModifyCollectionOptions::builder()
+        .validator(doc! { "x": { "$type": "int" } })
+        .validation_level(ValidationLevel::Moderate)
+        .validation_action(ValidationAction::Warn)
+        .index(
+            IndexModification::builder()
+                .name("x_1".to_string())
+                .expire_after(Duration::from_secs(60))
+                .hidden(true)
+                .build(),
+        )
+        .change_stream_pre_and_post_images(
+            ChangeStreamPreAndPostImages::builder()
+                .enabled(true)
+                .build(),
+        )
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        })
+        .build();
+    let mut op = ModifyCollection::new(namespace(), Some(options));
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "collMod");
+    assert_eq!(cmd.target_db.as_str(), "test_db");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "collMod": "test_coll",
+            "validator": { "x": { "$type": "int" } },
+            "validationLevel": "moderate",
+            "validationAction": "warn",
+            "index": {
+                "name": "x_1",
+                "expireAfterSeconds": 60,
+                "hidden": true,
+            },
+            "changeStreamPreAndPostImages": { "enabled": true },
+            "writeConcern": { "w": "majority" },
+        }
+    );
+
+    let options = ModifyCollectionOptions::builder()
+        .view_on("source".to_string())
+        .pipeline(vec![doc! { "$match": { "x": 1 } }])
+        .build();
+    let mut op = ModifyCollection::new(namespace(), Some(options));
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "collMod": "test_coll",
+            "viewOn": "source",
+            "pipeline": [{ "$match": { "x": 1 } }],
+        }
+    );
+}
+
+#[test]
+fn build_invalid_options() {
+    let description = StreamDescription::new_testing();
+    let invalid = vec![
+        ModifyCollectionOptions::builder()
+            .view_on("source".to_string())
+            .build(),
+        ModifyCollectionOptions::builder()
+            .index(IndexModification::builder().hidden(true).build())
+            .build(),
+        ModifyCollectionOptions::builder()
+            .index(
+                IndexModification::builder()
+                    .name("x_1".to_string())
+                    .key_pattern(doc! { "x": 1 })
+                    .hidden(true)
+                    .build(),
+            )
+            .build(),
+    ];
+
+    for options in invalid {
+        let mut op = ModifyCollection::new(namespace(), Some(options.clone()));
+        let error = op.build(&description).unwrap_err();
+        assert!(
+            matches!(*error.kind, ErrorKind::InvalidArgument { .. }),
+            "expected {:?} to be rejected, got {:?}",
+            options,
+            error
+        );
+    }
+}
+
+#[test]
+fn handle_success() {
+    let op = ModifyCollection::empty();
+
+    let result = handle_response_test(&op, doc! { "ok": 1.0 }).unwrap();
+    assert_eq!(result, ModifyCollectionResult::default());
+
+    let response = doc! {
+        "expireAfterSeconds_old": 60_i64,
+        "expireAfterSeconds_new": 120_i64,
+        "hidden_old": false,
+        "hidden_new": true,
+        "ok": 1.0,
+    };
+    let result = handle_response_test(&op, response).unwrap();
+    assert_eq!(result.expire_after_old, Some(Duration::from_secs(60)));
+    assert_eq!(result.expire_after_new, Some(Duration::from_secs(120)));
+    assert_eq!(result.hidden_old, Some(false));
+    assert_eq!(result.hidden_new, Some(true));
+}
+
+#[test]
+fn handle_write_concern_error() {
+    let op = ModifyCollection::empty();
+
+    let response = doc! {
+        "writeConcernError": {
+            "code": 100,
+            "codeName": "hello world",
+            "errmsg": "12345"
+        },
+        "ok": 1
+    };
+
+    let err = handle_response_test(&op, response).unwrap_err();
+    match *err.kind {
+        ErrorKind::Write(WriteFailure::WriteConcernError(ref wc_err)) => {
+            assert_eq!(wc_err.code, 100);
+            assert_eq!(wc_err.code_name, "hello world");
+            assert_eq!(wc_err.message, "12345");
+        }
+        ref e => panic!("expected write concern error, got {:?}", e),
+    }
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod test;
+
+use std::time::Duration;
+
+use bson::Document;
+
+use crate::{
+    bson::doc,
+    cmap::{Command, RawCommandResponse, StreamDescription},
+    error::{Error, Result},
+    operation::{append_options, remove_empty_write_concern, Operation, WriteConcernOnlyBody},
+    options::{RenameCollectionOptions, WriteConcern},
+    Namespace,
+};
+
+/// Renames a collection, possibly moving it to a different database. The `renameCollection`
+/// command must be run against the admin database, and takes the full namespaces of the source and
+/// target collections.
+#[derive(Debug)]
+pub(crate) struct RenameCollection {
+    ns: Namespace,
+    to: Namespace,
+    options: Option<RenameCollectionOptions>,
+}
+
+impl RenameCollection {
+    pub(crate) fn new(
+        ns: Namespace,
+        to: Namespace,
+        options: Option<RenameCollectionOptions>,
+    ) -> Self {
+        Self { ns, to, options }
+    }
+
+    #[cfg(test)]
+    fn empty() -> Self {
+        Self::new(Namespace::empty(), Namespace::empty(), None)
+    }
+}
+
+impl Operation for RenameCollection {
+    type O = ();
+    type Command = Document;
+
+    const NAME: &'static str = "renameCollection";
+
+    fn build(&mut self, _description: &StreamDescription) -> Result<Command> {
+        if self.to.db.is_empty() || self.to.coll.is_empty() {
+            return Err(Error::invalid_argument(format!(
+                "invalid target namespace for rename: \"{}\"",
+                self.to
+            )));
+        }
+
+        let mut body = doc! {
+            Self::NAME: self.ns.to_string(),
+            "to": self.to.to_string(),
+        };
+
+        remove_empty_write_concern!(self.options);
+        append_options(&mut body, self.options.as_ref())?;
+
+        Ok(Command::new(
+            Self::NAME.to_string(),
+            "admin".to_string(),
+            body,
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        response: RawCommandResponse,
+        _description: &StreamDescription,
+    ) -> Result<Self::O> {
+        let response: WriteConcernOnlyBody = response.body()?;
+        response.validate()
+    }
+
+    fn write_concern(&self) -> Option<&WriteConcern> {
+        self.options
+            .as_ref()
+            .and_then(|opts| opts.write_concern.as_ref())
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    bson::doc,
+    cmap::StreamDescription,
+    concern::{Acknowledgment, WriteConcern},
+    error::{ErrorKind, WriteFailure},
+    operation::{test::handle_response_test, Operation, RenameCollection},
+    options::RenameCollectionOptions,
+    Namespace,
+};
+
+fn namespace(db: &str, coll: &str) -> Namespace {
+    Namespace {
+        db: db.to_string(),
+        coll: coll.to_string(),
+    }
+}
+
+#[test]
+fn build() {
+    let options = RenameCollectionOptions::builder()
+        .drop_target(true)
+        .write_concern(WriteConcern {
+            w: Some(Acknowledgment::Majority),
+            ..Default::default()
+        })
+        .build();
+    let mut op = RenameCollection::new(
+        namespace("test_db", "test_coll"),
+        namespace("other_db", "other_coll"),
+        Some(options),
+    );
+
+    let description = StreamDescription::new_testing();
+    let cmd = op.build(&description).expect("build should succeed");
+
+    assert_eq!(cmd.name.as_str(), "renameCollection");
+    assert_eq!(cmd.target_db.as_str(), "admin");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "renameCollection": "test_db.test_coll",
+            "to": "other_db.other_coll",
+            "dropTarget": true,
+            "writeConcern": { "w": "majority" }
+        }
+    );
+
+    let mut op = RenameCollection::new(
+        namespace("test_db", "test_coll"),
+        namespace("test_db", "renamed"),
+        None,
+    );
+    let cmd = op.build(&description).expect("build should succeed");
+    assert_eq!(cmd.target_db.as_str(), "admin");
+    assert_eq!(
+        cmd.body,
+        doc! {
+            "renameCollection": "test_db.test_coll",
+            "to": "test_db.renamed",
+        }
+    );
+}
+
+#[test]
+fn build_invalid_target() {
+    let description = StreamDescription::new_testing();
+    for to in &[namespace("", "coll"), namespace("db", "")] {
+        let mut op = RenameCollection::new(namespace("test_db", "test_coll"), to.clone(), None);
+        let error = op.build(&description).unwrap_err();
+        assert!(
+            matches!(*error.kind, ErrorKind::InvalidArgument { .. }),
+            "{:?}",
+            error
+        );
+    }
+}
+
+#[test]
+fn handle_success() {
+    let op = RenameCollection::empty();
+
+    let ok_response = doc! { "ok": 1.0 };
+    handle_response_test(&op, ok_response).unwrap();
+    let ok_extra = doc! { "ok": 1.0, "hello": "world" };
+    handle_response_test(&op, ok_extra).unwrap();
+}
+
+#[test]
+fn handle_write_concern_error() {
+    let op = RenameCollection::empty();
+
+    let response = doc! {
+        "writeConcernError": {
+            "code": 100,
+            "codeName": "hello world",
+            "errmsg": "12345"
+        },
+        "ok": 1
+    };
+
+    let err = handle_response_test(&op, response).unwrap_err();
+    match *err.kind {
+        ErrorKind::Write(WriteFailure::WriteConcernError(ref wc_err)) => {
+            assert_eq!(wc_err.code, 100);
+            assert_eq!(wc_err.code_name, "hello world");
+            assert_eq!(wc_err.message, "12345");
+        }
+        ref e => panic!("expected write concern error, got {:?}", e),
+    }
+}
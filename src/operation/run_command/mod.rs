@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod test;
 
-use std::convert::TryInto;
+use std::{convert::TryInto, time::Duration};
 
 use bson::RawBsonRef;
 
@@ -21,6 +21,7 @@ pub(crate) struct RunCommand<'conn> {
     command: Document,
     selection_criteria: Option<SelectionCriteria>,
     write_concern: Option<WriteConcern>,
+    timeout: Option<Duration>,
     pinned_connection: Option<&'conn PinnedConnectionHandle>,
 }
 
@@ -29,6 +30,7 @@ impl<'conn> RunCommand<'conn> {
         db: String,
         command: Document,
         selection_criteria: Option<SelectionCriteria>,
+        timeout: Option<Duration>,
         pinned_connection: Option<&'conn PinnedConnectionHandle>,
     ) -> Result<Self> {
         let write_concern = command
@@ -41,6 +43,7 @@ impl<'conn> RunCommand<'conn> {
             command,
             selection_criteria,
             write_concern,
+            timeout,
             pinned_connection,
         })
     }
@@ -95,6 +98,10 @@ impl<'conn> Operation for RunCommand<'conn> {
         self.selection_criteria.as_ref()
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
     fn write_concern(&self) -> Option<&WriteConcern> {
         self.write_concern.as_ref()
     }
@@ -9,7 +9,7 @@ use crate::{
 
 #[test]
 fn build() {
-    let mut op = RunCommand::new("foo".into(), doc! { "hello": 1 }, None, None).unwrap();
+    let mut op = RunCommand::new("foo".into(), doc! { "hello": 1 }, None, None, None).unwrap();
     assert!(op.selection_criteria().is_none());
 
     let command = op.build(&StreamDescription::new_testing()).unwrap();
@@ -27,7 +27,7 @@ fn build() {
 
 #[test]
 fn handle_success() {
-    let op = RunCommand::new("foo".into(), doc! { "hello": 1 }, None, None).unwrap();
+    let op = RunCommand::new("foo".into(), doc! { "hello": 1 }, None, None, None).unwrap();
 
     let doc = doc! {
         "ok": 1,
@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod test;
 
+use std::time::Duration;
+
 use serde::Deserialize;
 
 use crate::{
@@ -144,6 +146,10 @@ impl Operation for Update {
             .and_then(|opts| opts.write_concern.as_ref())
     }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.options.as_ref().and_then(|opts| opts.timeout)
+    }
+
     fn retryability(&self) -> Retryability {
         if self.multi != Some(true) {
             Retryability::Write
@@ -23,10 +23,14 @@ pub use crate::{
     compression::*,
     concern::*,
     db::options::*,
+    gridfs::options::*,
     index::options::*,
     selection_criteria::*,
 };
 
+#[cfg(feature = "csfle")]
+pub use crate::client::csfle::options::*;
+
 /// Updates an options struct with the read preference/read concern/write concern of a
 /// client/database/collection.
 macro_rules! resolve_options {
@@ -1,6 +1,9 @@
 //! Contains the types of results returned by CRUD operations.
 
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
 
 use crate::{
     bson::{Bson, Document},
@@ -14,7 +17,7 @@ use serde::{Deserialize, Serialize};
 
 /// The result of a [`Collection::insert_one`](../struct.Collection.html#method.insert_one)
 /// operation.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct InsertOneResult {
@@ -50,7 +53,7 @@ impl InsertManyResult {
 
 /// The result of a [`Collection::update_one`](../struct.Collection.html#method.update_one) or
 /// [`Collection::update_many`](../struct.Collection.html#method.update_many) operation.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct UpdateResult {
@@ -68,7 +71,7 @@ pub struct UpdateResult {
 
 /// The result of a [`Collection::delete_one`](../struct.Collection.html#method.delete_one) or
 /// [`Collection::delete_many`](../struct.Collection.html#method.delete_many) operation.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct DeleteResult {
@@ -77,6 +80,122 @@ pub struct DeleteResult {
     pub deleted_count: u64,
 }
 
+/// The result of a [`Collection::bulk_write`](../struct.Collection.html#method.bulk_write)
+/// operation.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BulkWriteResult {
+    /// The number of documents inserted by the operation.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub inserted_count: u64,
+
+    /// The number of documents that matched the filters of update and replace models.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub matched_count: u64,
+
+    /// The number of documents that were modified by update and replace models.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub modified_count: u64,
+
+    /// The number of documents deleted by the operation.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub deleted_count: u64,
+
+    /// The number of documents upserted by update and replace models.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub upserted_count: u64,
+
+    /// The `_id` field of the documents inserted, keyed by the index of the corresponding model.
+    pub inserted_ids: HashMap<usize, Bson>,
+
+    /// The `_id` field of the documents upserted, keyed by the index of the corresponding model.
+    pub upserted_ids: HashMap<usize, Bson>,
+}
+
+impl BulkWriteResult {
+    /// Adds the counts and ids from `other` to this result, offsetting the indexes in `other` by
+    /// `offset`.
+    pub(crate) fn merge(&mut self, other: BulkWriteResult, offset: usize) {
+        self.inserted_count += other.inserted_count;
+        self.matched_count += other.matched_count;
+        self.modified_count += other.modified_count;
+        self.deleted_count += other.deleted_count;
+        self.upserted_count += other.upserted_count;
+        self.inserted_ids.extend(
+            other
+                .inserted_ids
+                .into_iter()
+                .map(|(index, id)| (index + offset, id)),
+        );
+        self.upserted_ids.extend(
+            other
+                .upserted_ids
+                .into_iter()
+                .map(|(index, id)| (index + offset, id)),
+        );
+    }
+}
+
+/// The result of a [`Client::bulk_write`](../struct.Client.html#method.bulk_write) operation.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ClientBulkWriteResult {
+    /// The number of documents inserted by the operation.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub inserted_count: u64,
+
+    /// The number of documents upserted by the operation.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub upserted_count: u64,
+
+    /// The number of documents that matched the filters of update and replace models.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub matched_count: u64,
+
+    /// The number of documents that were modified by update and replace models.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub modified_count: u64,
+
+    /// The number of documents deleted by the operation.
+    #[serde(serialize_with = "crate::bson::serde_helpers::serialize_u64_as_i64")]
+    pub deleted_count: u64,
+
+    /// The results of the successful insert models, keyed by the index of the corresponding
+    /// model. This is only populated if
+    /// [`ClientBulkWriteOptions::verbose_results`](options/struct.ClientBulkWriteOptions.html#
+    /// structfield.verbose_results) is set to true.
+    pub insert_results: Option<HashMap<usize, InsertOneResult>>,
+
+    /// The results of the successful update and replace models, keyed by the index of the
+    /// corresponding model. This is only populated if
+    /// [`ClientBulkWriteOptions::verbose_results`](options/struct.ClientBulkWriteOptions.html#
+    /// structfield.verbose_results) is set to true.
+    pub update_results: Option<HashMap<usize, UpdateResult>>,
+
+    /// The results of the successful delete models, keyed by the index of the corresponding
+    /// model. This is only populated if
+    /// [`ClientBulkWriteOptions::verbose_results`](options/struct.ClientBulkWriteOptions.html#
+    /// structfield.verbose_results) is set to true.
+    pub delete_results: Option<HashMap<usize, DeleteResult>>,
+}
+
+impl ClientBulkWriteResult {
+    pub(crate) fn new(verbose: bool) -> Self {
+        if verbose {
+            Self {
+                insert_results: Some(HashMap::new()),
+                update_results: Some(HashMap::new()),
+                delete_results: Some(HashMap::new()),
+                ..Default::default()
+            }
+        } else {
+            Self::default()
+        }
+    }
+}
+
 /// Information about the index created as a result of a
 /// [`Collection::create_index`](../struct.Collection.html#method.create_index).
 #[derive(Debug, Clone, PartialEq)]
@@ -103,6 +222,37 @@ impl CreateIndexesResult {
     }
 }
 
+/// The previous and new values of the index settings changed by a
+/// [`Database::modify_collection`](../struct.Database.html#method.modify_collection) operation.
+/// A pair of values is only set if the corresponding setting was changed.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[non_exhaustive]
+pub struct ModifyCollectionResult {
+    /// The TTL of the modified index before the operation.
+    #[serde(
+        rename = "expireAfterSeconds_old",
+        default,
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_seconds",
+        serialize_with = "bson_util::serialize_duration_option_as_int_secs"
+    )]
+    pub expire_after_old: Option<Duration>,
+
+    /// The TTL of the modified index after the operation.
+    #[serde(
+        rename = "expireAfterSeconds_new",
+        default,
+        deserialize_with = "bson_util::deserialize_duration_option_from_u64_seconds",
+        serialize_with = "bson_util::serialize_duration_option_as_int_secs"
+    )]
+    pub expire_after_new: Option<Duration>,
+
+    /// Whether the modified index was hidden before the operation.
+    pub hidden_old: Option<bool>,
+
+    /// Whether the modified index is hidden after the operation.
+    pub hidden_new: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct GetMoreResult {
     pub(crate) batch: VecDeque<RawDocumentBuf>,
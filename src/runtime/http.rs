@@ -1,12 +1,61 @@
-#[cfg(feature = "aws-auth")]
-use reqwest::{Method, Response};
+use std::{sync::Arc, time::Duration};
+
+use derivative::Derivative;
+use futures_util::io::{AsyncReadExt, AsyncWriteExt};
 #[cfg(feature = "aws-auth")]
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Default)]
+use super::{socks5::Socks5Proxy, stream::AsyncStream};
+use crate::{
+    cmap::options::StreamOptions,
+    error::{ErrorKind, Result},
+    options::{DnsResolver, ServerAddress, TlsOptions},
+    runtime,
+};
+
+/// The maximum amount of time to spend on a single HTTP request, including connecting.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A minimal runtime-agnostic HTTP/1.1 client. Each request is sent over a new connection, which
+/// is closed once the response has been read.
+#[derive(Clone, Default, Derivative)]
+#[derivative(Debug)]
 pub(crate) struct HttpClient {
-    #[cfg(feature = "aws-auth")]
-    inner: reqwest::Client,
+    /// The SOCKS5 proxy that requests are tunneled through, if any.
+    proxy: Option<Socks5Proxy>,
+
+    /// The resolver used to look up the addresses of hosts, if not the default one.
+    #[derivative(Debug = "ignore")]
+    dns_resolver: Option<Arc<dyn DnsResolver>>,
+}
+
+/// The HTTP methods supported by `HttpClient`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    not(all(feature = "aws-auth", feature = "cert-revocation")),
+    allow(dead_code)
+)]
+pub(crate) enum Method {
+    Get,
+    Put,
+    Post,
+}
+
+impl Method {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Put => "PUT",
+            Self::Post => "POST",
+        }
+    }
+}
+
+/// The status and body of an HTTP response.
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) body: Vec<u8>,
 }
 
 #[cfg(feature = "aws-auth")]
@@ -16,17 +65,18 @@ impl HttpClient {
         &self,
         uri: &str,
         headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
-    ) -> reqwest::Result<T>
+    ) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let value = self
-            .request(Method::GET, uri, headers)
-            .await?
-            .json()
-            .await?;
+        let body = self.request(Method::Get, uri, headers).await?.body;
 
-        Ok(value)
+        serde_json::from_slice(&body).map_err(|e| {
+            ErrorKind::InvalidResponse {
+                message: format!("invalid JSON in HTTP response from {}: {}", uri, e),
+            }
+            .into()
+        })
     }
 
     /// Executes an HTTP GET request and returns the response body as a string.
@@ -34,8 +84,8 @@ impl HttpClient {
         &self,
         uri: &str,
         headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
-    ) -> reqwest::Result<String> {
-        self.request_and_read_string(Method::GET, uri, headers)
+    ) -> Result<String> {
+        self.request_and_read_string(Method::Get, uri, headers)
             .await
     }
 
@@ -44,8 +94,8 @@ impl HttpClient {
         &self,
         uri: &str,
         headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
-    ) -> reqwest::Result<String> {
-        self.request_and_read_string(Method::PUT, uri, headers)
+    ) -> Result<String> {
+        self.request_and_read_string(Method::Put, uri, headers)
             .await
     }
 
@@ -55,27 +105,221 @@ impl HttpClient {
         method: Method,
         uri: &str,
         headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
-    ) -> reqwest::Result<String> {
-        let text = self.request(method, uri, headers).await?.text().await?;
+    ) -> Result<String> {
+        let body = self.request(method, uri, headers).await?.body;
 
-        Ok(text)
+        String::from_utf8(body).map_err(|_| {
+            ErrorKind::InvalidResponse {
+                message: format!("HTTP response from {} is not valid UTF-8", uri),
+            }
+            .into()
+        })
+    }
+}
+
+impl HttpClient {
+    /// Creates a client that connects through `proxy` and resolves hosts with `dns_resolver` when
+    /// they are specified.
+    pub(crate) fn new(
+        proxy: Option<Socks5Proxy>,
+        dns_resolver: Option<Arc<dyn DnsResolver>>,
+    ) -> Self {
+        Self {
+            proxy,
+            dns_resolver,
+        }
     }
 
-    /// Executes an HTTP equest and returns the response.
+    /// Executes an HTTP request and returns the response. Responses with a status other than
+    /// 2xx are returned as errors.
+    #[cfg(feature = "aws-auth")]
     pub(crate) async fn request<'a>(
         &self,
         method: Method,
         uri: &str,
         headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
-    ) -> reqwest::Result<Response> {
-        let response = headers
-            .into_iter()
-            .fold(self.inner.request(method, uri), |request, (k, v)| {
-                request.header(*k, *v)
-            })
-            .send()
-            .await?;
+    ) -> Result<Response> {
+        self.request_with_body(method, uri, headers, &[]).await
+    }
+
+    /// Executes an HTTP request with the given body and returns the response. Responses with a
+    /// status other than 2xx are returned as errors.
+    pub(crate) async fn request_with_body<'a>(
+        &self,
+        method: Method,
+        uri: &str,
+        headers: impl IntoIterator<Item = &'a (&'a str, &'a str)>,
+        body: &[u8],
+    ) -> Result<Response> {
+        let target = Target::parse(uri)?;
+
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method.as_str(),
+            target.path,
+            target.authority,
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        let mut request = request.into_bytes();
+        request.extend_from_slice(body);
+
+        let exchange = async {
+            let options = StreamOptions::builder()
+                .address(target.address)
+                .connect_timeout(Some(REQUEST_TIMEOUT))
+                .proxy(self.proxy.clone())
+                .dns_resolver(self.dns_resolver.clone())
+                .tls_options(
+                    if target.tls {
+                        Some(TlsOptions::default())
+                    } else {
+                        None
+                    },
+                )
+                .build();
+            let mut stream = AsyncStream::connect(options).await?;
+            stream.write_all(&request).await?;
+            stream.flush().await?;
+
+            read_to_end(&mut stream).await
+        };
+        let raw = runtime::timeout(REQUEST_TIMEOUT, exchange).await??;
+
+        let response = parse_response(raw).ok_or_else(|| ErrorKind::InvalidResponse {
+            message: format!("invalid HTTP response from {}", uri),
+        })?;
+        if !(200..300).contains(&response.status) {
+            return Err(ErrorKind::InvalidResponse {
+                message: format!(
+                    "HTTP request to {} failed with status {}",
+                    uri, response.status
+                ),
+            }
+            .into());
+        }
 
         Ok(response)
     }
 }
+
+/// The parsed form of an HTTP or HTTPS URL.
+struct Target {
+    tls: bool,
+    address: ServerAddress,
+    authority: String,
+    path: String,
+}
+
+impl Target {
+    fn parse(uri: &str) -> Result<Self> {
+        let invalid_uri = || ErrorKind::InvalidArgument {
+            message: format!("invalid HTTP URL: {}", uri),
+        };
+
+        let (tls, rest) = if let Some(rest) = uri.strip_prefix("http://") {
+            (false, rest)
+        } else if let Some(rest) = uri.strip_prefix("https://") {
+            (true, rest)
+        } else {
+            return Err(invalid_uri().into());
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if !authority.ends_with(']') => {
+                (host, port.parse::<u16>().map_err(|_| invalid_uri())?)
+            }
+            _ => (authority, if tls { 443 } else { 80 }),
+        };
+        if host.is_empty() {
+            return Err(invalid_uri().into());
+        }
+
+        Ok(Self {
+            tls,
+            address: ServerAddress::Tcp {
+                host: host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string(),
+                port: Some(port),
+            },
+            authority: authority.to_string(),
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Reads from `stream` until the server closes the connection. Some servers close TLS connections
+/// without sending a close_notify alert, so an unexpected EOF is treated as the end of the
+/// response; a truncated body is detected when the response is parsed.
+async fn read_to_end(stream: &mut AsyncStream) -> Result<Vec<u8>> {
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        match stream.read(&mut buffer).await {
+            Ok(0) => return Ok(response),
+            Ok(n) => response.extend_from_slice(&buffer[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(response),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Parses a raw HTTP/1.x response, returning `None` if it is malformed or truncated.
+fn parse_response(mut raw: Vec<u8>) -> Option<Response> {
+    let header_end = raw.windows(4).position(|window| window == b"\r\n\r\n")?;
+    let body = raw.split_off(header_end + 4);
+    let header = std::str::from_utf8(&raw[..header_end]).ok()?;
+
+    let mut lines = header.split("\r\n");
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.parse::<usize>().ok()?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        }
+    }
+
+    let body = if chunked {
+        decode_chunked(&body)?
+    } else {
+        match content_length {
+            Some(length) => body.get(..length)?.to_vec(),
+            None => body,
+        }
+    };
+
+    Some(Response { status, body })
+}
+
+/// Decodes a body sent with the chunked transfer encoding.
+fn decode_chunked(mut encoded: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = encoded.windows(2).position(|window| window == b"\r\n")?;
+        let size_line = std::str::from_utf8(&encoded[..line_end]).ok()?;
+        // Chunk extensions follow the size after a semicolon and are ignored.
+        let size_str = size_line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_str, 16).ok()?;
+        encoded = &encoded[line_end + 2..];
+        if size == 0 {
+            return Some(body);
+        }
+
+        body.extend_from_slice(encoded.get(..size)?);
+        encoded = encoded.get(size + 2..)?;
+    }
+}
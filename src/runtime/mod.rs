@@ -1,12 +1,21 @@
 mod acknowledged_message;
 mod async_read_ext;
 mod async_write_ext;
+#[cfg_attr(
+    not(any(feature = "aws-auth", feature = "cert-revocation")),
+    allow(dead_code)
+)]
 mod http;
 #[cfg(feature = "async-std-runtime")]
 mod interval;
 mod join_handle;
+#[cfg(feature = "cert-revocation")]
+mod ocsp;
 mod resolver;
+mod socks5;
 mod stream;
+#[cfg(all(test, any(feature = "openssl-tls", feature = "cert-revocation")))]
+mod test;
 #[cfg(feature = "openssl-tls")]
 mod tls_openssl;
 #[cfg_attr(feature = "openssl-tls", allow(unused))]
@@ -21,13 +30,19 @@ pub(crate) use self::{
     async_write_ext::{AsyncLittleEndianWrite, SyncLittleEndianWrite},
     join_handle::AsyncJoinHandle,
     resolver::AsyncResolver,
-    stream::AsyncStream,
+    socks5::{Socks5Proxy, DEFAULT_PROXY_PORT},
+    stream::{AsyncStream, DEFAULT_CONNECT_TIMEOUT},
     worker_handle::{WorkerHandle, WorkerHandleListener},
 };
-use crate::{error::Result, options::ServerAddress};
+use crate::{
+    error::Result,
+    options::{DnsResolver, ServerAddress},
+};
 pub(crate) use http::HttpClient;
 #[cfg(feature = "async-std-runtime")]
 use interval::Interval;
+#[cfg(feature = "cert-revocation")]
+use ocsp::PendingQuery;
 #[cfg(feature = "openssl-tls")]
 use tls_openssl as tls;
 #[cfg(not(feature = "openssl-tls"))]
@@ -35,6 +50,11 @@ use tls_rustls as tls;
 #[cfg(feature = "tokio-runtime")]
 use tokio::time::Interval;
 
+/// Without the `cert-revocation` feature, OCSP responders are never queried after a handshake.
+#[cfg(not(feature = "cert-revocation"))]
+#[derive(Default)]
+struct PendingQuery {}
+
 /// Spawn a task in the background to run a future.
 ///
 /// If the runtime is still running, this will return a handle to the background task.
@@ -140,19 +160,33 @@ pub(crate) fn interval(duration: Duration) -> Interval {
     }
 }
 
+/// Resolves the socket addresses of `address`, using the custom resolver if one is provided and it
+/// returns a result for the host.
 pub(crate) async fn resolve_address(
     address: &ServerAddress,
-) -> Result<impl Iterator<Item = SocketAddr>> {
+    dns_resolver: Option<&dyn DnsResolver>,
+) -> Result<Vec<SocketAddr>> {
+    if let Some(dns_resolver) = dns_resolver {
+        if let Some(ips) = dns_resolver.ip_lookup(&address.host()).await? {
+            let port = address.port().unwrap_or(27017);
+            return Ok(ips
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect());
+        }
+    }
+
     #[cfg(feature = "tokio-runtime")]
     {
         let socket_addrs = tokio::net::lookup_host(format!("{}", address)).await?;
-        Ok(socket_addrs)
+        Ok(socket_addrs.collect())
     }
 
     #[cfg(feature = "async-std-runtime")]
     {
-        let host = (address.host(), address.port().unwrap_or(27017));
+        let host = address.host();
+        let host = (host.as_ref(), address.port().unwrap_or(27017));
         let socket_addrs = async_std::net::ToSocketAddrs::to_socket_addrs(&host).await?;
-        Ok(socket_addrs)
+        Ok(socket_addrs.collect())
     }
 }
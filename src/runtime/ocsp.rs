@@ -0,0 +1,702 @@
+//! Support for checking the revocation status of server certificates via OCSP, as described in
+//! [RFC 6960](https://www.rfc-editor.org/rfc/rfc6960).
+//!
+//! A response stapled by the server during the handshake is always checked when present. If the
+//! server does not staple one, the OCSP responders listed in the server's certificate may be
+//! queried once the handshake completes. Responses that cannot be obtained or verified are
+//! ignored; a connection only fails if a valid response indicates that the certificate has been
+//! revoked, or if the certificate requires a stapled response and the server did not provide one.
+//!
+//! Responders are contacted through the same SOCKS5 proxy and DNS resolver as the server itself.
+
+#[cfg(test)]
+mod test;
+
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use futures_util::future::{BoxFuture, FutureExt};
+use lazy_static::lazy_static;
+use ring::signature::{self, UnparsedPublicKey, VerificationAlgorithm};
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use x509_parser::{
+    certificate::X509Certificate,
+    extensions::{GeneralName, ParsedExtension},
+    parse_x509_certificate,
+};
+
+use super::http::{HttpClient, Method};
+use crate::{
+    error::{ErrorKind, Result},
+    runtime,
+};
+
+/// The maximum amount of time to spend querying the OCSP responders for a certificate.
+const RESPONDER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How far in the future a response's `thisUpdate` time may be to account for clock skew.
+const ALLOWED_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+// DER-encoded object identifiers.
+const OID_SHA1: &[u8] = &[0x2b, 0x0e, 0x03, 0x02, 0x1a];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_OCSP_BASIC: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01];
+const OID_OCSP_ACCESS: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01];
+const OID_TLS_FEATURE: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x01, 0x18];
+const OID_SHA1_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05];
+const OID_SHA256_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+const OID_SHA384_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c];
+const OID_SHA512_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d];
+const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+
+// DER tags.
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_ENUMERATED: u8 = 0x0a;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_CONTEXT_1: u8 = 0xa1;
+const TAG_STATUS_GOOD: u8 = 0x80;
+const TAG_STATUS_REVOKED: u8 = 0xa1;
+
+/// The TLS feature extension value indicating that a certificate must be accompanied by a
+/// stapled OCSP response ("OCSP Must-Staple").
+const TLS_FEATURE_STATUS_REQUEST: &[u8] = &[0x05];
+
+lazy_static! {
+    /// Verified OCSP responses, keyed by the certificate they describe. Only responses that
+    /// specify when newer information will be available are cached, and they are evicted at
+    /// that point.
+    static ref RESPONSE_CACHE: Mutex<HashMap<CertId, CachedResponse>> = Default::default();
+}
+
+/// Holds the responder query produced while verifying a server's certificate so that it can be
+/// performed once the handshake has completed.
+pub(crate) type PendingQuery = Arc<Mutex<Option<ResponderQuery>>>;
+
+/// The revocation status of a certificate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum CertStatus {
+    Good,
+    Revoked,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct CachedResponse {
+    status: CertStatus,
+    next_update: SystemTime,
+}
+
+/// A response whose signature and validity period have been verified.
+#[derive(Clone, Copy, Debug)]
+struct VerifiedResponse {
+    status: CertStatus,
+    next_update: Option<SystemTime>,
+}
+
+/// Identifies the certificate that an OCSP request or response refers to.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct CertId {
+    /// The DER encoding of the issuer's distinguished name.
+    issuer_name: Vec<u8>,
+
+    /// The issuer's public key, excluding the tag, length and unused bits count of its BIT
+    /// STRING.
+    issuer_key: Vec<u8>,
+
+    /// The contents of the certificate's serial number INTEGER.
+    serial: Vec<u8>,
+}
+
+impl CertId {
+    fn new(certificate: &X509Certificate, issuer: &X509Certificate) -> Self {
+        Self {
+            issuer_name: issuer.subject().as_raw().to_vec(),
+            issuer_key: issuer.public_key().subject_public_key.data.to_vec(),
+            serial: certificate.raw_serial().to_vec(),
+        }
+    }
+
+    /// Whether the fields of a `CertID` hashed with `hash_algorithm` describe this certificate.
+    fn matches(
+        &self,
+        hash_algorithm: &[u8],
+        issuer_name_hash: &[u8],
+        issuer_key_hash: &[u8],
+        serial: &[u8],
+    ) -> bool {
+        let hash = |input: &[u8]| match hash_algorithm {
+            OID_SHA1 => Some(Sha1::digest(input).to_vec()),
+            OID_SHA256 => Some(Sha256::digest(input).to_vec()),
+            _ => None,
+        };
+
+        serial == self.serial.as_slice()
+            && hash(&self.issuer_name).as_deref() == Some(issuer_name_hash)
+            && hash(&self.issuer_key).as_deref() == Some(issuer_key_hash)
+    }
+
+    /// Encodes an `OCSPRequest` for this certificate with no extensions.
+    fn encode_request(&self) -> Vec<u8> {
+        let algorithm = encode(
+            TAG_SEQUENCE,
+            &[encode(TAG_OID, OID_SHA1), encode(TAG_NULL, &[])].concat(),
+        );
+        let cert_id = encode(
+            TAG_SEQUENCE,
+            &[
+                algorithm,
+                encode(TAG_OCTET_STRING, &Sha1::digest(&self.issuer_name)),
+                encode(TAG_OCTET_STRING, &Sha1::digest(&self.issuer_key)),
+                encode(TAG_INTEGER, &self.serial),
+            ]
+            .concat(),
+        );
+
+        // OCSPRequest { TBSRequest { requestList { Request { CertID } } } }
+        let request = encode(TAG_SEQUENCE, &cert_id);
+        let request_list = encode(TAG_SEQUENCE, &request);
+        let tbs_request = encode(TAG_SEQUENCE, &request_list);
+        encode(TAG_SEQUENCE, &tbs_request)
+    }
+}
+
+/// The information needed to query the OCSP responders for a server's certificate.
+#[derive(Clone, Debug)]
+pub(crate) struct ResponderQuery {
+    cert_id: CertId,
+
+    /// The DER encoding of the certificate's issuer.
+    issuer: Vec<u8>,
+
+    /// The URLs of the responders listed in the certificate.
+    responders: Vec<String>,
+}
+
+/// Checks the revocation status of the server certificate `end_entity` during the handshake.
+/// `chain` contains the DER encodings of the other certificates that may have issued it, i.e.
+/// the intermediates sent by the server and the configured CA certificates, and
+/// `stapled_response` is the response stapled by the server, which is empty if there was none.
+///
+/// Returns an error describing the problem if the certificate has been revoked or requires a
+/// stapled response that the server did not provide. If the status should instead be fetched
+/// from the certificate's OCSP responders, returns the query to perform once the handshake has
+/// completed.
+pub(crate) fn check_certificate<'a>(
+    end_entity: &[u8],
+    chain: impl IntoIterator<Item = &'a [u8]>,
+    stapled_response: &[u8],
+    endpoint_check: bool,
+    now: SystemTime,
+) -> std::result::Result<Option<ResponderQuery>, String> {
+    let (_, certificate) = parse_x509_certificate(end_entity).map_err(|e| e.to_string())?;
+
+    let issuer = chain.into_iter().find_map(|der| {
+        parse_x509_certificate(der)
+            .ok()
+            .filter(|(_, issuer)| issuer.subject().as_raw() == certificate.issuer().as_raw())
+            .map(|(_, issuer)| (der, issuer))
+    });
+    // The status of a certificate can only be verified using its issuer.
+    let (issuer_der, issuer) = match issuer {
+        Some(issuer) => issuer,
+        None => return Ok(None),
+    };
+    let cert_id = CertId::new(&certificate, &issuer);
+
+    let stapled = if stapled_response.is_empty() {
+        None
+    } else {
+        verify_response(stapled_response, &cert_id, &issuer, now).ok()
+    };
+    match stapled {
+        Some(response) if response.status != CertStatus::Unknown => {
+            cache_response(&cert_id, response);
+            return status_result(response.status).map(|_| None);
+        }
+        _ if requires_stapling(&certificate) => {
+            return Err(
+                "certificate requires a stapled OCSP response, but the server did not provide a \
+                 valid one"
+                    .to_string(),
+            );
+        }
+        _ => {}
+    }
+
+    if let Some(status) = cached_status(&cert_id, now) {
+        return status_result(status).map(|_| None);
+    }
+
+    let responders = ocsp_responders(&certificate);
+    if !endpoint_check || responders.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ResponderQuery {
+        cert_id,
+        issuer: issuer_der.to_vec(),
+        responders,
+    }))
+}
+
+/// Queries the responders in `query` in turn until one of them returns a valid response. Failing
+/// to obtain a valid response is not an error; only a response indicating that the certificate
+/// has been revoked is.
+pub(crate) async fn query_responders(
+    query: ResponderQuery,
+    http_client: &HttpClient,
+) -> Result<()> {
+    let status = match runtime::timeout(RESPONDER_TIMEOUT, fetch_status(&query, http_client)).await
+    {
+        Ok(Some(status)) => status,
+        _ => return Ok(()),
+    };
+
+    status_result(status).map_err(|message| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("invalid peer certificate: {}", message),
+        )
+        .into()
+    })
+}
+
+// Querying a responder opens a connection through `HttpClient`, which in turn can perform OCSP
+// checks, so the future is boxed to keep its type finite.
+fn fetch_status<'a>(
+    query: &'a ResponderQuery,
+    http_client: &'a HttpClient,
+) -> BoxFuture<'a, Option<CertStatus>> {
+    async move {
+        let (_, issuer) = parse_x509_certificate(&query.issuer).ok()?;
+        let request = query.cert_id.encode_request();
+
+        for url in &query.responders {
+            let body = match post(http_client, url, &request).await {
+                Ok(body) => body,
+                Err(_) => continue,
+            };
+            match verify_response(&body, &query.cert_id, &issuer, SystemTime::now()) {
+                Ok(response) if response.status != CertStatus::Unknown => {
+                    cache_response(&query.cert_id, response);
+                    return Some(response.status);
+                }
+                _ => continue,
+            }
+        }
+
+        None
+    }
+    .boxed()
+}
+
+fn status_result(status: CertStatus) -> std::result::Result<(), String> {
+    match status {
+        CertStatus::Revoked => Err("certificate has been revoked".to_string()),
+        CertStatus::Good | CertStatus::Unknown => Ok(()),
+    }
+}
+
+fn cache_response(cert_id: &CertId, response: VerifiedResponse) {
+    if let Some(next_update) = response.next_update {
+        RESPONSE_CACHE.lock().unwrap().insert(
+            cert_id.clone(),
+            CachedResponse {
+                status: response.status,
+                next_update,
+            },
+        );
+    }
+}
+
+fn cached_status(cert_id: &CertId, now: SystemTime) -> Option<CertStatus> {
+    let mut cache = RESPONSE_CACHE.lock().unwrap();
+    match cache.get(cert_id) {
+        Some(cached) if cached.next_update >= now => Some(cached.status),
+        Some(_) => {
+            cache.remove(cert_id);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Whether `certificate` has the TLS feature extension requiring OCSP stapling.
+fn requires_stapling(certificate: &X509Certificate) -> bool {
+    certificate
+        .extensions()
+        .iter()
+        .filter(|extension| extension.oid.as_bytes() == OID_TLS_FEATURE)
+        .any(|extension| {
+            let mut features = Der::new(extension.value);
+            let mut features = match features.read(TAG_SEQUENCE) {
+                Some(features) => Der::new(features),
+                None => return false,
+            };
+            std::iter::from_fn(|| features.read(TAG_INTEGER))
+                .any(|feature| feature == TLS_FEATURE_STATUS_REQUEST)
+        })
+}
+
+/// The HTTP URLs of the OCSP responders listed in `certificate`.
+fn ocsp_responders(certificate: &X509Certificate) -> Vec<String> {
+    certificate
+        .extensions()
+        .iter()
+        .filter_map(|extension| match extension.parsed_extension() {
+            ParsedExtension::AuthorityInfoAccess(access) => Some(access),
+            _ => None,
+        })
+        .flat_map(|access| access.accessdescs.iter())
+        .filter(|description| description.access_method.as_bytes() == OID_OCSP_ACCESS)
+        .filter_map(|description| match description.access_location {
+            GeneralName::URI(uri) if uri.starts_with("http://") => Some(uri.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parses and verifies the DER-encoded `OCSPResponse` in `der`, returning the status it reports
+/// for the certificate identified by `cert_id`.
+fn verify_response(
+    der: &[u8],
+    cert_id: &CertId,
+    issuer: &X509Certificate,
+    now: SystemTime,
+) -> std::result::Result<VerifiedResponse, String> {
+    let response = parse_response(der).ok_or_else(|| "malformed OCSP response".to_string())?;
+
+    let single = response
+        .responses
+        .iter()
+        .find(|single| {
+            cert_id.matches(
+                single.hash_algorithm,
+                single.issuer_name_hash,
+                single.issuer_key_hash,
+                single.serial,
+            )
+        })
+        .ok_or_else(|| "OCSP response does not describe the certificate".to_string())?;
+
+    if !response.is_signed_by(issuer, now) {
+        return Err("OCSP response is not signed by an authorized responder".to_string());
+    }
+
+    if single.this_update > now + ALLOWED_CLOCK_SKEW
+        || matches!(single.next_update, Some(next_update) if next_update < now)
+    {
+        return Err("OCSP response is not currently valid".to_string());
+    }
+
+    Ok(VerifiedResponse {
+        status: single.status,
+        next_update: single.next_update,
+    })
+}
+
+/// A parsed `BasicOCSPResponse`.
+struct BasicResponse<'a> {
+    /// The complete encoding of the signed `ResponseData`.
+    tbs_response_data: &'a [u8],
+    signature_algorithm: &'a [u8],
+    signature: &'a [u8],
+    responses: Vec<SingleResponse<'a>>,
+
+    /// The DER encodings of the certificates included with the response.
+    certs: Vec<&'a [u8]>,
+}
+
+/// A parsed `SingleResponse`.
+struct SingleResponse<'a> {
+    hash_algorithm: &'a [u8],
+    issuer_name_hash: &'a [u8],
+    issuer_key_hash: &'a [u8],
+    serial: &'a [u8],
+    status: CertStatus,
+    this_update: SystemTime,
+    next_update: Option<SystemTime>,
+}
+
+impl<'a> BasicResponse<'a> {
+    /// Whether the response was signed by `issuer` or by a responder that `issuer` delegated
+    /// signing to, as indicated by the `id-kp-OCSPSigning` extended key usage. A delegated
+    /// responder's certificate must be valid at `now`.
+    fn is_signed_by(&self, issuer: &X509Certificate, now: SystemTime) -> bool {
+        if self.is_signed_with(issuer.public_key().subject_public_key.data.as_ref()) {
+            return true;
+        }
+
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs() as i64)
+            .unwrap_or(0);
+
+        self.certs.iter().any(|der| {
+            let responder = match parse_x509_certificate(der) {
+                Ok((_, responder)) => responder,
+                Err(_) => return false,
+            };
+            let authorized = responder.issuer().as_raw() == issuer.subject().as_raw()
+                && responder.extensions().iter().any(|extension| {
+                    matches!(
+                        extension.parsed_extension(),
+                        ParsedExtension::ExtendedKeyUsage(usage) if usage.ocsp_signing
+                    )
+                })
+                && verify_signature(
+                    responder.signature_algorithm.algorithm.as_bytes(),
+                    issuer.public_key().subject_public_key.data.as_ref(),
+                    responder.tbs_certificate.as_ref(),
+                    responder.signature_value.data.as_ref(),
+                );
+            let validity = responder.validity();
+            authorized
+                && validity.not_before.timestamp() <= now
+                && now <= validity.not_after.timestamp()
+                && self.is_signed_with(responder.public_key().subject_public_key.data.as_ref())
+        })
+    }
+
+    fn is_signed_with(&self, public_key: &[u8]) -> bool {
+        verify_signature(
+            self.signature_algorithm,
+            public_key,
+            self.tbs_response_data,
+            self.signature,
+        )
+    }
+}
+
+/// Whether `signature` is a valid signature of `message` by `public_key` using the algorithm
+/// identified by the OID `algorithm`.
+pub(crate) fn verify_signature(
+    algorithm: &[u8],
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> bool {
+    // The curve used by an ECDSA key is not specified by the signature algorithm, so each
+    // supported curve is tried in turn.
+    let algorithms: &[&'static dyn VerificationAlgorithm] = match algorithm {
+        OID_SHA1_WITH_RSA => &[&signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY],
+        OID_SHA256_WITH_RSA => &[&signature::RSA_PKCS1_2048_8192_SHA256],
+        OID_SHA384_WITH_RSA => &[&signature::RSA_PKCS1_2048_8192_SHA384],
+        OID_SHA512_WITH_RSA => &[&signature::RSA_PKCS1_2048_8192_SHA512],
+        OID_ECDSA_WITH_SHA256 => &[
+            &signature::ECDSA_P256_SHA256_ASN1,
+            &signature::ECDSA_P384_SHA256_ASN1,
+        ],
+        OID_ECDSA_WITH_SHA384 => &[
+            &signature::ECDSA_P256_SHA384_ASN1,
+            &signature::ECDSA_P384_SHA384_ASN1,
+        ],
+        OID_ED25519 => &[&signature::ED25519],
+        _ => &[],
+    };
+
+    algorithms.iter().any(|algorithm| {
+        UnparsedPublicKey::new(*algorithm, public_key)
+            .verify(message, signature)
+            .is_ok()
+    })
+}
+
+/// Parses an `OCSPResponse`, returning the `BasicOCSPResponse` it contains.
+fn parse_response(der: &[u8]) -> Option<BasicResponse<'_>> {
+    let mut response = Der::new(Der::new(der).read(TAG_SEQUENCE)?);
+    // Only a status of "successful" is accompanied by response bytes.
+    if response.read(TAG_ENUMERATED)? != [0] {
+        return None;
+    }
+    let mut response_bytes = Der::new(Der::new(response.read(TAG_CONTEXT_0)?).read(TAG_SEQUENCE)?);
+    if response_bytes.read(TAG_OID)? != OID_OCSP_BASIC {
+        return None;
+    }
+
+    let basic = Der::new(response_bytes.read(TAG_OCTET_STRING)?).read(TAG_SEQUENCE)?;
+    let mut basic = Der::new(basic);
+    let (tag, tbs_contents, tbs_response_data) = basic.read_any()?;
+    if tag != TAG_SEQUENCE {
+        return None;
+    }
+    let signature_algorithm = Der::new(basic.read(TAG_SEQUENCE)?).read(TAG_OID)?;
+    // The first byte of a BIT STRING's contents is its number of unused bits.
+    let signature = basic.read(TAG_BIT_STRING)?.get(1..)?;
+    let mut certs = Vec::new();
+    if let Some(certs_field) = basic.read_optional(TAG_CONTEXT_0) {
+        let mut sequence = Der::new(Der::new(certs_field).read(TAG_SEQUENCE)?);
+        while !sequence.is_empty() {
+            let (_, _, cert) = sequence.read_any()?;
+            certs.push(cert);
+        }
+    }
+
+    let mut tbs = Der::new(tbs_contents);
+    tbs.read_optional(TAG_CONTEXT_0); // version
+    tbs.read_any()?; // responderID
+    tbs.read(TAG_GENERALIZED_TIME)?; // producedAt
+    let mut list = Der::new(tbs.read(TAG_SEQUENCE)?);
+    let mut responses = Vec::new();
+    while !list.is_empty() {
+        responses.push(parse_single_response(list.read(TAG_SEQUENCE)?)?);
+    }
+
+    Some(BasicResponse {
+        tbs_response_data,
+        signature_algorithm,
+        signature,
+        responses,
+        certs,
+    })
+}
+
+fn parse_single_response(contents: &[u8]) -> Option<SingleResponse<'_>> {
+    let mut single = Der::new(contents);
+
+    let mut cert_id = Der::new(single.read(TAG_SEQUENCE)?);
+    let hash_algorithm = Der::new(cert_id.read(TAG_SEQUENCE)?).read(TAG_OID)?;
+    let issuer_name_hash = cert_id.read(TAG_OCTET_STRING)?;
+    let issuer_key_hash = cert_id.read(TAG_OCTET_STRING)?;
+    let serial = cert_id.read(TAG_INTEGER)?;
+
+    let status = match single.read_any()?.0 {
+        TAG_STATUS_GOOD => CertStatus::Good,
+        TAG_STATUS_REVOKED => CertStatus::Revoked,
+        _ => CertStatus::Unknown,
+    };
+    let this_update = parse_time(single.read(TAG_GENERALIZED_TIME)?)?;
+    let next_update = match single.read_optional(TAG_CONTEXT_0) {
+        Some(next_update) => Some(parse_time(
+            Der::new(next_update).read(TAG_GENERALIZED_TIME)?,
+        )?),
+        None => None,
+    };
+    single.read_optional(TAG_CONTEXT_1); // singleExtensions
+
+    Some(SingleResponse {
+        hash_algorithm,
+        issuer_name_hash,
+        issuer_key_hash,
+        serial,
+        status,
+        this_update,
+        next_update,
+    })
+}
+
+/// Parses a GeneralizedTime of the form `YYYYMMDDHHMMSS[.fff]Z`.
+fn parse_time(contents: &[u8]) -> Option<SystemTime> {
+    let time = std::str::from_utf8(contents).ok()?.strip_suffix('Z')?;
+    let seconds = time.split('.').next()?;
+    let time = NaiveDateTime::parse_from_str(seconds, "%Y%m%d%H%M%S").ok()?;
+    let timestamp = Utc.from_utc_datetime(&time).timestamp();
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(timestamp).ok()?))
+}
+
+/// A reader for the subset of DER needed to parse OCSP responses.
+struct Der<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { input }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// Reads the next element, returning its tag, its contents and its complete encoding.
+    fn read_any(&mut self) -> Option<(u8, &'a [u8], &'a [u8])> {
+        let tag = *self.input.first()?;
+        let first = *self.input.get(1)?;
+        let (length, header_length) = if first < 0x80 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let bytes = self.input.get(2..2 + count)?;
+            let length = bytes
+                .iter()
+                .fold(0usize, |length, byte| (length << 8) | *byte as usize);
+            (length, 2 + count)
+        };
+
+        let end = header_length.checked_add(length)?;
+        let element = self.input.get(..end)?;
+        self.input = &self.input[end..];
+        Some((tag, &element[header_length..], element))
+    }
+
+    /// Reads the contents of the next element, which must have the given tag.
+    fn read(&mut self, tag: u8) -> Option<&'a [u8]> {
+        match self.read_any()? {
+            (actual, contents, _) if actual == tag => Some(contents),
+            _ => None,
+        }
+    }
+
+    /// Reads the contents of the next element if it has the given tag.
+    fn read_optional(&mut self, tag: u8) -> Option<&'a [u8]> {
+        if self.input.first() == Some(&tag) {
+            self.read(tag)
+        } else {
+            None
+        }
+    }
+}
+
+/// DER-encodes an element with the given tag and contents.
+fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    if contents.len() < 0x80 {
+        encoded.push(contents.len() as u8);
+    } else {
+        let length = (contents.len() as u32).to_be_bytes();
+        let skip = length.iter().take_while(|byte| **byte == 0).count();
+        encoded.push(0x80 | (length.len() - skip) as u8);
+        encoded.extend_from_slice(&length[skip..]);
+    }
+    encoded.extend_from_slice(contents);
+    encoded
+}
+
+/// Sends `request` to the OCSP responder at the HTTP URL `url` and returns the body of the
+/// response. Responders are only queried over plain HTTP, as checking the certificate of an HTTPS
+/// responder would itself require an OCSP query.
+async fn post(http_client: &HttpClient, url: &str, request: &[u8]) -> Result<Vec<u8>> {
+    if !url.starts_with("http://") {
+        return Err(ErrorKind::InvalidArgument {
+            message: format!("invalid OCSP responder URL: {}", url),
+        }
+        .into());
+    }
+
+    let headers = [
+        ("Content-Type", "application/ocsp-request"),
+        ("Accept", "application/ocsp-response"),
+    ];
+    let response = http_client
+        .request_with_body(Method::Post, url, &headers, request)
+        .await?;
+
+    Ok(response.body)
+}
@@ -0,0 +1,367 @@
+use std::{
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, TcpListener},
+    path::PathBuf,
+    sync::{mpsc, Arc},
+    time::{Duration, SystemTime},
+};
+
+use rustls_pemfile::certs;
+use x509_parser::parse_x509_certificate;
+
+use super::{
+    cache_response,
+    cached_status,
+    check_certificate,
+    ocsp_responders,
+    parse_response,
+    query_responders,
+    requires_stapling,
+    verify_response,
+    CertId,
+    CertStatus,
+    ResponderQuery,
+    VerifiedResponse,
+    TAG_OCTET_STRING,
+};
+use crate::{
+    runtime::{HttpClient, Socks5Proxy},
+    test::StaticDnsResolver,
+};
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/test/tls")
+        .join(name);
+    std::fs::read(path).unwrap()
+}
+
+fn read_certificate(name: &str) -> Vec<u8> {
+    certs(&mut read_fixture(name).as_slice()).unwrap().remove(0)
+}
+
+fn server_cert_id() -> CertId {
+    let server = read_certificate("server.pem");
+    let ca = read_certificate("ca.pem");
+    CertId::new(
+        &parse_x509_certificate(&server).unwrap().1,
+        &parse_x509_certificate(&ca).unwrap().1,
+    )
+}
+
+fn check_server_certificate(
+    certificate: &str,
+    stapled_response: &[u8],
+) -> Result<Option<ResponderQuery>, String> {
+    let ca = read_certificate("ca.pem");
+    check_certificate(
+        &read_certificate(certificate),
+        std::iter::once(ca.as_slice()),
+        stapled_response,
+        true,
+        SystemTime::now(),
+    )
+}
+
+/// Starts a stand-in OCSP responder on a local port that answers every request with `response`.
+/// Returns the responder's URL and a receiver for the HTTP requests it receives.
+fn start_responder(response: Vec<u8>) -> (String, mpsc::Receiver<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/ocsp", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => return,
+            };
+
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            loop {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buffer[..n]),
+                }
+                let text = String::from_utf8_lossy(&request);
+                if let Some(header_end) = text.find("\r\n\r\n") {
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|line| line.strip_prefix("Content-Length: "))
+                        .and_then(|length| length.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if request.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+            }
+
+            let header = format!(
+                "HTTP/1.0 200 OK\r\nContent-Type: application/ocsp-response\r\nContent-Length: \
+                 {}\r\n\r\n",
+                response.len()
+            );
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&response);
+            let _ = sender.send(request);
+        }
+    });
+
+    (url, receiver)
+}
+
+fn server_query(responders: Vec<String>) -> ResponderQuery {
+    ResponderQuery {
+        cert_id: server_cert_id(),
+        issuer: read_certificate("ca.pem"),
+        responders,
+    }
+}
+
+#[test]
+fn stapled_responses() {
+    assert!(matches!(
+        check_server_certificate("server.pem", &read_fixture("ocsp-good.der")),
+        Ok(None)
+    ));
+    assert!(matches!(
+        check_server_certificate("server.pem", &read_fixture("ocsp-delegated.der")),
+        Ok(None)
+    ));
+
+    let error =
+        check_server_certificate("server.pem", &read_fixture("ocsp-revoked.der")).unwrap_err();
+    assert!(error.contains("revoked"), "{}", error);
+}
+
+#[test]
+fn invalid_responses_are_rejected() {
+    let ca = read_certificate("ca.pem");
+    let (_, ca) = parse_x509_certificate(&ca).unwrap();
+    let cert_id = server_cert_id();
+    let now = SystemTime::now();
+
+    let response = verify_response(&read_fixture("ocsp-revoked.der"), &cert_id, &ca, now).unwrap();
+    assert_eq!(response.status, CertStatus::Revoked);
+
+    // Change a digit of the producedAt time so that the signature no longer matches.
+    let mut tampered = read_fixture("ocsp-revoked.der");
+    let produced_at = tampered
+        .windows(2)
+        .position(|header| header == [0x18, 0x0f])
+        .unwrap();
+    tampered[produced_at + 2 + 3] ^= 0x01;
+    assert!(verify_response(&tampered, &cert_id, &ca, now).is_err());
+
+    let mut other_cert_id = cert_id.clone();
+    other_cert_id.serial = vec![0x10, 0x01];
+    assert!(verify_response(&read_fixture("ocsp-good.der"), &other_cert_id, &ca, now).is_err());
+
+    let after_next_update = now + Duration::from_secs(200 * 365 * 24 * 60 * 60);
+    assert!(verify_response(
+        &read_fixture("ocsp-good.der"),
+        &cert_id,
+        &ca,
+        after_next_update
+    )
+    .is_err());
+}
+
+#[test]
+fn malformed_responses_are_rejected() {
+    let ca = read_certificate("ca.pem");
+    let (_, ca) = parse_x509_certificate(&ca).unwrap();
+    let cert_id = server_cert_id();
+    let now = SystemTime::now();
+
+    let response = read_fixture("ocsp-delegated.der");
+    verify_response(&response, &cert_id, &ca, now).unwrap();
+
+    for length in 0..response.len() {
+        assert!(
+            verify_response(&response[..length], &cert_id, &ca, now).is_err(),
+            "truncated to {} bytes",
+            length
+        );
+    }
+
+    // Change the tag of the delegated responder's TBSCertificate so that its certificate can no
+    // longer be parsed, while leaving the encoding of the response itself intact.
+    let responder = parse_response(&response).unwrap().certs[0];
+    let offset = response
+        .windows(responder.len())
+        .position(|window| window == responder)
+        .unwrap();
+    let mut malformed = response.clone();
+    malformed[offset + 4] = TAG_OCTET_STRING;
+    assert!(verify_response(&malformed, &cert_id, &ca, now).is_err());
+}
+
+#[test]
+fn delegated_responders_must_be_authorized() {
+    let ca = read_certificate("delegation-ca.pem");
+    let (_, ca) = parse_x509_certificate(&ca).unwrap();
+    let server = read_certificate("delegation-server.pem");
+    let cert_id = CertId::new(&parse_x509_certificate(&server).unwrap().1, &ca);
+    let now = SystemTime::now();
+
+    let response =
+        verify_response(&read_fixture("ocsp-delegate-valid.der"), &cert_id, &ca, now).unwrap();
+    assert_eq!(response.status, CertStatus::Good);
+
+    // The responder's certificate expired in 2021, before the response was produced.
+    let error = verify_response(
+        &read_fixture("ocsp-delegate-expired.der"),
+        &cert_id,
+        &ca,
+        now,
+    )
+    .unwrap_err();
+    assert!(error.contains("authorized responder"), "{}", error);
+
+    // The responder's certificate names the CA as its issuer, but was signed by a different CA
+    // with the same name.
+    let error = verify_response(
+        &read_fixture("ocsp-delegate-wrong-issuer.der"),
+        &cert_id,
+        &ca,
+        now,
+    )
+    .unwrap_err();
+    assert!(error.contains("authorized responder"), "{}", error);
+}
+
+#[test]
+fn must_staple() {
+    let must_staple = read_certificate("server-must-staple.pem");
+    let (_, must_staple) = parse_x509_certificate(&must_staple).unwrap();
+    assert!(requires_stapling(&must_staple));
+    assert_eq!(
+        ocsp_responders(&must_staple),
+        vec!["http://localhost:9000/ocsp".to_string()]
+    );
+
+    let server = read_certificate("server.pem");
+    let (_, server) = parse_x509_certificate(&server).unwrap();
+    assert!(!requires_stapling(&server));
+    assert!(ocsp_responders(&server).is_empty());
+
+    assert!(check_server_certificate("server-must-staple.pem", &[]).is_err());
+    // A response for a different certificate does not satisfy the requirement.
+    assert!(
+        check_server_certificate("server-must-staple.pem", &read_fixture("ocsp-good.der")).is_err()
+    );
+}
+
+#[test]
+fn cached_responses_expire() {
+    let mut cert_id = server_cert_id();
+    cert_id.serial = vec![0x7f, 0xff];
+    let now = SystemTime::now();
+
+    cache_response(
+        &cert_id,
+        VerifiedResponse {
+            status: CertStatus::Revoked,
+            next_update: Some(now + Duration::from_secs(60)),
+        },
+    );
+    assert_eq!(cached_status(&cert_id, now), Some(CertStatus::Revoked));
+    assert_eq!(
+        cached_status(&cert_id, now + Duration::from_secs(120)),
+        None
+    );
+    assert_eq!(cached_status(&cert_id, now), None);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn query_stand_in_responder() {
+    let (url, requests) = start_responder(read_fixture("ocsp-good.der"));
+    query_responders(server_query(vec![url]), &HttpClient::default())
+        .await
+        .unwrap();
+
+    let request = requests.recv().unwrap();
+    let request = String::from_utf8_lossy(&request);
+    assert!(
+        request.starts_with("POST /ocsp HTTP/1.1\r\n"),
+        "{}",
+        request
+    );
+    assert!(request.contains("Content-Type: application/ocsp-request\r\n"));
+
+    let (url, _) = start_responder(read_fixture("ocsp-revoked.der"));
+    let error = query_responders(server_query(vec![url]), &HttpClient::default())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("revoked"), "{}", error);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn unusable_responders_are_ignored() {
+    let (invalid_url, _) = start_responder(b"not an OCSP response".to_vec());
+    let (revoked_url, _) = start_responder(read_fixture("ocsp-revoked.der"));
+
+    // The first responder that returns a valid response determines the status.
+    let query = server_query(vec![
+        "http://127.0.0.1:1/ocsp".to_string(),
+        invalid_url.clone(),
+        revoked_url,
+    ]);
+    assert!(query_responders(query, &HttpClient::default())
+        .await
+        .is_err());
+
+    let query = server_query(vec!["http://127.0.0.1:1/ocsp".to_string(), invalid_url]);
+    query_responders(query, &HttpClient::default())
+        .await
+        .unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn responders_use_dns_resolver() {
+    let (url, requests) = start_responder(read_fixture("ocsp-revoked.der"));
+    let url = url.replace("127.0.0.1", "ocsp.test");
+    let resolver = StaticDnsResolver::new().ip("ocsp.test", IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    let http_client = HttpClient::new(None, Some(Arc::new(resolver)));
+    let error = query_responders(server_query(vec![url]), &http_client)
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("revoked"), "{}", error);
+    assert!(requests.recv().is_ok());
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn responders_use_proxy() {
+    let (url, _) = start_responder(read_fixture("ocsp-revoked.der"));
+
+    // The stand-in proxy hangs up after the greeting, so a revoked status can only be observed if
+    // the responder was contacted directly.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, greetings) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut greeting = [0; 3];
+        stream.read_exact(&mut greeting).unwrap();
+        let _ = sender.send(greeting);
+    });
+
+    let proxy = Socks5Proxy {
+        host: "127.0.0.1".to_string(),
+        port,
+        username: None,
+        password: None,
+    };
+    let http_client = HttpClient::new(Some(proxy), None);
+    query_responders(server_query(vec![url]), &http_client)
+        .await
+        .unwrap();
+    assert_eq!(greetings.recv().unwrap(), [0x05, 0x01, 0x00]);
+}
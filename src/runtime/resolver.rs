@@ -1,11 +1,16 @@
-use trust_dns_resolver::{
-    config::ResolverConfig,
-    error::ResolveErrorKind,
-    lookup::{SrvLookup, TxtLookup},
-    IntoName,
-};
+#[cfg(feature = "gssapi-auth")]
+use std::net::IpAddr;
+use std::{future::Future, pin::Pin, time::Duration};
+
+use trust_dns_proto::rr::RData;
+#[cfg(feature = "gssapi-auth")]
+use trust_dns_proto::rr::RecordType;
+use trust_dns_resolver::{config::ResolverConfig, error::ResolveErrorKind};
 
-use crate::error::{Error, Result};
+use crate::{
+    error::{Error, Result},
+    options::{DnsResolver, SrvRecord},
+};
 
 /// An async runtime agnostic DNS resolver.
 pub(crate) struct AsyncResolver {
@@ -42,24 +47,103 @@ impl AsyncResolver {
     }
 }
 
+#[cfg(feature = "gssapi-auth")]
 impl AsyncResolver {
-    pub async fn srv_lookup<N: IntoName>(&self, query: N) -> Result<SrvLookup> {
+    /// Returns the name that `host` is an alias of, or `None` if it has no CNAME records.
+    pub(crate) async fn canonical_name(&self, host: &str) -> Result<Option<String>> {
+        let lookup = match self
+            .resolver
+            .lookup(host, RecordType::CNAME, Default::default())
+            .await
+        {
+            Ok(lookup) => lookup,
+            Err(e) => match e.kind() {
+                ResolveErrorKind::NoRecordsFound { .. } => return Ok(None),
+                _ => return Err(Error::from_resolve_error(e)),
+            },
+        };
+
+        Ok(lookup.record_iter().find_map(|record| match record.data() {
+            Some(RData::CNAME(name)) => Some(name.to_utf8()),
+            _ => None,
+        }))
+    }
+
+    /// Returns the addresses that `host` resolves to.
+    pub(crate) async fn lookup_ip(&self, host: &str) -> Result<Vec<IpAddr>> {
         let lookup = self
             .resolver
-            .srv_lookup(query)
+            .lookup_ip(host)
             .await
             .map_err(Error::from_resolve_error)?;
-        Ok(lookup)
+
+        Ok(lookup.iter().collect())
     }
 
-    pub async fn txt_lookup<N: IntoName>(&self, query: N) -> Result<Option<TxtLookup>> {
-        let lookup_result = self.resolver.txt_lookup(query).await;
-        match lookup_result {
-            Ok(lookup) => Ok(Some(lookup)),
+    /// Returns the name that `address` points to, or `None` if it has no PTR records.
+    pub(crate) async fn reverse_lookup(&self, address: IpAddr) -> Result<Option<String>> {
+        let lookup = match self.resolver.reverse_lookup(address).await {
+            Ok(lookup) => lookup,
             Err(e) => match e.kind() {
-                ResolveErrorKind::NoRecordsFound { .. } => Ok(None),
-                _ => Err(Error::from_resolve_error(e)),
+                ResolveErrorKind::NoRecordsFound { .. } => return Ok(None),
+                _ => return Err(Error::from_resolve_error(e)),
             },
-        }
+        };
+
+        Ok(lookup.iter().next().map(|name| name.to_utf8()))
+    }
+}
+
+impl DnsResolver for AsyncResolver {
+    fn srv_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SrvRecord>>> + Send + 'a>> {
+        Box::pin(async move {
+            let lookup = self
+                .resolver
+                .srv_lookup(name)
+                .await
+                .map_err(Error::from_resolve_error)?;
+
+            Ok(lookup
+                .as_lookup()
+                .record_iter()
+                .filter_map(|record| match record.data() {
+                    Some(RData::SRV(srv)) => Some(SrvRecord {
+                        target: srv.target().to_utf8(),
+                        port: srv.port(),
+                        ttl: Duration::from_secs(record.ttl().into()),
+                    }),
+                    _ => None,
+                })
+                .collect())
+        })
+    }
+
+    fn txt_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + 'a>> {
+        Box::pin(async move {
+            let lookup = match self.resolver.txt_lookup(name).await {
+                Ok(lookup) => lookup,
+                Err(e) => match e.kind() {
+                    ResolveErrorKind::NoRecordsFound { .. } => return Ok(Vec::new()),
+                    _ => return Err(Error::from_resolve_error(e)),
+                },
+            };
+
+            Ok(lookup
+                .iter()
+                .map(|record| {
+                    record
+                        .txt_data()
+                        .iter()
+                        .map(|bytes| String::from_utf8_lossy(bytes.as_ref()))
+                        .collect()
+                })
+                .collect())
+        })
     }
 }
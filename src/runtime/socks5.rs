@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod test;
+
+use std::{net::IpAddr, time::Duration};
+
+use futures_util::{AsyncReadExt, AsyncWriteExt};
+
+use super::{stream::AsyncTcpStream, DEFAULT_CONNECT_TIMEOUT};
+use crate::{
+    error::{Error, Result},
+    options::{DnsResolver, ServerAddress},
+    runtime,
+};
+
+/// The port used for the proxy when none is specified.
+pub(crate) const DEFAULT_PROXY_PORT: u16 = 1080;
+
+const VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+const USERNAME_PASSWORD: u8 = 0x02;
+const NO_ACCEPTABLE_METHODS: u8 = 0xff;
+const USERNAME_PASSWORD_VERSION: u8 = 0x01;
+const CONNECT: u8 = 0x01;
+const RESERVED: u8 = 0x00;
+const ADDRESS_IPV4: u8 = 0x01;
+const ADDRESS_DOMAIN: u8 = 0x03;
+const ADDRESS_IPV6: u8 = 0x04;
+const SUCCEEDED: u8 = 0x00;
+
+/// The SOCKS5 proxy that connections are tunneled through.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Socks5Proxy {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+}
+
+impl Socks5Proxy {
+    /// Connects to the proxy and asks it to open a tunnel to `address`, returning the stream to
+    /// the proxy once the tunnel is established. The whole process is bounded by the connect
+    /// timeout.
+    pub(crate) async fn connect(
+        &self,
+        address: &ServerAddress,
+        connect_timeout: Option<Duration>,
+        dns_resolver: Option<&dyn DnsResolver>,
+    ) -> Result<AsyncTcpStream> {
+        let proxy_address = ServerAddress::Tcp {
+            host: self.host.clone(),
+            port: Some(self.port),
+        };
+        let connect = async {
+            let mut stream =
+                AsyncTcpStream::connect(&proxy_address, connect_timeout, dns_resolver).await?;
+            self.handshake(&mut stream, address).await?;
+            Ok(stream)
+        };
+
+        let timeout = connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        if timeout == Duration::from_secs(0) {
+            connect.await
+        } else {
+            runtime::timeout(timeout, connect).await?
+        }
+    }
+
+    async fn handshake(&self, stream: &mut AsyncTcpStream, address: &ServerAddress) -> Result<()> {
+        // Negotiate the authentication method, offering username/password authentication only
+        // when credentials were provided.
+        let methods: &[u8] = match (&self.username, &self.password) {
+            (Some(_), Some(_)) => &[NO_AUTHENTICATION, USERNAME_PASSWORD],
+            _ => &[NO_AUTHENTICATION],
+        };
+        let mut greeting = vec![VERSION, methods.len() as u8];
+        greeting.extend_from_slice(methods);
+        stream.write_all(&greeting).await?;
+        stream.flush().await?;
+
+        let mut choice = [0u8; 2];
+        stream.read_exact(&mut choice).await?;
+        if choice[0] != VERSION {
+            return Err(proxy_error("the proxy server does not support SOCKS5"));
+        }
+        match choice[1] {
+            NO_AUTHENTICATION => {}
+            USERNAME_PASSWORD if methods.contains(&USERNAME_PASSWORD) => {
+                self.authenticate(stream).await?
+            }
+            NO_ACCEPTABLE_METHODS => {
+                return Err(proxy_error(
+                    "the proxy server did not accept any of the offered authentication methods",
+                ))
+            }
+            other => {
+                return Err(proxy_error(format!(
+                    "the proxy server selected an unsupported authentication method {:#04x}",
+                    other
+                )))
+            }
+        }
+
+        let mut request = vec![VERSION, CONNECT, RESERVED];
+        encode_address(address, &mut request)?;
+        stream.write_all(&request).await?;
+        stream.flush().await?;
+
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await?;
+        if reply[0] != VERSION {
+            return Err(proxy_error("invalid reply from the proxy server"));
+        }
+        if reply[1] != SUCCEEDED {
+            return Err(proxy_error(format!(
+                "the proxy server failed to connect to {}: {}",
+                address,
+                reply_message(reply[1])
+            )));
+        }
+
+        // The reply ends with the address the proxy bound for the connection, which is not needed.
+        let address_length = match reply[3] {
+            ADDRESS_IPV4 => 4,
+            ADDRESS_IPV6 => 16,
+            ADDRESS_DOMAIN => {
+                let mut length = [0u8; 1];
+                stream.read_exact(&mut length).await?;
+                length[0] as usize
+            }
+            other => {
+                return Err(proxy_error(format!(
+                    "invalid address type {:#04x} in reply from the proxy server",
+                    other
+                )))
+            }
+        };
+        let mut bound_address = vec![0u8; address_length + 2];
+        stream.read_exact(&mut bound_address).await?;
+
+        Ok(())
+    }
+
+    /// Performs username/password authentication as described in RFC 1929.
+    async fn authenticate(&self, stream: &mut AsyncTcpStream) -> Result<()> {
+        let username = self.username.as_deref().unwrap_or_default().as_bytes();
+        let password = self.password.as_deref().unwrap_or_default().as_bytes();
+
+        let mut request = vec![USERNAME_PASSWORD_VERSION, username.len() as u8];
+        request.extend_from_slice(username);
+        request.push(password.len() as u8);
+        request.extend_from_slice(password);
+        stream.write_all(&request).await?;
+        stream.flush().await?;
+
+        let mut response = [0u8; 2];
+        stream.read_exact(&mut response).await?;
+        if response[1] != SUCCEEDED {
+            return Err(proxy_error(
+                "authentication with the proxy server failed; check the proxy username and \
+                 password",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends the SOCKS5 encoding of `address` to `request`. Hostnames are sent to the proxy to be
+/// resolved rather than being resolved locally.
+fn encode_address(address: &ServerAddress, request: &mut Vec<u8>) -> Result<()> {
+    let host = address.host();
+    let port = address.port().unwrap_or(27017);
+
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(ADDRESS_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(ADDRESS_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            if host.len() > u8::MAX as usize {
+                return Err(Error::invalid_argument(format!(
+                    "the hostname {} is too long to be sent to a SOCKS5 proxy",
+                    host
+                )));
+            }
+            request.push(ADDRESS_DOMAIN);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+
+    Ok(())
+}
+
+fn reply_message(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn proxy_error(message: impl Into<String>) -> Error {
+    std::io::Error::new(
+        std::io::ErrorKind::ConnectionRefused,
+        format!("SOCKS5 proxy error: {}", message.into()),
+    )
+    .into()
+}
@@ -0,0 +1,203 @@
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+
+use futures_util::{AsyncReadExt, AsyncWriteExt};
+
+use super::Socks5Proxy;
+use crate::{
+    cmap::options::StreamOptions,
+    error::Result,
+    options::ServerAddress,
+    runtime::AsyncStream,
+};
+
+/// Starts a stand-in SOCKS5 proxy on a local port that accepts a single connection. If the
+/// handshake succeeds, the proxy echoes back everything sent through the tunnel rather than
+/// connecting to the requested address. Returns the proxy's port and a receiver for the encoded
+/// address that was requested.
+fn start_proxy(credentials: Option<(&str, &str)>, reply: u8) -> (u16, mpsc::Receiver<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let credentials = credentials
+        .map(|(username, password)| (username.as_bytes().to_vec(), password.as_bytes().to_vec()));
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let _ = serve(&mut stream, credentials, reply, sender);
+    });
+
+    (port, receiver)
+}
+
+fn serve(
+    stream: &mut TcpStream,
+    credentials: Option<(Vec<u8>, Vec<u8>)>,
+    reply: u8,
+    sender: mpsc::Sender<Vec<u8>>,
+) -> std::io::Result<()> {
+    let greeting = read_bytes(stream, 2)?;
+    let methods = read_bytes(stream, greeting[1] as usize)?;
+
+    if let Some((username, password)) = credentials {
+        if !methods.contains(&0x02) {
+            return stream.write_all(&[0x05, 0xff]);
+        }
+        stream.write_all(&[0x05, 0x02])?;
+
+        let header = read_bytes(stream, 2)?;
+        let given_username = read_bytes(stream, header[1] as usize)?;
+        let length = read_bytes(stream, 1)?;
+        let given_password = read_bytes(stream, length[0] as usize)?;
+        if given_username != username || given_password != password {
+            return stream.write_all(&[0x01, 0x01]);
+        }
+        stream.write_all(&[0x01, 0x00])?;
+    } else {
+        stream.write_all(&[0x05, 0x00])?;
+    }
+
+    let request = read_bytes(stream, 4)?;
+    let mut address = vec![request[3]];
+    match request[3] {
+        0x01 => address.extend(read_bytes(stream, 4 + 2)?),
+        0x04 => address.extend(read_bytes(stream, 16 + 2)?),
+        _ => {
+            let length = read_bytes(stream, 1)?;
+            address.extend(&length);
+            address.extend(read_bytes(stream, length[0] as usize + 2)?);
+        }
+    }
+    let _ = sender.send(address);
+
+    stream.write_all(&[0x05, reply, 0x00, 0x01, 127, 0, 0, 1, 0x04, 0x38])?;
+    if reply != 0x00 {
+        return Ok(());
+    }
+
+    let mut buffer = [0u8; 1024];
+    loop {
+        match stream.read(&mut buffer)? {
+            0 => return Ok(()),
+            n => stream.write_all(&buffer[..n])?,
+        }
+    }
+}
+
+fn read_bytes(stream: &mut TcpStream, length: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; length];
+    stream.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+async fn connect_through_proxy(proxy: Socks5Proxy, address: ServerAddress) -> Result<AsyncStream> {
+    let options = StreamOptions::builder()
+        .address(address)
+        .proxy(proxy)
+        .build();
+    AsyncStream::connect(options).await
+}
+
+fn local_proxy(port: u16, credentials: Option<(&str, &str)>) -> Socks5Proxy {
+    Socks5Proxy {
+        host: "localhost".to_string(),
+        port,
+        username: credentials.map(|(username, _)| username.to_string()),
+        password: credentials.map(|(_, password)| password.to_string()),
+    }
+}
+
+async fn assert_tunnel_echoes(stream: &mut AsyncStream) {
+    stream.write_all(b"hello").await.unwrap();
+    stream.flush().await.unwrap();
+    let mut echoed = [0u8; 5];
+    stream.read_exact(&mut echoed).await.unwrap();
+    assert_eq!(&echoed, b"hello");
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn connect_without_authentication() {
+    let (port, requests) = start_proxy(None, 0x00);
+
+    let mut stream = connect_through_proxy(
+        local_proxy(port, None),
+        ServerAddress::parse("db.example.com:27018").unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_tunnel_echoes(&mut stream).await;
+
+    // The hostname is sent to the proxy to be resolved there.
+    let mut expected = vec![0x03, 14];
+    expected.extend_from_slice(b"db.example.com");
+    expected.extend_from_slice(&27018u16.to_be_bytes());
+    assert_eq!(requests.recv().unwrap(), expected);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn connect_with_authentication() {
+    let credentials = Some(("analyst", "p@ssword"));
+    let (port, requests) = start_proxy(credentials, 0x00);
+
+    let mut stream = connect_through_proxy(
+        local_proxy(port, credentials),
+        ServerAddress::parse("10.1.2.3").unwrap(),
+    )
+    .await
+    .unwrap();
+    assert_tunnel_echoes(&mut stream).await;
+    assert_eq!(
+        requests.recv().unwrap(),
+        vec![0x01, 10, 1, 2, 3, 0x69, 0x89]
+    );
+
+    let (port, _) = start_proxy(credentials, 0x00);
+    let error = connect_through_proxy(
+        local_proxy(port, Some(("analyst", "wrong"))),
+        ServerAddress::parse("10.1.2.3:27017").unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.is_network_error(), "{:?}", error);
+    assert!(error.to_string().contains("authentication"), "{}", error);
+
+    // The proxy requires authentication, but no credentials were provided.
+    let (port, _) = start_proxy(credentials, 0x00);
+    let error = connect_through_proxy(
+        local_proxy(port, None),
+        ServerAddress::parse("10.1.2.3:27017").unwrap(),
+    )
+    .await
+    .unwrap_err();
+    assert!(error.to_string().contains("authentication"), "{}", error);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn proxy_connection_failure() {
+    let (port, requests) = start_proxy(None, 0x05);
+
+    let address = ServerAddress::Tcp {
+        host: "::1".to_string(),
+        port: Some(27017),
+    };
+    let error = connect_through_proxy(local_proxy(port, None), address)
+        .await
+        .unwrap_err();
+    assert!(error.is_network_error(), "{:?}", error);
+    assert!(
+        error.to_string().contains("connection refused"),
+        "{}",
+        error
+    );
+
+    let mut expected = vec![0x04];
+    expected.extend_from_slice(&std::net::Ipv6Addr::LOCALHOST.octets());
+    expected.extend_from_slice(&27017u16.to_be_bytes());
+    assert_eq!(requests.recv().unwrap(), expected);
+}
@@ -1,3 +1,5 @@
+#[cfg(unix)]
+use std::path::Path;
 use std::{
     net::SocketAddr,
     ops::DerefMut,
@@ -12,13 +14,13 @@ use tokio::io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, Read
 use crate::{
     cmap::options::StreamOptions,
     error::{ErrorKind, Result},
-    options::ServerAddress,
+    options::{DnsResolver, ServerAddress},
     runtime,
 };
 
-use super::tls::AsyncTlsStream;
+use super::{http::HttpClient, tls::AsyncTlsStream};
 
-const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+pub(crate) const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 const KEEPALIVE_TIME: Duration = Duration::from_secs(120);
 
 /// A runtime-agnostic async stream possibly using TLS.
@@ -32,6 +34,10 @@ pub(crate) enum AsyncStream {
 
     /// A TLS connection over TCP.
     Tls(AsyncTlsStream),
+
+    /// A connection over a Unix Domain Socket.
+    #[cfg(unix)]
+    Unix(AsyncUnixStream),
 }
 
 /// A runtime-agnostic async stream.
@@ -46,6 +52,19 @@ pub(crate) enum AsyncTcpStream {
     AsyncStd(async_std::net::TcpStream),
 }
 
+/// A runtime-agnostic Unix Domain Socket stream.
+#[cfg(unix)]
+#[derive(Debug)]
+pub(crate) enum AsyncUnixStream {
+    /// Wrapper around `tokio::net::UnixStream`.
+    #[cfg(feature = "tokio-runtime")]
+    Tokio(tokio::net::UnixStream),
+
+    /// Wrapper around `async_std::os::unix::net::UnixStream`.
+    #[cfg(feature = "async-std-runtime")]
+    AsyncStd(async_std::os::unix::net::UnixStream),
+}
+
 #[cfg(feature = "tokio-runtime")]
 impl From<tokio::net::TcpStream> for AsyncTcpStream {
     fn from(stream: tokio::net::TcpStream) -> Self {
@@ -107,10 +126,14 @@ impl AsyncTcpStream {
         Ok(stream.into())
     }
 
-    async fn connect(address: &ServerAddress, connect_timeout: Option<Duration>) -> Result<Self> {
+    pub(crate) async fn connect(
+        address: &ServerAddress,
+        connect_timeout: Option<Duration>,
+        dns_resolver: Option<&dyn DnsResolver>,
+    ) -> Result<Self> {
         let timeout = connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
 
-        let mut socket_addrs: Vec<_> = runtime::resolve_address(address).await?.collect();
+        let mut socket_addrs = runtime::resolve_address(address, dns_resolver).await?;
 
         if socket_addrs.is_empty() {
             return Err(ErrorKind::DnsResolve {
@@ -141,16 +164,72 @@ impl AsyncTcpStream {
     }
 }
 
+#[cfg(unix)]
+impl AsyncUnixStream {
+    async fn connect(path: &Path, connect_timeout: Option<Duration>) -> Result<Self> {
+        #[cfg(feature = "tokio-runtime")]
+        use tokio::net::UnixStream;
+
+        #[cfg(feature = "async-std-runtime")]
+        use async_std::os::unix::net::UnixStream;
+
+        let timeout = connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        let stream_future = UnixStream::connect(path);
+
+        let stream = if timeout == Duration::from_secs(0) {
+            stream_future.await?
+        } else {
+            runtime::timeout(timeout, stream_future).await??
+        };
+
+        #[cfg(feature = "tokio-runtime")]
+        return Ok(Self::Tokio(stream));
+
+        #[cfg(feature = "async-std-runtime")]
+        return Ok(Self::AsyncStd(stream));
+    }
+}
+
 impl AsyncStream {
-    /// Creates a new Tokio TCP stream connected to the server as specified by `options`.
+    /// Creates a new stream connected to the server as specified by `options`, tunneled through
+    /// the SOCKS5 proxy if one is specified. Connections over a Unix Domain Socket are never
+    /// proxied or wrapped with TLS.
     pub(crate) async fn connect(options: StreamOptions) -> Result<Self> {
-        let inner = AsyncTcpStream::connect(&options.address, options.connect_timeout).await?;
+        #[cfg(unix)]
+        if let ServerAddress::Unix { ref path } = options.address {
+            let inner = AsyncUnixStream::connect(path, options.connect_timeout).await?;
+            return Ok(Self::Unix(inner));
+        }
+
+        let inner = match options.proxy {
+            Some(ref proxy) => {
+                proxy
+                    .connect(
+                        &options.address,
+                        options.connect_timeout,
+                        options.dns_resolver.as_deref(),
+                    )
+                    .await?
+            }
+            None => {
+                AsyncTcpStream::connect(
+                    &options.address,
+                    options.connect_timeout,
+                    options.dns_resolver.as_deref(),
+                )
+                .await?
+            }
+        };
 
         // If there are TLS options, wrap the inner stream with rustls.
         match options.tls_options {
             Some(cfg) => {
                 let host = options.address.host();
-                Ok(Self::Tls(AsyncTlsStream::connect(host, inner, cfg).await?))
+                // OCSP responders are queried over the same route as the server.
+                let http_client = HttpClient::new(options.proxy, options.dns_resolver);
+                Ok(Self::Tls(
+                    AsyncTlsStream::connect(&host, inner, cfg, &http_client).await?,
+                ))
             }
             None => Ok(Self::Tcp(inner)),
         }
@@ -167,6 +246,8 @@ impl AsyncRead for AsyncStream {
             Self::Null => Poll::Ready(Ok(0)),
             Self::Tcp(ref mut inner) => AsyncRead::poll_read(Pin::new(inner), cx, buf),
             Self::Tls(ref mut inner) => Pin::new(inner).poll_read(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(ref mut inner) => Pin::new(inner).poll_read(cx, buf),
         }
     }
 }
@@ -181,6 +262,8 @@ impl AsyncWrite for AsyncStream {
             Self::Null => Poll::Ready(Ok(0)),
             Self::Tcp(ref mut inner) => AsyncWrite::poll_write(Pin::new(inner), cx, buf),
             Self::Tls(ref mut inner) => Pin::new(inner).poll_write(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(ref mut inner) => Pin::new(inner).poll_write(cx, buf),
         }
     }
 
@@ -189,6 +272,8 @@ impl AsyncWrite for AsyncStream {
             Self::Null => Poll::Ready(Ok(())),
             Self::Tcp(ref mut inner) => AsyncWrite::poll_flush(Pin::new(inner), cx),
             Self::Tls(ref mut inner) => Pin::new(inner).poll_flush(cx),
+            #[cfg(unix)]
+            Self::Unix(ref mut inner) => Pin::new(inner).poll_flush(cx),
         }
     }
 
@@ -197,6 +282,8 @@ impl AsyncWrite for AsyncStream {
             Self::Null => Poll::Ready(Ok(())),
             Self::Tcp(ref mut inner) => Pin::new(inner).poll_close(cx),
             Self::Tls(ref mut inner) => Pin::new(inner).poll_close(cx),
+            #[cfg(unix)]
+            Self::Unix(ref mut inner) => Pin::new(inner).poll_close(cx),
         }
     }
 }
@@ -269,6 +356,76 @@ impl AsyncWrite for AsyncTcpStream {
     }
 }
 
+#[cfg(unix)]
+impl AsyncRead for AsyncUnixStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        // We need `mut` here for the tokio impl, but it isn't used by the async-std version, so we
+        // suppress the warning.
+        #[allow(unused_mut)] mut buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.deref_mut() {
+            #[cfg(feature = "tokio-runtime")]
+            Self::Tokio(ref mut stream) => {
+                tokio_util::io::poll_read_buf(Pin::new(stream), cx, &mut buf)
+            }
+
+            #[cfg(feature = "async-std-runtime")]
+            Self::AsyncStd(ref mut stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl AsyncWrite for AsyncUnixStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.deref_mut() {
+            #[cfg(feature = "tokio-runtime")]
+            Self::Tokio(ref mut stream) => {
+                use tokio::io::AsyncWrite;
+
+                Pin::new(stream).poll_write(cx, buf)
+            }
+
+            #[cfg(feature = "async-std-runtime")]
+            Self::AsyncStd(ref mut stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.deref_mut() {
+            #[cfg(feature = "tokio-runtime")]
+            Self::Tokio(ref mut stream) => {
+                use tokio::io::AsyncWrite;
+
+                Pin::new(stream).poll_flush(cx)
+            }
+
+            #[cfg(feature = "async-std-runtime")]
+            Self::AsyncStd(ref mut stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.deref_mut() {
+            #[cfg(feature = "tokio-runtime")]
+            Self::Tokio(ref mut stream) => {
+                use tokio::io::AsyncWrite;
+
+                Pin::new(stream).poll_shutdown(cx)
+            }
+
+            #[cfg(feature = "async-std-runtime")]
+            Self::AsyncStd(ref mut stream) => Pin::new(stream).poll_close(cx),
+        }
+    }
+}
+
 // These trait implementations are required to interface with tokio-rustls.
 
 impl TokioAsyncRead for AsyncTcpStream {
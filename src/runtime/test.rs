@@ -0,0 +1,14 @@
+/// The CRL files that the certificate chain in `crl-server.pem` is checked against by each TLS
+/// backend, along with whether the chain should be accepted. The server's certificate is issued
+/// by an intermediate CA whose certificate is issued by the CA in `crl-ca.pem`.
+pub(super) const CRL_CASES: &[(&str, bool)] = &[
+    // The root's CRL in this file revokes the intermediate, but only the server's certificate is
+    // checked.
+    ("crl-valid.pem", true),
+    ("crl-revoked.pem", false),
+    ("crl-expired.pem", false),
+    // Signed with a key other than the intermediate's.
+    ("crl-forged.pem", false),
+    // Only contains a CRL from the root, so there is none from the server certificate's issuer.
+    ("crl-other-issuer.pem", false),
+];
@@ -1,3 +1,8 @@
+#[cfg(test)]
+mod test;
+
+#[cfg(feature = "cert-revocation")]
+use std::time::SystemTime;
 use std::{
     pin::Pin,
     sync::Once,
@@ -7,7 +12,9 @@ use std::{
 use futures_io::{AsyncRead, AsyncWrite};
 use openssl::{
     error::ErrorStack,
-    ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode},
+    pkey::PKey,
+    ssl::{SslConnector, SslFiletype, SslMethod, SslVerifyMode, StatusType},
+    x509::{store::X509Lookup, verify::X509VerifyFlags, X509},
 };
 use tokio::io::AsyncWrite as TokioAsyncWrite;
 use tokio_openssl::SslStream;
@@ -17,7 +24,9 @@ use crate::{
     error::{Error, ErrorKind, Result},
 };
 
-use super::stream::AsyncTcpStream;
+#[cfg(feature = "cert-revocation")]
+use super::ocsp;
+use super::{http::HttpClient, stream::AsyncTcpStream, PendingQuery};
 
 #[derive(Debug)]
 pub(crate) struct AsyncTlsStream {
@@ -29,14 +38,13 @@ impl AsyncTlsStream {
         host: &str,
         tcp_stream: AsyncTcpStream,
         cfg: TlsOptions,
+        #[cfg_attr(not(feature = "cert-revocation"), allow(unused_variables))]
+        http_client: &HttpClient,
     ) -> Result<Self> {
         init_trust();
 
-        let mut stream = make_ssl_stream(host, tcp_stream, cfg).map_err(|err| {
-            Error::from(ErrorKind::InvalidTlsConfig {
-                message: err.to_string(),
-            })
-        })?;
+        let pending_query = PendingQuery::default();
+        let mut stream = make_ssl_stream(host, tcp_stream, cfg, &pending_query)?;
         Pin::new(&mut stream).connect().await.map_err(|err| {
             use std::io;
             match err.into_io_error() {
@@ -44,6 +52,15 @@ impl AsyncTlsStream {
                 Err(err) => io::Error::new(io::ErrorKind::Other, err),
             }
         })?;
+
+        #[cfg(feature = "cert-revocation")]
+        {
+            let query = pending_query.lock().unwrap().take();
+            if let Some(query) = query {
+                ocsp::query_responders(query, http_client).await?;
+            }
+        }
+
         Ok(AsyncTlsStream { inner: stream })
     }
 }
@@ -76,25 +93,132 @@ impl AsyncWrite for AsyncTlsStream {
     }
 }
 
-fn make_openssl_connector(cfg: TlsOptions) -> std::result::Result<SslConnector, ErrorStack> {
-    let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+/// Converts `TlsOptions` into an `SslConnector`. Any OCSP responder query that needs to be
+/// performed after the handshake is stored in `pending_query`.
+fn make_openssl_connector(
+    cfg: TlsOptions,
+    #[cfg_attr(not(feature = "cert-revocation"), allow(unused_variables))]
+    pending_query: &PendingQuery,
+) -> Result<SslConnector> {
+    let openssl_err = |err: ErrorStack| {
+        Error::from(ErrorKind::InvalidTlsConfig {
+            message: err.to_string(),
+        })
+    };
+
+    let mut builder = SslConnector::builder(SslMethod::tls_client()).map_err(openssl_err)?;
 
+    #[cfg_attr(not(feature = "cert-revocation"), allow(unused_variables))]
     let TlsOptions {
         allow_invalid_certificates,
         ca_file_path,
+        ca_pem,
         cert_key_file_path,
+        cert_key_pem,
+        #[cfg(feature = "cert-key-password")]
+        cert_key_file_password,
+        crl_file_path,
+        disable_ocsp_endpoint_check,
+        disable_certificate_revocation_check,
         allow_invalid_hostnames: _,
     } = cfg;
 
     if let Some(true) = allow_invalid_certificates {
         builder.set_verify(SslVerifyMode::NONE);
     }
+    let mut ca_certificates = Vec::new();
     if let Some(path) = ca_file_path {
-        builder.set_ca_file(path)?;
+        for cert in X509::stack_from_pem(&std::fs::read(&path)?).map_err(openssl_err)? {
+            ca_certificates.push(cert.to_der().map_err(openssl_err)?);
+        }
+        builder.set_ca_file(path).map_err(openssl_err)?;
     }
-    if let Some(path) = cert_key_file_path {
-        builder.set_certificate_file(path.clone(), SslFiletype::PEM)?;
-        builder.set_private_key_file(path, SslFiletype::PEM)?;
+    if let Some(pem) = ca_pem {
+        for cert in X509::stack_from_pem(&pem).map_err(openssl_err)? {
+            ca_certificates.push(cert.to_der().map_err(openssl_err)?);
+            builder
+                .cert_store_mut()
+                .add_cert(cert)
+                .map_err(openssl_err)?;
+        }
+    }
+
+    let cert_key_pem = match (cert_key_file_path, cert_key_pem) {
+        (Some(_), Some(_)) => {
+            return Err(ErrorKind::InvalidTlsConfig {
+                message: "cert_key_file_path and cert_key_pem cannot both be specified".to_string(),
+            }
+            .into())
+        }
+        (Some(path), None) => Some(std::fs::read(path)?),
+        (None, pem) => pem,
+    };
+    if let Some(pem) = cert_key_pem {
+        let mut certs = X509::stack_from_pem(&pem).map_err(openssl_err)?.into_iter();
+        if let Some(cert) = certs.next() {
+            builder.set_certificate(&cert).map_err(openssl_err)?;
+        }
+        for cert in certs {
+            builder.add_extra_chain_cert(cert).map_err(openssl_err)?;
+        }
+
+        #[cfg(feature = "cert-key-password")]
+        let key = match cert_key_file_password {
+            Some(password) => PKey::private_key_from_pem_passphrase(&pem, &password),
+            None => PKey::private_key_from_pem(&pem),
+        };
+        #[cfg(not(feature = "cert-key-password"))]
+        let key = PKey::private_key_from_pem(&pem);
+        let key = key.map_err(openssl_err)?;
+        builder.set_private_key(&key).map_err(openssl_err)?;
+    }
+
+    if let Some(path) = crl_file_path {
+        builder
+            .cert_store_mut()
+            .add_lookup(X509Lookup::file())
+            .and_then(|lookup| lookup.load_crl_file(path, SslFiletype::PEM))
+            .map_err(openssl_err)?;
+        // Only the server's own certificate is checked against the CRLs.
+        builder
+            .cert_store_mut()
+            .set_flags(X509VerifyFlags::CRL_CHECK)
+            .map_err(openssl_err)?;
+    }
+
+    #[cfg(feature = "cert-revocation")]
+    if disable_certificate_revocation_check != Some(true)
+        && allow_invalid_certificates != Some(true)
+    {
+        let endpoint_check = disable_ocsp_endpoint_check != Some(true);
+        let pending_query = pending_query.clone();
+        builder
+            .set_status_callback(move |ssl| {
+                let end_entity = match ssl.peer_certificate() {
+                    Some(certificate) => certificate.to_der()?,
+                    None => return Ok(true),
+                };
+                let mut chain = Vec::new();
+                for certificate in ssl.peer_cert_chain().into_iter().flatten() {
+                    chain.push(certificate.to_der()?);
+                }
+                chain.extend(ca_certificates.iter().cloned());
+
+                match ocsp::check_certificate(
+                    &end_entity,
+                    chain.iter().map(Vec::as_slice),
+                    ssl.ocsp_status().unwrap_or_default(),
+                    endpoint_check,
+                    SystemTime::now(),
+                ) {
+                    Ok(query) => {
+                        *pending_query.lock().unwrap() = query;
+                        Ok(true)
+                    }
+                    Err(_) => Ok(false),
+                }
+            })
+            .map_err(openssl_err)?;
     }
 
     Ok(builder.build())
@@ -109,13 +233,28 @@ fn make_ssl_stream(
     host: &str,
     tcp_stream: AsyncTcpStream,
     cfg: TlsOptions,
-) -> std::result::Result<SslStream<AsyncTcpStream>, ErrorStack> {
+    pending_query: &PendingQuery,
+) -> Result<SslStream<AsyncTcpStream>> {
     let verify_hostname = !cfg.allow_invalid_hostnames.unwrap_or(false);
-    let connector = make_openssl_connector(cfg)?;
+    let check_revocation =
+        cfg!(feature = "cert-revocation") && cfg.disable_certificate_revocation_check != Some(true);
+    let connector = make_openssl_connector(cfg, pending_query)?;
     let ssl = connector
-        .configure()?
-        .use_server_name_indication(true)
-        .verify_hostname(verify_hostname)
-        .into_ssl(host)?;
-    SslStream::new(ssl, tcp_stream)
+        .configure()
+        .and_then(|mut config| {
+            if check_revocation {
+                config.set_status_type(StatusType::OCSP)?;
+            }
+            config
+                .use_server_name_indication(true)
+                .verify_hostname(verify_hostname)
+                .into_ssl(host)
+        })
+        .and_then(|ssl| SslStream::new(ssl, tcp_stream))
+        .map_err(|err| {
+            Error::from(ErrorKind::InvalidTlsConfig {
+                message: err.to_string(),
+            })
+        })?;
+    Ok(ssl)
 }
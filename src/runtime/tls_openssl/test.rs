@@ -0,0 +1,46 @@
+use std::path::PathBuf;
+
+use openssl::{
+    stack::Stack,
+    x509::{X509StoreContext, X509},
+};
+
+use super::make_openssl_connector;
+use crate::{client::options::TlsOptions, runtime::test::CRL_CASES};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/test/tls")
+        .join(name)
+}
+
+#[test]
+fn crl_cases() {
+    let pem = std::fs::read(fixture("crl-server.pem")).unwrap();
+    let mut chain = X509::stack_from_pem(&pem).unwrap().into_iter();
+    let end_entity = chain.next().unwrap();
+    let mut intermediates = Stack::new().unwrap();
+    for certificate in chain {
+        intermediates.push(certificate).unwrap();
+    }
+
+    for (crl, accepted) in CRL_CASES {
+        let options = TlsOptions::builder()
+            .ca_file_path(fixture("crl-ca.pem"))
+            .crl_file_path(fixture(crl))
+            .disable_certificate_revocation_check(true)
+            .build();
+        let connector = make_openssl_connector(options, &Default::default()).unwrap();
+
+        let mut context = X509StoreContext::new().unwrap();
+        let verified = context
+            .init(
+                connector.context().cert_store(),
+                &end_entity,
+                &intermediates,
+                |context| context.verify_cert(),
+            )
+            .unwrap();
+        assert_eq!(verified, *accepted, "{}", crl);
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+#[cfg(feature = "cert-revocation")]
+use std::{convert::TryFrom, sync::Arc, time::SystemTime};
+
+#[cfg(feature = "cert-revocation")]
+use rustls::{
+    client::{ServerCertVerifier, ServerName, WebPkiVerifier},
+    Certificate,
+    RootCertStore,
+};
+#[cfg(feature = "cert-revocation")]
+use rustls_pemfile::certs;
+
+use super::make_rustls_config;
+#[cfg(feature = "cert-revocation")]
+use super::{add_root_certificates, read_crls, CrlVerifier, OcspVerifier};
+#[cfg(feature = "cert-revocation")]
+use crate::runtime::test::CRL_CASES;
+use crate::{client::options::TlsOptions, error::ErrorKind};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/test/tls")
+        .join(name)
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    std::fs::read(fixture(name)).unwrap()
+}
+
+#[cfg(feature = "cert-revocation")]
+fn verify_server_certificate(
+    verifier: &dyn ServerCertVerifier,
+    ocsp_response: &[u8],
+) -> Result<(), rustls::Error> {
+    let server_cert = certs(&mut read_fixture("server.pem").as_slice())
+        .unwrap()
+        .remove(0);
+    verifier
+        .verify_server_cert(
+            &Certificate(server_cert),
+            &[],
+            &ServerName::try_from("localhost").unwrap(),
+            &mut std::iter::empty(),
+            ocsp_response,
+            SystemTime::now(),
+        )
+        .map(|_| ())
+}
+
+#[test]
+fn in_memory_certificates() {
+    let options = TlsOptions::builder()
+        .ca_pem(read_fixture("ca.pem"))
+        .cert_key_pem(read_fixture("client.pem"))
+        .build();
+    make_rustls_config(options, &Default::default()).unwrap();
+}
+
+#[test]
+fn cert_key_file_path_and_pem_are_exclusive() {
+    let options = TlsOptions::builder()
+        .cert_key_file_path(fixture("client.pem"))
+        .cert_key_pem(read_fixture("client.pem"))
+        .build();
+    let error = make_rustls_config(options, &Default::default()).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidTlsConfig { .. }));
+}
+
+#[cfg(feature = "cert-revocation")]
+#[test]
+fn revoked_certificate_is_rejected() {
+    let mut store = RootCertStore::empty();
+    let ca_certificates =
+        add_root_certificates(&mut store, &read_fixture("ca.pem"), "ca.pem").unwrap();
+
+    let verifier = WebPkiVerifier::new(store.clone(), None);
+    verify_server_certificate(&verifier, &[]).unwrap();
+
+    let verifier = CrlVerifier {
+        inner: WebPkiVerifier::new(store, None),
+        ca_certificates,
+        crls: read_crls(&read_fixture("crl.pem"), "crl.pem").unwrap(),
+    };
+    let error = verify_server_certificate(&verifier, &[]).unwrap_err();
+    assert!(error.to_string().contains("revoked"), "{}", error);
+}
+
+#[cfg(feature = "cert-revocation")]
+#[test]
+fn revoked_stapled_response_is_rejected() {
+    let mut store = RootCertStore::empty();
+    let ca_certificates =
+        add_root_certificates(&mut store, &read_fixture("ca.pem"), "ca.pem").unwrap();
+
+    let verifier = OcspVerifier {
+        inner: Arc::new(WebPkiVerifier::new(store, None)),
+        ca_certificates,
+        endpoint_check: true,
+        pending_query: Default::default(),
+    };
+    verify_server_certificate(&verifier, &read_fixture("ocsp-good.der")).unwrap();
+
+    let error =
+        verify_server_certificate(&verifier, &read_fixture("ocsp-revoked.der")).unwrap_err();
+    assert!(error.to_string().contains("revoked"), "{}", error);
+}
+
+#[cfg(feature = "cert-revocation")]
+#[test]
+fn crl_cases() {
+    let mut store = RootCertStore::empty();
+    let ca_certificates =
+        add_root_certificates(&mut store, &read_fixture("crl-ca.pem"), "crl-ca.pem").unwrap();
+    let mut chain = certs(&mut read_fixture("crl-server.pem").as_slice())
+        .unwrap()
+        .into_iter()
+        .map(Certificate);
+    let end_entity = chain.next().unwrap();
+    let intermediates: Vec<_> = chain.collect();
+
+    for (crl, accepted) in CRL_CASES {
+        let verifier = CrlVerifier {
+            inner: WebPkiVerifier::new(store.clone(), None),
+            ca_certificates: ca_certificates.clone(),
+            crls: read_crls(&read_fixture(crl), crl).unwrap(),
+        };
+        let result = verifier.verify_server_cert(
+            &end_entity,
+            &intermediates,
+            &ServerName::try_from("localhost").unwrap(),
+            &mut std::iter::empty(),
+            &[],
+            SystemTime::now(),
+        );
+        assert_eq!(result.is_ok(), *accepted, "{}: {:?}", crl, result.err());
+    }
+}
+
+#[cfg(feature = "cert-revocation")]
+#[test]
+fn crl_file_without_crls_is_rejected() {
+    let error = read_crls(&read_fixture("ca.pem"), "ca.pem").unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidTlsConfig { .. }));
+}
+
+#[cfg(not(feature = "cert-revocation"))]
+#[test]
+fn crl_file_requires_cert_revocation_feature() {
+    let options = TlsOptions::builder()
+        .ca_file_path(fixture("crl-ca.pem"))
+        .crl_file_path(fixture("crl-valid.pem"))
+        .build();
+    let error = make_rustls_config(options, &Default::default()).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidTlsConfig { .. }));
+}
+
+#[cfg(feature = "cert-key-password")]
+#[test]
+fn encrypted_private_key() {
+    let options = TlsOptions::builder()
+        .cert_key_pem(read_fixture("client-encrypted.pem"))
+        .cert_key_file_password(b"password".to_vec())
+        .build();
+    make_rustls_config(options, &Default::default()).unwrap();
+
+    let options = TlsOptions::builder()
+        .cert_key_pem(read_fixture("client-encrypted.pem"))
+        .cert_key_file_password(b"wrong".to_vec())
+        .build();
+    let error = make_rustls_config(options, &Default::default()).unwrap_err();
+    assert!(matches!(*error.kind, ErrorKind::InvalidTlsConfig { .. }));
+}
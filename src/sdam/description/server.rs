@@ -5,7 +5,7 @@ use serde::Deserialize;
 use crate::{
     bson::{oid::ObjectId, DateTime},
     client::ClusterTime,
-    hello::HelloReply,
+    hello::{HelloReply, TopologyVersion},
     options::ServerAddress,
     selection_criteria::TagSet,
 };
@@ -95,6 +95,10 @@ pub(crate) struct ServerDescription {
     /// The average duration of this server's hello calls.
     pub(crate) average_round_trip_time: Option<Duration>,
 
+    /// The topology version most recently reported by this server, either in a hello reply or
+    /// alongside an error.
+    pub(crate) topology_version: Option<TopologyVersion>,
+
     // The SDAM spec indicates that a ServerDescription needs to contain an error message if an
     // error occurred when trying to send an hello for the server's heartbeat. Additionally,
     // we need to be able to create a server description that doesn't contain either an hello
@@ -132,10 +136,9 @@ impl ServerDescription {
         mut address: ServerAddress,
         hello_reply: Option<Result<HelloReply, String>>,
     ) -> Self {
-        address = ServerAddress::Tcp {
-            host: address.host().to_lowercase(),
-            port: address.port(),
-        };
+        if let ServerAddress::Tcp { ref mut host, .. } = address {
+            *host = host.to_lowercase();
+        }
 
         let mut description = Self {
             address,
@@ -143,6 +146,7 @@ impl ServerDescription {
             last_update_time: None,
             reply: hello_reply.transpose(),
             average_round_trip_time: None,
+            topology_version: None,
         };
 
         // We want to set last_update_time if we got any sort of response from the server.
@@ -160,6 +164,8 @@ impl ServerDescription {
             // description.
             description.average_round_trip_time = Some(reply.round_trip_time);
 
+            description.topology_version = reply.command_response.topology_version;
+
             // Normalize all instances of hostnames to lowercase.
             if let Some(ref mut hosts) = reply.command_response.hosts {
                 let normalized_hostnames = hosts
@@ -299,16 +299,47 @@ impl TopologyDescription {
     }
 
     /// Update the ServerDescription's round trip time based on the rolling average.
+    ///
+    /// Descriptions produced from awaited hello replies don't carry a round trip time, so the
+    /// previous average is kept for them as long as the server is still available.
     fn update_round_trip_time(&self, server_description: &mut ServerDescription) {
         if let Some(old_rtt) = self
             .servers
             .get(&server_description.address)
             .and_then(|server_desc| server_desc.average_round_trip_time)
         {
-            if let Some(new_rtt) = server_description.average_round_trip_time {
+            match server_description.average_round_trip_time {
+                Some(new_rtt) => {
+                    server_description.average_round_trip_time =
+                        Some(weighted_average_round_trip_time(old_rtt, new_rtt));
+                }
+                None if server_description.is_available() => {
+                    server_description.average_round_trip_time = Some(old_rtt);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Incorporates a round trip time measured outside of the server's regular heartbeats (i.e. by
+    /// its RTT monitor) into the server's average.
+    ///
+    /// Returns whether the sample was recorded, which only happens for available servers.
+    pub(crate) fn add_round_trip_time_sample(
+        &mut self,
+        address: &ServerAddress,
+        round_trip_time: Duration,
+    ) -> bool {
+        match self.servers.get_mut(address) {
+            Some(server_description) if server_description.is_available() => {
                 server_description.average_round_trip_time =
-                    Some((new_rtt / 5) + (old_rtt * 4 / 5));
+                    Some(match server_description.average_round_trip_time {
+                        Some(old_rtt) => weighted_average_round_trip_time(old_rtt, round_trip_time),
+                        None => round_trip_time,
+                    });
+                true
             }
+            _ => false,
         }
     }
 
@@ -459,8 +490,20 @@ impl TopologyDescription {
         mut server_description: ServerDescription,
     ) -> Result<(), String> {
         // Ignore updates from servers not currently in the cluster.
-        if !self.servers.contains_key(&server_description.address) {
-            return Ok(());
+        let current_topology_version = match self.servers.get(&server_description.address) {
+            Some(current) => current.topology_version,
+            None => return Ok(()),
+        };
+
+        // Ignore responses describing an older state of the server than the one we already know
+        // about.
+        if let (Some(new), Some(current)) = (
+            server_description.topology_version,
+            current_topology_version,
+        ) {
+            if new < current {
+                return Ok(());
+            }
         }
 
         // Update the round trip time on the server description to the weighted average as described
@@ -864,3 +907,9 @@ fn verify_max_staleness_inner(max_staleness: Option<Duration>) -> std::result::R
 
     Ok(())
 }
+
+/// Computes the exponentially weighted moving average of a server's round trip time as described
+/// by the server selection spec.
+fn weighted_average_round_trip_time(old_rtt: Duration, new_rtt: Duration) -> Duration {
+    (new_rtt / 5) + (old_rtt * 4 / 5)
+}
@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use serde::Deserialize;
 
 use crate::{
     sdam::description::{
-        server::ServerDescription,
+        server::{ServerDescription, ServerType},
         topology::{test::f64_ms_as_duration, TopologyDescription, TopologyType},
     },
     test::run_spec_test,
@@ -40,7 +40,18 @@ async fn run_test(test_file: TestFile) {
     old_server_desc.average_round_trip_time = avg_rtt_ms.map(f64_ms_as_duration);
     new_server_desc.average_round_trip_time = Some(f64_ms_as_duration(test_file.new_rtt_ms));
 
-    let topology = TopologyDescription {
+    let topology = topology_with_server(old_server_desc);
+
+    topology.update_round_trip_time(&mut new_server_desc);
+
+    assert_eq!(
+        new_server_desc.average_round_trip_time,
+        Some(f64_ms_as_duration(test_file.new_avg_rtt))
+    );
+}
+
+fn topology_with_server(server_description: ServerDescription) -> TopologyDescription {
+    TopologyDescription {
         single_seed: false,
         topology_type: TopologyType::ReplicaSetNoPrimary,
         set_name: None,
@@ -54,17 +65,38 @@ async fn run_test(test_file: TestFile) {
         heartbeat_freq: None,
         servers: {
             let mut servers = HashMap::new();
-            servers.insert(Default::default(), old_server_desc);
+            servers.insert(server_description.address.clone(), server_description);
 
             servers
         },
-    };
+    }
+}
 
-    topology.update_round_trip_time(&mut new_server_desc);
+#[test]
+fn rtt_monitor_samples() {
+    let mut server_desc = ServerDescription::new(Default::default(), None);
+    server_desc.server_type = ServerType::RsSecondary;
+    let mut topology = topology_with_server(server_desc);
+    let address = Default::default();
 
+    assert!(topology.add_round_trip_time_sample(&address, Duration::from_millis(10)));
     assert_eq!(
-        new_server_desc.average_round_trip_time,
-        Some(f64_ms_as_duration(test_file.new_avg_rtt))
+        topology.servers[&address].average_round_trip_time,
+        Some(Duration::from_millis(10))
+    );
+
+    assert!(topology.add_round_trip_time_sample(&address, Duration::from_millis(20)));
+    assert_eq!(
+        topology.servers[&address].average_round_trip_time,
+        Some(Duration::from_millis(12))
+    );
+
+    // Samples for servers that aren't available are ignored.
+    topology.servers.get_mut(&address).unwrap().server_type = ServerType::Unknown;
+    assert!(!topology.add_round_trip_time_sample(&address, Duration::from_millis(30)));
+    assert_eq!(
+        topology.servers[&address].average_round_trip_time,
+        Some(Duration::from_millis(12))
     );
 }
 
@@ -11,7 +11,13 @@ use crate::{
     client::Client,
     cmap::{conn::ConnectionGeneration, PoolGeneration},
     error::{BulkWriteFailure, CommandError, Error, ErrorKind},
-    hello::{HelloCommandResponse, HelloReply, LastWrite, LEGACY_HELLO_COMMAND_NAME},
+    hello::{
+        HelloCommandResponse,
+        HelloReply,
+        LastWrite,
+        TopologyVersion,
+        LEGACY_HELLO_COMMAND_NAME,
+    },
     options::{ClientOptions, ReadPreference, SelectionCriteria, ServerAddress},
     sdam::{
         description::{
@@ -92,6 +98,7 @@ pub(crate) struct TestHelloCommandResponse {
     pub max_bson_object_size: Option<i64>,
     pub max_write_batch_size: Option<i64>,
     pub service_id: Option<ObjectId>,
+    pub topology_version: Option<TopologyVersion>,
 }
 
 impl From<TestHelloCommandResponse> for HelloCommandResponse {
@@ -122,7 +129,7 @@ impl From<TestHelloCommandResponse> for HelloCommandResponse {
             max_bson_object_size: test.max_bson_object_size.unwrap_or(1234),
             max_write_batch_size: test.max_write_batch_size.unwrap_or(1234),
             service_id: test.service_id,
-            topology_version: None,
+            topology_version: test.topology_version,
             compressors: None,
             hello_ok: test.hello_ok,
             max_message_size_bytes: 48 * 1024 * 1024,
@@ -220,6 +227,7 @@ pub struct Server {
     logical_session_timeout_minutes: Option<i32>,
     min_wire_version: Option<i32>,
     max_wire_version: Option<i32>,
+    topology_version: Option<TopologyVersion>,
 }
 
 fn server_type_from_str(s: &str) -> Option<ServerType> {
@@ -242,11 +250,9 @@ fn server_type_from_str(s: &str) -> Option<ServerType> {
 async fn run_test(test_file: TestFile) {
     let test_description = &test_file.description;
 
-    // TODO: RUST-360 unskip tests that rely on topology version
     // TODO: RUST-358 unskip tests
     // TODO: RUST-1081 unskip tests
     let skip_keywords = doc! {
-        "topologyVersion": "(RUST-360)",
         "wrong set name": "(RUST-358)",
         "election Id": "(RUST-1081)",
         "electionId": "(RUST-1081)",
@@ -291,6 +297,7 @@ async fn run_test(test_file: TestFile) {
                     code: 1234,
                     code_name: "dummy error".to_string(),
                     message: "dummy".to_string(),
+                    topology_version: None,
                 })))
             } else if command_response == Default::default() {
                 Err(Error::from(ErrorKind::Io(Arc::new(
@@ -519,6 +526,16 @@ fn verify_description_outcome(
                 phase_description
             );
         }
+
+        if let Some(topology_version) = server.topology_version {
+            assert_eq!(
+                actual_server.topology_version,
+                Some(topology_version),
+                "{} (phase {})",
+                test_description,
+                phase_description
+            );
+        }
     }
 }
 
@@ -18,15 +18,20 @@ use crate::{
         ServerHeartbeatStartedEvent,
         ServerHeartbeatSucceededEvent,
     },
-    hello::{hello_command, run_hello, HelloReply},
+    hello::{hello_command, run_hello, AwaitableHelloOptions, HelloReply, TopologyVersion},
     options::{ClientOptions, ServerAddress},
-    runtime,
+    runtime::{self, WorkerHandle, WorkerHandleListener},
 };
 
 pub(crate) const DEFAULT_HEARTBEAT_FREQUENCY: Duration = Duration::from_secs(10);
 pub(crate) const MIN_HEARTBEAT_FREQUENCY: Duration = Duration::from_millis(500);
 
 /// Monitor that performs regular heartbeats to determine server status.
+///
+/// Once the server has reported a topology version, the monitor switches to the streaming
+/// protocol: it sends an awaitable hello that the server only replies to when its state changes or
+/// the heartbeat frequency elapses, and servers that support it will then stream further replies
+/// on the same connection without waiting for new requests.
 pub(crate) struct Monitor {
     address: ServerAddress,
     connection: Option<Connection>,
@@ -36,6 +41,16 @@ pub(crate) struct Monitor {
     sdam_event_emitter: Option<SdamEventEmitter>,
     update_request_receiver: TopologyCheckRequestReceiver,
     client_options: ClientOptions,
+
+    /// The topology version reported in the server's most recent hello reply, if any.
+    topology_version: Option<TopologyVersion>,
+
+    /// Handle to the RTT monitor for this server, which only runs while the monitor uses the
+    /// streaming protocol. Dropping it stops the RTT monitor.
+    rtt_monitor: Option<WorkerHandle>,
+
+    /// Handle used by the topology to wait for this monitor to stop when shutting down.
+    handle: WorkerHandle,
 }
 
 impl Monitor {
@@ -46,6 +61,7 @@ impl Monitor {
         sdam_event_emitter: Option<SdamEventEmitter>,
         update_request_receiver: TopologyCheckRequestReceiver,
         client_options: ClientOptions,
+        handle: WorkerHandle,
     ) {
         let handshaker = Handshaker::new(Some(client_options.clone().into()));
         let monitor = Self {
@@ -57,19 +73,36 @@ impl Monitor {
             sdam_event_emitter,
             update_request_receiver,
             connection: None,
+            topology_version: None,
+            rtt_monitor: None,
+            handle,
         };
-        runtime::execute(monitor.execute())
+        monitor
+            .topology_watcher
+            .clone()
+            .run_until_closed(monitor.execute())
     }
 
     async fn execute(mut self) {
-        let heartbeat_frequency = self
-            .client_options
-            .heartbeat_freq
-            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
+        let heartbeat_frequency = self.heartbeat_frequency();
 
         while self.topology_watcher.is_alive() {
             self.check_server().await;
 
+            // An awaitable hello doesn't return until the server's state changes or the heartbeat
+            // frequency elapses, so the next check can start right away. Its duration doesn't
+            // reflect the round trip time either, so that is measured separately from here on.
+            if self.is_awaitable() {
+                if self.rtt_monitor.is_none() {
+                    self.rtt_monitor = Some(self.start_rtt_monitor());
+                }
+                continue;
+            }
+
+            // Polling checks measure the round trip time themselves, so the RTT monitor is stopped
+            // until the monitor switches back to the streaming protocol.
+            self.rtt_monitor = None;
+
             #[cfg(test)]
             let min_frequency = self
                 .client_options
@@ -88,6 +121,29 @@ impl Monitor {
         }
     }
 
+    fn start_rtt_monitor(&self) -> WorkerHandle {
+        RttMonitor::new(
+            self.address.clone(),
+            self.topology_updater.clone(),
+            self.topology_watcher.clone(),
+            self.client_options.clone(),
+            self.handle.clone(),
+        )
+        .start()
+    }
+
+    fn heartbeat_frequency(&self) -> Duration {
+        self.client_options
+            .heartbeat_freq
+            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY)
+    }
+
+    /// Whether the next check will use the streaming protocol, which requires an established
+    /// connection to a server that has reported its topology version.
+    fn is_awaitable(&self) -> bool {
+        self.connection.is_some() && self.topology_version.is_some()
+    }
+
     /// Checks the the server by running a hello command. If an I/O error occurs, the
     /// connection will replaced with a new one.
     ///
@@ -96,6 +152,9 @@ impl Monitor {
         self.update_request_receiver.clear_check_requests();
         let mut retried = false;
         let check_result = match self.perform_hello().await {
+            // The server has already been marked unknown, so check it again right away on a new
+            // connection.
+            Ok(None) => self.perform_hello().await,
             Ok(reply) => Ok(reply),
             Err(e) => {
                 let previous_description = self.topology_watcher.server_description(&self.address);
@@ -114,45 +173,118 @@ impl Monitor {
         };
 
         match check_result {
-            Ok(reply) => {
-                let server_description =
+            Ok(Some((reply, awaited))) => {
+                let mut server_description =
                     ServerDescription::new(self.address.clone(), Some(Ok(reply)));
+                // The server may have waited before replying to an awaitable hello, so its
+                // duration says nothing about the round trip time.
+                if awaited {
+                    server_description.average_round_trip_time = None;
+                }
                 self.topology_updater.update(server_description).await
             }
+            // Checks on a new connection are never awaited, so they can't be cancelled.
+            Ok(None) => false,
             Err(e) => self.handle_error(e).await || retried,
         }
     }
 
-    async fn perform_hello(&mut self) -> Result<HelloReply> {
+    /// Runs a single check of the server, returning its reply along with whether the check was
+    /// awaited.
+    ///
+    /// An awaited check is cancelled if the server is marked unknown while waiting for the reply,
+    /// e.g. due to an application error. In that case the connection is closed and `None` is
+    /// returned.
+    async fn perform_hello(&mut self) -> Result<Option<(HelloReply, bool)>> {
+        let awaited = self.is_awaitable();
         self.emit_event(|| {
             SdamEvent::ServerHeartbeatStarted(ServerHeartbeatStartedEvent {
                 server_address: self.address.clone(),
+                awaited,
             })
         })
         .await;
 
-        let (duration, result) = match self.connection {
+        let heartbeat_frequency = self.heartbeat_frequency();
+        let start = Instant::now();
+        let mut cancelled = false;
+        let result = match self.connection {
             Some(ref mut conn) => {
-                let command = hello_command(
-                    self.client_options.server_api.as_ref(),
-                    self.client_options.load_balanced,
-                    Some(conn.stream_description()?.hello_ok),
-                );
-                let start = Instant::now();
-                (
-                    start.elapsed(),
-                    run_hello(conn, command, None, &self.client_options.sdam_event_handler).await,
-                )
+                // If the server is streaming replies, the next one is already on its way.
+                let command = if conn.is_streaming() {
+                    None
+                } else {
+                    let awaitable_options = if awaited {
+                        self.topology_version
+                            .map(|topology_version| AwaitableHelloOptions {
+                                topology_version,
+                                max_await_time: heartbeat_frequency,
+                            })
+                    } else {
+                        None
+                    };
+                    Some(hello_command(
+                        self.client_options.server_api.as_ref(),
+                        self.client_options.load_balanced,
+                        Some(conn.stream_description()?.hello_ok),
+                        awaitable_options,
+                    ))
+                };
+
+                let response = async {
+                    match command {
+                        Some(command) => conn.send_command(command, None).await,
+                        None => conn.receive_message().await,
+                    }
+                };
+                let connect_timeout = self
+                    .client_options
+                    .connect_timeout
+                    .unwrap_or(runtime::DEFAULT_CONNECT_TIMEOUT);
+                let response = if awaited {
+                    let response = async {
+                        if connect_timeout == Duration::from_secs(0) {
+                            return response.await;
+                        }
+                        // The server may wait up to the heartbeat frequency before replying to an
+                        // awaitable hello, so allow for that on top of the usual timeout.
+                        runtime::timeout(connect_timeout + heartbeat_frequency, response)
+                            .await
+                            .and_then(std::convert::identity)
+                    };
+                    let cancellation = wait_for_cancellation(
+                        &mut self.update_request_receiver,
+                        &self.topology_watcher,
+                        &self.address,
+                        heartbeat_frequency,
+                    );
+                    tokio::select! {
+                        response = response => response,
+                        _ = cancellation => {
+                            cancelled = true;
+                            Err(std::io::Error::new(
+                                std::io::ErrorKind::Interrupted,
+                                "awaited hello was cancelled after the server was marked unknown",
+                            )
+                            .into())
+                        }
+                    }
+                } else {
+                    response.await
+                };
+
+                response.and_then(|response| response.into_hello_reply(start.elapsed()))
             }
             None => {
                 let mut connection = Connection::connect_monitoring(
                     self.address.clone(),
                     self.client_options.connect_timeout,
                     self.client_options.tls_options(),
+                    self.client_options.dns_resolver.clone(),
+                    self.client_options.socks5_proxy(),
                 )
                 .await?;
 
-                let start = Instant::now();
                 let res = self
                     .handshaker
                     .handshake(
@@ -164,12 +296,14 @@ impl Monitor {
                     .map(|r| r.hello_reply);
 
                 self.connection = Some(connection);
-                (start.elapsed(), res)
+                res
             }
         };
+        let duration = start.elapsed();
 
         match result {
             Ok(ref r) => {
+                self.topology_version = r.command_response.topology_version;
                 self.emit_event(|| {
                     let mut reply = r
                         .raw_command_response
@@ -182,24 +316,30 @@ impl Monitor {
                         duration,
                         reply,
                         server_address: self.address.clone(),
+                        awaited,
                     })
                 })
                 .await;
             }
             Err(ref e) => {
                 self.connection.take();
+                self.topology_version.take();
                 self.emit_event(|| {
                     SdamEvent::ServerHeartbeatFailed(ServerHeartbeatFailedEvent {
                         duration,
                         failure: e.clone(),
                         server_address: self.address.clone(),
+                        awaited,
                     })
                 })
                 .await;
             }
         }
 
-        result
+        if cancelled {
+            return Ok(None);
+        }
+        result.map(|reply| Some((reply, awaited)))
     }
 
     async fn handle_error(&mut self, error: Error) -> bool {
@@ -217,3 +357,138 @@ impl Monitor {
         }
     }
 }
+
+/// Waits until a check is requested after the server at `address` has been marked unknown, which
+/// cancels an awaited check of it.
+async fn wait_for_cancellation(
+    update_request_receiver: &mut TopologyCheckRequestReceiver,
+    topology_watcher: &TopologyWatcher,
+    address: &ServerAddress,
+    heartbeat_frequency: Duration,
+) {
+    loop {
+        if update_request_receiver
+            .wait_for_check_request(heartbeat_frequency)
+            .await
+            && !topology_watcher
+                .server_description(address)
+                .map(|sd| sd.is_available())
+                .unwrap_or(false)
+        {
+            return;
+        }
+    }
+}
+
+/// Monitor that measures the round trip time to a server by periodically running a `hello` on its
+/// own connection. This is needed once the main monitor uses the streaming protocol, since the
+/// server may wait before replying to an awaitable hello.
+struct RttMonitor {
+    address: ServerAddress,
+    connection: Option<Connection>,
+    handshaker: Handshaker,
+    topology_updater: TopologyUpdater,
+    topology_watcher: TopologyWatcher,
+    client_options: ClientOptions,
+    _handle: WorkerHandle,
+}
+
+impl RttMonitor {
+    fn new(
+        address: ServerAddress,
+        topology_updater: TopologyUpdater,
+        topology_watcher: TopologyWatcher,
+        client_options: ClientOptions,
+        handle: WorkerHandle,
+    ) -> Self {
+        let handshaker = Handshaker::new(Some(client_options.clone().into()));
+        Self {
+            address,
+            connection: None,
+            handshaker,
+            topology_updater,
+            topology_watcher,
+            client_options,
+            _handle: handle,
+        }
+    }
+
+    /// Starts the RTT monitor, which runs until the returned handle is dropped or the topology is
+    /// closed.
+    fn start(self) -> WorkerHandle {
+        let (handle, mut handle_listener) = WorkerHandleListener::channel();
+        self.topology_watcher.clone().run_until_closed(async move {
+            tokio::select! {
+                _ = self.execute() => {},
+                _ = handle_listener.wait_for_all_handle_drops() => {},
+            }
+        });
+        handle
+    }
+
+    async fn execute(mut self) {
+        let heartbeat_frequency = self
+            .client_options
+            .heartbeat_freq
+            .unwrap_or(DEFAULT_HEARTBEAT_FREQUENCY);
+
+        while self.topology_watcher.is_alive() {
+            match self.measure_round_trip_time().await {
+                Ok(round_trip_time) => {
+                    self.topology_updater
+                        .update_round_trip_time(self.address.clone(), round_trip_time)
+                        .await;
+                }
+                // Errors are left to the main monitor to handle; the connection is just replaced on
+                // the next attempt.
+                Err(_) => {
+                    self.connection.take();
+                }
+            }
+
+            runtime::delay_for(heartbeat_frequency).await;
+        }
+    }
+
+    async fn measure_round_trip_time(&mut self) -> Result<Duration> {
+        let reply = match self.connection {
+            Some(ref mut conn) => {
+                let command = hello_command(
+                    self.client_options.server_api.as_ref(),
+                    self.client_options.load_balanced,
+                    Some(conn.stream_description()?.hello_ok),
+                    None,
+                );
+                let connect_timeout = self
+                    .client_options
+                    .connect_timeout
+                    .unwrap_or(runtime::DEFAULT_CONNECT_TIMEOUT);
+                let hello = run_hello(conn, command, None, &None);
+                if connect_timeout == Duration::from_secs(0) {
+                    hello.await?
+                } else {
+                    runtime::timeout(connect_timeout, hello).await??
+                }
+            }
+            None => {
+                let mut connection = Connection::connect_monitoring(
+                    self.address.clone(),
+                    self.client_options.connect_timeout,
+                    self.client_options.tls_options(),
+                    self.client_options.dns_resolver.clone(),
+                    self.client_options.socks5_proxy(),
+                )
+                .await?;
+                let reply = self
+                    .handshaker
+                    .handshake(&mut connection, None, &None)
+                    .await?
+                    .hello_reply;
+                self.connection = Some(connection);
+                reply
+            }
+        };
+
+        Ok(reply.round_trip_time)
+    }
+}
@@ -48,6 +48,10 @@ impl<'a> ServerInfo<'a> {
     /// Gets the weighted average of the time it has taken for a server check to round-trip
     /// from the driver to the server.
     ///
+    /// When the server supports streaming monitoring, heartbeats don't reflect the round trip time,
+    /// so it is instead measured by running a `hello` on a separate connection every
+    /// `heartbeat_freq`.
+    ///
     /// This is the value that the driver uses internally to determine the latency window as part of
     /// server selection.
     pub fn average_round_trip_time(&self) -> Option<Duration> {
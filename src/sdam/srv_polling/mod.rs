@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod test;
 
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
+
+use rand::seq::SliceRandom;
 
 use super::{
     description::topology::TopologyType,
@@ -11,8 +13,8 @@ use super::{
 };
 use crate::{
     error::{Error, Result},
-    options::ClientOptions,
-    runtime,
+    options::{ClientOptions, ServerAddress},
+    runtime::{self, WorkerHandle},
     srv::{LookupHosts, SrvResolver},
 };
 
@@ -25,6 +27,7 @@ pub(crate) struct SrvPollingMonitor {
     topology_watcher: TopologyWatcher,
     rescan_interval: Duration,
     client_options: ClientOptions,
+    _handle: Option<WorkerHandle>,
 }
 
 impl SrvPollingMonitor {
@@ -32,6 +35,7 @@ impl SrvPollingMonitor {
         topology_updater: TopologyUpdater,
         topology_watcher: TopologyWatcher,
         mut client_options: ClientOptions,
+        handle: Option<WorkerHandle>,
     ) -> Option<Self> {
         let initial_info = match client_options.original_srv_info.take() {
             Some(info) => info,
@@ -45,6 +49,7 @@ impl SrvPollingMonitor {
             topology_watcher,
             rescan_interval: initial_info.min_ttl,
             client_options,
+            _handle: handle,
         })
     }
 
@@ -55,9 +60,13 @@ impl SrvPollingMonitor {
         topology: TopologyUpdater,
         topology_watcher: TopologyWatcher,
         client_options: ClientOptions,
+        handle: WorkerHandle,
     ) {
-        if let Some(monitor) = Self::new(topology, topology_watcher, client_options) {
-            runtime::execute(monitor.execute());
+        if let Some(monitor) = Self::new(topology, topology_watcher, client_options, Some(handle)) {
+            monitor
+                .topology_watcher
+                .clone()
+                .run_until_closed(monitor.execute());
         }
     }
 
@@ -103,9 +112,42 @@ impl SrvPollingMonitor {
         self.rescan_interval = lookup.min_ttl;
 
         // TODO: RUST-230 Log error with host that was returned.
-        self.topology_updater
-            .sync_hosts(lookup.hosts.into_iter().filter_map(Result::ok).collect())
-            .await;
+        let hosts = self.choose_hosts(lookup.hosts.into_iter().filter_map(Result::ok).collect());
+        self.topology_updater.sync_hosts(hosts).await;
+    }
+
+    /// Determines which of the valid hosts returned by a lookup should be in the topology. If
+    /// there are more valid hosts than `srv_max_hosts` allows, the current hosts that are still
+    /// valid are kept and randomly chosen new hosts are added to take the place of removed ones.
+    fn choose_hosts(&self, valid_hosts: HashSet<ServerAddress>) -> HashSet<ServerAddress> {
+        let max_hosts = match self.client_options.srv_max_hosts {
+            Some(max_hosts) if max_hosts > 0 && (max_hosts as usize) < valid_hosts.len() => {
+                max_hosts as usize
+            }
+            _ => return valid_hosts,
+        };
+
+        let mut hosts: HashSet<ServerAddress> = self
+            .topology_watcher
+            .peek_latest()
+            .servers
+            .keys()
+            .filter(|address| valid_hosts.contains(address))
+            .cloned()
+            .collect();
+
+        let mut new_hosts: Vec<ServerAddress> = valid_hosts
+            .into_iter()
+            .filter(|address| !hosts.contains(address))
+            .collect();
+        new_hosts.shuffle(&mut rand::thread_rng());
+        hosts.extend(
+            new_hosts
+                .into_iter()
+                .take(max_hosts.saturating_sub(hosts.len())),
+        );
+
+        hosts
     }
 
     async fn lookup_hosts(&mut self) -> Result<LookupHosts> {
@@ -128,8 +170,12 @@ impl SrvPollingMonitor {
             return Ok(resolver);
         }
 
-        let resolver =
-            SrvResolver::new(self.client_options.resolver_config.clone().map(|c| c.inner)).await?;
+        let resolver = SrvResolver::new(
+            self.client_options.resolver_config.clone().map(|c| c.inner),
+            self.client_options.dns_resolver.clone(),
+            self.client_options.srv_service_name.clone(),
+        )
+        .await?;
 
         // Since the connection was not `Some` above, this will always insert the new connection and
         // return a reference to it.
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, time::Duration};
+use std::{collections::HashSet, sync::Arc, time::Duration};
 
 use pretty_assertions::assert_eq;
 use tokio::sync::RwLockReadGuard;
@@ -9,7 +9,7 @@ use crate::{
     options::{ClientOptions, ServerAddress},
     runtime,
     sdam::Topology,
-    test::{log_uncaptured, CLIENT_OPTIONS, LOCK},
+    test::{log_uncaptured, StaticDnsResolver, CLIENT_OPTIONS, LOCK},
 };
 
 fn localhost_test_build_10gen(port: u16) -> ServerAddress {
@@ -27,20 +27,33 @@ lazy_static::lazy_static! {
 }
 
 async fn run_test(new_hosts: Result<Vec<ServerAddress>>, expected_hosts: HashSet<ServerAddress>) {
+    let actual_hosts = run_test_srv(None, new_hosts).await;
+    assert_eq!(expected_hosts, actual_hosts);
+}
+
+async fn run_test_srv(
+    max_hosts: Option<u32>,
+    new_hosts: Result<Vec<ServerAddress>>,
+) -> HashSet<ServerAddress> {
     let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
 
     let mut options = ClientOptions::new_srv();
     options.hosts = DEFAULT_HOSTS.clone();
+    options.srv_max_hosts = max_hosts;
     options.test_options_mut().disable_monitoring_threads = true;
     let topology = Topology::new(options.clone()).unwrap();
-    let mut monitor =
-        SrvPollingMonitor::new(topology.clone_updater(), topology.watch(), options.clone())
-            .unwrap();
+    let mut monitor = SrvPollingMonitor::new(
+        topology.clone_updater(),
+        topology.watch(),
+        options.clone(),
+        None,
+    )
+    .unwrap();
     monitor
         .update_hosts(new_hosts.and_then(make_lookup_hosts))
         .await;
 
-    assert_eq!(expected_hosts, topology.server_addresses());
+    topology.server_addresses()
 }
 
 fn make_lookup_hosts(hosts: Vec<ServerAddress>) -> Result<LookupHosts> {
@@ -111,6 +124,90 @@ async fn no_results() {
     run_test(Ok(Vec::new()), DEFAULT_HOSTS.iter().cloned().collect()).await;
 }
 
+// srvMaxHosts of zero places no limit on the number of hosts.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn srv_max_hosts_zero() {
+    let hosts = vec![
+        localhost_test_build_10gen(27017),
+        localhost_test_build_10gen(27019),
+        localhost_test_build_10gen(27020),
+    ];
+
+    let actual = run_test_srv(Some(0), Ok(hosts.clone())).await;
+    assert_eq!(hosts.into_iter().collect::<HashSet<_>>(), actual);
+}
+
+// If srvMaxHosts is at least the number of returned hosts, all of them are used.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn srv_max_hosts_gte_num_hosts() {
+    let hosts = vec![
+        localhost_test_build_10gen(27017),
+        localhost_test_build_10gen(27019),
+        localhost_test_build_10gen(27020),
+    ];
+
+    let actual = run_test_srv(Some(3), Ok(hosts.clone())).await;
+    assert_eq!(hosts.iter().cloned().collect::<HashSet<_>>(), actual);
+
+    let actual = run_test_srv(Some(4), Ok(hosts.clone())).await;
+    assert_eq!(hosts.into_iter().collect::<HashSet<_>>(), actual);
+}
+
+// If srvMaxHosts is less than the number of returned hosts, hosts that are still valid are kept
+// and removed hosts are replaced by randomly chosen new ones.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn srv_max_hosts_less_than_num_hosts() {
+    let hosts = vec![
+        localhost_test_build_10gen(27017),
+        localhost_test_build_10gen(27019),
+        localhost_test_build_10gen(27020),
+    ];
+
+    let actual = run_test_srv(Some(2), Ok(hosts)).await;
+    assert_eq!(actual.len(), 2);
+    assert!(actual.contains(&localhost_test_build_10gen(27017)));
+    assert!(!actual.contains(&localhost_test_build_10gen(27108)));
+}
+
+// Rescans are done with the client's custom DNS resolver if one was provided.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn custom_dns_resolver() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let mut options = ClientOptions::new_srv();
+    let lookup_hostname = format!(
+        "_mongodb._tcp.{}",
+        options.original_srv_info.as_ref().unwrap().hostname
+    );
+    options.hosts = DEFAULT_HOSTS.clone();
+    options.test_options_mut().disable_monitoring_threads = true;
+    options.dns_resolver = Some(Arc::new(StaticDnsResolver::new().srv(
+        &lookup_hostname,
+        "a.test.test.build.10gen.cc.",
+        27019,
+        Duration::from_secs(90),
+    )));
+    let topology = Topology::new(options.clone()).unwrap();
+    let mut monitor =
+        SrvPollingMonitor::new(topology.clone_updater(), topology.watch(), options, None).unwrap();
+
+    let lookup = monitor.lookup_hosts().await;
+    monitor.update_hosts(lookup).await;
+
+    let expected_hosts: HashSet<_> = vec![ServerAddress::Tcp {
+        host: "a.test.test.build.10gen.cc".into(),
+        port: Some(27019),
+    }]
+    .into_iter()
+    .collect();
+    assert_eq!(expected_hosts, topology.server_addresses());
+    assert_eq!(monitor.rescan_interval, Duration::from_secs(90));
+}
+
 // SRV polling is not done for load-balanced clusters (as per spec at
 // https://github.com/mongodb/specifications/blob/master/source/polling-srv-records-for-mongos-discovery/tests/README.rst#test-that-srv-polling-is-not-done-for-load-balalanced-clusters).
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
@@ -462,3 +462,152 @@ async fn hello_ok_true() {
             .expect("subsequent heartbeats should use hello");
     }
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn streaming_heartbeats_are_awaited() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let mut setup_client_options = CLIENT_OPTIONS.clone();
+    setup_client_options.hosts.drain(1..);
+
+    if setup_client_options.load_balanced == Some(true) {
+        log_uncaptured(
+            "skipping streaming_heartbeats_are_awaited test due to load balanced topology",
+        );
+        return;
+    }
+
+    let setup_client = TestClient::with_options(Some(setup_client_options.clone())).await;
+    if setup_client.server_version_lt(4, 4) {
+        log_uncaptured(
+            "skipping streaming_heartbeats_are_awaited test due to server not supporting \
+             awaitable hello",
+        );
+        return;
+    }
+
+    let handler = Arc::new(EventHandler::new());
+    let mut subscriber = handler.subscribe();
+
+    let mut options = setup_client_options.clone();
+    options.sdam_event_handler = Some(handler.clone());
+    options.direct_connection = Some(true);
+    options.heartbeat_freq = Some(Duration::from_millis(500));
+    let _client = Client::with_options(options).expect("client creation should succeed");
+
+    // the handshake performed on a new monitoring connection is never awaited
+    subscriber
+        .wait_for_event(Duration::from_millis(2000), |event| {
+            if let Event::Sdam(SdamEvent::ServerHeartbeatSucceeded(e)) = event {
+                assert!(!e.awaited);
+                assert!(e.reply.get("topologyVersion").is_some());
+                return true;
+            }
+            false
+        })
+        .await
+        .expect("first heartbeat should succeed");
+
+    // once the topology version is known, heartbeats use the streaming protocol
+    for _ in 0..3 {
+        subscriber
+            .wait_for_event(Duration::from_millis(2000), |event| {
+                if let Event::Sdam(SdamEvent::ServerHeartbeatSucceeded(e)) = event {
+                    assert!(e.awaited);
+                    return true;
+                }
+                false
+            })
+            .await
+            .expect("subsequent heartbeats should be awaited");
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn awaited_heartbeat_cancelled_by_application_error() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let mut setup_client_options = CLIENT_OPTIONS.clone();
+    setup_client_options.hosts.drain(1..);
+
+    if setup_client_options.load_balanced == Some(true) {
+        log_uncaptured(
+            "skipping awaited_heartbeat_cancelled_by_application_error test due to load balanced \
+             topology",
+        );
+        return;
+    }
+
+    let setup_client = TestClient::with_options(Some(setup_client_options.clone())).await;
+    if setup_client.server_version_lt(4, 4) {
+        log_uncaptured(
+            "skipping awaited_heartbeat_cancelled_by_application_error test due to server not \
+             supporting awaitable hello",
+        );
+        return;
+    }
+
+    let handler = Arc::new(EventHandler::new());
+    let mut subscriber = handler.subscribe();
+
+    let mut options = setup_client_options.clone();
+    options.app_name = Some("awaitedHeartbeatCancelled".to_string());
+    options.sdam_event_handler = Some(handler.clone());
+    options.direct_connection = Some(true);
+    // long enough that only a cancelled check explains another heartbeat during the test
+    options.heartbeat_freq = Some(Duration::from_secs(60));
+    let client = Client::with_options(options).expect("client creation should succeed");
+
+    subscriber
+        .wait_for_event(Duration::from_millis(2000), |event| {
+            matches!(
+                event,
+                Event::Sdam(SdamEvent::ServerHeartbeatStarted(e)) if e.awaited
+            )
+        })
+        .await
+        .expect("heartbeats should be awaited once the topology version is known");
+
+    let fail_point_options = FailCommandOptions::builder()
+        .close_connection(true)
+        .app_name("awaitedHeartbeatCancelled".to_string())
+        .build();
+    let failpoint =
+        FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(fail_point_options));
+    let _fp_guard = setup_client
+        .enable_failpoint(failpoint, None)
+        .await
+        .expect("enabling failpoint should succeed");
+
+    client
+        .database("test")
+        .collection::<bson::Document>("test")
+        .find_one(None, None)
+        .await
+        .expect_err("find should fail with a network error");
+
+    // the network error marks the server unknown, which cancels the awaited heartbeat and
+    // triggers an immediate check on a new connection
+    subscriber
+        .wait_for_event(Duration::from_millis(2000), |event| {
+            if let Event::Sdam(SdamEvent::ServerHeartbeatFailed(e)) = event {
+                assert!(e.awaited);
+                return true;
+            }
+            false
+        })
+        .await
+        .expect("awaited heartbeat should be cancelled");
+    subscriber
+        .wait_for_event(Duration::from_millis(2000), |event| {
+            if let Event::Sdam(SdamEvent::ServerHeartbeatSucceeded(e)) = event {
+                assert!(!e.awaited);
+                return true;
+            }
+            false
+        })
+        .await
+        .expect("server should be checked again right away");
+}
@@ -2,7 +2,7 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use bson::oid::ObjectId;
@@ -88,6 +88,7 @@ impl Topology {
 
         let (updater, update_receiver) = TopologyUpdater::channel();
         let (worker_handle, handle_listener) = WorkerHandleListener::channel();
+        let (monitor_handle, monitor_handle_listener) = WorkerHandleListener::channel();
 
         let servers = description
             .server_addresses()
@@ -130,10 +131,16 @@ impl Topology {
                     event_emitter.clone(),
                     update_requester.subscribe(),
                     options.clone(),
+                    monitor_handle.clone(),
                 );
             }
 
-            SrvPollingMonitor::start(updater.clone(), watcher.clone(), options.clone());
+            SrvPollingMonitor::start(
+                updater.clone(),
+                watcher.clone(),
+                options.clone(),
+                monitor_handle.clone(),
+            );
         }
 
         let worker = TopologyWorker {
@@ -146,6 +153,8 @@ impl Topology {
             topology_updater: updater.clone(),
             update_requester: update_requester.clone(),
             handle_listener,
+            monitor_handle,
+            monitor_handle_listener,
             event_emitter,
         };
 
@@ -236,6 +245,12 @@ impl Topology {
             .await;
     }
 
+    /// Close the connection pools for all of the servers in the topology and stop monitoring them,
+    /// waiting for the monitors to finish. The topology can no longer be used afterwards.
+    pub(crate) async fn shutdown(&self) {
+        self.updater.shutdown().await;
+    }
+
     /// Get the topology's currently highest seen cluster time.
     pub(crate) fn cluster_time(&self) -> Option<ClusterTime> {
         self.watcher
@@ -339,6 +354,11 @@ pub(crate) enum UpdateMessage {
         error: Error,
         phase: HandshakePhase,
     },
+    RoundTripTime {
+        address: ServerAddress,
+        round_trip_time: Duration,
+    },
+    Shutdown,
     #[cfg(test)]
     SyncWorkers,
 }
@@ -348,6 +368,11 @@ struct TopologyWorker {
     update_receiver: TopologyUpdateReceiver,
     handle_listener: WorkerHandleListener,
     publisher: TopologyPublisher,
+
+    /// Handle held by each server monitor task so that shutting down can wait for them to stop.
+    monitor_handle: WorkerHandle,
+    monitor_handle_listener: WorkerHandleListener,
+
     event_emitter: Option<SdamEventEmitter>,
     options: ClientOptions,
     http_client: HttpClient,
@@ -361,11 +386,17 @@ struct TopologyWorker {
 impl TopologyWorker {
     fn start(mut self) {
         runtime::execute(async move {
+            let mut shutdown_ack = None;
+
             loop {
                 tokio::select! {
                     Some(update) = self.update_receiver.recv() => {
                         let (update, ack) = update.into_parts();
                         let changed = match update {
+                            UpdateMessage::Shutdown => {
+                                shutdown_ack = Some(ack);
+                                break;
+                            }
                             UpdateMessage::AdvanceClusterTime(to) => {
                                 self.advance_cluster_time(to);
                                 true
@@ -385,6 +416,13 @@ impl TopologyWorker {
                                 error,
                                 phase,
                             } => self.handle_application_error(address, error, phase).await,
+                            UpdateMessage::RoundTripTime {
+                                address,
+                                round_trip_time,
+                            } => {
+                                self.update_round_trip_time(&address, round_trip_time);
+                                false
+                            }
                             #[cfg(test)]
                             UpdateMessage::SyncWorkers => {
                                 let rxen: FuturesUnordered<_> = self
@@ -405,8 +443,31 @@ impl TopologyWorker {
                 }
             }
 
-            // indicate to the topology watchers that the topology is no longer alive
+            if shutdown_ack.is_some() {
+                let servers = self.borrow_latest_state().servers.clone();
+                for (address, server) in servers {
+                    server.pool.close().await;
+                    if let Some(ref emitter) = self.event_emitter {
+                        emitter
+                            .emit(SdamEvent::ServerClosed(ServerClosedEvent {
+                                address,
+                                topology_id: self.id,
+                            }))
+                            .await;
+                    }
+                }
+            }
+
+            // indicate to the topology watchers that the topology is no longer alive, which stops
+            // the server monitors
             drop(self.publisher);
+            drop(self.monitor_handle);
+
+            if shutdown_ack.is_some() {
+                self.monitor_handle_listener
+                    .wait_for_all_handle_drops()
+                    .await;
+            }
 
             if let Some(emitter) = self.event_emitter {
                 emitter
@@ -415,6 +476,10 @@ impl TopologyWorker {
                     }))
                     .await;
             }
+
+            if let Some(ack) = shutdown_ack {
+                ack.acknowledge(true);
+            }
         });
     }
 
@@ -466,6 +531,7 @@ impl TopologyWorker {
                     self.event_emitter.clone(),
                     self.update_requester.subscribe(),
                     self.options.clone(),
+                    self.monitor_handle.clone(),
                 );
             }
         }
@@ -507,6 +573,18 @@ impl TopologyWorker {
         topology_changed
     }
 
+    /// Incorporate a round trip time measured by a server's RTT monitor into the server's average.
+    /// This doesn't affect anything other than server selection, so no events are emitted.
+    fn update_round_trip_time(&mut self, address: &ServerAddress, round_trip_time: Duration) {
+        let mut latest_state = self.borrow_latest_state().clone();
+        if latest_state
+            .description
+            .add_round_trip_time_sample(address, round_trip_time)
+        {
+            self.publisher.publish_new_state(latest_state);
+        }
+    }
+
     /// Emit the appropriate SDAM monitoring events given the changes to the
     /// topology as the result of an update, if any.
     fn process_topology_diff(
@@ -557,7 +635,8 @@ impl TopologyWorker {
 
     /// Mark the server at the given address as Unknown using the provided error as the cause.
     async fn mark_server_as_unknown(&mut self, address: ServerAddress, error: Error) -> bool {
-        let description = ServerDescription::new(address, Some(Err(error.to_string())));
+        let mut description = ServerDescription::new(address, Some(Err(error.to_string())));
+        description.topology_version = error.topology_version();
         self.update_server(description).await
     }
 
@@ -599,6 +678,20 @@ impl TopologyWorker {
         let is_load_balanced =
             self.borrow_latest_state().description.topology_type() == TopologyType::LoadBalanced;
         if error.is_state_change_error() {
+            // Ignore errors from servers whose topology version is no newer than the one we
+            // already know about, as they describe a state we've already accounted for.
+            if let (Some(error_topology_version), Some(current_topology_version)) = (
+                error.topology_version(),
+                self.borrow_latest_state()
+                    .description
+                    .get_server_description(&address)
+                    .and_then(|sd| sd.topology_version),
+            ) {
+                if error_topology_version <= current_topology_version {
+                    return false;
+                }
+            }
+
             let updated =
                 is_load_balanced || self.mark_server_as_unknown(address, error.clone()).await;
 
@@ -620,6 +713,8 @@ impl TopologyWorker {
                     .await;
             if updated {
                 server.pool.clear(error, handshake.service_id()).await;
+                // Let the server's monitor cancel any check it is awaiting and check it again.
+                self.update_requester.request();
             }
             updated
         } else {
@@ -709,6 +804,19 @@ impl TopologyUpdater {
             .await
     }
 
+    /// Update the average round trip time of the server at the given address with a new sample.
+    pub(crate) async fn update_round_trip_time(
+        &self,
+        address: ServerAddress,
+        round_trip_time: Duration,
+    ) {
+        self.send_message(UpdateMessage::RoundTripTime {
+            address,
+            round_trip_time,
+        })
+        .await;
+    }
+
     pub(crate) async fn advance_cluster_time(&self, to: ClusterTime) {
         self.send_message(UpdateMessage::AdvanceClusterTime(to))
             .await;
@@ -722,6 +830,11 @@ impl TopologyUpdater {
         self.send_message(UpdateMessage::SyncHosts(hosts)).await;
     }
 
+    /// Shut down the topology, waiting until its pools are closed and its monitors have stopped.
+    pub(crate) async fn shutdown(&self) {
+        self.send_message(UpdateMessage::Shutdown).await;
+    }
+
     #[cfg(test)]
     pub(crate) async fn sync_workers(&self) {
         self.send_message(UpdateMessage::SyncWorkers).await;
@@ -759,6 +872,18 @@ impl TopologyWatcher {
         self.receiver.has_changed().is_ok()
     }
 
+    /// Runs `task` in the background until it completes or the topology is closed, whichever
+    /// happens first.
+    pub(crate) fn run_until_closed(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let mut receiver = self.receiver.clone();
+        runtime::execute(async move {
+            tokio::select! {
+                _ = task => {},
+                _ = async { while receiver.changed().await.is_ok() {} } => {},
+            }
+        });
+    }
+
     /// Get a server description for the server at the provided address.
     pub(crate) fn server_description(&self, address: &ServerAddress) -> Option<ServerDescription> {
         self.receiver
@@ -845,9 +970,19 @@ pub(crate) struct TopologyCheckRequestReceiver {
 }
 
 impl TopologyCheckRequestReceiver {
-    /// Wait until a check request is seen or the given duration has elapsed.
-    pub(crate) async fn wait_for_check_request(&mut self, timeout: Duration) {
-        let _: std::result::Result<_, _> = runtime::timeout(timeout, self.receiver.recv()).await;
+    /// Wait until a check request is seen or the given duration has elapsed, returning whether a
+    /// request was seen.
+    pub(crate) async fn wait_for_check_request(&mut self, timeout: Duration) -> bool {
+        let start = Instant::now();
+        match runtime::timeout(timeout, self.receiver.recv()).await {
+            Ok(Ok(())) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => true,
+            // No more requests can be sent once the topology is closed.
+            Ok(Err(broadcast::error::RecvError::Closed)) => {
+                runtime::delay_for(timeout.saturating_sub(start.elapsed())).await;
+                false
+            }
+            Err(_) => false,
+        }
     }
 
     /// Clear out prior check requests so that the next call to `wait_for_check_requests` doesn't
@@ -1,16 +1,18 @@
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
-use trust_dns_proto::rr::RData;
 use trust_dns_resolver::config::ResolverConfig;
 
 use crate::{
     error::{ErrorKind, Result},
-    options::ServerAddress,
+    options::{DnsResolver, ServerAddress},
     runtime::AsyncResolver,
 };
 
+const DEFAULT_SRV_SERVICE_NAME: &str = "mongodb";
+
 pub(crate) struct SrvResolver {
-    resolver: AsyncResolver,
+    resolver: Arc<dyn DnsResolver>,
+    srv_service_name: Option<String>,
 }
 
 #[derive(Debug)]
@@ -32,13 +34,27 @@ pub(crate) struct LookupHosts {
 pub(crate) struct OriginalSrvInfo {
     pub(crate) hostname: String,
     pub(crate) min_ttl: Duration,
+    /// The authSource found in the TXT record, if the connection string did not specify one.
+    pub(crate) auth_source: Option<String>,
 }
 
 impl SrvResolver {
-    pub(crate) async fn new(config: Option<ResolverConfig>) -> Result<Self> {
-        let resolver = AsyncResolver::new(config).await?;
+    /// Creates a resolver that performs lookups using `dns_resolver` if provided, or otherwise
+    /// using trust-dns with the given configuration.
+    pub(crate) async fn new(
+        config: Option<ResolverConfig>,
+        dns_resolver: Option<Arc<dyn DnsResolver>>,
+        srv_service_name: Option<String>,
+    ) -> Result<Self> {
+        let resolver = match dns_resolver {
+            Some(resolver) => resolver,
+            None => Arc::new(AsyncResolver::new(config).await?),
+        };
 
-        Ok(Self { resolver })
+        Ok(Self {
+            resolver,
+            srv_service_name,
+        })
     }
 
     pub(crate) async fn resolve_client_options(
@@ -73,28 +89,25 @@ impl SrvResolver {
             .into());
         }
 
-        let lookup_hostname = format!("_mongodb._tcp.{}", original_hostname);
+        let lookup_hostname = format!(
+            "_{}._tcp.{}",
+            self.srv_service_name
+                .as_deref()
+                .unwrap_or(DEFAULT_SRV_SERVICE_NAME),
+            original_hostname
+        );
 
-        let srv_lookup = self.resolver.srv_lookup(lookup_hostname.as_str()).await?;
+        let srv_records = self.resolver.srv_lookup(lookup_hostname.as_str()).await?;
         let mut srv_addresses: Vec<Result<ServerAddress>> = Vec::new();
-        let mut min_ttl = u32::MAX;
+        let mut min_ttl = Duration::MAX;
 
-        for record in srv_lookup.as_lookup().record_iter() {
-            let srv = match record.data() {
-                Some(RData::SRV(s)) => s,
-                _ => continue,
-            };
-
-            let hostname = srv.target().to_utf8();
-            let port = Some(srv.port());
-            let mut address = ServerAddress::Tcp {
-                host: hostname,
-                port,
-            };
+        for record in srv_records {
+            let hostname = record.target;
+            let port = Some(record.port);
 
             let domain_name = &hostname_parts[1..];
 
-            let mut hostname_parts: Vec<_> = address.host().split('.').collect();
+            let mut hostname_parts: Vec<_> = hostname.split('.').collect();
 
             // Remove empty final section, which indicates a trailing dot.
             if hostname_parts.last().map(|s| s.is_empty()).unwrap_or(false) {
@@ -106,7 +119,10 @@ impl SrvResolver {
                     message: format!(
                         "SRV lookup for {} returned result {}, which does not match domain name {}",
                         original_hostname,
-                        address,
+                        ServerAddress::Tcp {
+                            host: hostname.clone(),
+                            port,
+                        },
                         domain_name.join(".")
                     ),
                 }
@@ -115,12 +131,12 @@ impl SrvResolver {
 
             // The spec tests list the seeds without the trailing '.', so we remove it by
             // joining the parts we split rather than manipulating the string.
-            address = ServerAddress::Tcp {
+            let address = ServerAddress::Tcp {
                 host: hostname_parts.join("."),
-                port: address.port(),
+                port,
             };
 
-            min_ttl = std::cmp::min(min_ttl, record.ttl());
+            min_ttl = std::cmp::min(min_ttl, record.ttl);
             srv_addresses.push(Ok(address));
         }
 
@@ -133,7 +149,7 @@ impl SrvResolver {
 
         Ok(LookupHosts {
             hosts: srv_addresses,
-            min_ttl: Duration::from_secs(min_ttl.into()),
+            min_ttl,
         })
     }
 
@@ -142,13 +158,10 @@ impl SrvResolver {
         original_hostname: &str,
         config: &mut ResolvedConfig,
     ) -> Result<()> {
-        let txt_records_response = match self.resolver.txt_lookup(original_hostname).await? {
-            Some(response) => response,
-            None => return Ok(()),
-        };
-        let mut txt_records = txt_records_response.iter();
+        let txt_records = self.resolver.txt_lookup(original_hostname).await?;
+        let mut txt_records = txt_records.into_iter();
 
-        let txt_record = match txt_records.next() {
+        let txt_string = match txt_records.next() {
             Some(record) => record,
             None => return Ok(()),
         };
@@ -164,14 +177,6 @@ impl SrvResolver {
             .into());
         }
 
-        let txt_data: Vec<_> = txt_record
-            .txt_data()
-            .iter()
-            .map(|bytes| String::from_utf8_lossy(bytes.as_ref()).into_owned())
-            .collect();
-
-        let txt_string = txt_data.join("");
-
         for option_pair in txt_string.split('&') {
             let parts: Vec<_> = option_pair.split('=').collect();
 
@@ -7,13 +7,15 @@ use crate::{
     concern::{ReadConcern, WriteConcern},
     error::Result,
     options::{
+        ClientBulkWriteOptions,
         ClientOptions,
         DatabaseOptions,
         ListDatabasesOptions,
+        NamespacedWriteModel,
         SelectionCriteria,
         SessionOptions,
     },
-    results::DatabaseSpecification,
+    results::{ClientBulkWriteResult, DatabaseSpecification},
     runtime,
     Client as AsyncClient,
 };
@@ -152,6 +154,47 @@ impl Client {
         )
     }
 
+    /// Executes the writes described by `models`, each of which may target a different namespace,
+    /// using as few `bulkWrite` commands as the server's batch limits allow. This method requires
+    /// MongoDB 8.0 or later.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any errors are reported in a
+    /// [`ClientBulkWriteFailure`](../error/struct.ClientBulkWriteFailure.html) with indexes
+    /// corresponding to positions in `models`.
+    ///
+    /// By default, only the summary counts of the writes are returned. Set
+    /// [`ClientBulkWriteOptions::verbose_results`](../options/struct.ClientBulkWriteOptions.html#
+    /// structfield.verbose_results) to also receive the result of each individual write.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub fn bulk_write(
+        &self,
+        models: impl IntoIterator<Item = NamespacedWriteModel>,
+        options: impl Into<Option<ClientBulkWriteOptions>>,
+    ) -> Result<ClientBulkWriteResult> {
+        runtime::block_on(self.async_client.bulk_write(models, options.into()))
+    }
+
+    /// Executes the writes described by `models` using the provided `ClientSession`. See
+    /// [`Client::bulk_write`] for more information.
+    pub fn bulk_write_with_session(
+        &self,
+        models: impl IntoIterator<Item = NamespacedWriteModel>,
+        options: impl Into<Option<ClientBulkWriteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ClientBulkWriteResult> {
+        runtime::block_on(self.async_client.bulk_write_with_session(
+            models,
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Starts a new `ClientSession`.
     pub fn start_session(&self, options: Option<SessionOptions>) -> Result<ClientSession> {
         runtime::block_on(self.async_client.start_session(options)).map(Into::into)
@@ -198,4 +241,19 @@ impl Client {
         ))
         .map(SessionChangeStream::new)
     }
+
+    /// Shuts down this `Client`, waiting for any operations that are currently executing to
+    /// finish, killing any open cursors, ending the pooled server sessions, closing all connection
+    /// pools and stopping topology monitoring. See
+    /// [`Client::shutdown`](../struct.Client.html#method.shutdown) for more details.
+    pub fn shutdown(self) {
+        runtime::block_on(self.async_client.shutdown())
+    }
+
+    /// Shuts down this `Client` without waiting for in-flight operations to finish. See
+    /// [`Client::shutdown_immediate`](../struct.Client.html#method.shutdown_immediate) for more
+    /// details.
+    pub fn shutdown_immediate(self) {
+        runtime::block_on(self.async_client.shutdown_immediate())
+    }
 }
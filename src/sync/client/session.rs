@@ -1,7 +1,7 @@
 use super::Client;
 use crate::{
     bson::Document,
-    client::session::ClusterTime,
+    client::session::{ClusterTime, CommitErrorAction, WithTransactionRetrier},
     error::Result,
     options::{SessionOptions, TransactionOptions},
     runtime,
@@ -135,4 +135,78 @@ impl ClientSession {
     pub fn abort_transaction(&mut self) -> Result<()> {
         runtime::block_on(self.async_client_session.abort_transaction())
     }
+
+    /// Starts a transaction, runs the given callback, and commits the transaction.
+    ///
+    /// If the callback or the commit fails with an error carrying a
+    /// [`crate::error::TRANSIENT_TRANSACTION_ERROR`] label, the transaction is aborted and the
+    /// whole callback is run again in a new transaction. If the commit fails with an error
+    /// carrying a [`crate::error::UNKNOWN_TRANSACTION_COMMIT_RESULT`] label, only the commit is
+    /// retried. Any other error causes the transaction to be aborted and is returned to the
+    /// caller. Retries stop once 120 seconds have elapsed since `with_transaction` was called, at
+    /// which point the most recent error is returned.
+    ///
+    /// ```rust
+    /// # use mongodb::{bson::{doc, Document}, error::Result, sync::Client};
+    /// #
+    /// # fn do_stuff() -> Result<()> {
+    /// # let client = Client::with_uri_str("mongodb://example.com")?;
+    /// # let mut session = client.start_session(None)?;
+    /// let coll = client.database("foo").collection::<Document>("bar");
+    /// session.with_transaction(
+    ///     |session| {
+    ///         coll.insert_one_with_session(doc! { "x": 1 }, None, session)?;
+    ///         coll.delete_one_with_session(doc! { "y": 2 }, None, session)?;
+    ///         Ok(())
+    ///     },
+    ///     None,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_transaction<R, F>(
+        &mut self,
+        mut callback: F,
+        options: impl Into<Option<TransactionOptions>>,
+    ) -> Result<R>
+    where
+        F: for<'a> FnMut(&'a mut ClientSession) -> Result<R>,
+    {
+        // The callback can't be run through the async implementation, since it blocks on the
+        // operations it runs, so only the retry decisions are shared with it.
+        let options = options.into();
+        let retrier = WithTransactionRetrier::new();
+
+        'transaction: loop {
+            self.start_transaction(options.clone())?;
+            let ret = match callback(self) {
+                Ok(v) => v,
+                Err(e) => {
+                    if self.async_client_session.in_transaction() {
+                        self.abort_transaction()?;
+                    }
+                    if retrier.retry_after_callback_error(&e) {
+                        continue 'transaction;
+                    }
+                    return Err(e);
+                }
+            };
+
+            // The callback may have committed or aborted the transaction itself.
+            if !self.async_client_session.in_transaction() {
+                return Ok(ret);
+            }
+
+            loop {
+                match self.commit_transaction() {
+                    Ok(()) => return Ok(ret),
+                    Err(e) => match retrier.after_commit_error(&e) {
+                        CommitErrorAction::RetryCommit => continue,
+                        CommitErrorAction::RetryTransaction => continue 'transaction,
+                        CommitErrorAction::Return => return Err(e),
+                    },
+                }
+            }
+        }
+    }
 }
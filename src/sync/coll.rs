@@ -10,6 +10,7 @@ use crate::{
     index::IndexModel,
     options::{
         AggregateOptions,
+        BulkWriteOptions,
         CountOptions,
         CreateIndexOptions,
         DeleteOptions,
@@ -17,6 +18,8 @@ use crate::{
         DropCollectionOptions,
         DropIndexOptions,
         EstimatedDocumentCountOptions,
+        ExplainVerbosity,
+        Explainable,
         FindOneAndDeleteOptions,
         FindOneAndReplaceOptions,
         FindOneAndUpdateOptions,
@@ -25,19 +28,24 @@ use crate::{
         InsertManyOptions,
         InsertOneOptions,
         ListIndexesOptions,
+        ModifyCollectionOptions,
         ReadConcern,
+        RenameCollectionOptions,
         ReplaceOptions,
         SelectionCriteria,
         UpdateModifications,
         UpdateOptions,
         WriteConcern,
+        WriteModel,
     },
     results::{
+        BulkWriteResult,
         CreateIndexResult,
         CreateIndexesResult,
         DeleteResult,
         InsertManyResult,
         InsertOneResult,
+        ModifyCollectionResult,
         UpdateResult,
     },
     runtime,
@@ -144,6 +152,58 @@ impl<T> Collection<T> {
         )
     }
 
+    /// Modifies the collection using the `collMod` command, e.g. to change its validation rules or
+    /// the TTL of one of its indexes. If an index was modified, the returned result contains its
+    /// previous and new settings.
+    pub fn modify(
+        &self,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+    ) -> Result<ModifyCollectionResult> {
+        runtime::block_on(self.async_collection.modify(options.into()))
+    }
+
+    /// Modifies the collection using the `collMod` command and the provided `ClientSession`. If an
+    /// index was modified, the returned result contains its previous and new settings.
+    pub fn modify_with_session(
+        &self,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ModifyCollectionResult> {
+        runtime::block_on(
+            self.async_collection
+                .modify_with_session(options.into(), &mut session.async_client_session),
+        )
+    }
+
+    /// Renames the collection to the namespace `to`, which may be in a different database. This
+    /// `Collection` handle continues to refer to the original namespace.
+    ///
+    /// See the documentation [here](https://www.mongodb.com/docs/manual/reference/command/renameCollection/)
+    /// for more information on the restrictions on renaming collections.
+    pub fn rename(
+        &self,
+        to: Namespace,
+        options: impl Into<Option<RenameCollectionOptions>>,
+    ) -> Result<()> {
+        runtime::block_on(self.async_collection.rename(to, options.into()))
+    }
+
+    /// Renames the collection to the namespace `to`, which may be in a different database, using
+    /// the provided `ClientSession`. This `Collection` handle continues to refer to the original
+    /// namespace.
+    pub fn rename_with_session(
+        &self,
+        to: Namespace,
+        options: impl Into<Option<RenameCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<()> {
+        runtime::block_on(self.async_collection.rename_with_session(
+            to,
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Runs an aggregation operation.
     ///
     /// See the documentation [here](https://docs.mongodb.com/manual/aggregation/) for more
@@ -364,6 +424,39 @@ impl<T> Collection<T> {
         ))
     }
 
+    /// Asks the server to describe how it would execute the given operation rather than executing
+    /// it, returning the raw response to the `explain` command.
+    ///
+    /// The format of the response varies between server versions and topologies. See the official
+    /// MongoDB [documentation](https://www.mongodb.com/docs/manual/reference/command/explain/) for
+    /// more information.
+    pub fn explain(
+        &self,
+        explainable: Explainable,
+        verbosity: ExplainVerbosity,
+    ) -> Result<Document> {
+        runtime::block_on(self.async_collection.explain(explainable, verbosity))
+    }
+
+    /// Asks the server to describe how it would execute the given operation rather than executing
+    /// it using the provided `ClientSession`, returning the raw response to the `explain` command.
+    ///
+    /// The format of the response varies between server versions and topologies. See the official
+    /// MongoDB [documentation](https://www.mongodb.com/docs/manual/reference/command/explain/) for
+    /// more information.
+    pub fn explain_with_session(
+        &self,
+        explainable: Explainable,
+        verbosity: ExplainVerbosity,
+        session: &mut ClientSession,
+    ) -> Result<Document> {
+        runtime::block_on(self.async_collection.explain_with_session(
+            explainable,
+            verbosity,
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Updates all documents matching `query` in the collection.
     ///
     /// Both `Document` and `Vec<Document>` implement `Into<UpdateModifications>`, so either can be
@@ -776,6 +869,54 @@ impl<T> Collection<T>
 where
     T: Serialize,
 {
+    /// Executes the writes described by `models` against the collection, splitting them into as
+    /// few `insert`, `update` and `delete` commands as the server's batch limits allow.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any write errors are reported in a
+    /// [`BulkWriteFailure`](../error/struct.BulkWriteFailure.html) with indexes corresponding to
+    /// positions in `models`.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub fn bulk_write(
+        &self,
+        models: impl IntoIterator<Item = impl Into<WriteModel<T>>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+    ) -> Result<BulkWriteResult> {
+        runtime::block_on(self.async_collection.bulk_write(models, options.into()))
+    }
+
+    /// Executes the writes described by `models` against the collection using the provided
+    /// `ClientSession`, splitting them into as few `insert`, `update` and `delete` commands as the
+    /// server's batch limits allow.
+    ///
+    /// If `ordered` is true (the default), the writes are performed in order and the operation
+    /// stops at the first write that fails. Otherwise, all of the writes are attempted. In either
+    /// case, any write errors are reported in a
+    /// [`BulkWriteFailure`](../error/struct.BulkWriteFailure.html) with indexes corresponding to
+    /// positions in `models`.
+    ///
+    /// Batches that only contain single-document writes will retry once upon failure if the
+    /// connection and encountered error support retryability. See the documentation
+    /// [here](https://docs.mongodb.com/manual/core/retryable-writes/) for more information on
+    /// retryable writes.
+    pub fn bulk_write_with_session(
+        &self,
+        models: impl IntoIterator<Item = impl Into<WriteModel<T>>>,
+        options: impl Into<Option<BulkWriteOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<BulkWriteResult> {
+        runtime::block_on(self.async_collection.bulk_write_with_session(
+            models,
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Inserts the documents in `docs` into the collection.
     ///
     /// This operation will retry once upon failure if the connection and encountered error support
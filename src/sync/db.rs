@@ -11,11 +11,12 @@ use crate::{
         CreateCollectionOptions,
         DropDatabaseOptions,
         ListCollectionsOptions,
+        ModifyCollectionOptions,
         ReadConcern,
         SelectionCriteria,
         WriteConcern,
     },
-    results::CollectionSpecification,
+    results::{CollectionSpecification, ModifyCollectionResult},
     runtime,
     Database as AsyncDatabase,
 };
@@ -210,6 +211,36 @@ impl Database {
         ))
     }
 
+    /// Modifies the collection or view with the given `name` using the `collMod` command, e.g. to
+    /// change its validation rules or the TTL of one of its indexes. If an index was modified, the
+    /// returned result contains its previous and new settings.
+    pub fn modify_collection(
+        &self,
+        name: impl AsRef<str>,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+    ) -> Result<ModifyCollectionResult> {
+        runtime::block_on(
+            self.async_database
+                .modify_collection(name.as_ref(), options.into()),
+        )
+    }
+
+    /// Modifies the collection or view with the given `name` using the `collMod` command and the
+    /// provided `ClientSession`. If an index was modified, the returned result contains its
+    /// previous and new settings.
+    pub fn modify_collection_with_session(
+        &self,
+        name: impl AsRef<str>,
+        options: impl Into<Option<ModifyCollectionOptions>>,
+        session: &mut ClientSession,
+    ) -> Result<ModifyCollectionResult> {
+        runtime::block_on(self.async_database.modify_collection_with_session(
+            name.as_ref(),
+            options.into(),
+            &mut session.async_client_session,
+        ))
+    }
+
     /// Runs a database-level command.
     ///
     /// Note that no inspection is done on `doc`, so the command will not use the database's default
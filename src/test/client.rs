@@ -1,4 +1,4 @@
-use std::{borrow::Cow, collections::HashMap, time::Duration};
+use std::{borrow::Cow, collections::HashMap, future::Future, pin::Pin, sync::Arc, time::Duration};
 
 use bson::Document;
 use serde::Deserialize;
@@ -7,10 +7,34 @@ use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 use crate::{
     bson::{doc, Bson},
     error::{CommandError, Error, ErrorKind},
-    options::{AuthMechanism, ClientOptions, Credential, ListDatabasesOptions, ServerAddress},
+    options::{
+        AuthMechanism,
+        ClientBulkWriteOptions,
+        ClientOptions,
+        Credential,
+        CredentialProvider,
+        DeleteOneModel,
+        FindOptions,
+        InsertOneModel,
+        ListDatabasesOptions,
+        NamespacedWriteModel,
+        OidcCallback,
+        OidcCallbackContext,
+        OidcToken,
+        ServerAddress,
+        UpdateOneModel,
+    },
     runtime,
     selection_criteria::{ReadPreference, ReadPreferenceOptions, SelectionCriteria},
-    test::{log_uncaptured, util::TestClient, CLIENT_OPTIONS, LOCK},
+    test::{
+        log_uncaptured,
+        util::{EventClient, TestClient},
+        FailCommandOptions,
+        FailPoint,
+        FailPointMode,
+        CLIENT_OPTIONS,
+        LOCK,
+    },
     Client,
 };
 
@@ -255,6 +279,151 @@ async fn list_database_names() {
     }
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if client.server_version_lt(8, 0) {
+        log_uncaptured("skipping bulk_write due to server version");
+        return;
+    }
+
+    let coll1 = client
+        .init_db_and_coll(function_name!(), &format!("{}1", function_name!()))
+        .await;
+    let coll2 = client
+        .init_db_and_coll(function_name!(), &format!("{}2", function_name!()))
+        .await;
+
+    let models = vec![
+        NamespacedWriteModel::new(
+            coll1.namespace(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 1, "x": 1 })
+                .build(),
+        ),
+        NamespacedWriteModel::new(
+            coll2.namespace(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 1, "x": 1 })
+                .build(),
+        ),
+        NamespacedWriteModel::new(
+            coll1.namespace(),
+            UpdateOneModel::builder()
+                .filter(doc! { "_id": 1 })
+                .update(doc! { "$set": { "x": 2 } })
+                .build(),
+        ),
+        NamespacedWriteModel::new(
+            coll2.namespace(),
+            UpdateOneModel::builder()
+                .filter(doc! { "_id": 2 })
+                .update(doc! { "$set": { "x": 2 } })
+                .upsert(true)
+                .build(),
+        ),
+        NamespacedWriteModel::new(
+            coll2.namespace(),
+            DeleteOneModel::builder().filter(doc! { "_id": 1 }).build(),
+        ),
+    ];
+    let options = ClientBulkWriteOptions::builder()
+        .verbose_results(true)
+        .build();
+    let result = client.bulk_write(models, options).await.unwrap();
+
+    assert_eq!(result.inserted_count, 2);
+    assert_eq!(result.matched_count, 1);
+    assert_eq!(result.modified_count, 1);
+    assert_eq!(result.upserted_count, 1);
+    assert_eq!(result.deleted_count, 1);
+
+    let insert_results = result.insert_results.unwrap();
+    assert_eq!(insert_results.get(&0).unwrap().inserted_id, Bson::Int32(1));
+    let update_results = result.update_results.unwrap();
+    assert_eq!(update_results.get(&2).unwrap().modified_count, 1);
+    assert_eq!(
+        update_results.get(&3).unwrap().upserted_id,
+        Some(Bson::Int32(2))
+    );
+    assert_eq!(
+        result
+            .delete_results
+            .unwrap()
+            .get(&4)
+            .unwrap()
+            .deleted_count,
+        1
+    );
+
+    assert_eq!(
+        coll1.find_one(doc! { "_id": 1 }, None).await.unwrap(),
+        Some(doc! { "_id": 1, "x": 2 })
+    );
+    assert_eq!(coll2.count_documents(None, None).await.unwrap(), 1);
+
+    let events = client.get_command_started_events(&["bulkWrite"]);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].command.get_array("ops").unwrap().len(), 5);
+    assert_eq!(events[0].command.get_array("nsInfo").unwrap().len(), 2);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write_errors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if client.server_version_lt(8, 0) {
+        log_uncaptured("skipping bulk_write_errors due to server version");
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let models: Vec<NamespacedWriteModel> = vec![1, 1, 2, 2, 3]
+        .into_iter()
+        .map(|id| {
+            NamespacedWriteModel::new(
+                coll.namespace(),
+                InsertOneModel::builder()
+                    .document(doc! { "_id": id })
+                    .build(),
+            )
+        })
+        .collect();
+
+    let error = client.bulk_write(models.clone(), None).await.unwrap_err();
+    match *error.kind {
+        ErrorKind::ClientBulkWrite(ref failure) => {
+            assert_eq!(failure.write_errors.len(), 1);
+            assert_eq!(failure.write_errors.get(&1).unwrap().code, 11000);
+            assert_eq!(failure.partial_result.as_ref().unwrap().inserted_count, 1);
+        }
+        ref other => panic!("expected client bulk write error, got {:?}", other),
+    }
+
+    coll.drop(None).await.unwrap();
+    let options = ClientBulkWriteOptions::builder().ordered(false).build();
+    let error = client.bulk_write(models, options).await.unwrap_err();
+    match *error.kind {
+        ErrorKind::ClientBulkWrite(ref failure) => {
+            let mut indexes: Vec<usize> = failure.write_errors.keys().cloned().collect();
+            indexes.sort_unstable();
+            assert_eq!(indexes, vec![1, 3]);
+            assert_eq!(failure.partial_result.as_ref().unwrap().inserted_count, 3);
+        }
+        ref other => panic!("expected client bulk write error, got {:?}", other),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -689,3 +858,316 @@ async fn plain_auth() {
         }
     );
 }
+
+#[cfg(feature = "gssapi-auth")]
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn gssapi_auth() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let (host, principal) = match (
+        std::env::var("MONGO_GSSAPI_HOST"),
+        std::env::var("MONGO_GSSAPI_PRINCIPAL"),
+    ) {
+        (Ok(host), Ok(principal)) => (host, principal),
+        _ => {
+            log_uncaptured(
+                "skipping gssapi_auth due to environment variables MONGO_GSSAPI_HOST and \
+                 MONGO_GSSAPI_PRINCIPAL",
+            );
+            return;
+        }
+    };
+
+    let options = ClientOptions::builder()
+        .hosts(vec![ServerAddress::parse(host).unwrap()])
+        .credential(
+            Credential::builder()
+                .mechanism(AuthMechanism::Gssapi)
+                .username(principal)
+                .build(),
+        )
+        .build();
+
+    let client = Client::with_options(options).unwrap();
+    let coll = client.database("kerberos").collection("test");
+
+    let doc = coll.find_one(None, None).await.unwrap().unwrap();
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestDocument {
+        kerberos: bool,
+        authenticated: String,
+    }
+
+    let doc: TestDocument = bson::from_document(doc).unwrap();
+
+    assert_eq!(
+        doc,
+        TestDocument {
+            kerberos: true,
+            authenticated: "yeah".into()
+        }
+    );
+}
+
+/// Reads the access token from the file at `OIDC_TOKEN_FILE`, counting the number of calls.
+#[derive(Default)]
+struct TokenFileCallback {
+    calls: std::sync::atomic::AtomicUsize,
+}
+
+impl OidcCallback for TokenFileCallback {
+    fn request_token<'a>(
+        &'a self,
+        _context: OidcCallbackContext,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<OidcToken>> + Send + 'a>> {
+        Box::pin(async move {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let path = std::env::var("OIDC_TOKEN_FILE").unwrap();
+            let access_token = std::fs::read_to_string(path)?;
+            Ok(OidcToken::builder().access_token(access_token).build())
+        })
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn oidc_auth() {
+    let _guard: RwLockReadGuard<_> = LOCK.run_concurrently().await;
+
+    let uri = match (
+        std::env::var("MONGO_OIDC_URI"),
+        std::env::var("OIDC_TOKEN_FILE"),
+    ) {
+        (Ok(uri), Ok(_)) => uri,
+        _ => {
+            log_uncaptured(
+                "skipping oidc_auth due to environment variables MONGO_OIDC_URI and \
+                 OIDC_TOKEN_FILE",
+            );
+            return;
+        }
+    };
+
+    let callback = Arc::new(TokenFileCallback::default());
+    let mut options = ClientOptions::parse_uri(uri, None).await.unwrap();
+    options.credential = Some(
+        Credential::builder()
+            .mechanism(AuthMechanism::MongoDbOidc)
+            .oidc_callback(callback.clone() as Arc<dyn OidcCallback>)
+            .build(),
+    );
+    let client = Client::with_options(options).unwrap();
+    let coll = client.database("test").collection::<Document>("test");
+
+    coll.find_one(None, None).await.unwrap();
+    assert_eq!(callback.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // The connection reauthenticates with a new token and the find is run again.
+    let failpoint = FailPoint::fail_command(
+        &["find"],
+        FailPointMode::Times(1),
+        FailCommandOptions::builder().error_code(391).build(),
+    );
+    let _fp_guard = failpoint.enable(&client, None).await.unwrap();
+
+    coll.find_one(None, None).await.unwrap();
+    assert_eq!(callback.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+/// Provides a credential for the "rotating" user with its current password, counting the number of
+/// calls.
+struct RotatingPassword {
+    password: std::sync::Mutex<String>,
+    calls: std::sync::atomic::AtomicUsize,
+}
+
+impl CredentialProvider for RotatingPassword {
+    fn credential<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = crate::error::Result<Credential>> + Send + 'a>> {
+        Box::pin(async move {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Credential::builder()
+                .username("rotating".to_string())
+                .password(self.password.lock().unwrap().clone())
+                .build())
+        })
+    }
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+async fn credential_provider() {
+    let _guard: RwLockWriteGuard<_> = LOCK.run_exclusively().await;
+
+    let provider = Arc::new(RotatingPassword {
+        password: std::sync::Mutex::new("first".to_string()),
+        calls: Default::default(),
+    });
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.credential = Some(Credential::default());
+    options.credential_provider = Some(provider.clone());
+    let error = Client::with_options(options).unwrap_err();
+    assert!(
+        matches!(*error.kind, ErrorKind::InvalidArgument { .. }),
+        "{:?}",
+        error
+    );
+
+    let client = TestClient::new().await;
+    if !client.auth_enabled() {
+        log_uncaptured("skipping credential_provider due to missing authentication");
+        return;
+    }
+    client
+        .drop_and_create_user("rotating", "first", &[Bson::from("root")], &[], None)
+        .await
+        .unwrap();
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.credential = None;
+    options.credential_provider = Some(provider.clone());
+    options.max_pool_size = Some(1);
+    options.max_idle_time = Some(Duration::from_secs(1));
+    options.hosts.truncate(1);
+    options.direct_connection = Some(true);
+    let rotating_client = Client::with_options(options).unwrap();
+    let db = rotating_client.database("admin");
+
+    db.run_command(doc! { "ping": 1 }, None).await.unwrap();
+    assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Rotate the password. The connection that has already authenticated keeps working.
+    client
+        .database("admin")
+        .run_command(doc! { "updateUser": "rotating", "pwd": "second" }, None)
+        .await
+        .unwrap();
+    *provider.password.lock().unwrap() = "second".to_string();
+    db.run_command(doc! { "ping": 1 }, None).await.unwrap();
+    assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    // Once the connection has been idle for too long, it is replaced by a new one that
+    // authenticates with the new password.
+    runtime::delay_for(Duration::from_millis(1500)).await;
+    db.run_command(doc! { "ping": 1 }, None).await.unwrap();
+    assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    client
+        .database("admin")
+        .run_command(doc! { "dropUser": "rotating" }, None)
+        .await
+        .unwrap();
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn shutdown_kills_cursors_and_ends_sessions() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many(
+        vec![doc! { "x": 1 }, doc! { "x": 2 }, doc! { "x": 3 }],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let options = FindOptions::builder().batch_size(1).build();
+    let cursor = coll.find(None, options).await.unwrap();
+
+    let handler = client.handler.clone();
+    Client::clone(&client).shutdown().await;
+
+    let kill_cursors = handler.get_command_started_events(&["killCursors"]);
+    assert_eq!(kill_cursors.len(), 1);
+    assert_eq!(
+        kill_cursors[0].command.get_str("killCursors"),
+        Ok(function_name!())
+    );
+    assert!(!handler
+        .get_command_started_events(&["endSessions"])
+        .is_empty());
+
+    let error = coll.find_one(None, None).await.unwrap_err();
+    assert!(
+        matches!(*error.kind, ErrorKind::Shutdown),
+        "expected shutdown error, got {:?}",
+        error
+    );
+
+    // Dropping the cursor after shutdown should not send another `killCursors`.
+    drop(cursor);
+    runtime::delay_for(Duration::from_millis(100)).await;
+    assert_eq!(
+        handler.get_command_started_events(&["killCursors"]).len(),
+        1
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn shutdown_rejects_operations_while_draining() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.supports_block_connection() {
+        log_uncaptured(
+            "skipping shutdown_rejects_operations_while_draining due to blockConnection not being \
+             supported",
+        );
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(500))
+        .build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(options));
+    // The failpoint is disabled with a separate client, since this one is shut down.
+    let setup_client = TestClient::new().await;
+    let _fp_guard = setup_client
+        .enable_failpoint(failpoint, None)
+        .await
+        .unwrap();
+
+    // Start an operation that will still be in flight when shutdown begins.
+    let in_flight_coll = coll.clone();
+    let in_flight =
+        runtime::spawn(async move { in_flight_coll.find_one(None, None).await.map(|_| ()) });
+    runtime::delay_for(Duration::from_millis(100)).await;
+
+    let shutdown = runtime::spawn(Client::clone(&client).shutdown());
+    runtime::delay_for(Duration::from_millis(100)).await;
+
+    // Operations started while shutdown is waiting for the in-flight operation are rejected, and
+    // a second call to shutdown returns without repeating the shutdown.
+    let error = coll.count_documents(None, None).await.unwrap_err();
+    assert!(
+        matches!(*error.kind, ErrorKind::Shutdown),
+        "expected shutdown error, got {:?}",
+        error
+    );
+    Client::clone(&client).shutdown().await;
+
+    in_flight.await.unwrap();
+    shutdown.await;
+    assert_eq!(
+        client
+            .handler
+            .get_command_started_events(&["endSessions"])
+            .len(),
+        1
+    );
+}
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, time::Duration};
+use std::{collections::HashSet, fmt::Debug, time::Duration};
 
 use futures::stream::{StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
@@ -12,26 +12,43 @@ use crate::{
     options::{
         Acknowledgment,
         AggregateOptions,
+        BulkWriteOptions,
         CollectionOptions,
+        DeleteManyModel,
+        DeleteOneModel,
         DeleteOptions,
         DropCollectionOptions,
+        ExplainVerbosity,
+        Explainable,
         FindOneAndDeleteOptions,
         FindOneOptions,
         FindOptions,
         Hint,
         IndexOptions,
         InsertManyOptions,
+        InsertOneModel,
         ReadConcern,
         ReadPreference,
+        RenameCollectionOptions,
+        ReplaceOneModel,
         SelectionCriteria,
+        UpdateOneModel,
         UpdateOptions,
         WriteConcern,
+        WriteModel,
     },
     results::DeleteResult,
     runtime,
     test::{
         log_uncaptured,
-        util::{drop_collection, EventClient, TestClient},
+        util::{
+            drop_collection,
+            EventClient,
+            FailCommandOptions,
+            FailPoint,
+            FailPointMode,
+            TestClient,
+        },
         CLIENT_OPTIONS,
         LOCK,
     },
@@ -218,6 +235,313 @@ async fn delete() {
     assert_eq!(coll.count_documents(doc! {"x": 3 }, None).await.unwrap(), 0);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn rename() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    let target = client
+        .init_db_and_coll(function_name!(), &format!("{}_target", function_name!()))
+        .await;
+
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+    coll.rename(target.namespace(), None).await.unwrap();
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 0);
+    assert_eq!(target.count_documents(None, None).await.unwrap(), 1);
+
+    // Renaming onto an existing collection fails unless the target is dropped.
+    coll.insert_one(doc! { "x": 2 }, None).await.unwrap();
+    let error = coll.rename(target.namespace(), None).await.unwrap_err();
+    assert_eq!(error.code(), Some(48), "{:?}", error);
+
+    let options = RenameCollectionOptions::builder()
+        .drop_target(true)
+        .write_concern(WriteConcern::builder().w(Acknowledgment::Majority).build())
+        .build();
+    coll.rename(target.namespace(), options).await.unwrap();
+    assert_eq!(
+        target.find_one(None, None).await.unwrap().unwrap().get("x"),
+        Some(&Bson::Int32(2))
+    );
+
+    // Collections in sharded clusters can only be moved between databases whose primary shards
+    // are the same.
+    if client.is_sharded() {
+        return;
+    }
+    let other_db = client
+        .init_db_and_coll(&format!("{}_other", function_name!()), function_name!())
+        .await;
+    target.rename(other_db.namespace(), None).await.unwrap();
+    assert_eq!(target.count_documents(None, None).await.unwrap(), 0);
+    assert_eq!(other_db.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn explain() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    coll.insert_many((0i32..5).map(|i| doc! { "x": i }).collect::<Vec<_>>(), None)
+        .await
+        .unwrap();
+
+    let explainables = vec![
+        Explainable::Find {
+            filter: Some(doc! { "x": { "$gt": 1 } }),
+            options: None,
+        },
+        Explainable::Aggregate {
+            pipeline: vec![doc! { "$match": { "x": 1 } }],
+            options: None,
+        },
+        Explainable::Distinct {
+            field_name: "x".to_string(),
+            filter: None,
+            options: None,
+        },
+        Explainable::UpdateMany {
+            filter: doc! { "x": 1 },
+            update: doc! { "$set": { "y": 1 } }.into(),
+            options: None,
+        },
+        Explainable::DeleteOne {
+            filter: doc! { "x": 1 },
+            options: None,
+        },
+    ];
+
+    for explainable in explainables {
+        let explanation = coll
+            .explain(explainable.clone(), ExplainVerbosity::QueryPlanner)
+            .await
+            .unwrap();
+        assert!(
+            explanation.contains_key("queryPlanner") || explanation.contains_key("stages"),
+            "unexpected explain output for {:?}: {}",
+            explainable,
+            explanation
+        );
+    }
+
+    // Explaining a write must not execute it.
+    assert_eq!(
+        coll.count_documents(doc! { "x": 1 }, None).await.unwrap(),
+        1
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let models: Vec<WriteModel<Document>> = vec![
+        InsertOneModel::builder()
+            .document(doc! { "x": 1 })
+            .build()
+            .into(),
+        InsertOneModel::builder()
+            .document(doc! { "x": 2 })
+            .build()
+            .into(),
+        UpdateOneModel::builder()
+            .filter(doc! { "x": 1 })
+            .update(doc! { "$set": { "y": 1 } })
+            .build()
+            .into(),
+        UpdateOneModel::builder()
+            .filter(doc! { "x": 10 })
+            .update(doc! { "$set": { "y": 10 } })
+            .upsert(true)
+            .build()
+            .into(),
+        ReplaceOneModel::builder()
+            .filter(doc! { "x": 2 })
+            .replacement(doc! { "x": 3 })
+            .build()
+            .into(),
+        InsertOneModel::builder()
+            .document(doc! { "x": 4 })
+            .build()
+            .into(),
+        DeleteManyModel::builder()
+            .filter(doc! { "x": { "$gte": 3 } })
+            .build()
+            .into(),
+    ];
+
+    let result = coll.bulk_write(models, None).await.unwrap();
+    assert_eq!(result.inserted_count, 3);
+    assert_eq!(result.matched_count, 2);
+    assert_eq!(result.modified_count, 2);
+    assert_eq!(result.upserted_count, 1);
+    assert_eq!(result.deleted_count, 2);
+    assert_eq!(
+        result.inserted_ids.keys().copied().collect::<HashSet<_>>(),
+        vec![0, 1, 5].into_iter().collect::<HashSet<_>>()
+    );
+    assert!(result.upserted_ids.contains_key(&3));
+
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 2);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write_errors() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let models = || -> Vec<WriteModel<Document>> {
+        vec![
+            InsertOneModel::builder()
+                .document(doc! { "_id": 1 })
+                .build()
+                .into(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 1 })
+                .build()
+                .into(),
+            DeleteOneModel::builder()
+                .filter(doc! { "_id": 1 })
+                .build()
+                .into(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 2 })
+                .build()
+                .into(),
+        ]
+    };
+
+    match *coll.bulk_write(models(), None).await.unwrap_err().kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            let write_errors = failure.write_errors.as_ref().unwrap();
+            assert_eq!(write_errors.len(), 1);
+            assert_eq!(write_errors[0].index, 1);
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+
+    coll.drop(None).await.unwrap();
+    let options = BulkWriteOptions::builder().ordered(false).build();
+    match *coll.bulk_write(models(), options).await.unwrap_err().kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            let write_errors = failure.write_errors.as_ref().unwrap();
+            assert_eq!(write_errors.len(), 1);
+            assert_eq!(write_errors[0].index, 1);
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+    let docs: Vec<Document> = coll
+        .find(None, None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(docs, vec![doc! { "_id": 2 }]);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn bulk_write_errors_partial_result() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // each change of model type starts a new batch, so the failed insert is in the second of four
+    // batches.
+    let models = || -> Vec<WriteModel<Document>> {
+        vec![
+            UpdateOneModel::builder()
+                .filter(doc! { "_id": 1 })
+                .update(doc! { "$set": { "y": 1 } })
+                .upsert(true)
+                .build()
+                .into(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 1 })
+                .build()
+                .into(),
+            InsertOneModel::builder()
+                .document(doc! { "_id": 2 })
+                .build()
+                .into(),
+            UpdateOneModel::builder()
+                .filter(doc! { "_id": 2 })
+                .update(doc! { "$set": { "y": 2 } })
+                .build()
+                .into(),
+            DeleteOneModel::builder()
+                .filter(doc! { "_id": 1 })
+                .build()
+                .into(),
+        ]
+    };
+
+    let options = BulkWriteOptions::builder().ordered(false).build();
+    match *coll.bulk_write(models(), options).await.unwrap_err().kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            let write_errors = failure.write_errors.as_ref().unwrap();
+            assert_eq!(write_errors.len(), 1);
+            assert_eq!(write_errors[0].index, 1);
+
+            let result = failure.partial_result.as_ref().unwrap();
+            assert_eq!(result.inserted_count, 1);
+            assert_eq!(result.matched_count, 1);
+            assert_eq!(result.modified_count, 1);
+            assert_eq!(result.upserted_count, 1);
+            assert_eq!(result.deleted_count, 1);
+            assert_eq!(
+                result.inserted_ids.keys().copied().collect::<Vec<_>>(),
+                vec![2]
+            );
+            assert_eq!(result.upserted_ids.get(&0), Some(&Bson::Int32(1)));
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+
+    coll.drop(None).await.unwrap();
+    match *coll.bulk_write(models(), None).await.unwrap_err().kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            let result = failure.partial_result.as_ref().unwrap();
+            assert_eq!(result.inserted_count, 0);
+            assert_eq!(result.upserted_count, 1);
+            assert_eq!(result.deleted_count, 0);
+            assert!(result.inserted_ids.is_empty());
+            assert_eq!(result.upserted_ids.get(&0), Some(&Bson::Int32(1)));
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+}
+
 #[cfg_attr(feature = "tokio-runtime", tokio::test)]
 #[cfg_attr(feature = "async-std-runtime", async_std::test)]
 #[function_name::named]
@@ -1173,6 +1497,304 @@ async fn invalid_utf8_response() {
     assert_duplicate_key_error_with_utf8_replacement(&replace_err);
 }
 
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_sets_max_time_ms() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    let coll = client
+        .database(function_name!())
+        .collection_with_options::<Document>(
+            function_name!(),
+            CollectionOptions::builder()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        );
+
+    coll.find_one(None, None).await.unwrap();
+    coll.find_one(
+        None,
+        FindOneOptions::builder()
+            .max_time(Duration::from_millis(500))
+            .build(),
+    )
+    .await
+    .unwrap();
+    coll.find_one(
+        None,
+        FindOneOptions::builder()
+            .timeout(Duration::from_secs(0))
+            .build(),
+    )
+    .await
+    .unwrap();
+
+    let events = client.get_command_started_events(&["find"]);
+    assert_eq!(events.len(), 3);
+
+    let max_time_ms = events[0].command.get_i64("maxTimeMS").unwrap();
+    assert!(max_time_ms > 0 && max_time_ms <= 10_000);
+    assert_eq!(events[1].command.get_i64("maxTimeMS"), Ok(500));
+    assert!(!events[2].command.contains_key("maxTimeMS"));
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_expires() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.supports_block_connection() {
+        log_uncaptured("skipping timeout_expires due to blockConnection not being supported");
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(500))
+        .build();
+    let failpoint = FailPoint::fail_command(&["find"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let error = coll
+        .find_one(
+            None,
+            FindOneOptions::builder()
+                .timeout(Duration::from_millis(100))
+                .build(),
+        )
+        .await
+        .expect_err("find should time out");
+    assert!(
+        matches!(*error.kind, ErrorKind::Timeout { .. }),
+        "expected timeout error, got {:?}",
+        error
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_shared_across_batches() {
+    if std::env::consts::OS != "linux" {
+        log_uncaptured("skipping timeout_shared_across_batches due to unsupported OS");
+        return;
+    }
+
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = EventClient::new().await;
+    if !client.supports_block_connection() {
+        log_uncaptured(
+            "skipping timeout_shared_across_batches due to blockConnection not being supported",
+        );
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // every batch takes 400ms, so the insert can't finish all of its batches within one second
+    // even though each batch on its own would.
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(400))
+        .build();
+    let failpoint = FailPoint::fail_command(&["insert"], FailPointMode::AlwaysOn, Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let docs = vec![LARGE_DOC.clone(); 35000];
+    let error = coll
+        .insert_many(
+            docs,
+            InsertManyOptions::builder()
+                .timeout(Duration::from_secs(1))
+                .build(),
+        )
+        .await
+        .expect_err("insert_many should time out");
+    match *error.kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            assert!(
+                matches!(
+                    failure.error.as_ref().map(|e| e.kind.as_ref()),
+                    Some(ErrorKind::Timeout { .. })
+                ),
+                "expected timeout error, got {:?}",
+                failure.error
+            );
+            assert!(failure.partial_result.as_ref().unwrap().inserted_count > 0);
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+
+    let events = client.get_command_started_events(&["insert"]);
+    assert!(
+        events.len() >= 2,
+        "expected multiple batches, got {:?}",
+        events
+    );
+    let first = events[0].command.get_i64("maxTimeMS").unwrap();
+    let second = events[1].command.get_i64("maxTimeMS").unwrap();
+    assert!(first <= 1000);
+    assert!(
+        second <= first - 400,
+        "second batch was given {}ms after a 400ms first batch",
+        second
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_after_first_batch_reports_partial_result() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.supports_block_connection() {
+        log_uncaptured(
+            "skipping timeout_after_first_batch_reports_partial_result due to blockConnection not \
+             being supported",
+        );
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    // the insert and the update are sent in separate batches, and the update is blocked for longer
+    // than the whole operation may take.
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(1500))
+        .build();
+    let failpoint = FailPoint::fail_command(&["update"], FailPointMode::Times(1), Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let models: Vec<WriteModel<Document>> = vec![
+        InsertOneModel::builder()
+            .document(doc! { "_id": 1 })
+            .build()
+            .into(),
+        UpdateOneModel::builder()
+            .filter(doc! { "_id": 1 })
+            .update(doc! { "$set": { "x": 1 } })
+            .build()
+            .into(),
+    ];
+    let options = BulkWriteOptions::builder()
+        .timeout(Duration::from_secs(1))
+        .build();
+    let error = coll.bulk_write(models, options).await.unwrap_err();
+    match *error.kind {
+        ErrorKind::BulkWrite(ref failure) => {
+            assert!(failure.write_errors.is_none());
+            assert!(
+                matches!(
+                    failure.error.as_ref().map(|e| e.kind.as_ref()),
+                    Some(ErrorKind::Timeout { .. })
+                ),
+                "expected timeout error, got {:?}",
+                failure.error
+            );
+            let result = failure.partial_result.as_ref().unwrap();
+            assert_eq!(result.inserted_count, 1);
+            assert_eq!(result.matched_count, 0);
+            assert_eq!(result.inserted_ids.get(&0), Some(&Bson::Int32(1)));
+        }
+        ref e => panic!("expected bulk write error, got {:?} instead", e),
+    }
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_bounds_cursor_lifetime() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.supports_block_connection() {
+        log_uncaptured(
+            "skipping timeout_bounds_cursor_lifetime due to blockConnection not being supported",
+        );
+        return;
+    }
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+    coll.insert_many((0..3).map(|i| doc! { "x": i }), None)
+        .await
+        .unwrap();
+
+    // every getMore takes 600ms, so the cursor can't be exhausted within one second even though
+    // each getMore on its own would finish in time.
+    let options = FailCommandOptions::builder()
+        .block_connection(Duration::from_millis(600))
+        .build();
+    let failpoint = FailPoint::fail_command(&["getMore"], FailPointMode::AlwaysOn, Some(options));
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let options = FindOptions::builder()
+        .batch_size(1)
+        .timeout(Duration::from_secs(1))
+        .build();
+    let mut cursor = coll.find(None, options).await.unwrap();
+    assert!(cursor.try_next().await.unwrap().is_some());
+    assert!(cursor.try_next().await.unwrap().is_some());
+    let error = cursor
+        .try_next()
+        .await
+        .expect_err("second getMore should time out");
+    assert!(
+        matches!(*error.kind, ErrorKind::Timeout { .. }),
+        "expected timeout error, got {:?}",
+        error
+    );
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn timeout_omits_max_time_ms_in_transaction() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = EventClient::new().await;
+    if !client.supports_transactions() {
+        log_uncaptured(
+            "skipping timeout_omits_max_time_ms_in_transaction due to transactions not being \
+             supported",
+        );
+        return;
+    }
+    let coll = client
+        .init_db_and_coll_with_options(
+            function_name!(),
+            function_name!(),
+            CollectionOptions::builder()
+                .timeout(Duration::from_secs(10))
+                .build(),
+        )
+        .await;
+    // create the collection outside of the transaction, which older servers require.
+    coll.insert_one(doc! { "x": 1 }, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    session.start_transaction(None).await.unwrap();
+    coll.insert_one_with_session(doc! { "x": 2 }, None, &mut session)
+        .await
+        .unwrap();
+    session.commit_transaction().await.unwrap();
+
+    let events = client.get_command_started_events(&["insert"]);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].command.contains_key("maxTimeMS"));
+    assert!(!events[1].command.contains_key("maxTimeMS"));
+}
+
 /// Check that we successfully decoded a duplicate key error and that the error message contains the
 /// unicode replacement character, meaning we gracefully handled the invalid UTF-8.
 fn assert_duplicate_key_error_with_utf8_replacement(error: &ErrorKind) {
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use futures::stream::TryStreamExt;
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    bson::{doc, spec::BinarySubtype, Binary, Bson, Document},
+    client_encryption::{
+        Algorithm,
+        ClientEncryption,
+        DataKeyOptions,
+        EncryptKey,
+        EncryptOptions,
+        KmsProviders,
+        MasterKey,
+    },
+    error::ErrorKind,
+    options::{AutoEncryptionOptions, ClientOptions},
+    test::{
+        util::{EventClient, TestClient},
+        CLIENT_OPTIONS,
+        LOCK,
+    },
+    Client,
+    Namespace,
+};
+
+fn key_vault_namespace(db: &str) -> Namespace {
+    Namespace {
+        db: db.to_string(),
+        coll: "datakeys".to_string(),
+    }
+}
+
+fn kms_providers() -> KmsProviders {
+    KmsProviders::builder().local(vec![7u8; 96]).build()
+}
+
+async fn init_client_encryption(client: &TestClient, db: &str) -> ClientEncryption {
+    let namespace = key_vault_namespace(db);
+    client
+        .database(&namespace.db)
+        .collection::<Document>(&namespace.coll)
+        .drop(None)
+        .await
+        .unwrap();
+    ClientEncryption::new(Client::clone(client), namespace, kms_providers()).unwrap()
+}
+
+// Test that data keys can be created, retrieved and deleted.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn data_key_management() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let client_encryption = init_client_encryption(&client, function_name!()).await;
+
+    let id = client_encryption
+        .create_data_key(
+            MasterKey::Local,
+            DataKeyOptions::builder()
+                .key_alt_names(vec!["alt".to_string()])
+                .build(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(id.subtype, BinarySubtype::Uuid);
+
+    let key = client_encryption.get_key(&id).await.unwrap().unwrap();
+    assert_eq!(
+        key.get_document("masterKey"),
+        Ok(&doc! { "provider": "local" })
+    );
+    assert_eq!(
+        key.get_array("keyAltNames").unwrap(),
+        &vec![Bson::from("alt")]
+    );
+
+    let by_name = client_encryption
+        .get_key_by_alt_name("alt")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_name, key);
+
+    let result = client_encryption
+        .rewrap_many_data_key(doc! {}, None)
+        .await
+        .unwrap();
+    assert_eq!(result.bulk_write_result.unwrap().modified_count, 1);
+    let rewrapped = client_encryption.get_key(&id).await.unwrap().unwrap();
+    assert_ne!(rewrapped.get("keyMaterial"), key.get("keyMaterial"));
+
+    let keys: Vec<Document> = client_encryption
+        .get_keys()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(keys.len(), 1);
+
+    let result = client_encryption.delete_key(&id).await.unwrap();
+    assert_eq!(result.deleted_count, 1);
+    assert!(client_encryption.get_key(&id).await.unwrap().is_none());
+}
+
+// Test that values can be explicitly encrypted and decrypted.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn explicit_encrypt_decrypt() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let client_encryption = init_client_encryption(&client, function_name!()).await;
+
+    let id = client_encryption
+        .create_data_key(
+            MasterKey::Local,
+            DataKeyOptions::builder()
+                .key_alt_names(vec!["alt".to_string()])
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let encrypt = |key: EncryptKey, algorithm: Algorithm| {
+        client_encryption.encrypt(
+            "secret",
+            EncryptOptions::builder()
+                .key(key)
+                .algorithm(algorithm)
+                .build(),
+        )
+    };
+
+    let deterministic = encrypt(
+        EncryptKey::Id(id.clone()),
+        Algorithm::AeadAes256CbcHmacSha512Deterministic,
+    )
+    .await
+    .unwrap();
+    assert_eq!(deterministic.subtype, BinarySubtype::Encrypted);
+    let by_alt_name = encrypt(
+        EncryptKey::AltName("alt".to_string()),
+        Algorithm::AeadAes256CbcHmacSha512Deterministic,
+    )
+    .await
+    .unwrap();
+    assert_eq!(deterministic, by_alt_name);
+
+    let random = encrypt(
+        EncryptKey::Id(id.clone()),
+        Algorithm::AeadAes256CbcHmacSha512Random,
+    )
+    .await
+    .unwrap();
+    assert_ne!(deterministic, random);
+
+    for ciphertext in [&deterministic, &random] {
+        assert_eq!(
+            client_encryption.decrypt(ciphertext).await.unwrap(),
+            Bson::from("secret")
+        );
+    }
+
+    // A client configured with a different master key cannot decrypt the data key.
+    let other = ClientEncryption::new(
+        Client::clone(&client),
+        key_vault_namespace(function_name!()),
+        KmsProviders::builder().local(vec![8u8; 96]).build(),
+    )
+    .unwrap();
+    assert!(other.decrypt(&random).await.is_err());
+
+    assert!(encrypt(
+        EncryptKey::AltName("missing".to_string()),
+        Algorithm::AeadAes256CbcHmacSha512Random
+    )
+    .await
+    .is_err());
+}
+
+// Test that fields in the schema map are encrypted when written and decrypted when read.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn automatic_encryption() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let client_encryption = init_client_encryption(&client, function_name!()).await;
+    let key_id = client_encryption
+        .create_data_key(MasterKey::Local, None)
+        .await
+        .unwrap();
+
+    let encrypted_client =
+        Client::with_options(auto_encryption_options(key_id, function_name!())).unwrap();
+    let coll = encrypted_client
+        .database(function_name!())
+        .collection::<Document>("coll");
+    coll.drop(None).await.unwrap();
+
+    coll.insert_one(doc! { "name": "Alice", "ssn": "123-45-6789" }, None)
+        .await
+        .unwrap();
+
+    let found = coll
+        .find_one(doc! { "ssn": "123-45-6789" }, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.get_str("ssn"), Ok("123-45-6789"));
+
+    let raw = client
+        .database(function_name!())
+        .collection::<Document>("coll")
+        .find_one(doc! { "name": "Alice" }, None)
+        .await
+        .unwrap()
+        .unwrap();
+    match raw.get("ssn") {
+        Some(Bson::Binary(binary)) => assert_eq!(binary.subtype, BinarySubtype::Encrypted),
+        other => panic!("expected encrypted ssn, got {:?}", other),
+    }
+}
+
+// Test that commands and aggregation stages that cannot be encrypted are rejected without being
+// sent to the server.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn automatic_encryption_rejects_unsupported_commands() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let client_encryption = init_client_encryption(&client, function_name!()).await;
+    let key_id = client_encryption
+        .create_data_key(MasterKey::Local, None)
+        .await
+        .unwrap();
+
+    let encrypted_client =
+        EventClient::with_options(auto_encryption_options(key_id, function_name!())).await;
+    let db = encrypted_client.database(function_name!());
+    let coll = db.collection::<Document>("coll");
+    coll.drop(None).await.unwrap();
+    coll.insert_one(doc! { "ssn": "123-45-6789" }, None)
+        .await
+        .unwrap();
+
+    for stage in [
+        doc! { "$group": { "_id": "$ssn" } },
+        doc! { "$lookup": { "from": "other", "localField": "ssn", "foreignField": "ssn", "as": "o" } },
+        doc! { "$unionWith": "other" },
+        doc! { "$out": "other" },
+    ] {
+        let error = coll
+            .aggregate(
+                vec![doc! { "$match": { "ssn": "123-45-6789" } }, stage],
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(*error.kind, ErrorKind::InvalidArgument { .. }),
+            "{:?}",
+            error
+        );
+    }
+    assert!(encrypted_client
+        .get_command_started_events(&["aggregate"])
+        .is_empty());
+
+    let error = db
+        .run_command(
+            doc! { "collMod": "coll", "validator": { "ssn": "123-45-6789" } },
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(*error.kind, ErrorKind::InvalidArgument { .. }),
+        "{:?}",
+        error
+    );
+    assert!(encrypted_client
+        .get_command_started_events(&["collMod"])
+        .is_empty());
+}
+
+/// Returns client options that automatically encrypt the "ssn" field of the "coll" collection in
+/// `db` with the data key `key_id`.
+fn auto_encryption_options(key_id: Binary, db: &str) -> ClientOptions {
+    let schema = doc! {
+        "bsonType": "object",
+        "properties": {
+            "ssn": {
+                "encrypt": {
+                    "keyId": [key_id],
+                    "bsonType": "string",
+                    "algorithm": "AEAD_AES_256_CBC_HMAC_SHA_512-Deterministic",
+                },
+            },
+        },
+    };
+    let mut schema_map = HashMap::new();
+    schema_map.insert(format!("{}.coll", db), schema);
+
+    let mut options = CLIENT_OPTIONS.clone();
+    options.auto_encryption_opts = Some(
+        AutoEncryptionOptions::builder()
+            .key_vault_namespace(key_vault_namespace(db))
+            .kms_providers(kms_providers())
+            .schema_map(schema_map)
+            .build(),
+    );
+    options
+}
@@ -1,4 +1,4 @@
-use std::cmp::Ord;
+use std::{cmp::Ord, time::Duration};
 
 use futures::stream::TryStreamExt;
 use tokio::sync::RwLockReadGuard;
@@ -10,16 +10,20 @@ use crate::{
         AggregateOptions,
         Collation,
         CreateCollectionOptions,
+        IndexModification,
         IndexOptionDefaults,
+        IndexOptions,
+        ModifyCollectionOptions,
         ValidationAction,
         ValidationLevel,
     },
-    results::{CollectionSpecification, CollectionType},
+    results::{CollectionSpecification, CollectionType, ModifyCollectionResult},
     test::{
         util::{EventClient, TestClient},
         LOCK,
     },
     Database,
+    IndexModel,
 };
 
 use super::log_uncaptured;
@@ -370,3 +374,86 @@ async fn index_option_defaults_test(defaults: Option<IndexOptionDefaults>, name:
     };
     assert_eq!(event_defaults, defaults);
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn modify_collection() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    db.drop(None).await.unwrap();
+
+    let options = CreateCollectionOptions::builder()
+        .validator(doc! { "x": { "$type": "int" } })
+        .build();
+    db.create_collection(function_name!(), options)
+        .await
+        .unwrap();
+    let coll = db.collection::<Document>(function_name!());
+    let index = IndexModel::builder()
+        .keys(doc! { "created": 1 })
+        .options(
+            IndexOptions::builder()
+                .expire_after(Duration::from_secs(60))
+                .build(),
+        )
+        .build();
+    let index_name = coll.create_index(index, None).await.unwrap().index_name;
+
+    // Changing the validation rules does not report any index changes.
+    let options = ModifyCollectionOptions::builder()
+        .validator(doc! { "x": { "$type": "string" } })
+        .validation_level(ValidationLevel::Moderate)
+        .validation_action(ValidationAction::Warn)
+        .build();
+    let result = db
+        .modify_collection(function_name!(), options)
+        .await
+        .unwrap();
+    assert_eq!(result, ModifyCollectionResult::default());
+
+    let coll_options = get_coll_info(&db, Some(doc! { "name": function_name!() }))
+        .await
+        .remove(0)
+        .options;
+    assert_eq!(
+        coll_options.validator,
+        Some(doc! { "x": { "$type": "string" } })
+    );
+    assert_eq!(
+        coll_options.validation_level,
+        Some(ValidationLevel::Moderate)
+    );
+    assert_eq!(coll_options.validation_action, Some(ValidationAction::Warn));
+
+    let options = ModifyCollectionOptions::builder()
+        .index(
+            IndexModification::builder()
+                .key_pattern(doc! { "created": 1 })
+                .expire_after(Duration::from_secs(120))
+                .build(),
+        )
+        .build();
+    let result = coll.modify(options).await.unwrap();
+    assert_eq!(result.expire_after_old, Some(Duration::from_secs(60)));
+    assert_eq!(result.expire_after_new, Some(Duration::from_secs(120)));
+
+    if client.server_version_lt(4, 4) {
+        log_uncaptured("skipping hidden index modification due to server version < 4.4");
+        return;
+    }
+
+    let options = ModifyCollectionOptions::builder()
+        .index(
+            IndexModification::builder()
+                .name(index_name)
+                .hidden(true)
+                .build(),
+        )
+        .build();
+    let result = coll.modify(options).await.unwrap();
+    assert_eq!(result.hidden_old, Some(false));
+    assert_eq!(result.hidden_new, Some(true));
+}
@@ -0,0 +1,258 @@
+use futures::stream::TryStreamExt;
+use tokio::sync::RwLockReadGuard;
+
+use crate::{
+    bson::{doc, Bson, Document},
+    error::{ErrorKind, GridFsErrorKind, GridFsFileIdentifier},
+    options::{
+        GridFsBucketOptions,
+        GridFsDownloadByNameOptions,
+        GridFsFindOptions,
+        GridFsUploadOptions,
+    },
+    test::{util::TestClient, LOCK},
+};
+
+// Test that a file can be uploaded in multiple chunks and downloaded again.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn upload_and_download() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    let bucket = db.gridfs_bucket(GridFsBucketOptions::builder().chunk_size_bytes(4).build());
+    bucket.drop().await.unwrap();
+
+    let bytes = b"hello world".to_vec();
+    let metadata = doc! { "x": 1 };
+    let id = bucket
+        .upload_from_bytes(
+            "hello.txt",
+            &bytes,
+            GridFsUploadOptions::builder()
+                .metadata(metadata.clone())
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let chunks: Vec<Document> = db
+        .collection::<Document>("fs.chunks")
+        .find(doc! { "files_id": id }, None)
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(chunks.len(), 3);
+
+    let mut stream = bucket.open_download_stream(id.into()).await.unwrap();
+    let file = stream.files_collection_document();
+    assert_eq!(file.length, bytes.len() as u64);
+    assert_eq!(file.chunk_size, 4);
+    assert_eq!(file.filename.as_deref(), Some("hello.txt"));
+    assert_eq!(file.metadata, Some(metadata));
+
+    let mut downloaded = Vec::new();
+    while let Some(data) = stream.try_next().await.unwrap() {
+        downloaded.extend(data);
+    }
+    assert_eq!(downloaded, bytes);
+
+    let mut downloaded = Vec::new();
+    bucket
+        .download_to_futures_0_3_writer(id.into(), &mut downloaded)
+        .await
+        .unwrap();
+    assert_eq!(downloaded, bytes);
+
+    // Empty files should be stored without any chunks.
+    let id = bucket.upload_from_bytes("empty", b"", None).await.unwrap();
+    let mut downloaded = Vec::new();
+    bucket
+        .download_to_futures_0_3_writer(id.into(), &mut downloaded)
+        .await
+        .unwrap();
+    assert!(downloaded.is_empty());
+}
+
+// Test that uploading a file creates the required indexes.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn upload_creates_indexes() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    let bucket = db.gridfs_bucket(
+        GridFsBucketOptions::builder()
+            .bucket_name("custom".to_string())
+            .build(),
+    );
+    bucket.drop().await.unwrap();
+
+    bucket.upload_from_bytes("a", b"abc", None).await.unwrap();
+
+    let files_indexes = db
+        .collection::<Document>("custom.files")
+        .list_index_names()
+        .await
+        .unwrap();
+    assert!(files_indexes.contains(&"filename_1_uploadDate_1".to_string()));
+
+    let chunks_indexes = db
+        .collection::<Document>("custom.chunks")
+        .list_index_names()
+        .await
+        .unwrap();
+    assert!(chunks_indexes.contains(&"files_id_1_n_1".to_string()));
+}
+
+// Test that files can be downloaded by name and revision.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_by_name() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let bucket = client.database(function_name!()).gridfs_bucket(None);
+    bucket.drop().await.unwrap();
+
+    for contents in &["first", "second", "third"] {
+        bucket
+            .upload_from_bytes("file", contents.as_bytes(), None)
+            .await
+            .unwrap();
+        // Ensure that the upload dates are distinct.
+        crate::runtime::delay_for(std::time::Duration::from_millis(5)).await;
+    }
+
+    let download = |revision: Option<i32>| {
+        let bucket = bucket.clone();
+        async move {
+            let mut downloaded = Vec::new();
+            bucket
+                .download_to_futures_0_3_writer_by_name(
+                    "file",
+                    &mut downloaded,
+                    GridFsDownloadByNameOptions::builder()
+                        .revision(revision)
+                        .build(),
+                )
+                .await
+                .map(|_| String::from_utf8(downloaded).unwrap())
+        }
+    };
+
+    assert_eq!(download(None).await.unwrap(), "third");
+    assert_eq!(download(Some(0)).await.unwrap(), "first");
+    assert_eq!(download(Some(1)).await.unwrap(), "second");
+    assert_eq!(download(Some(-2)).await.unwrap(), "second");
+    assert_eq!(download(Some(-3)).await.unwrap(), "first");
+
+    let error = download(Some(3)).await.unwrap_err();
+    assert!(matches!(
+        *error.kind,
+        ErrorKind::GridFs(GridFsErrorKind::RevisionNotFound { revision: 3 })
+    ));
+
+    let error = bucket
+        .open_download_stream_by_name("missing", None)
+        .await
+        .unwrap_err();
+    match *error.kind {
+        ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+            identifier: GridFsFileIdentifier::Filename(ref name),
+        }) => assert_eq!(name, "missing"),
+        ref other => panic!("expected FileNotFound error, got {:?}", other),
+    }
+}
+
+// Test that find, rename and delete work as expected.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn find_rename_delete() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    let bucket = db.gridfs_bucket(GridFsBucketOptions::builder().chunk_size_bytes(2).build());
+    bucket.drop().await.unwrap();
+
+    let a = bucket.upload_from_bytes("a", b"aaaa", None).await.unwrap();
+    let b = bucket.upload_from_bytes("b", b"bbbb", None).await.unwrap();
+
+    bucket.rename(a.into(), "c").await.unwrap();
+
+    let files: Vec<_> = bucket
+        .find(
+            doc! {},
+            GridFsFindOptions::builder()
+                .sort(doc! { "filename": 1 })
+                .build(),
+        )
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let names: Vec<_> = files
+        .iter()
+        .map(|file| file.filename.clone().unwrap())
+        .collect();
+    assert_eq!(names, vec!["b", "c"]);
+
+    bucket.delete(b.into()).await.unwrap();
+    let remaining_chunks = db
+        .collection::<Document>("fs.chunks")
+        .count_documents(doc! { "files_id": b }, None)
+        .await
+        .unwrap();
+    assert_eq!(remaining_chunks, 0);
+
+    let error = bucket.delete(b.into()).await.unwrap_err();
+    assert!(matches!(
+        *error.kind,
+        ErrorKind::GridFs(GridFsErrorKind::FileNotFound {
+            identifier: GridFsFileIdentifier::Id(Bson::ObjectId(_)),
+        })
+    ));
+
+    let error = bucket.rename(b.into(), "d").await.unwrap_err();
+    assert!(matches!(
+        *error.kind,
+        ErrorKind::GridFs(GridFsErrorKind::FileNotFound { .. })
+    ));
+}
+
+// Test that a download fails if the chunks stored for a file are inconsistent with its length.
+#[cfg_attr(feature = "tokio-runtime", tokio::test)]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn download_missing_chunk() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+    let client = TestClient::new().await;
+    let db = client.database(function_name!());
+    let bucket = db.gridfs_bucket(GridFsBucketOptions::builder().chunk_size_bytes(2).build());
+    bucket.drop().await.unwrap();
+
+    let id = bucket
+        .upload_from_bytes("a", b"abcdef", None)
+        .await
+        .unwrap();
+    db.collection::<Document>("fs.chunks")
+        .delete_one(doc! { "files_id": id, "n": 1 }, None)
+        .await
+        .unwrap();
+
+    let mut downloaded = Vec::new();
+    let error = bucket
+        .download_to_futures_0_3_writer(id.into(), &mut downloaded)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        *error.kind,
+        ErrorKind::GridFs(GridFsErrorKind::MissingChunk { n: 1 })
+    ));
+}
@@ -4,10 +4,13 @@ mod auth_aws;
 mod change_stream;
 mod client;
 mod coll;
+#[cfg(feature = "csfle")]
+mod csfle;
 mod cursor;
 mod db;
 #[cfg(all(not(feature = "sync"), not(feature = "tokio-sync")))]
 mod documentation_examples;
+mod gridfs;
 mod index_management;
 mod spec;
 mod util;
@@ -29,6 +32,7 @@ pub(crate) use self::{
         MatchErrExt,
         Matchable,
         SdamEvent,
+        StaticDnsResolver,
         TestClient,
     },
 };
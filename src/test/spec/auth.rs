@@ -32,6 +32,7 @@ impl From<TestCredential> for Credential {
                 .mechanism
                 .and_then(|s| AuthMechanism::from_str(s.as_str()).ok()),
             mechanism_properties: test_credential.mechanism_properties,
+            ..Default::default()
         }
     }
 }
@@ -51,13 +52,11 @@ async fn run_auth_test(test_file: TestFile) {
         test_case.description = test_case.description.replace('$', "%");
 
         let skipped_mechanisms = [
-            "GSSAPI",
             "MONGODB-CR",
             #[cfg(not(feature = "aws-auth"))]
             "MONGODB-AWS",
         ];
 
-        // TODO: GSSAPI (RUST-196)
         if skipped_mechanisms
             .iter()
             .any(|mech| test_case.description.contains(mech))
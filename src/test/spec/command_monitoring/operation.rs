@@ -173,6 +173,7 @@ impl TestOperation for Find {
                 no_cursor_timeout: None,
                 projection: None,
                 read_concern: None,
+                timeout: None,
                 selection_criteria: None,
                 collation: None,
             };
@@ -50,6 +50,8 @@ impl ResolvedOptions {
         assert_eq!(self.ssl, options.tls_options().is_some());
         assert_eq!(self.load_balanced, options.load_balanced);
         assert_eq!(self.direct_connection, options.direct_connection);
+        assert_eq!(self.srv_max_hosts, options.srv_max_hosts);
+        assert_eq!(self.srv_service_name, options.srv_service_name);
     }
 }
 
@@ -62,27 +64,6 @@ struct ParsedOptions {
 }
 
 async fn run_test(mut test_file: TestFile) {
-    if let Some(ref options) = test_file.options {
-        // TODO RUST-933: Remove this skip.
-        let skip = if options.srv_max_hosts.is_some() {
-            Some("srvMaxHosts")
-        // TODO RUST-911: Remove this skip.
-        } else if options.srv_service_name.is_some() {
-            Some("srvServiceName")
-        } else {
-            None
-        };
-
-        if let Some(skip) = skip {
-            log_uncaptured(format!(
-                "skipping initial_dns_seedlist_discovery test case due to unsupported connection \
-                 string option: {}",
-                skip,
-            ));
-            return;
-        }
-    }
-
     // "encoded-userinfo-and-db.json" specifies a database name with a question mark which is
     // disallowed on Windows. See
     // <https://docs.mongodb.com/manual/reference/limits/#restrictions-on-db-names>
@@ -109,20 +90,19 @@ async fn run_test(mut test_file: TestFile) {
 
     let options = result.unwrap();
 
-    if let Some(ref mut expected_seeds) = test_file.seeds {
-        let mut actual_seeds = options
-            .hosts
-            .iter()
-            .map(|address| address.to_string())
-            .collect::<Vec<_>>();
+    let mut actual_seeds = options
+        .hosts
+        .iter()
+        .map(|address| address.to_string())
+        .collect::<Vec<_>>();
+    actual_seeds.sort();
 
+    if let Some(ref mut expected_seeds) = test_file.seeds {
         expected_seeds.sort();
-        actual_seeds.sort();
-
         assert_eq!(*expected_seeds, actual_seeds);
-        if let Some(expected_seed_count) = test_file.num_seeds {
-            assert_eq!(actual_seeds.len(), expected_seed_count)
-        }
+    }
+    if let Some(expected_seed_count) = test_file.num_seeds {
+        assert_eq!(actual_seeds.len(), expected_seed_count)
     }
 
     // "txt-record-with-overridden-ssl-option.json" requires SSL be disabled; see DRIVERS-1324.
@@ -1,10 +1,18 @@
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 use crate::{
     bson::{doc, serde_helpers::serialize_u64_as_i32, Document},
     client::session::TransactionState,
-    test::{log_uncaptured, run_spec_test, TestClient, LOCK},
+    error::{TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    test::{
+        log_uncaptured,
+        run_spec_test,
+        util::{FailCommandOptions, FailPoint, FailPointMode},
+        TestClient,
+        LOCK,
+    },
     Collection,
 };
 
@@ -141,3 +149,139 @@ async fn deserialize_recovery_token() {
     // Nevertheless, the recovery token should have been retrieved from the ok: 1 response.
     assert!(session.transaction.recovery_token.is_some());
 }
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_retries_transient_errors() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 4) {
+        log_uncaptured("skipping with_transaction_retries_transient_errors due to test topology");
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FailCommandOptions::builder()
+        .error_code(112)
+        .error_labels(vec![TRANSIENT_TRANSACTION_ERROR.to_string()])
+        .build();
+    let failpoint = FailPoint::fail_command(&["insert"], FailPointMode::Times(1), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let mut attempts = 0;
+    session
+        .with_transaction(
+            (&coll, &mut attempts),
+            |session, (coll, attempts)| {
+                async move {
+                    **attempts += 1;
+                    coll.insert_one_with_session(doc! { "x": 1 }, None, session)
+                        .await?;
+                    Ok(())
+                }
+                .boxed()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(attempts, 2);
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_retries_commit() {
+    let _guard: RwLockWriteGuard<()> = LOCK.run_exclusively().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 4) {
+        log_uncaptured("skipping with_transaction_retries_commit due to test topology");
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let options = FailCommandOptions::builder()
+        .error_code(64)
+        .error_labels(vec![UNKNOWN_TRANSACTION_COMMIT_RESULT.to_string()])
+        .build();
+    let failpoint =
+        FailPoint::fail_command(&["commitTransaction"], FailPointMode::Times(2), options);
+    let _fp_guard = client.enable_failpoint(failpoint, None).await.unwrap();
+
+    let mut session = client.start_session(None).await.unwrap();
+    let mut attempts = 0;
+    session
+        .with_transaction(
+            (&coll, &mut attempts),
+            |session, (coll, attempts)| {
+                async move {
+                    **attempts += 1;
+                    coll.insert_one_with_session(doc! { "x": 1 }, None, session)
+                        .await?;
+                    Ok(())
+                }
+                .boxed()
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Only the commit should have been retried.
+    assert_eq!(attempts, 1);
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 1);
+}
+
+#[cfg_attr(feature = "tokio-runtime", tokio::test(flavor = "multi_thread"))]
+#[cfg_attr(feature = "async-std-runtime", async_std::test)]
+#[function_name::named]
+async fn with_transaction_aborts_on_callback_error() {
+    let _guard: RwLockReadGuard<()> = LOCK.run_concurrently().await;
+
+    let client = TestClient::new().await;
+    if !client.is_replica_set() || client.server_version_lt(4, 4) {
+        log_uncaptured("skipping with_transaction_aborts_on_callback_error due to test topology");
+        return;
+    }
+
+    let coll = client
+        .init_db_and_coll(function_name!(), function_name!())
+        .await;
+
+    let mut session = client.start_session(None).await.unwrap();
+    let result: crate::error::Result<()> = session
+        .with_transaction(
+            &coll,
+            |session, coll| {
+                async move {
+                    coll.insert_one_with_session(doc! { "x": 1 }, None, session)
+                        .await?;
+                    // A duplicate key error is not retryable.
+                    coll.insert_one_with_session(doc! { "_id": 1 }, None, session)
+                        .await?;
+                    coll.insert_one_with_session(doc! { "_id": 1 }, None, session)
+                        .await?;
+                    Ok(())
+                }
+                .boxed()
+            },
+            None,
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(session.transaction.state, TransactionState::Aborted);
+    assert_eq!(coll.count_documents(None, None).await.unwrap(), 0);
+}
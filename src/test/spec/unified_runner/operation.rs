@@ -373,6 +373,7 @@ impl Find {
             cursor_type: None,
             max_await_time: None,
             selection_criteria: None,
+            timeout: None,
         };
         match &self.session {
             Some(session_id) => {
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, future::Future, net::IpAddr, pin::Pin, time::Duration};
+
+use crate::{
+    error::Result,
+    options::{DnsResolver, SrvRecord},
+};
+
+/// A `DnsResolver` that answers lookups from an in-memory table. Names that are not in the table
+/// have no records, and hosts without IP addresses fall back to the system's resolution.
+#[derive(Clone, Debug, Default)]
+pub struct StaticDnsResolver {
+    srv: HashMap<String, Vec<SrvRecord>>,
+    txt: HashMap<String, Vec<String>>,
+    ip: HashMap<String, Vec<IpAddr>>,
+}
+
+impl StaticDnsResolver {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn srv(mut self, name: &str, target: &str, port: u16, ttl: Duration) -> Self {
+        self.srv
+            .entry(name.to_string())
+            .or_default()
+            .push(SrvRecord {
+                target: target.to_string(),
+                port,
+                ttl,
+            });
+        self
+    }
+
+    pub fn txt(mut self, name: &str, record: &str) -> Self {
+        self.txt
+            .entry(name.to_string())
+            .or_default()
+            .push(record.to_string());
+        self
+    }
+
+    pub fn ip(mut self, host: &str, ip: IpAddr) -> Self {
+        self.ip.entry(host.to_string()).or_default().push(ip);
+        self
+    }
+}
+
+impl DnsResolver for StaticDnsResolver {
+    fn srv_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<SrvRecord>>> + Send + 'a>> {
+        Box::pin(async move { Ok(self.srv.get(name).cloned().unwrap_or_default()) })
+    }
+
+    fn txt_lookup<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + 'a>> {
+        Box::pin(async move { Ok(self.txt.get(name).cloned().unwrap_or_default()) })
+    }
+
+    fn ip_lookup<'a>(
+        &'a self,
+        host: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Vec<IpAddr>>>> + Send + 'a>> {
+        Box::pin(async move { Ok(self.ip.get(host).cloned()) })
+    }
+}
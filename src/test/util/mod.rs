@@ -1,9 +1,11 @@
+mod dns;
 mod event;
 mod failpoint;
 mod lock;
 mod matchable;
 
 pub use self::{
+    dns::StaticDnsResolver,
     event::{CmapEvent, CommandEvent, Event, EventClient, EventHandler, SdamEvent},
     failpoint::{FailCommandOptions, FailPoint, FailPointGuard, FailPointMode},
     lock::TestLock,
@@ -83,8 +85,13 @@ impl TestClient {
             .await;
         session.mark_dirty();
 
-        let hello_cmd = hello_command(options.server_api.as_ref(), options.load_balanced, None);
-        let hello = RunCommand::new("admin".into(), hello_cmd.body, None, None).unwrap();
+        let hello_cmd = hello_command(
+            options.server_api.as_ref(),
+            options.load_balanced,
+            None,
+            None,
+        );
+        let hello = RunCommand::new("admin".into(), hello_cmd.body, None, None, None).unwrap();
 
         let server_info = bson::from_bson(Bson::Document(
             client.execute_operation(hello, &mut session).await.unwrap(),
@@ -92,7 +99,7 @@ impl TestClient {
         .unwrap();
 
         let build_info =
-            RunCommand::new("test".into(), doc! { "buildInfo":  1 }, None, None).unwrap();
+            RunCommand::new("test".into(), doc! { "buildInfo":  1 }, None, None, None).unwrap();
 
         let response = client
             .execute_operation(build_info, &mut session)
@@ -103,8 +110,14 @@ impl TestClient {
         let server_version_str = info.version.split('-').next().unwrap();
         let server_version = Version::parse(server_version_str).unwrap();
 
-        let get_parameters =
-            RunCommand::new("admin".into(), doc! { "getParameter": "*" }, None, None).unwrap();
+        let get_parameters = RunCommand::new(
+            "admin".into(),
+            doc! { "getParameter": "*" },
+            None,
+            None,
+            None,
+        )
+        .unwrap();
         let server_parameters = client
             .execute_operation(get_parameters, &mut session)
             .await
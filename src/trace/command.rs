@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use tracing::Level;
+
+use super::{serialize_document, COMMAND_TRACING_EVENT_TARGET};
+use crate::event::command::{
+    CommandEventHandler,
+    CommandFailedEvent,
+    CommandStartedEvent,
+    CommandSucceededEvent,
+};
+
+/// Emits a tracing event for each command event before forwarding it to the wrapped handler.
+pub(super) struct CommandTracingEventEmitter {
+    handler: Option<Arc<dyn CommandEventHandler>>,
+    max_document_length_bytes: usize,
+}
+
+impl CommandTracingEventEmitter {
+    pub(super) fn new(
+        handler: Option<Arc<dyn CommandEventHandler>>,
+        max_document_length_bytes: usize,
+    ) -> Self {
+        Self {
+            handler,
+            max_document_length_bytes,
+        }
+    }
+}
+
+impl CommandEventHandler for CommandTracingEventEmitter {
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        if tracing::enabled!(target: COMMAND_TRACING_EVENT_TARGET, Level::DEBUG) {
+            tracing::debug!(
+                target: COMMAND_TRACING_EVENT_TARGET,
+                command = serialize_document(&event.command, self.max_document_length_bytes)
+                    .as_str(),
+                database_name = event.db.as_str(),
+                command_name = event.command_name.as_str(),
+                request_id = event.request_id,
+                driver_connection_id = event.connection.id,
+                server_address = %event.connection.address,
+                service_id = ?event.service_id,
+                "Command started",
+            );
+        }
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_command_started_event(event);
+        }
+    }
+
+    fn handle_command_succeeded_event(&self, event: CommandSucceededEvent) {
+        if tracing::enabled!(target: COMMAND_TRACING_EVENT_TARGET, Level::DEBUG) {
+            tracing::debug!(
+                target: COMMAND_TRACING_EVENT_TARGET,
+                reply = serialize_document(&event.reply, self.max_document_length_bytes).as_str(),
+                command_name = event.command_name.as_str(),
+                request_id = event.request_id,
+                driver_connection_id = event.connection.id,
+                server_address = %event.connection.address,
+                service_id = ?event.service_id,
+                duration_ms = event.duration.as_millis() as u64,
+                "Command succeeded",
+            );
+        }
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_command_succeeded_event(event);
+        }
+    }
+
+    fn handle_command_failed_event(&self, event: CommandFailedEvent) {
+        tracing::debug!(
+            target: COMMAND_TRACING_EVENT_TARGET,
+            failure = %event.failure,
+            command_name = event.command_name.as_str(),
+            request_id = event.request_id,
+            driver_connection_id = event.connection.id,
+            server_address = %event.connection.address,
+            service_id = ?event.service_id,
+            duration_ms = event.duration.as_millis() as u64,
+            "Command failed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_command_failed_event(event);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use super::CONNECTION_TRACING_EVENT_TARGET;
+use crate::event::cmap::{
+    CmapEventHandler,
+    ConnectionCheckedInEvent,
+    ConnectionCheckedOutEvent,
+    ConnectionCheckoutFailedEvent,
+    ConnectionCheckoutStartedEvent,
+    ConnectionClosedEvent,
+    ConnectionCreatedEvent,
+    ConnectionReadyEvent,
+    PoolClearedEvent,
+    PoolClosedEvent,
+    PoolCreatedEvent,
+    PoolReadyEvent,
+};
+
+/// Emits a tracing event for each connection pool event before forwarding it to the wrapped
+/// handler.
+pub(super) struct ConnectionTracingEventEmitter {
+    handler: Option<Arc<dyn CmapEventHandler>>,
+}
+
+impl ConnectionTracingEventEmitter {
+    pub(super) fn new(handler: Option<Arc<dyn CmapEventHandler>>) -> Self {
+        Self { handler }
+    }
+}
+
+impl CmapEventHandler for ConnectionTracingEventEmitter {
+    fn handle_pool_created_event(&self, event: PoolCreatedEvent) {
+        let options = event.options.as_ref();
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            max_idle_time = ?options.and_then(|options| options.max_idle_time),
+            max_pool_size = ?options.and_then(|options| options.max_pool_size),
+            min_pool_size = ?options.and_then(|options| options.min_pool_size),
+            "Connection pool created",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_pool_created_event(event);
+        }
+    }
+
+    fn handle_pool_ready_event(&self, event: PoolReadyEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            "Connection pool ready",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_pool_ready_event(event);
+        }
+    }
+
+    fn handle_pool_cleared_event(&self, event: PoolClearedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            service_id = ?event.service_id,
+            "Connection pool cleared",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_pool_cleared_event(event);
+        }
+    }
+
+    fn handle_pool_closed_event(&self, event: PoolClosedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            "Connection pool closed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_pool_closed_event(event);
+        }
+    }
+
+    fn handle_connection_created_event(&self, event: ConnectionCreatedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            driver_connection_id = event.connection_id,
+            "Connection created",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_created_event(event);
+        }
+    }
+
+    fn handle_connection_ready_event(&self, event: ConnectionReadyEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            driver_connection_id = event.connection_id,
+            "Connection ready",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_ready_event(event);
+        }
+    }
+
+    fn handle_connection_closed_event(&self, event: ConnectionClosedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            driver_connection_id = event.connection_id,
+            reason = ?event.reason,
+            "Connection closed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_closed_event(event);
+        }
+    }
+
+    fn handle_connection_checkout_started_event(&self, event: ConnectionCheckoutStartedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            "Connection checkout started",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_checkout_started_event(event);
+        }
+    }
+
+    fn handle_connection_checkout_failed_event(&self, event: ConnectionCheckoutFailedEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            reason = ?event.reason,
+            "Connection checkout failed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_checkout_failed_event(event);
+        }
+    }
+
+    fn handle_connection_checked_out_event(&self, event: ConnectionCheckedOutEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            driver_connection_id = event.connection_id,
+            "Connection checked out",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_checked_out_event(event);
+        }
+    }
+
+    fn handle_connection_checked_in_event(&self, event: ConnectionCheckedInEvent) {
+        tracing::debug!(
+            target: CONNECTION_TRACING_EVENT_TARGET,
+            server_address = %event.address,
+            driver_connection_id = event.connection_id,
+            "Connection checked in",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_connection_checked_in_event(event);
+        }
+    }
+}
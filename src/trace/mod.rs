@@ -0,0 +1,97 @@
+//! Contains the functionality for emitting [`tracing`](https://docs.rs/tracing) events describing
+//! what the driver is doing.
+//!
+//! Events are emitted with one of the following targets, which can be used to filter them by
+//! component:
+//!
+//! - `mongodb::command`: commands that are started, succeed or fail.
+//! - `mongodb::connection`: the lifecycle of connection pools and their connections.
+//! - `mongodb::server_selection`: attempts to select a server for an operation.
+//! - `mongodb::topology`: changes to the topology and server monitoring.
+
+mod command;
+mod connection;
+pub(crate) mod server_selection;
+#[cfg(test)]
+mod test;
+mod topology;
+
+use std::sync::Arc;
+
+use tracing::Level;
+
+use crate::{
+    bson::{Bson, Document},
+    options::ClientOptions,
+};
+
+use self::{
+    command::CommandTracingEventEmitter,
+    connection::ConnectionTracingEventEmitter,
+    topology::TopologyTracingEventEmitter,
+};
+
+pub(crate) const COMMAND_TRACING_EVENT_TARGET: &str = "mongodb::command";
+pub(crate) const CONNECTION_TRACING_EVENT_TARGET: &str = "mongodb::connection";
+pub(crate) const SERVER_SELECTION_TRACING_EVENT_TARGET: &str = "mongodb::server_selection";
+pub(crate) const TOPOLOGY_TRACING_EVENT_TARGET: &str = "mongodb::topology";
+
+/// The default maximum length of the extended JSON documents included in tracing events.
+pub(crate) const DEFAULT_MAX_DOCUMENT_LENGTH_BYTES: usize = 1000;
+
+/// Replaces the event handlers in `options` with ones that emit a tracing event for each command,
+/// connection pool and SDAM event before forwarding it to the handler configured by the user, if
+/// any.
+///
+/// A handler is only wrapped if its target is enabled when the `Client` is created, so the
+/// driver doesn't construct events that nothing will consume. A subscriber must therefore be
+/// installed before the `Client` is created to receive these events.
+pub(crate) fn with_tracing_event_emitters(mut options: ClientOptions) -> ClientOptions {
+    let max_document_length_bytes = options
+        .tracing_max_document_length_bytes
+        .unwrap_or(DEFAULT_MAX_DOCUMENT_LENGTH_BYTES);
+
+    if tracing::enabled!(target: COMMAND_TRACING_EVENT_TARGET, Level::DEBUG) {
+        options.command_event_handler = Some(Arc::new(CommandTracingEventEmitter::new(
+            options.command_event_handler.take(),
+            max_document_length_bytes,
+        )));
+    }
+    if tracing::enabled!(target: CONNECTION_TRACING_EVENT_TARGET, Level::DEBUG) {
+        options.cmap_event_handler = Some(Arc::new(ConnectionTracingEventEmitter::new(
+            options.cmap_event_handler.take(),
+        )));
+    }
+    if tracing::enabled!(target: TOPOLOGY_TRACING_EVENT_TARGET, Level::DEBUG) {
+        options.sdam_event_handler = Some(Arc::new(TopologyTracingEventEmitter::new(
+            options.sdam_event_handler.take(),
+            max_document_length_bytes,
+        )));
+    }
+    options
+}
+
+/// Serializes `document` as relaxed extended JSON, truncated to at most `max_length_bytes` bytes
+/// followed by an ellipsis.
+pub(crate) fn serialize_document(document: &Document, max_length_bytes: usize) -> String {
+    let mut json = Bson::Document(document.clone())
+        .into_relaxed_extjson()
+        .to_string();
+    truncate_on_char_boundary(&mut json, max_length_bytes);
+    json
+}
+
+/// Truncates `s` to at most `max_length_bytes` bytes, backing off to the nearest character
+/// boundary, and appends an ellipsis if anything was removed.
+pub(crate) fn truncate_on_char_boundary(s: &mut String, max_length_bytes: usize) {
+    if s.len() <= max_length_bytes {
+        return;
+    }
+
+    let mut length = max_length_bytes;
+    while !s.is_char_boundary(length) {
+        length -= 1;
+    }
+    s.truncate(length);
+    s.push_str("...");
+}
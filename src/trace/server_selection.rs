@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use super::SERVER_SELECTION_TRACING_EVENT_TARGET;
+use crate::{
+    error::Error,
+    options::{SelectionCriteria, ServerAddress},
+    sdam::TopologyDescription,
+};
+
+/// Emits the tracing events describing an attempt to select a server for an operation.
+pub(crate) struct ServerSelectionTracer<'a> {
+    operation_name: &'a str,
+    criteria: &'a SelectionCriteria,
+}
+
+impl<'a> ServerSelectionTracer<'a> {
+    pub(crate) fn new(operation_name: &'a str, criteria: &'a SelectionCriteria) -> Self {
+        Self {
+            operation_name,
+            criteria,
+        }
+    }
+
+    pub(crate) fn started(&self, description: &TopologyDescription) {
+        tracing::debug!(
+            target: SERVER_SELECTION_TRACING_EVENT_TARGET,
+            operation = self.operation_name,
+            selector = ?self.criteria,
+            topology_description = %description,
+            "Server selection started",
+        );
+    }
+
+    pub(crate) fn waiting(&self, remaining_time: Duration, description: &TopologyDescription) {
+        tracing::info!(
+            target: SERVER_SELECTION_TRACING_EVENT_TARGET,
+            operation = self.operation_name,
+            selector = ?self.criteria,
+            topology_description = %description,
+            remaining_time_ms = remaining_time.as_millis() as u64,
+            "Waiting for suitable server to become available",
+        );
+    }
+
+    pub(crate) fn succeeded(&self, address: &ServerAddress) {
+        tracing::debug!(
+            target: SERVER_SELECTION_TRACING_EVENT_TARGET,
+            operation = self.operation_name,
+            selector = ?self.criteria,
+            server_address = %address,
+            "Server selection succeeded",
+        );
+    }
+
+    pub(crate) fn failed(&self, failure: &Error) {
+        tracing::debug!(
+            target: SERVER_SELECTION_TRACING_EVENT_TARGET,
+            operation = self.operation_name,
+            selector = ?self.criteria,
+            failure = %failure,
+            "Server selection failed",
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::{Arc, Mutex};
+
+use super::{serialize_document, truncate_on_char_boundary, with_tracing_event_emitters};
+use crate::{
+    bson::doc,
+    cmap::ConnectionInfo,
+    event::command::{CommandEventHandler, CommandStartedEvent},
+    options::{ClientOptions, ServerAddress},
+};
+
+#[test]
+fn truncate_on_char_boundary_ascii() {
+    let mut s = "abcdef".to_string();
+    truncate_on_char_boundary(&mut s, 10);
+    assert_eq!(s, "abcdef");
+
+    truncate_on_char_boundary(&mut s, 6);
+    assert_eq!(s, "abcdef");
+
+    truncate_on_char_boundary(&mut s, 3);
+    assert_eq!(s, "abc...");
+}
+
+#[test]
+fn truncate_on_char_boundary_multibyte() {
+    // Each of these characters is three bytes long.
+    let mut s = "日本語".to_string();
+    truncate_on_char_boundary(&mut s, 4);
+    assert_eq!(s, "日...");
+
+    let mut s = "日本語".to_string();
+    truncate_on_char_boundary(&mut s, 2);
+    assert_eq!(s, "...");
+}
+
+#[test]
+fn serialize_truncated_document() {
+    let document = doc! { "insert": "coll", "documents": [{ "x": "a".repeat(100) }] };
+
+    let json = serialize_document(&document, 2000);
+    assert!(json.starts_with(r#"{"insert":"coll""#));
+    assert!(!json.ends_with("..."));
+
+    let json = serialize_document(&document, 20);
+    assert_eq!(json.len(), 23);
+    assert!(json.ends_with("..."));
+}
+
+#[derive(Default)]
+struct StartedEventRecorder {
+    events: Mutex<Vec<CommandStartedEvent>>,
+}
+
+impl CommandEventHandler for StartedEventRecorder {
+    fn handle_command_started_event(&self, event: CommandStartedEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+/// A subscriber that enables every event from the driver and discards them.
+struct EnableDriverEvents;
+
+impl tracing::Subscriber for EnableDriverEvents {
+    fn register_callsite(
+        &self,
+        _metadata: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        tracing::subscriber::Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        metadata.target().starts_with("mongodb::")
+    }
+
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, _event: &tracing::Event<'_>) {}
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+#[test]
+fn emitters_not_installed_without_subscriber() {
+    let recorder: Arc<dyn CommandEventHandler> = Arc::new(StartedEventRecorder::default());
+    let options = ClientOptions::builder()
+        .command_event_handler(recorder.clone())
+        .build();
+
+    let options =
+        tracing::subscriber::with_default(tracing::subscriber::NoSubscriber::default(), || {
+            with_tracing_event_emitters(options)
+        });
+    assert!(Arc::ptr_eq(
+        options.command_event_handler.as_ref().unwrap(),
+        &recorder
+    ));
+    assert!(options.cmap_event_handler.is_none());
+    assert!(options.sdam_event_handler.is_none());
+}
+
+#[test]
+fn emitters_forward_to_handlers() {
+    let recorder = Arc::new(StartedEventRecorder::default());
+    let options = ClientOptions::builder()
+        .command_event_handler(recorder.clone() as Arc<dyn CommandEventHandler>)
+        .build();
+
+    let options = tracing::subscriber::with_default(EnableDriverEvents, || {
+        with_tracing_event_emitters(options)
+    });
+    assert!(options.cmap_event_handler.is_some());
+    assert!(options.sdam_event_handler.is_some());
+
+    let event = CommandStartedEvent {
+        command: doc! { "ping": 1 },
+        db: "admin".to_string(),
+        command_name: "ping".to_string(),
+        request_id: 1,
+        connection: ConnectionInfo {
+            id: 1,
+            address: ServerAddress::default(),
+        },
+        service_id: None,
+    };
+    options
+        .command_event_handler
+        .unwrap()
+        .handle_command_started_event(event);
+
+    let events = recorder.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].command_name, "ping");
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use tracing::Level;
+
+use super::{serialize_document, TOPOLOGY_TRACING_EVENT_TARGET};
+use crate::event::sdam::{
+    SdamEventHandler,
+    ServerClosedEvent,
+    ServerDescriptionChangedEvent,
+    ServerHeartbeatFailedEvent,
+    ServerHeartbeatStartedEvent,
+    ServerHeartbeatSucceededEvent,
+    ServerOpeningEvent,
+    TopologyClosedEvent,
+    TopologyDescriptionChangedEvent,
+    TopologyOpeningEvent,
+};
+
+/// Emits a tracing event for each SDAM event before forwarding it to the wrapped handler.
+pub(super) struct TopologyTracingEventEmitter {
+    handler: Option<Arc<dyn SdamEventHandler>>,
+    max_document_length_bytes: usize,
+}
+
+impl TopologyTracingEventEmitter {
+    pub(super) fn new(
+        handler: Option<Arc<dyn SdamEventHandler>>,
+        max_document_length_bytes: usize,
+    ) -> Self {
+        Self {
+            handler,
+            max_document_length_bytes,
+        }
+    }
+}
+
+impl SdamEventHandler for TopologyTracingEventEmitter {
+    fn handle_server_description_changed_event(&self, event: ServerDescriptionChangedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            server_address = %event.address,
+            previous_description = %event.previous_description,
+            new_description = %event.new_description,
+            "Server description changed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_description_changed_event(event);
+        }
+    }
+
+    fn handle_server_opening_event(&self, event: ServerOpeningEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            server_address = %event.address,
+            "Starting server monitoring",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_opening_event(event);
+        }
+    }
+
+    fn handle_server_closed_event(&self, event: ServerClosedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            server_address = %event.address,
+            "Stopped server monitoring",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_closed_event(event);
+        }
+    }
+
+    fn handle_topology_description_changed_event(&self, event: TopologyDescriptionChangedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            previous_description = %event.previous_description,
+            new_description = %event.new_description,
+            "Topology description changed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_topology_description_changed_event(event);
+        }
+    }
+
+    fn handle_topology_opening_event(&self, event: TopologyOpeningEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            "Starting topology monitoring",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_topology_opening_event(event);
+        }
+    }
+
+    fn handle_topology_closed_event(&self, event: TopologyClosedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            topology_id = %event.topology_id,
+            "Stopped topology monitoring",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_topology_closed_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_started_event(&self, event: ServerHeartbeatStartedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            server_address = %event.server_address,
+            awaited = event.awaited,
+            "Server heartbeat started",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_heartbeat_started_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_succeeded_event(&self, event: ServerHeartbeatSucceededEvent) {
+        if tracing::enabled!(target: TOPOLOGY_TRACING_EVENT_TARGET, Level::DEBUG) {
+            tracing::debug!(
+                target: TOPOLOGY_TRACING_EVENT_TARGET,
+                server_address = %event.server_address,
+                reply = serialize_document(&event.reply, self.max_document_length_bytes).as_str(),
+                duration_ms = event.duration.as_millis() as u64,
+                awaited = event.awaited,
+                "Server heartbeat succeeded",
+            );
+        }
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_heartbeat_succeeded_event(event);
+        }
+    }
+
+    fn handle_server_heartbeat_failed_event(&self, event: ServerHeartbeatFailedEvent) {
+        tracing::debug!(
+            target: TOPOLOGY_TRACING_EVENT_TARGET,
+            server_address = %event.server_address,
+            failure = %event.failure,
+            duration_ms = event.duration.as_millis() as u64,
+            awaited = event.awaited,
+            "Server heartbeat failed",
+        );
+
+        if let Some(ref handler) = self.handler {
+            handler.handle_server_heartbeat_failed_event(event);
+        }
+    }
+}